@@ -2,6 +2,7 @@ use crate::Diagnostic;
 use proc_macro2::{Ident, Span};
 use std::hash::{Hash, Hasher};
 use syn;
+use syn::ext::IdentExt;
 use wasm_bindgen_shared as shared;
 
 /// An abstract syntax tree representing a rust program. Contains
@@ -27,6 +28,42 @@ pub struct Program {
     pub typescript_custom_sections: Vec<String>,
     /// Inline JS snippets
     pub inline_js: Vec<String>,
+    /// Local files whose URL is requested at runtime through `link_to!`
+    pub linked_modules: Vec<LinkToModule>,
+    /// JS registered through `js_prelude!` to run before any bindings
+    pub js_preludes: Vec<JsPrelude>,
+    /// rust consts exported to JS
+    pub exported_consts: Vec<ExportedConst>,
+}
+
+/// JS which is inserted at the top of the generated JS module, registered
+/// through `js_prelude!`.
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub enum JsPrelude {
+    /// JS source given inline
+    Inline(String),
+    /// A local file whose contents are inserted, relative to the crate root
+    /// and starting with `/`
+    Module {
+        /// The path of the file
+        path: String,
+        /// The span of the path, for error reporting
+        span: Span,
+    },
+}
+
+/// A local file in the crate which is copied alongside the generated JS, and
+/// whose URL is acquired at runtime through an imported function.
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub struct LinkToModule {
+    /// The path of the file, relative to the crate root and starting with `/`
+    pub path: String,
+    /// The span of the path, for error reporting
+    pub span: Span,
+    /// The name of the imported function returning the URL of the file
+    pub link_function_name: Ident,
 }
 
 /// A rust to js interface. Allows interaction with rust objects/functions
@@ -40,6 +77,8 @@ pub struct Export {
     pub function: Function,
     /// The class name in JS this is attached to
     pub js_class: Option<String>,
+    /// The JS namespace object a free function is exported under, if any
+    pub js_namespace: Option<Ident>,
     /// The kind (static, named, regular)
     pub method_kind: MethodKind,
     /// The type of `self` (either `self`, `&self`, or `&mut self`)
@@ -51,6 +90,34 @@ pub struct Export {
     /// Whether or not this function should be flagged as the wasm start
     /// function.
     pub start: bool,
+    /// Whether this method is used to implement the JS iteration protocol for
+    /// its class, via `#[wasm_bindgen(iterator)]`.
+    pub iterator: bool,
+    /// Whether this getter's value is cached by JS after it's first read, via
+    /// `#[wasm_bindgen(getter, cached)]`.
+    pub cached: bool,
+    /// The attribute of a custom element whose changes this method is called
+    /// with, via `#[wasm_bindgen(observed_attribute = "...")]`.
+    pub observed_attribute: Option<String>,
+    /// Whether this export was marked as unstable or deprecated.
+    pub stability: Stability,
+    /// Whether a returned vector is handed to JS as a view of wasm memory
+    /// instead of being copied, via `#[wasm_bindgen(no_copy)]`.
+    pub no_copy: bool,
+    /// The Cargo feature this export is only compiled with, via
+    /// `#[wasm_bindgen(cfg_feature = "...")]`
+    pub cfg_feature: Option<String>,
+}
+
+/// Whether an export is marked with `#[wasm_bindgen(unstable)]` or
+/// `#[wasm_bindgen(deprecated)]`.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub enum Stability {
+    Stable,
+    Unstable,
+    /// Deprecated, with an optional message explaining what to use instead
+    Deprecated(Option<String>),
 }
 
 /// The 3 types variations of `self`.
@@ -111,6 +178,7 @@ pub enum ImportKind {
     Static(ImportStatic),
     Type(ImportType),
     Enum(ImportEnum),
+    Property(ImportProperty),
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug))]
@@ -120,9 +188,17 @@ pub struct ImportFunction {
     pub rust_name: Ident,
     pub js_ret: Option<syn::Type>,
     pub catch: bool,
+    /// The error type of a `catch` import if it's something other than
+    /// `JsValue`, in which case only exceptions which are instances of this
+    /// type are caught.
+    pub catch_ty: Option<syn::Type>,
     pub variadic: bool,
     pub structural: bool,
     pub assert_no_shim: bool,
+    /// If set, this method is declared in a trait of this name implemented for
+    /// the imported type, rather than in an inherent `impl`, which allows
+    /// crates to add methods to imported types defined elsewhere.
+    pub extension_trait: Option<Ident>,
     pub kind: ImportFunctionKind,
     pub shim: Ident,
     pub doc_comment: Option<String>,
@@ -197,12 +273,42 @@ pub struct ImportEnum {
     pub name: Ident,
     /// The Rust identifiers for the variants
     pub variants: Vec<Ident>,
-    /// The JS string values of the variants
-    pub variant_values: Vec<String>,
+    /// The JS values of the variants
+    pub variant_values: Vec<ImportEnumValue>,
     /// Attributes to apply to the Rust enum
     pub rust_attrs: Vec<syn::Attribute>,
 }
 
+/// A method of an imported type marked `#[wasm_bindgen(property)]`, which
+/// returns a `Property` handle for a property of the object rather than
+/// calling into JS.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub struct ImportProperty {
+    /// The visibility of the generated method
+    pub vis: syn::Visibility,
+    /// The Rust name of the generated method
+    pub rust_name: Ident,
+    /// The name of the JS property the handle refers to
+    pub js_name: String,
+    /// The imported type the method is defined on
+    pub class: syn::Type,
+    /// The return type of the method, a `Property<T>`
+    pub ty: syn::Type,
+    /// Attributes to apply to the generated method
+    pub rust_attrs: Vec<syn::Attribute>,
+}
+
+/// The JS value that a variant of an imported enum corresponds to
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub enum ImportEnumValue {
+    /// A JS string, like the values of WebIDL enums
+    String(String),
+    /// A JS number, like the `readyState` constants of `XMLHttpRequest`
+    Number(u32),
+}
+
 #[cfg_attr(feature = "extra-traits", derive(Debug))]
 #[derive(Clone)]
 pub struct Function {
@@ -214,6 +320,9 @@ pub struct Function {
     pub rust_attrs: Vec<syn::Attribute>,
     pub rust_vis: syn::Visibility,
     pub r#async: bool,
+    /// Whether this function returns `impl Iterator<Item = T>`, in which case
+    /// `ret` is `T` and the iterator is handed to JS as an iterator object.
+    pub generator: bool,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -224,6 +333,12 @@ pub struct Struct {
     pub fields: Vec<StructField>,
     pub comments: Vec<String>,
     pub is_inspectable: bool,
+    pub cfg_feature: Option<String>,
+    /// The JS class the generated class extends, if any
+    pub extends_js: Option<String>,
+    /// The tag name the generated class is defined as a custom element with,
+    /// via `#[wasm_bindgen(custom_element = "...")]`
+    pub custom_element: Option<String>,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -232,10 +347,31 @@ pub struct StructField {
     pub name: syn::Member,
     pub struct_name: Ident,
     pub readonly: bool,
+    pub cached: bool,
     pub ty: syn::Type,
     pub getter: Ident,
     pub setter: Ident,
     pub comments: Vec<String>,
+    pub kind: StructFieldKind,
+}
+
+/// How a public struct field is converted when accessed from JS
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub enum StructFieldKind {
+    /// The field is copied out and in as-is, and must be `Copy`
+    Copy,
+    /// An `Option<Box<T>>` field, accessed as an `Option<T>` by cloning `T`
+    OptionBox(syn::Type),
+    /// An `Rc<RefCell<T>>` field, accessed as a `T` by cloning `T` out of and
+    /// assigning it into the shared `RefCell`
+    RcRefCell(syn::Type),
+    /// A `Vec<T>` of numbers read as a typed array which views the vector's
+    /// memory directly, assigned by copying a typed array into a new vector
+    TypedArrayView(syn::Type),
+    /// A `Vec<T>` of numbers read as a typed array copied out of the vector,
+    /// assigned by copying a typed array into a new vector
+    TypedArrayCopy(syn::Type),
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -245,6 +381,22 @@ pub struct Enum {
     pub variants: Vec<Variant>,
     pub comments: Vec<String>,
     pub hole: u32,
+    pub cfg_feature: Option<String>,
+    pub repr: EnumRepr,
+}
+
+/// How the values of an exported enum are represented in JS, selected with
+/// `#[wasm_bindgen(js_repr = ...)]`.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Copy, Clone)]
+pub enum EnumRepr {
+    /// The numeric discriminant of the variant, the default
+    Number,
+    /// The name of the variant as a string
+    String,
+    /// The numeric discriminant, with the exported object also mapping
+    /// discriminants back to variant names
+    Both,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -290,6 +442,30 @@ pub enum ConstValue {
     Null,
 }
 
+/// A constant exported to JS with `#[wasm_bindgen] const`.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub struct ExportedConst {
+    /// The name of the constant in JS
+    pub js_name: String,
+    /// The value of the constant
+    pub value: ExportedConstValue,
+    /// The doc comments on the constant
+    pub comments: Vec<String>,
+}
+
+/// The value of a constant exported to JS.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub enum ExportedConstValue {
+    /// A string
+    String(String),
+    /// A boolean
+    Boolean(bool),
+    /// A number, as written in the Rust source without any suffix
+    Number(String),
+}
+
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
 #[derive(Clone)]
 pub struct Dictionary {
@@ -315,23 +491,35 @@ impl Export {
     /// name and class name, if the function belongs to a javascript class.
     pub(crate) fn rust_symbol(&self) -> Ident {
         let mut generated_name = String::from("__wasm_bindgen_generated");
-        if let Some(class) = &self.js_class {
-            generated_name.push_str("_");
-            generated_name.push_str(class);
+        match &self.js_class {
+            Some(class) => {
+                generated_name.push_str("_");
+                generated_name.push_str(class);
+                generated_name.push_str("_");
+                generated_name.push_str(&self.method_name());
+            }
+            None => {
+                generated_name.push_str("_");
+                generated_name.push_str(&self.function.name);
+            }
         }
-        generated_name.push_str("_");
-        generated_name.push_str(&self.function.name.to_string());
         Ident::new(&generated_name, Span::call_site())
     }
 
+    /// The Rust name of this export without any `r#` prefix. Methods are
+    /// identified by this rather than their JS name, which several methods of
+    /// a class may share as overloads.
+    pub(crate) fn method_name(&self) -> String {
+        self.rust_name.unraw().to_string()
+    }
+
     /// This is the name of the shim function that gets exported and takes the raw
     /// ABI form of its arguments and converts them back into their normal,
     /// "high level" form before calling the actual function.
     pub(crate) fn export_name(&self) -> String {
-        let fn_name = self.function.name.to_string();
         match &self.js_class {
-            Some(class) => shared::struct_function_export_name(class, &fn_name),
-            None => shared::free_function_export_name(&fn_name),
+            Some(class) => shared::struct_function_export_name(class, &self.method_name()),
+            None => shared::free_function_export_name(&self.function.name),
         }
     }
 }
@@ -344,6 +532,7 @@ impl ImportKind {
             ImportKind::Static(_) => false,
             ImportKind::Type(_) => false,
             ImportKind::Enum(_) => false,
+            ImportKind::Property(_) => false,
         }
     }
 }
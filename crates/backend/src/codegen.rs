@@ -4,8 +4,7 @@ use crate::util::ShortHash;
 use crate::Diagnostic;
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::{quote, ToTokens};
-use std::collections::HashSet;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 use syn;
 use wasm_bindgen_shared as shared;
@@ -61,6 +60,9 @@ impl TryToTokens for ast::Program {
                 errors.push(e);
             }
         }
+        if let Err(e) = extension_traits(&self.imports, tokens) {
+            errors.push(e);
+        }
         for e in self.enums.iter() {
             e.to_tokens(tokens);
         }
@@ -77,14 +79,6 @@ impl TryToTokens for ast::Program {
         // of the wasm executable. For now it's just a plain old static, but we'll
         // eventually have it actually in its own section.
 
-        static CNT: AtomicUsize = AtomicUsize::new(0);
-
-        let generated_static_name = format!(
-            "__WASM_BINDGEN_GENERATED_{}",
-            ShortHash(CNT.fetch_add(1, Ordering::SeqCst)),
-        );
-        let generated_static_name = Ident::new(&generated_static_name, Span::call_site());
-
         // See comments in `crates/cli-support/src/lib.rs` about what this
         // `schema_version` is.
         let prefix_json = format!(
@@ -101,6 +95,27 @@ impl TryToTokens for ast::Program {
         bytes.extend_from_slice(prefix_json.as_bytes());
         bytes.extend_from_slice(&encoded.custom_section);
 
+        // The static is named after its contents rather than the order
+        // programs are generated in, which isn't stable when several threads
+        // generate code at once like the `web-sys` build script does. Identical
+        // programs, which may show up more than once in a crate, are told apart
+        // by how many times the same contents have been seen before.
+        lazy_static::lazy_static! {
+            static ref GENERATED_STATICS: Mutex<HashMap<String, usize>> = Default::default();
+        }
+        let content_hash = ShortHash(&bytes).to_string();
+        let occurrence = {
+            let mut seen = GENERATED_STATICS.lock().unwrap();
+            let count = seen.entry(content_hash.clone()).or_insert(0);
+            *count += 1;
+            *count - 1
+        };
+        let generated_static_name = format!(
+            "__WASM_BINDGEN_GENERATED_{}",
+            ShortHash((&content_hash, occurrence)),
+        );
+        let generated_static_name = Ident::new(&generated_static_name, Span::call_site());
+
         let generated_static_length = bytes.len();
         let generated_static_value = syn::LitByteStr::new(&bytes, Span::call_site());
 
@@ -119,7 +134,7 @@ impl TryToTokens for ast::Program {
 
         (quote! {
             #[allow(non_upper_case_globals)]
-            #[cfg(target_arch = "wasm32")]
+            #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
             #[link_section = "__wasm_bindgen_unstable"]
             #[doc(hidden)]
             #[allow(clippy::all)]
@@ -136,6 +151,32 @@ impl TryToTokens for ast::Program {
     }
 }
 
+/// Note that unlike most other items this generates an expression, evaluating
+/// to the URL of the linked file, rather than item definitions.
+impl ToTokens for ast::LinkToModule {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = &self.link_function_name;
+        (quote! {
+            {
+                #[link(wasm_import_module = "__wbindgen_placeholder__")]
+                #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
+                extern "C" {
+                    fn #name() -> u32;
+                }
+                #[cfg(not(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten"))))]
+                unsafe fn #name() -> u32 {
+                    panic!("cannot link to files on non-wasm targets");
+                }
+                let url = unsafe {
+                    <wasm_bindgen::JsValue as wasm_bindgen::convert::FromWasmAbi>::from_abi(#name())
+                };
+                url.as_string().unwrap()
+            }
+        })
+        .to_tokens(tokens);
+    }
+}
+
 impl ToTokens for ast::Struct {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let name = &self.rust_name;
@@ -144,6 +185,7 @@ impl ToTokens for ast::Struct {
         let name_chars = name_str.chars().map(|c| c as u32);
         let new_fn = Ident::new(&shared::new_function(&name_str), Span::call_site());
         let free_fn = Ident::new(&shared::free_function(&name_str), Span::call_site());
+        let instanceof_fn = Ident::new(&shared::instanceof_function(&name_str), Span::call_site());
         (quote! {
             #[allow(clippy::all)]
             impl wasm_bindgen::describe::WasmDescribe for #name {
@@ -164,20 +206,20 @@ impl ToTokens for ast::Struct {
 
             #[allow(clippy::all)]
             impl wasm_bindgen::convert::IntoWasmAbi for #name {
-                type Abi = u32;
+                type Abi = wasm_bindgen::convert::WasmWord;
 
-                fn into_abi(self) -> u32 {
+                fn into_abi(self) -> wasm_bindgen::convert::WasmWord {
                     use wasm_bindgen::__rt::std::boxed::Box;
                     use wasm_bindgen::__rt::WasmRefCell;
-                    Box::into_raw(Box::new(WasmRefCell::new(self))) as u32
+                    Box::into_raw(Box::new(WasmRefCell::new(self))) as wasm_bindgen::convert::WasmWord
                 }
             }
 
             #[allow(clippy::all)]
             impl wasm_bindgen::convert::FromWasmAbi for #name {
-                type Abi = u32;
+                type Abi = wasm_bindgen::convert::WasmWord;
 
-                unsafe fn from_abi(js: u32) -> Self {
+                unsafe fn from_abi(js: wasm_bindgen::convert::WasmWord) -> Self {
                     use wasm_bindgen::__rt::std::boxed::Box;
                     use wasm_bindgen::__rt::{assert_not_null, WasmRefCell};
 
@@ -197,13 +239,13 @@ impl ToTokens for ast::Struct {
                     let ptr = wasm_bindgen::convert::IntoWasmAbi::into_abi(value);
 
                     #[link(wasm_import_module = "__wbindgen_placeholder__")]
-                    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+                    #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
                     extern "C" {
-                        fn #new_fn(ptr: u32) -> u32;
+                        fn #new_fn(ptr: wasm_bindgen::convert::WasmWord) -> u32;
                     }
 
-                    #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
-                    unsafe fn #new_fn(_: u32) -> u32 {
+                    #[cfg(not(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten"))))]
+                    unsafe fn #new_fn(_: wasm_bindgen::convert::WasmWord) -> u32 {
                         panic!("cannot convert to JsValue outside of the wasm target")
                     }
 
@@ -214,17 +256,17 @@ impl ToTokens for ast::Struct {
                 }
             }
 
-            #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+            #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
             #[no_mangle]
             #[doc(hidden)]
             #[allow(clippy::all)]
-            pub unsafe extern "C" fn #free_fn(ptr: u32) {
+            pub unsafe extern "C" fn #free_fn(ptr: wasm_bindgen::convert::WasmWord) {
                 <#name as wasm_bindgen::convert::FromWasmAbi>::from_abi(ptr);
             }
 
             #[allow(clippy::all)]
             impl wasm_bindgen::convert::RefFromWasmAbi for #name {
-                type Abi = u32;
+                type Abi = wasm_bindgen::convert::WasmWord;
                 type Anchor = wasm_bindgen::__rt::Ref<'static, #name>;
 
                 unsafe fn ref_from_abi(js: Self::Abi) -> Self::Anchor {
@@ -236,7 +278,7 @@ impl ToTokens for ast::Struct {
 
             #[allow(clippy::all)]
             impl wasm_bindgen::convert::RefMutFromWasmAbi for #name {
-                type Abi = u32;
+                type Abi = wasm_bindgen::convert::WasmWord;
                 type Anchor = wasm_bindgen::__rt::RefMut<'static, #name>;
 
                 unsafe fn ref_mut_from_abi(js: Self::Abi) -> Self::Anchor {
@@ -256,6 +298,27 @@ impl ToTokens for ast::Struct {
                 fn is_none(abi: &Self::Abi) -> bool { *abi == 0 }
             }
 
+            #[allow(clippy::all)]
+            impl wasm_bindgen::convert::ExportedClass for #name {
+                fn is_instance(val: &wasm_bindgen::JsValue) -> bool {
+                    #[link(wasm_import_module = "__wbindgen_placeholder__")]
+                    #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
+                    extern "C" {
+                        fn #instanceof_fn(idx: u32) -> u32;
+                    }
+
+                    #[cfg(not(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten"))))]
+                    unsafe fn #instanceof_fn(_: u32) -> u32 {
+                        panic!("cannot check instanceof outside of the wasm target")
+                    }
+
+                    unsafe {
+                        let idx = wasm_bindgen::convert::IntoWasmAbi::into_abi(val);
+                        #instanceof_fn(idx) != 0
+                    }
+                }
+            }
+
         })
         .to_tokens(tokens);
 
@@ -269,28 +332,119 @@ impl ToTokens for ast::StructField {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let name = &self.name;
         let struct_name = &self.struct_name;
-        let ty = &self.ty;
         let getter = &self.getter;
         let setter = &self.setter;
 
-        let assert_copy = quote! { assert_copy::<#ty>() };
-        let assert_copy = respan(assert_copy, ty);
+        // Smart pointer fields are exposed to JS as a different type `ty` than
+        // the field's own, so along with it determine the statements which
+        // convert between the two. Typed array views are read and written as
+        // different types, so those set `set_ty` as well.
+        let mut set_ty = None;
+        let (ty, get_val, set_val) = match &self.kind {
+            ast::StructFieldKind::Copy => {
+                let ty = &self.ty;
+                let assert_copy = respan(quote! { assert_copy::<#ty>() }, ty);
+                (
+                    quote! { #ty },
+                    quote! {
+                        fn assert_copy<T: Copy>(){}
+                        #assert_copy;
+                        let val = (*js).borrow().#name;
+                    },
+                    quote! {
+                        (*js).borrow_mut().#name = val;
+                    },
+                )
+            }
+            ast::StructFieldKind::OptionBox(inner) => {
+                let assert_clone = respan(quote! { assert_clone::<#inner>() }, inner);
+                (
+                    quote! { wasm_bindgen::__rt::std::option::Option<#inner> },
+                    quote! {
+                        fn assert_clone<T: Clone>(){}
+                        #assert_clone;
+                        let val = (*js).borrow().#name.as_ref().map(|b| (**b).clone());
+                    },
+                    quote! {
+                        (*js).borrow_mut().#name = val.map(wasm_bindgen::__rt::std::boxed::Box::new);
+                    },
+                )
+            }
+            ast::StructFieldKind::RcRefCell(inner) => {
+                let assert_clone = respan(quote! { assert_clone::<#inner>() }, inner);
+                let field = match name {
+                    syn::Member::Named(ident) => ident.to_string(),
+                    syn::Member::Unnamed(index) => index.index.to_string(),
+                };
+                let borrowed = format!(
+                    "cannot read field `{}` of `{}` while it is mutably borrowed",
+                    field, struct_name,
+                );
+                let aliased = format!(
+                    "cannot set field `{}` of `{}` while it is borrowed",
+                    field, struct_name,
+                );
+                (
+                    quote! { #inner },
+                    quote! {
+                        fn assert_clone<T: Clone>(){}
+                        #assert_clone;
+                        let val = match (*js).borrow().#name.try_borrow() {
+                            Ok(val) => val.clone(),
+                            Err(_) => wasm_bindgen::throw_str(#borrowed),
+                        };
+                    },
+                    quote! {
+                        let field = (*js).borrow().#name.clone();
+                        let mut field = match field.try_borrow_mut() {
+                            Ok(field) => field,
+                            Err(_) => wasm_bindgen::throw_str(#aliased),
+                        };
+                        *field = val;
+                    },
+                )
+            }
+            ast::StructFieldKind::TypedArrayView(elem) => {
+                set_ty = Some(quote! { Vec<#elem> });
+                (
+                    quote! { &'static [#elem] },
+                    quote! {
+                        let field = (*js).borrow();
+                        // The slice is only turned into a pointer and length
+                        // below, while `field` is still alive, so extending
+                        // its lifetime here doesn't let it escape.
+                        let val: &'static [#elem] = &*(&field.#name[..] as *const [#elem]);
+                    },
+                    quote! {
+                        (*js).borrow_mut().#name = val;
+                    },
+                )
+            }
+            ast::StructFieldKind::TypedArrayCopy(elem) => (
+                quote! { Vec<#elem> },
+                quote! {
+                    let val = (*js).borrow().#name.clone();
+                },
+                quote! {
+                    (*js).borrow_mut().#name = val;
+                },
+            ),
+        };
+        let set_ty = set_ty.unwrap_or_else(|| ty.clone());
+
         (quote! {
             #[doc(hidden)]
             #[allow(clippy::all)]
-            #[cfg_attr(all(target_arch = "wasm32", not(target_os = "emscripten")), no_mangle)]
-            pub unsafe extern "C" fn #getter(js: u32)
+            #[cfg_attr(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")), no_mangle)]
+            pub unsafe extern "C" fn #getter(js: wasm_bindgen::convert::WasmWord)
                 -> <#ty as wasm_bindgen::convert::IntoWasmAbi>::Abi
             {
                 use wasm_bindgen::__rt::{WasmRefCell, assert_not_null};
                 use wasm_bindgen::convert::IntoWasmAbi;
 
-                fn assert_copy<T: Copy>(){}
-                #assert_copy;
-
                 let js = js as *mut WasmRefCell<#struct_name>;
                 assert_not_null(js);
-                let val = (*js).borrow().#name;
+                #get_val
                 <#ty as IntoWasmAbi>::into_abi(val)
             }
         })
@@ -311,19 +465,19 @@ impl ToTokens for ast::StructField {
         (quote! {
             #[no_mangle]
             #[doc(hidden)]
-            #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+            #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
             #[allow(clippy::all)]
             pub unsafe extern "C" fn #setter(
-                js: u32,
-                val: <#ty as wasm_bindgen::convert::FromWasmAbi>::Abi,
+                js: wasm_bindgen::convert::WasmWord,
+                val: <#set_ty as wasm_bindgen::convert::FromWasmAbi>::Abi,
             ) {
                 use wasm_bindgen::__rt::{WasmRefCell, assert_not_null};
                 use wasm_bindgen::convert::FromWasmAbi;
 
                 let js = js as *mut WasmRefCell<#struct_name>;
                 assert_not_null(js);
-                let val = <#ty as FromWasmAbi>::from_abi(val);
-                (*js).borrow_mut().#name = val;
+                let val = <#set_ty as FromWasmAbi>::from_abi(val);
+                #set_val
             }
         })
         .to_tokens(tokens);
@@ -340,7 +494,7 @@ impl TryToTokens for ast::Export {
         let ret = Ident::new("_ret", Span::call_site());
 
         let offset = if self.method_self.is_some() {
-            args.push(quote! { me: u32 });
+            args.push(quote! { me: wasm_bindgen::convert::WasmWord });
             1
         } else {
             0
@@ -446,7 +600,16 @@ impl TryToTokens for ast::Export {
         // For an `async` function we always run it through `future_to_promise`
         // since we're returning a promise to JS, and this will implicitly
         // require that the function returns a `Future<Output = Result<...>>`
-        let (ret_ty, ret_expr) = if self.function.r#async {
+        let (ret_ty, ret_expr) = if self.function.generator {
+            if self.start {
+                bail_span!(syn_ret, "the start function cannot return an iterator",);
+            }
+            let wrapper = self.generator_wrapper(syn_ret, into);
+            (
+                quote! { #wrapper },
+                quote! { #wrapper { iter: wasm_bindgen::__rt::std::boxed::Box::new(#ret) } },
+            )
+        } else if self.function.r#async {
             if self.start {
                 (
                     quote! { () },
@@ -480,8 +643,17 @@ impl TryToTokens for ast::Export {
 
         let projection = quote! { <#ret_ty as wasm_bindgen::convert::ReturnWasmAbi> };
         let convert_ret = quote! { #projection::return_abi(#ret_expr) };
-        let describe_ret = quote! {
-            <#ret_ty as WasmDescribe>::describe();
+        let describe_ret = if self.function.r#async && !self.start {
+            // The promise returned to JS is described along with what it
+            // resolves to, so it can be typed accordingly in TypeScript.
+            quote! {
+                inform(PROMISE);
+                <#syn_ret as wasm_bindgen::__rt::IntoJsResult>::describe_resolved();
+            }
+        } else {
+            quote! {
+                <#ret_ty as WasmDescribe>::describe();
+            }
         };
         let nargs = self.function.arguments.len() as u32;
         let attrs = &self.function.rust_attrs;
@@ -496,7 +668,7 @@ impl TryToTokens for ast::Export {
             #(#attrs)*
             #[allow(non_snake_case)]
             #[cfg_attr(
-                all(target_arch = "wasm32", not(target_os = "emscripten")),
+                all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")),
                 export_name = #export_name,
             )]
             #[allow(clippy::all)]
@@ -547,6 +719,43 @@ impl TryToTokens for ast::Export {
     }
 }
 
+impl ast::Export {
+    /// Generates the exported struct which an iterator returned from this
+    /// export is boxed up in, returning its name.
+    ///
+    /// The struct is exported to JS as a class whose instances implement the
+    /// JS iterator protocol. It's named after the export with the reserved
+    /// `__wbg_` prefix, like `__wbg_range_Iterator` for `range` or
+    /// `__wbg_Foo_values_Iterator` for `Foo.values`, so it can't collide with
+    /// user-defined types.
+    fn generator_wrapper(&self, item: &syn::Type, into: &mut TokenStream) -> Ident {
+        let name = match &self.js_class {
+            Some(class) => format!("__wbg_{}_{}_Iterator", class, self.method_name()),
+            None => format!("__wbg_{}_Iterator", self.function.name),
+        };
+        let wrapper = Ident::new(&name, Span::call_site());
+        (quote! {
+            #[wasm_bindgen::prelude::wasm_bindgen]
+            #[allow(non_camel_case_types)]
+            #[doc(hidden)]
+            pub struct #wrapper {
+                iter: wasm_bindgen::__rt::std::boxed::Box<dyn wasm_bindgen::__rt::std::iter::Iterator<Item = #item>>,
+            }
+
+            #[wasm_bindgen::prelude::wasm_bindgen]
+            #[allow(clippy::all)]
+            impl #wrapper {
+                #[wasm_bindgen(iterator)]
+                pub fn next(&mut self) -> wasm_bindgen::__rt::std::option::Option<#item> {
+                    self.iter.next()
+                }
+            }
+        })
+        .to_tokens(into);
+        wrapper
+    }
+}
+
 impl TryToTokens for ast::ImportKind {
     fn try_to_tokens(&self, tokens: &mut TokenStream) -> Result<(), Diagnostic> {
         match *self {
@@ -554,6 +763,7 @@ impl TryToTokens for ast::ImportKind {
             ast::ImportKind::Static(ref s) => s.to_tokens(tokens),
             ast::ImportKind::Type(ref t) => t.to_tokens(tokens),
             ast::ImportKind::Enum(ref e) => e.to_tokens(tokens),
+            ast::ImportKind::Property(ref p) => p.to_tokens(tokens),
         }
 
         Ok(())
@@ -717,11 +927,11 @@ impl ToTokens for ast::ImportType {
                 impl JsCast for #rust_name {
                     fn instanceof(val: &JsValue) -> bool {
                         #[link(wasm_import_module = "__wbindgen_placeholder__")]
-                        #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+                        #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
                         extern "C" {
                             fn #instanceof_shim(val: u32) -> u32;
                         }
-                        #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+                        #[cfg(not(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten"))))]
                         unsafe fn #instanceof_shim(_: u32) -> u32 {
                             panic!("cannot check instanceof on non-wasm targets");
                         }
@@ -782,7 +992,6 @@ impl ToTokens for ast::ImportEnum {
         let name = &self.name;
         let expect_string = format!("attempted to convert invalid {} into JSValue", name);
         let variants = &self.variants;
-        let variant_strings = &self.variant_values;
         let attrs = &self.rust_attrs;
 
         let mut current_idx: usize = 0;
@@ -808,6 +1017,43 @@ impl ToTokens for ast::ImportEnum {
         // Borrow variant_paths because we need to use it multiple times inside the quote! macro
         let variant_paths_ref = &variant_paths;
 
+        // Variants are split up by the kind of JS value they correspond to, so
+        // we can test a `JsValue` for each kind of value once.
+        let mut string_paths = Vec::new();
+        let mut strings = Vec::new();
+        let mut number_paths = Vec::new();
+        let mut numbers = Vec::new();
+        let mut to_js = Vec::new();
+        for (path, value) in variant_paths.iter().zip(&self.variant_values) {
+            match value {
+                ast::ImportEnumValue::String(s) => {
+                    string_paths.push(path);
+                    strings.push(s);
+                    to_js.push(quote!(wasm_bindgen::JsValue::from_str(#s)));
+                }
+                ast::ImportEnumValue::Number(n) => {
+                    let n = Literal::f64_unsuffixed(*n as f64);
+                    number_paths.push(path);
+                    numbers.push(n.clone());
+                    to_js.push(quote!(wasm_bindgen::JsValue::from_f64(#n)));
+                }
+            }
+        }
+
+        let from_number = if numbers.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                if let Some(obj_num) = obj.as_f64() {
+                    #(
+                        if obj_num == #numbers {
+                            return Some(#number_paths);
+                        }
+                    )*
+                }
+            }
+        };
+
         (quote! {
             #[allow(bad_style)]
             #(#attrs)*
@@ -821,10 +1067,14 @@ impl ToTokens for ast::ImportEnum {
             #[allow(clippy::all)]
             impl #name {
                 #vis fn from_js_value(obj: &wasm_bindgen::JsValue) -> Option<#name> {
-                    obj.as_string().and_then(|obj_str| match obj_str.as_str() {
-                        #(#variant_strings => Some(#variant_paths_ref),)*
-                        _ => None,
-                    })
+                    if let Some(obj_str) = obj.as_string() {
+                        return match obj_str.as_str() {
+                            #(#strings => Some(#string_paths),)*
+                            _ => None,
+                        };
+                    }
+                    #from_number
+                    None
                 }
             }
 
@@ -859,20 +1109,20 @@ impl ToTokens for ast::ImportEnum {
             #[allow(clippy::all)]
             impl wasm_bindgen::convert::OptionIntoWasmAbi for #name {
                 #[inline]
-                fn none() -> Self::Abi { Object::none() }
+                fn none() -> Self::Abi { 0 }
             }
 
             #[allow(clippy::all)]
             impl wasm_bindgen::convert::OptionFromWasmAbi for #name {
                 #[inline]
-                fn is_none(abi: &Self::Abi) -> bool { Object::is_none(abi) }
+                fn is_none(abi: &Self::Abi) -> bool { *abi == 0 }
             }
 
             #[allow(clippy::all)]
             impl From<#name> for wasm_bindgen::JsValue {
                 fn from(obj: #name) -> wasm_bindgen::JsValue {
                     match obj {
-                        #(#variant_paths_ref => wasm_bindgen::JsValue::from_str(#variant_strings),)*
+                        #(#variant_paths_ref => #to_js,)*
                         #name::__Nonexhaustive => panic!(#expect_string),
                     }
                 }
@@ -883,27 +1133,34 @@ impl ToTokens for ast::ImportEnum {
 
 impl TryToTokens for ast::ImportFunction {
     fn try_to_tokens(&self, tokens: &mut TokenStream) -> Result<(), Diagnostic> {
-        let mut class_ty = None;
-        let mut is_method = false;
-        match self.kind {
-            ast::ImportFunctionKind::Method {
-                ref ty, ref kind, ..
-            } => {
-                if let ast::MethodKind::Operation(ast::Operation {
-                    is_static: false, ..
-                }) = kind
-                {
-                    is_method = true;
+        // Methods of an extension trait are emitted together with the rest of
+        // the trait's methods in `extension_traits`, since there can only be
+        // one `impl` of the trait for the type.
+        if self.extension_trait.is_some() {
+            return Ok(());
+        }
+        let invocation = self.invocation()?;
+        if let ast::ImportFunctionKind::Method { ty, .. } = &self.kind {
+            (quote! {
+                impl #ty {
+                    #invocation
                 }
-                class_ty = Some(ty);
-            }
-            ast::ImportFunctionKind::Normal => {}
+            })
+            .to_tokens(tokens);
+        } else {
+            invocation.to_tokens(tokens);
         }
+        Ok(())
+    }
+}
+
+impl ast::ImportFunction {
+    /// Returns the Rust function which calls this import, to be placed in an
+    /// `impl` block for methods.
+    fn invocation(&self) -> Result<TokenStream, Diagnostic> {
+        let is_method = self.is_method();
         let vis = &self.function.rust_vis;
-        let ret = match &self.function.ret {
-            Some(ty) => quote! { -> #ty },
-            None => quote!(),
-        };
+        let ret = self.rust_ret();
 
         let mut abi_argument_names = Vec::new();
         let mut abi_arguments = Vec::new();
@@ -913,23 +1170,17 @@ impl TryToTokens for ast::ImportFunction {
 
         for (i, arg) in self.function.arguments.iter().enumerate() {
             let ty = &arg.ty;
-            let name = match &*arg.pat {
-                syn::Pat::Ident(syn::PatIdent {
-                    by_ref: None,
-                    ident,
-                    subpat: None,
-                    ..
-                }) => ident.clone(),
-                syn::Pat::Wild(_) => syn::Ident::new(&format!("__genarg_{}", i), Span::call_site()),
-                _ => bail_span!(
-                    arg.pat,
-                    "unsupported pattern in #[wasm_bindgen] imported function",
-                ),
+            let name = import_arg_name(i, arg)?;
+
+            let abi_trait = if is_ref_arg_stack_closure(ty) {
+                quote! { wasm_bindgen::convert::RefArgStackClosure }
+            } else {
+                quote! { wasm_bindgen::convert::IntoWasmAbi }
             };
 
             abi_argument_names.push(name.clone());
             abi_arguments.push(quote! {
-                #name: <#ty as wasm_bindgen::convert::IntoWasmAbi>::Abi
+                #name: <#ty as #abi_trait>::Abi
             });
             let var = if i == 0 && is_method {
                 quote! { self }
@@ -938,20 +1189,28 @@ impl TryToTokens for ast::ImportFunction {
                 quote! { #name }
             };
             arg_conversions.push(quote! {
-                let #name = <#ty as wasm_bindgen::convert::IntoWasmAbi>
+                let #name = <#ty as #abi_trait>
                     ::into_abi(#var);
             });
         }
+        if let Some(syn::Type::Reference(_)) = &self.js_ret {
+            bail_span!(
+                self.js_ret,
+                "cannot return references in #[wasm_bindgen] imports yet"
+            );
+        }
+        // An `async` import returns the promise itself, which is then awaited
+        // by the future returned to the caller.
+        let js_value: syn::Type = syn::parse_quote!(wasm_bindgen::JsValue);
+        let js_ret = if self.function.r#async {
+            Some(&js_value)
+        } else {
+            self.js_ret.as_ref()
+        };
         let abi_ret;
         let mut convert_ret;
-        match &self.js_ret {
-            Some(syn::Type::Reference(_)) => {
-                bail_span!(
-                    self.js_ret,
-                    "cannot return references in #[wasm_bindgen] imports yet"
-                );
-            }
-            Some(ref ty) => {
+        match js_ret {
+            Some(ty) => {
                 abi_ret = quote! {
                     <#ty as wasm_bindgen::convert::FromWasmAbi>::Abi
                 };
@@ -967,10 +1226,55 @@ impl TryToTokens for ast::ImportFunction {
         }
 
         let mut exceptional_ret = quote!();
-        if self.catch {
+        if self.function.r#async {
+            let resolve = match &self.js_ret {
+                Some(ty) => quote! {
+                    wasm_bindgen::__rt::resolved_into::<#ty>(#ret_ident)
+                },
+                None => quote! { Ok(drop(#ret_ident)) },
+            };
+            let map_err = match &self.catch_ty {
+                Some(ty) => quote! { wasm_bindgen::__rt::catch_instanceof::<#ty>(e) },
+                None => quote! { e },
+            };
+            convert_ret = if self.catch {
+                quote! {
+                    let promise = wasm_bindgen::__rt::take_last_exception()
+                        .map(|()| #convert_ret);
+                    async move {
+                        let result = match promise {
+                            Ok(promise) => wasm_bindgen::__rt::await_js(promise).await,
+                            Err(e) => Err(e),
+                        };
+                        match result.and_then(|#ret_ident| #resolve) {
+                            Ok(#ret_ident) => Ok(#ret_ident),
+                            Err(e) => Err(#map_err),
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    let promise = #convert_ret;
+                    async move {
+                        let result = wasm_bindgen::__rt::await_js(promise).await;
+                        match result.and_then(|#ret_ident| #resolve) {
+                            Ok(#ret_ident) => #ret_ident,
+                            Err(e) => wasm_bindgen::throw_val(e),
+                        }
+                    }
+                }
+            };
+        } else if self.catch {
             convert_ret = quote! { Ok(#convert_ret) };
-            exceptional_ret = quote! {
-                wasm_bindgen::__rt::take_last_exception()?;
+            exceptional_ret = match &self.catch_ty {
+                Some(ty) => quote! {
+                    if let Err(e) = wasm_bindgen::__rt::take_last_exception() {
+                        return Err(wasm_bindgen::__rt::catch_instanceof::<#ty>(e));
+                    }
+                },
+                None => quote! {
+                    wasm_bindgen::__rt::take_last_exception()?;
+                },
             };
         }
 
@@ -1009,11 +1313,11 @@ impl TryToTokens for ast::ImportFunction {
         let extern_fn = respan(
             quote! {
                 #[link(wasm_import_module = "__wbindgen_placeholder__")]
-                #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+                #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
                 extern "C" {
                     fn #import_name(#(#abi_arguments),*) -> #abi_ret;
                 }
-                #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+                #[cfg(not(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten"))))]
                 unsafe fn #import_name(#(#abi_arguments),*) -> #abi_ret {
                     #(
                         drop(#abi_argument_names);
@@ -1025,6 +1329,13 @@ impl TryToTokens for ast::ImportFunction {
             &self.rust_name,
         );
 
+        // Methods of an extension trait inherit the trait's visibility
+        let vis = if self.extension_trait.is_some() {
+            quote!()
+        } else {
+            quote! { #vis }
+        };
+
         let invocation = quote! {
             #(#attrs)*
             #[allow(bad_style)]
@@ -1044,19 +1355,196 @@ impl TryToTokens for ast::ImportFunction {
             }
         };
 
-        if let Some(class) = class_ty {
-            (quote! {
-                impl #class {
-                    #invocation
+        Ok(invocation)
+    }
+
+    /// Returns the declaration of this method inside of its extension trait,
+    /// for functions with an `extension_trait` set.
+    fn extension_trait_signature(&self) -> Result<TokenStream, Diagnostic> {
+        let mut me = quote!();
+        let mut arguments = Vec::new();
+        for (i, arg) in self.function.arguments.iter().enumerate() {
+            if i == 0 && self.is_method() {
+                me = quote! { &self, };
+                continue;
+            }
+            let name = import_arg_name(i, arg)?;
+            let ty = &arg.ty;
+            arguments.push(quote! { #name: #ty });
+        }
+        let ret = self.rust_ret();
+        let rust_name = &self.rust_name;
+        let doc_comment = match &self.doc_comment {
+            None => "",
+            Some(doc_string) => doc_string,
+        };
+        Ok(quote! {
+            #[allow(bad_style)]
+            #[doc = #doc_comment]
+            fn #rust_name(#me #(#arguments),*) #ret;
+        })
+    }
+
+    /// Returns the return type of the Rust function calling this import,
+    /// which is a future for `async` imports.
+    fn rust_ret(&self) -> TokenStream {
+        match &self.function.ret {
+            Some(ty) if self.function.r#async => quote! {
+                -> impl wasm_bindgen::__rt::core::future::Future<Output = #ty>
+            },
+            None if self.function.r#async => quote! {
+                -> impl wasm_bindgen::__rt::core::future::Future<Output = ()>
+            },
+            Some(ty) => quote! { -> #ty },
+            None => quote!(),
+        }
+    }
+
+    fn is_method(&self) -> bool {
+        match &self.kind {
+            ast::ImportFunctionKind::Method {
+                kind:
+                    ast::MethodKind::Operation(ast::Operation {
+                        is_static: false, ..
+                    }),
+                ..
+            } => true,
+            _ => false,
+        }
+    }
+}
+
+fn import_arg_name(i: usize, arg: &syn::PatType) -> Result<Ident, Diagnostic> {
+    match &*arg.pat {
+        syn::Pat::Ident(syn::PatIdent {
+            by_ref: None,
+            ident,
+            subpat: None,
+            ..
+        }) => Ok(ident.clone()),
+        syn::Pat::Wild(_) => Ok(syn::Ident::new(
+            &format!("__genarg_{}", i),
+            Span::call_site(),
+        )),
+        _ => bail_span!(
+            arg.pat,
+            "unsupported pattern in #[wasm_bindgen] imported function",
+        ),
+    }
+}
+
+/// Returns whether `ty` is a stack closure taking its only argument by
+/// reference, like `&dyn Fn(&str)` or `&mut dyn FnMut(&mut [u8])`.
+///
+/// These are passed to imports through `RefArgStackClosure` rather than
+/// `IntoWasmAbi` and `WasmDescribe`, whose impls for them would overlap the
+/// ones for closures taking their argument by value.
+fn is_ref_arg_stack_closure(ty: &syn::Type) -> bool {
+    let mut elem = match ty {
+        syn::Type::Reference(r) => &*r.elem,
+        _ => return false,
+    };
+    loop {
+        elem = match elem {
+            syn::Type::Paren(p) => &*p.elem,
+            syn::Type::Group(g) => &*g.elem,
+            _ => break,
+        };
+    }
+    let path = match elem {
+        syn::Type::TraitObject(t) => match t.bounds.first() {
+            Some(syn::TypeParamBound::Trait(bound)) => &bound.path,
+            _ => return false,
+        },
+        syn::Type::Path(p) if p.qself.is_none() => &p.path,
+        _ => return false,
+    };
+    let segment = match path.segments.last() {
+        Some(segment) => segment,
+        None => return false,
+    };
+    let arg = match &segment.arguments {
+        syn::PathArguments::Parenthesized(args)
+            if segment.ident == "Fn" || segment.ident == "FnMut" =>
+        {
+            if args.inputs.len() != 1 {
+                return false;
+            }
+            &args.inputs[0]
+        }
+        syn::PathArguments::AngleBracketed(args) if segment.ident == "StackFnOnce" => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(syn::Type::Tuple(t))) if t.elems.len() == 1 => {
+                    &t.elems[0]
                 }
-            })
-            .to_tokens(tokens);
-        } else {
-            invocation.to_tokens(tokens);
+                _ => return false,
+            }
         }
+        _ => return false,
+    };
+    match arg {
+        syn::Type::Reference(_) => true,
+        _ => false,
+    }
+}
 
-        Ok(())
+/// Emits the trait declarations and implementations for all imported methods
+/// with an `extension_trait`, merging methods from every `extern` block in the
+/// crate which name the same trait.
+fn extension_traits(imports: &[ast::Import], tokens: &mut TokenStream) -> Result<(), Diagnostic> {
+    let mut traits: Vec<(
+        &Ident,
+        &syn::Visibility,
+        &syn::Type,
+        Vec<TokenStream>,
+        Vec<TokenStream>,
+    )> = Vec::new();
+    for i in imports {
+        let f = match &i.kind {
+            ast::ImportKind::Function(f) => f,
+            _ => continue,
+        };
+        let (name, ty) = match (&f.extension_trait, &f.kind) {
+            (Some(name), ast::ImportFunctionKind::Method { ty, .. }) => (name, ty),
+            _ => continue,
+        };
+        let signature = f.extension_trait_signature()?;
+        let invocation = f.invocation()?;
+        match traits.iter_mut().find(|t| t.0 == name) {
+            Some(t) => {
+                if t.2.to_token_stream().to_string() != ty.to_token_stream().to_string() {
+                    bail_span!(
+                        ty,
+                        "all methods of the extension trait `{}` must be methods \
+                         of the same imported type",
+                        name,
+                    );
+                }
+                t.3.push(signature);
+                t.4.push(invocation);
+            }
+            None => traits.push((
+                name,
+                &f.function.rust_vis,
+                ty,
+                vec![signature],
+                vec![invocation],
+            )),
+        }
+    }
+    for (name, vis, class, signatures, invocations) in traits {
+        (quote! {
+            #vis trait #name {
+                #(#signatures)*
+            }
+
+            impl #name for #class {
+                #(#invocations)*
+            }
+        })
+        .to_tokens(tokens);
     }
+    Ok(())
 }
 
 // See comment above in ast::Export for what's going on here.
@@ -1069,10 +1557,21 @@ impl<'a> ToTokens for DescribeImport<'a> {
             ast::ImportKind::Static(_) => return,
             ast::ImportKind::Type(_) => return,
             ast::ImportKind::Enum(_) => return,
+            ast::ImportKind::Property(_) => return,
         };
-        let argtys = f.function.arguments.iter().map(|arg| &arg.ty);
+        let inform_args = f.function.arguments.iter().map(|arg| {
+            let ty = &arg.ty;
+            if is_ref_arg_stack_closure(ty) {
+                quote! { <#ty as wasm_bindgen::convert::RefArgStackClosure>::describe(); }
+            } else {
+                quote! { <#ty as WasmDescribe>::describe(); }
+            }
+        });
         let nargs = f.function.arguments.len() as u32;
         let inform_ret = match &f.js_ret {
+            _ if f.function.r#async => quote! {
+                <wasm_bindgen::JsValue as WasmDescribe>::describe();
+            },
             Some(ref t) => quote! { <#t as WasmDescribe>::describe(); },
             None => quote! { <() as WasmDescribe>::describe(); },
         };
@@ -1083,7 +1582,7 @@ impl<'a> ToTokens for DescribeImport<'a> {
                 inform(FUNCTION);
                 inform(0);
                 inform(#nargs);
-                #(<#argtys as WasmDescribe>::describe();)*
+                #(#inform_args)*
                 #inform_ret
             },
         )
@@ -1093,6 +1592,9 @@ impl<'a> ToTokens for DescribeImport<'a> {
 
 impl ToTokens for ast::Enum {
     fn to_tokens(&self, into: &mut TokenStream) {
+        if let ast::EnumRepr::String = self.repr {
+            return self.string_repr_to_tokens(into);
+        }
         let enum_name = &self.name;
         let hole = &self.hole;
         let cast_clauses = self.variants.iter().map(|variant| {
@@ -1151,6 +1653,67 @@ impl ToTokens for ast::Enum {
     }
 }
 
+impl ast::Enum {
+    /// Enums with `js_repr = string` are passed to and from JS as the names of
+    /// their variants rather than as numbers.
+    fn string_repr_to_tokens(&self, into: &mut TokenStream) {
+        let enum_name = &self.name;
+        let variants = self.variants.iter().map(|v| &v.name).collect::<Vec<_>>();
+        let names = variants.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let variants = &variants;
+        let names = &names;
+        (quote! {
+            #[allow(clippy::all)]
+            impl wasm_bindgen::convert::IntoWasmAbi for #enum_name {
+                type Abi = <wasm_bindgen::JsValue as
+                    wasm_bindgen::convert::IntoWasmAbi>::Abi;
+
+                #[inline]
+                fn into_abi(self) -> Self::Abi {
+                    let name = match self {
+                        #(#enum_name::#variants => #names,)*
+                    };
+                    wasm_bindgen::JsValue::from_str(name).into_abi()
+                }
+            }
+
+            #[allow(clippy::all)]
+            impl wasm_bindgen::convert::FromWasmAbi for #enum_name {
+                type Abi = <wasm_bindgen::JsValue as
+                    wasm_bindgen::convert::FromWasmAbi>::Abi;
+
+                unsafe fn from_abi(js: Self::Abi) -> Self {
+                    let js = wasm_bindgen::JsValue::from_abi(js);
+                    match js.as_string().as_ref().map(|s| s.as_str()) {
+                        #(Some(#names) => #enum_name::#variants,)*
+                        _ => wasm_bindgen::throw_str("invalid enum value passed"),
+                    }
+                }
+            }
+
+            #[allow(clippy::all)]
+            impl wasm_bindgen::convert::OptionFromWasmAbi for #enum_name {
+                #[inline]
+                fn is_none(abi: &Self::Abi) -> bool { *abi == 0 }
+            }
+
+            #[allow(clippy::all)]
+            impl wasm_bindgen::convert::OptionIntoWasmAbi for #enum_name {
+                #[inline]
+                fn none() -> Self::Abi { 0 }
+            }
+
+            #[allow(clippy::all)]
+            impl wasm_bindgen::describe::WasmDescribe for #enum_name {
+                fn describe() {
+                    <wasm_bindgen::JsValue as wasm_bindgen::describe::WasmDescribe>::describe()
+                }
+            }
+        })
+        .to_tokens(into);
+    }
+}
+
 impl ToTokens for ast::ImportStatic {
     fn to_tokens(&self, into: &mut TokenStream) {
         let name = &self.rust_name;
@@ -1163,11 +1726,11 @@ impl ToTokens for ast::ImportStatic {
             #vis static #name: wasm_bindgen::JsStatic<#ty> = {
                 fn init() -> #ty {
                     #[link(wasm_import_module = "__wbindgen_placeholder__")]
-                    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+                    #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
                     extern "C" {
                         fn #shim_name() -> <#ty as wasm_bindgen::convert::FromWasmAbi>::Abi;
                     }
-                    #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+                    #[cfg(not(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten"))))]
                     unsafe fn #shim_name() -> <#ty as wasm_bindgen::convert::FromWasmAbi>::Abi {
                         panic!("cannot access imported statics on non-wasm targets")
                     }
@@ -1194,6 +1757,31 @@ impl ToTokens for ast::ImportStatic {
     }
 }
 
+impl ToTokens for ast::ImportProperty {
+    fn to_tokens(&self, into: &mut TokenStream) {
+        let vis = &self.vis;
+        let rust_name = &self.rust_name;
+        let js_name = &self.js_name;
+        let class = &self.class;
+        let ty = &self.ty;
+        let attrs = &self.rust_attrs;
+        (quote! {
+            #[allow(bad_style)]
+            #[allow(clippy::all)]
+            impl #class {
+                #(#attrs)*
+                #vis fn #rust_name(&self) -> #ty {
+                    wasm_bindgen::Property::new(
+                        wasm_bindgen::__rt::core::convert::AsRef::<wasm_bindgen::JsValue>::as_ref(self),
+                        #js_name,
+                    )
+                }
+            }
+        })
+        .to_tokens(into);
+    }
+}
+
 impl ToTokens for ast::Const {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         use crate::ast::ConstValue::*;
@@ -1460,7 +2048,7 @@ impl<'a, T: ToTokens> ToTokens for Descriptor<'a, T> {
             #[no_mangle]
             #[allow(non_snake_case)]
             #[doc(hidden)]
-            #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+            #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
             #[allow(clippy::all)]
             pub extern "C" fn #name() {
                 use wasm_bindgen::describe::*;
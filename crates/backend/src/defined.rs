@@ -130,6 +130,7 @@ impl ImportedTypes for ast::ImportKind {
             ast::ImportKind::Function(fun) => fun.imported_types(f),
             ast::ImportKind::Type(ty) => ty.imported_types(f),
             ast::ImportKind::Enum(enm) => enm.imported_types(f),
+            ast::ImportKind::Property(p) => p.imported_types(f),
         }
     }
 }
@@ -143,6 +144,16 @@ impl ImportedTypes for ast::ImportStatic {
     }
 }
 
+impl ImportedTypes for ast::ImportProperty {
+    fn imported_types<F>(&self, f: &mut F)
+    where
+        F: FnMut(&Ident, ImportedTypeKind),
+    {
+        self.class.imported_types(f);
+        self.ty.imported_types(f);
+    }
+}
+
 impl ImportedTypes for syn::Type {
     fn imported_types<F>(&self, f: &mut F)
     where
@@ -24,6 +24,7 @@ pub fn encode(program: &ast::Program) -> Result<EncodeResult, Diagnostic> {
         .borrow()
         .values()
         .map(|p| &p.path)
+        .chain(i.prelude_files.borrow().iter())
         .cloned()
         .collect();
     Ok(EncodeResult {
@@ -35,6 +36,7 @@ pub fn encode(program: &ast::Program) -> Result<EncodeResult, Diagnostic> {
 struct Interner {
     bump: bumpalo::Bump,
     files: RefCell<HashMap<String, LocalFile>>,
+    prelude_files: RefCell<Vec<PathBuf>>,
     root: PathBuf,
     crate_name: String,
     has_package_json: Cell<bool>,
@@ -51,6 +53,7 @@ impl Interner {
         Interner {
             bump: bumpalo::Bump::new(),
             files: RefCell::new(HashMap::new()),
+            prelude_files: RefCell::new(Vec::new()),
             root: env::var_os("CARGO_MANIFEST_DIR").unwrap().into(),
             crate_name: env::var("CARGO_PKG_NAME").unwrap(),
             has_package_json: Cell::new(false),
@@ -101,6 +104,18 @@ impl Interner {
         self.resolve_import_module(id, span)
     }
 
+    /// Reads the contents of a local file given to `js_prelude!`, which is
+    /// inlined into the generated JS rather than copied alongside it.
+    fn read_prelude_file(&self, id: &str, span: Span) -> Result<&str, Diagnostic> {
+        let path = self.root.join(&id[1..]);
+        let contents = fs::read_to_string(&path).map_err(|e| {
+            let msg = format!("failed to read file `{}`: {}", path.display(), e);
+            Diagnostic::span_error(span, msg)
+        })?;
+        self.prelude_files.borrow_mut().push(path);
+        Ok(self.intern_str(&contents))
+    }
+
     fn unique_crate_identifier(&self) -> String {
         format!("{}-{}", self.crate_name, ShortHash(0))
     }
@@ -120,6 +135,18 @@ fn shared_program<'a>(
     prog: &'a ast::Program,
     intern: &'a Interner,
 ) -> Result<Program<'a>, Diagnostic> {
+    // Linked modules are resolved first as they register the local files which
+    // are included in `local_modules` below.
+    let linked_modules = prog
+        .linked_modules
+        .iter()
+        .map(|m| {
+            Ok(LinkedModule {
+                identifier: intern.resolve_import_module(&m.path, m.span)?,
+                link_function_name: intern.intern(&m.link_function_name),
+            })
+        })
+        .collect::<Result<Vec<_>, Diagnostic>>()?;
     Ok(Program {
         exports: prog
             .exports
@@ -135,6 +162,12 @@ fn shared_program<'a>(
         imports: prog
             .imports
             .iter()
+            // Property handles are implemented entirely in Rust, so there's
+            // nothing for the CLI to generate for them.
+            .filter(|a| match a.kind {
+                ast::ImportKind::Property(_) => false,
+                _ => true,
+            })
             .map(|a| shared_import(a, intern))
             .collect::<Result<Vec<_>, _>>()?,
         typescript_custom_sections: prog
@@ -163,6 +196,16 @@ fn shared_program<'a>(
             .iter()
             .map(|js| intern.intern_str(js))
             .collect(),
+        linked_modules,
+        js_preludes: prog
+            .js_preludes
+            .iter()
+            .map(|prelude| match prelude {
+                ast::JsPrelude::Inline(js) => Ok(intern.intern_str(js)),
+                ast::JsPrelude::Module { path, span } => intern.read_prelude_file(path, *span),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        consts: prog.exported_consts.iter().map(shared_const).collect(),
         unique_crate_identifier: intern.intern_str(&intern.unique_crate_identifier()),
         package_json: if intern.has_package_json.get() {
             Some(intern.intern_str(intern.root.join("package.json").to_str().unwrap()))
@@ -181,13 +224,36 @@ fn shared_export<'a>(
         _ => false,
     };
     let method_kind = from_ast_method_kind(&export.function, intern, &export.method_kind)?;
+    let mut function = shared_function(&export.function, intern);
+    // A name given with `getter = ...` or `setter = ...` is as explicit as one
+    // given with `js_name`.
+    if let ast::MethodKind::Operation(op) = &export.method_kind {
+        match op.kind {
+            ast::OperationKind::Getter(Some(_)) | ast::OperationKind::Setter(Some(_)) => {
+                function.renamed_via_js_name = true;
+            }
+            _ => {}
+        }
+    }
     Ok(Export {
         class: export.js_class.as_ref().map(|s| &**s),
+        js_namespace: export.js_namespace.as_ref().map(|s| intern.intern(s)),
         comments: export.comments.iter().map(|s| &**s).collect(),
+        rust_name: intern.intern_str(&export.method_name()),
         consumed,
-        function: shared_function(&export.function, intern),
+        function,
         method_kind,
         start: export.start,
+        iterator: export.iterator,
+        cached: export.cached,
+        observed_attribute: export.observed_attribute.as_ref().map(|s| &**s),
+        stability: match &export.stability {
+            ast::Stability::Stable => Stability::Stable,
+            ast::Stability::Unstable => Stability::Unstable,
+            ast::Stability::Deprecated(note) => Stability::Deprecated(note.as_ref().map(|s| &**s)),
+        },
+        no_copy: export.no_copy,
+        cfg_feature: export.cfg_feature.as_ref().map(|s| &**s),
     })
 }
 
@@ -206,6 +272,7 @@ fn shared_function<'a>(func: &'a ast::Function, _intern: &'a Interner) -> Functi
     Function {
         arg_names,
         name: &func.name,
+        renamed_via_js_name: func.renamed_via_js_name,
     }
 }
 
@@ -218,6 +285,24 @@ fn shared_enum<'a>(e: &'a ast::Enum, intern: &'a Interner) -> Enum<'a> {
             .map(|v| shared_variant(v, intern))
             .collect(),
         comments: e.comments.iter().map(|s| &**s).collect(),
+        cfg_feature: e.cfg_feature.as_ref().map(|s| &**s),
+        repr: match e.repr {
+            ast::EnumRepr::Number => EnumRepr::Number,
+            ast::EnumRepr::String => EnumRepr::String,
+            ast::EnumRepr::Both => EnumRepr::Both,
+        },
+    }
+}
+
+fn shared_const(c: &ast::ExportedConst) -> Const<'_> {
+    Const {
+        name: &c.js_name,
+        value: match &c.value {
+            ast::ExportedConstValue::String(s) => ConstValue::String(s),
+            ast::ExportedConstValue::Boolean(b) => ConstValue::Boolean(*b),
+            ast::ExportedConstValue::Number(n) => ConstValue::Number(n),
+        },
+        comments: c.comments.iter().map(|s| &**s).collect(),
     }
 }
 
@@ -252,6 +337,7 @@ fn shared_import_kind<'a>(
         ast::ImportKind::Static(f) => ImportKind::Static(shared_import_static(f, intern)),
         ast::ImportKind::Type(f) => ImportKind::Type(shared_import_type(f, intern)),
         ast::ImportKind::Enum(f) => ImportKind::Enum(shared_import_enum(f, intern)),
+        ast::ImportKind::Property(_) => unreachable!(),
     })
 }
 
@@ -307,6 +393,9 @@ fn shared_struct<'a>(s: &'a ast::Struct, intern: &'a Interner) -> Struct<'a> {
             .collect(),
         comments: s.comments.iter().map(|s| &**s).collect(),
         is_inspectable: s.is_inspectable,
+        cfg_feature: s.cfg_feature.as_ref().map(|s| &**s),
+        extends_js: s.extends_js.as_ref().map(|s| &**s),
+        custom_element: s.custom_element.as_ref().map(|s| &**s),
     }
 }
 
@@ -317,6 +406,7 @@ fn shared_struct_field<'a>(s: &'a ast::StructField, intern: &'a Interner) -> Str
             syn::Member::Unnamed(index) => intern.intern_str(&index.index.to_string()),
         },
         readonly: s.readonly,
+        cached: s.cached,
         comments: s.comments.iter().map(|s| &**s).collect(),
     }
 }
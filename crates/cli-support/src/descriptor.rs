@@ -37,9 +37,13 @@ tys! {
     OPTIONAL
     UNIT
     CLAMPED
+    I128
+    U128
+    PROMISE
+    TUPLE
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Descriptor {
     I8,
     U8,
@@ -50,6 +54,8 @@ pub enum Descriptor {
     U32,
     I64,
     U64,
+    I128,
+    U128,
     F32,
     F64,
     Boolean,
@@ -67,16 +73,18 @@ pub enum Descriptor {
     Char,
     Option(Box<Descriptor>),
     Unit,
+    Promise(Box<Descriptor>),
+    Tuple(Vec<Descriptor>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Function {
     pub arguments: Vec<Descriptor>,
     pub shim_idx: u32,
     pub ret: Descriptor,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Closure {
     pub shim_idx: u32,
     pub dtor_idx: u32,
@@ -119,6 +127,8 @@ impl Descriptor {
             U16 => Descriptor::U16,
             U32 => Descriptor::U32,
             U64 => Descriptor::U64,
+            I128 => Descriptor::I128,
+            U128 => Descriptor::U128,
             F32 => Descriptor::F32,
             F64 => Descriptor::F64,
             BOOLEAN => Descriptor::Boolean,
@@ -141,6 +151,13 @@ impl Descriptor {
             }
             CHAR => Descriptor::Char,
             UNIT => Descriptor::Unit,
+            PROMISE => Descriptor::Promise(Box::new(Descriptor::_decode(data, clamped))),
+            TUPLE => {
+                let elements = (0..get(data))
+                    .map(|_| Descriptor::_decode(data, clamped))
+                    .collect();
+                Descriptor::Tuple(elements)
+            }
             CLAMPED => Descriptor::_decode(data, true),
             other => panic!("unknown descriptor: {}", other),
         }
@@ -160,6 +177,50 @@ impl Descriptor {
         }
     }
 
+    /// Returns a rendering of this type similar to the Rust type it was
+    /// described by, for use in diagnostics.
+    pub fn rust_name(&self) -> String {
+        match self {
+            Descriptor::I8 => "i8".to_string(),
+            Descriptor::U8 | Descriptor::ClampedU8 => "u8".to_string(),
+            Descriptor::I16 => "i16".to_string(),
+            Descriptor::U16 => "u16".to_string(),
+            Descriptor::I32 => "i32".to_string(),
+            Descriptor::U32 => "u32".to_string(),
+            Descriptor::I64 => "i64".to_string(),
+            Descriptor::U64 => "u64".to_string(),
+            Descriptor::I128 => "i128".to_string(),
+            Descriptor::U128 => "u128".to_string(),
+            Descriptor::F32 => "f32".to_string(),
+            Descriptor::F64 => "f64".to_string(),
+            Descriptor::Boolean => "bool".to_string(),
+            Descriptor::Char => "char".to_string(),
+            Descriptor::Unit => "()".to_string(),
+            Descriptor::String | Descriptor::CachedString => "String".to_string(),
+            Descriptor::Anyref => "JsValue".to_string(),
+            Descriptor::Enum { .. } => "enum".to_string(),
+            Descriptor::RustStruct(name) => name.clone(),
+            Descriptor::Function(f) => f.closure_name(false),
+            Descriptor::Closure(c) => format!("Closure<{}>", c.function.closure_name(c.mutable)),
+            Descriptor::Ref(d) => match &**d {
+                Descriptor::String | Descriptor::CachedString => "&str".to_string(),
+                d => format!("&{}", d.rust_name()),
+            },
+            Descriptor::RefMut(d) => match &**d {
+                Descriptor::Function(f) => format!("&mut {}", f.closure_name(true)),
+                d => format!("&mut {}", d.rust_name()),
+            },
+            Descriptor::Slice(d) => format!("[{}]", d.rust_name()),
+            Descriptor::Vector(d) => format!("Vec<{}>", d.rust_name()),
+            Descriptor::Option(d) => format!("Option<{}>", d.rust_name()),
+            Descriptor::Promise(_) => "Promise".to_string(),
+            Descriptor::Tuple(elements) => {
+                let elements = elements.iter().map(|d| d.rust_name()).collect::<Vec<_>>();
+                format!("({})", elements.join(", "))
+            }
+        }
+    }
+
     pub fn vector_kind(&self) -> Option<VectorKind> {
         let inner = match *self {
             Descriptor::String | Descriptor::CachedString => return Some(VectorKind::String),
@@ -227,6 +288,22 @@ impl Function {
             ret: Descriptor::_decode(data, false),
         }
     }
+
+    /// Returns a rendering of a closure type with this signature, like
+    /// `dyn FnMut(u32) -> String`, for use in diagnostics.
+    pub fn closure_name(&self, mutable: bool) -> String {
+        let args = self
+            .arguments
+            .iter()
+            .map(|arg| arg.rust_name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let kind = if mutable { "FnMut" } else { "Fn" };
+        match self.ret {
+            Descriptor::Unit => format!("dyn {}({})", kind, args),
+            ref ret => format!("dyn {}({}) -> {}", kind, args, ret.rust_name()),
+        }
+    }
 }
 
 impl VectorKind {
@@ -11,7 +11,7 @@
 //! functions.
 
 use crate::descriptor::{Closure, Descriptor};
-use anyhow::Error;
+use anyhow::{anyhow, bail, Error};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use walrus::ImportId;
@@ -22,6 +22,11 @@ use wasm_bindgen_wasm_interpreter::Interpreter;
 pub struct WasmBindgenDescriptorsSection {
     pub descriptors: HashMap<String, Descriptor>,
     pub closure_imports: HashMap<ImportId, Closure>,
+    /// The name of the function creating the closures of each closure
+    /// import, as far as it's known from the name section. Only recorded in
+    /// debug mode, where closures created by different functions get their
+    /// own imports.
+    pub closure_creators: HashMap<ImportId, String>,
 }
 
 pub type WasmBindgenDescriptorsSectionId = TypedCustomSectionId<WasmBindgenDescriptorsSection>;
@@ -30,12 +35,12 @@ pub type WasmBindgenDescriptorsSectionId = TypedCustomSectionId<WasmBindgenDescr
 /// custom section which represents the executed value of each descriptor.
 ///
 /// Afterwards this will delete all descriptor functions from the module.
-pub fn execute(module: &mut Module) -> Result<WasmBindgenDescriptorsSectionId, Error> {
+pub fn execute(module: &mut Module, debug: bool) -> Result<WasmBindgenDescriptorsSectionId, Error> {
     let mut section = WasmBindgenDescriptorsSection::default();
     let mut interpreter = Interpreter::new(module)?;
 
     section.execute_exports(module, &mut interpreter)?;
-    section.execute_closures(module, &mut interpreter)?;
+    section.execute_closures(module, &mut interpreter, debug)?;
 
     Ok(module.customs.add(section))
 }
@@ -74,6 +79,7 @@ impl WasmBindgenDescriptorsSection {
         &mut self,
         module: &mut Module,
         interpreter: &mut Interpreter,
+        debug: bool,
     ) -> Result<(), Error> {
         use walrus::ir::*;
 
@@ -85,22 +91,72 @@ impl WasmBindgenDescriptorsSection {
         };
 
         // Find all functions which call `wbindgen_describe_closure`. These are
-        // specially codegen'd so we know the rough structure of them. For each
-        // one we delegate to the interpreter to figure out the actual result.
+        // specially codegen'd so we know the rough structure of them.
+        //
+        // The descriptor argument is always a constant table index, so when
+        // each call is directly preceded by an `i32.const` we can resolve the
+        // descriptor statically. This is what happens when LTO inlines
+        // `breaks_if_inlined` into its callers (possibly more than once per
+        // function). Otherwise we delegate to the interpreter to figure out the
+        // actual result by executing the function.
         let mut element_removal_list = HashSet::new();
-        let mut func_to_descriptor = HashMap::new();
+        let mut func_to_descriptors = Vec::new();
         for (id, local) in module.funcs.iter_local() {
             let mut find = FindDescribeClosure {
                 wbindgen_describe_closure,
-                found: false,
+                sites: Vec::new(),
             };
             dfs_in_order(&mut find, local, local.entry_block());
-            if find.found {
+            if find.sites.is_empty() {
+                continue;
+            }
+            let name = module
+                .funcs
+                .get(id)
+                .name
+                .as_ref()
+                .map(|s| s.as_str())
+                .unwrap_or("<unknown>");
+
+            let mut descriptors = Vec::new();
+            if find.sites.iter().all(|s| s.is_some()) {
+                for idx in find.sites.iter().filter_map(|s| *s) {
+                    if descriptors.iter().any(|(i, _)| *i == Some(idx)) {
+                        continue;
+                    }
+                    let descriptor = interpreter
+                        .interpret_descriptor_at(idx as usize, module, &mut element_removal_list)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "failed to find closure descriptor at table index {} \
+                                 referenced by `{}`",
+                                idx,
+                                name,
+                            )
+                        })?;
+                    descriptors.push((Some(idx), Descriptor::decode(descriptor)));
+                }
+            } else if find.sites.len() == 1 {
                 let descriptor = interpreter
                     .interpret_closure_descriptor(id, module, &mut element_removal_list)
-                    .unwrap();
-                func_to_descriptor.insert(id, Descriptor::decode(descriptor));
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "failed to find closure descriptor in `{}`; this can \
+                             happen if the closure shim was inlined in an \
+                             unexpected way, try building without LTO",
+                            name,
+                        )
+                    })?;
+                descriptors.push((None, Descriptor::decode(descriptor)));
+            } else {
+                bail!(
+                    "`{}` creates {} closures but their descriptors could not \
+                     be determined statically; try building without LTO",
+                    name,
+                    find.sites.len(),
+                );
             }
+            func_to_descriptors.push((id, descriptors));
         }
 
         // For all indirect functions that were closure descriptors, delete them
@@ -122,14 +178,51 @@ impl WasmBindgenDescriptorsSection {
         }
 
         // And finally replace all calls of `wbindgen_describe_closure` with a
-        // freshly manufactured import. Save off the type of this import in
-        // ourselves, and then we're good to go.
+        // freshly manufactured import, one per distinct closure descriptor.
+        // Every place a closure of the same type is created has the same
+        // invoke shim, destructor and mutability, so they all share one import
+        // and therefore one JS factory. Note that the whole descriptor is
+        // compared since identical functions may be merged in release builds,
+        // so closures of different types can still share a shim or
+        // destructor. In debug mode closures created by different functions
+        // get their own imports though, so their JS functions can be named
+        // after the function creating them. Save off the type of this import
+        // in ourselves, and then we're good to go.
         let ty = module.funcs.get(wbindgen_describe_closure).ty();
-        for (func, descriptor) in func_to_descriptor {
-            let import_name = format!("__wbindgen_closure_wrapper{}", func.index());
-            let (id, import_id) =
-                module.add_import_func("__wbindgen_placeholder__", &import_name, ty);
-            module.funcs.get_mut(id).name = Some(import_name);
+        let mut shared = HashMap::new();
+        let mut callers = None;
+        for (func, descriptors) in func_to_descriptors {
+            let creator = if debug {
+                closure_creator(module, &mut callers, func)
+            } else {
+                None
+            };
+            let mut replacements = Vec::new();
+            for (idx, descriptor) in descriptors {
+                let closure = descriptor.unwrap_closure();
+                let key = (closure, creator.clone());
+                let (id, import_id) = match shared.get(&key) {
+                    Some(pair) => *pair,
+                    None => {
+                        let import_name = match idx {
+                            Some(idx) => {
+                                format!("__wbindgen_closure_wrapper{}_{}", func.index(), idx)
+                            }
+                            None => format!("__wbindgen_closure_wrapper{}", func.index()),
+                        };
+                        let (id, import_id) =
+                            module.add_import_func("__wbindgen_placeholder__", &import_name, ty);
+                        module.funcs.get_mut(id).name = Some(import_name);
+                        self.closure_imports.insert(import_id, key.0.clone());
+                        if let Some(creator) = &creator {
+                            self.closure_creators.insert(import_id, creator.clone());
+                        }
+                        shared.insert(key, (id, import_id));
+                        (id, import_id)
+                    }
+                };
+                replacements.push((idx.map(|i| i as i32), id));
+            }
 
             let local = match &mut module.funcs.get_mut(func).kind {
                 walrus::FunctionKind::Local(l) => l,
@@ -139,38 +232,121 @@ impl WasmBindgenDescriptorsSection {
             dfs_pre_order_mut(
                 &mut UpdateDescribeClosure {
                     wbindgen_describe_closure,
-                    replacement: id,
+                    replacements,
                 },
                 local,
                 entry,
             );
-            self.closure_imports
-                .insert(import_id, descriptor.unwrap_closure());
         }
         return Ok(());
 
+        /// Returns the name of the function creating closures by calling
+        /// `func`. Functions of wasm-bindgen itself, like `Closure::wrap`, are
+        /// looked through to find the function calling them, as long as
+        /// there's only one.
+        fn closure_creator(
+            module: &Module,
+            callers: &mut Option<HashMap<FunctionId, Vec<FunctionId>>>,
+            mut func: FunctionId,
+        ) -> Option<String> {
+            // Give up on long chains, which also protects against recursion.
+            for _ in 0..8 {
+                let name = module.funcs.get(func).name.as_ref()?;
+                if !name.starts_with("wasm_bindgen::") && !name.starts_with("<wasm_bindgen::") {
+                    return Some(strip_hash(name).to_string());
+                }
+                let callers = callers.get_or_insert_with(|| {
+                    let mut find = FindCallers {
+                        caller: func,
+                        callers: HashMap::new(),
+                    };
+                    for (id, local) in module.funcs.iter_local() {
+                        find.caller = id;
+                        dfs_in_order(&mut find, local, local.entry_block());
+                    }
+                    find.callers
+                });
+                match callers.get(&func).map(|c| &c[..]) {
+                    Some([caller]) => func = *caller,
+                    _ => return None,
+                }
+            }
+            None
+        }
+
+        /// Strips the hash from the end of a demangled legacy symbol name.
+        fn strip_hash(name: &str) -> &str {
+            match name.rfind("::h") {
+                Some(i) if name[i + 3..].chars().all(|c| c.is_ascii_hexdigit()) => &name[..i],
+                _ => name,
+            }
+        }
+
+        struct FindCallers {
+            caller: FunctionId,
+            callers: HashMap<FunctionId, Vec<FunctionId>>,
+        }
+
+        impl<'a> Visitor<'a> for FindCallers {
+            fn visit_call(&mut self, call: &Call) {
+                let callers = self.callers.entry(call.func).or_insert_with(Vec::new);
+                if !callers.contains(&self.caller) {
+                    callers.push(self.caller);
+                }
+            }
+        }
+
+        /// Returns the constant table index passed as the descriptor argument
+        /// of the call at `i` in `instrs`, if it's immediately preceded by one.
+        fn const_descriptor_arg(instrs: &[(Instr, InstrLocId)], i: usize) -> Option<i32> {
+            match instrs.get(i.checked_sub(1)?)?.0 {
+                Instr::Const(Const {
+                    value: Value::I32(n),
+                }) => Some(n),
+                _ => None,
+            }
+        }
+
         struct FindDescribeClosure {
             wbindgen_describe_closure: FunctionId,
-            found: bool,
+            sites: Vec<Option<u32>>,
         }
 
         impl<'a> Visitor<'a> for FindDescribeClosure {
-            fn visit_call(&mut self, call: &Call) {
-                if call.func == self.wbindgen_describe_closure {
-                    self.found = true;
+            fn start_instr_seq(&mut self, seq: &'a InstrSeq) {
+                for (i, (instr, _)) in seq.instrs.iter().enumerate() {
+                    match instr {
+                        Instr::Call(call) if call.func == self.wbindgen_describe_closure => {
+                            let idx = const_descriptor_arg(&seq.instrs, i).map(|n| n as u32);
+                            self.sites.push(idx);
+                        }
+                        _ => {}
+                    }
                 }
             }
         }
 
         struct UpdateDescribeClosure {
             wbindgen_describe_closure: FunctionId,
-            replacement: FunctionId,
+            replacements: Vec<(Option<i32>, FunctionId)>,
         }
 
-        impl<'a> VisitorMut for UpdateDescribeClosure {
-            fn visit_call_mut(&mut self, call: &mut Call) {
-                if call.func == self.wbindgen_describe_closure {
-                    call.func = self.replacement;
+        impl VisitorMut for UpdateDescribeClosure {
+            fn start_instr_seq_mut(&mut self, seq: &mut InstrSeq) {
+                for i in 0..seq.instrs.len() {
+                    let func = match &seq.instrs[i].0 {
+                        Instr::Call(call) if call.func == self.wbindgen_describe_closure => {
+                            let idx = const_descriptor_arg(&seq.instrs, i);
+                            self.replacements
+                                .iter()
+                                .find(|(i, _)| i.is_none() || *i == idx)
+                                .map(|(_, f)| *f)
+                        }
+                        _ => None,
+                    };
+                    if let (Some(func), Instr::Call(call)) = (func, &mut seq.instrs[i].0) {
+                        call.func = func;
+                    }
                 }
             }
         }
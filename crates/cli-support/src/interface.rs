@@ -0,0 +1,75 @@
+//! Support for describing the JS-facing interface of a module and checking it
+//! against a previously recorded description, used by `--write-interface` and
+//! `--check-interface`.
+//!
+//! The description is a JSON object listing each export along with its
+//! TypeScript signature, and each JS module imported along with the names
+//! imported from it.
+
+use anyhow::{bail, Context, Error};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Renders the interface description for the given exports and imports.
+pub fn describe(
+    exports: &BTreeMap<String, String>,
+    imports: &BTreeMap<String, Vec<String>>,
+) -> Result<String, Error> {
+    let description = serde_json::json!({
+        "exports": exports,
+        "imports": imports,
+    });
+    let mut json = serde_json::to_string_pretty(&description)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Checks that the interface description `actual` matches the one recorded in
+/// the file at `path`, returning an error listing every difference if not.
+pub fn check(path: &Path, actual: &str) -> Result<(), Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read interface file `{}`", path.display()))?;
+    let expected: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse interface file `{}`", path.display()))?;
+    let actual: Value = serde_json::from_str(actual)?;
+
+    let mut diff = Vec::new();
+    for section in ["exports", "imports"].iter() {
+        let empty = Map::new();
+        let expected = expected
+            .get(section)
+            .and_then(|v| v.as_object())
+            .unwrap_or(&empty);
+        let actual = actual
+            .get(section)
+            .and_then(|v| v.as_object())
+            .unwrap_or(&empty);
+        let item = &section[..section.len() - 1];
+        for (name, old) in expected.iter() {
+            match actual.get(name) {
+                None => diff.push(format!("- removed {} `{}`: {}", item, name, old)),
+                Some(new) if new != old => {
+                    diff.push(format!("~ changed {} `{}`: {} -> {}", item, name, old, new))
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, new) in actual.iter() {
+            if !expected.contains_key(name) {
+                diff.push(format!("+ added {} `{}`: {}", item, name, new));
+            }
+        }
+    }
+
+    if diff.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "the interface of this module doesn't match `{}`:\n\n  {}\n\n\
+         if this change is intended then update the file with `--write-interface`",
+        path.display(),
+        diff.join("\n  "),
+    )
+}
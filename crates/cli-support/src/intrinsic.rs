@@ -71,6 +71,18 @@ fn ref_string() -> Descriptor {
     Descriptor::Ref(Box::new(Descriptor::String))
 }
 
+fn ref_cached_string() -> Descriptor {
+    Descriptor::Ref(Box::new(Descriptor::CachedString))
+}
+
+fn ref_u32_slice() -> Descriptor {
+    Descriptor::Ref(Box::new(Descriptor::Slice(Box::new(Descriptor::U32))))
+}
+
+fn ref_mut_u8_slice() -> Descriptor {
+    Descriptor::RefMut(Box::new(Descriptor::Slice(Box::new(Descriptor::U8))))
+}
+
 fn opt_string() -> Descriptor {
     Descriptor::Option(Box::new(Descriptor::String))
 }
@@ -79,6 +91,10 @@ fn opt_f64() -> Descriptor {
     Descriptor::Option(Box::new(Descriptor::F64))
 }
 
+fn opt_u16_vector() -> Descriptor {
+    Descriptor::Option(Box::new(Descriptor::Vector(Box::new(Descriptor::U16))))
+}
+
 intrinsics! {
     pub enum Intrinsic {
         #[symbol = "__wbindgen_jsval_eq"]
@@ -117,6 +133,27 @@ intrinsics! {
         #[symbol = "__wbindgen_cb_forget"]
         #[signature = fn(Anyref) -> Unit]
         CallbackForget,
+        #[symbol = "__wbindgen_cb_forget_weak"]
+        #[signature = fn(Anyref) -> Unit]
+        CallbackForgetWeak,
+        #[symbol = "__wbindgen_queue_microtask"]
+        #[signature = fn(ref_anyref()) -> Unit]
+        QueueMicrotask,
+        #[symbol = "__wbindgen_queue_macrotask"]
+        #[signature = fn(ref_anyref()) -> Unit]
+        QueueMacrotask,
+        #[symbol = "__wbindgen_promise_then"]
+        #[signature = fn(ref_anyref(), ref_anyref(), ref_anyref()) -> Unit]
+        PromiseThen,
+        #[symbol = "__wbindgen_performance_now"]
+        #[signature = fn() -> F64]
+        PerformanceNow,
+        #[symbol = "__wbindgen_date_now"]
+        #[signature = fn() -> F64]
+        DateNow,
+        #[symbol = "__wbindgen_random_fill"]
+        #[signature = fn(ref_mut_u8_slice()) -> Anyref]
+        RandomFill,
         #[symbol = "__wbindgen_number_new"]
         #[signature = fn(F64) -> Anyref]
         NumberNew,
@@ -135,6 +172,12 @@ intrinsics! {
         #[symbol = "__wbindgen_string_get"]
         #[signature = fn(ref_anyref()) -> opt_string()]
         StringGet,
+        #[symbol = "__wbindgen_bigint_get_as_string"]
+        #[signature = fn(ref_anyref()) -> opt_string()]
+        BigIntGetAsString,
+        #[symbol = "__wbindgen_string_get_utf16"]
+        #[signature = fn(ref_anyref()) -> opt_u16_vector()]
+        StringGetUtf16,
         #[symbol = "__wbindgen_boolean_get"]
         #[signature = fn(ref_anyref()) -> I32]
         BooleanGet,
@@ -144,6 +187,12 @@ intrinsics! {
         #[symbol = "__wbindgen_rethrow"]
         #[signature = fn(Anyref) -> Unit]
         Rethrow,
+        #[symbol = "__wbindgen_try_catch"]
+        #[signature = fn(ref_anyref(), ref_anyref(), ref_anyref()) -> Anyref]
+        TryCatch,
+        #[symbol = "__wbindgen_function_call"]
+        #[signature = fn(ref_anyref(), Vector(Box::new(Anyref))) -> Anyref]
+        FunctionCall,
         #[symbol = "__wbindgen_memory"]
         #[signature = fn() -> Anyref]
         Memory,
@@ -156,6 +205,24 @@ intrinsics! {
         #[symbol = "__wbindgen_debug_string"]
         #[signature = fn(ref_anyref()) -> String]
         DebugString,
+        #[symbol = "__wbindgen_object_id"]
+        #[signature = fn(ref_anyref()) -> I32]
+        ObjectId,
+        #[symbol = "__wbindgen_weak_ref_new"]
+        #[signature = fn(ref_anyref()) -> Anyref]
+        WeakRefNew,
+        #[symbol = "__wbindgen_weak_ref_deref"]
+        #[signature = fn(ref_anyref()) -> Anyref]
+        WeakRefDeref,
+        #[symbol = "__wbindgen_object_get"]
+        #[signature = fn(ref_anyref(), ref_cached_string()) -> Anyref]
+        ObjectGet,
+        #[symbol = "__wbindgen_object_set"]
+        #[signature = fn(ref_anyref(), ref_cached_string(), Anyref) -> Unit]
+        ObjectSet,
+        #[symbol = "__wbindgen_object_get_path"]
+        #[signature = fn(ref_anyref(), ref_string(), ref_u32_slice()) -> Anyref]
+        ObjectGetPath,
         #[symbol = "__wbindgen_json_parse"]
         #[signature = fn(ref_string()) -> Anyref]
         JsonParse,
@@ -4,7 +4,7 @@
 //! exported functions, table elements, imports, etc. All function shims
 //! generated by `wasm-bindgen` run through this type.
 
-use crate::js::Context;
+use crate::js::{generate_identifier, Context};
 use crate::wit::InstructionData;
 use crate::wit::{Adapter, AdapterId, AdapterKind, AdapterType, Instruction};
 use anyhow::{anyhow, bail, Error};
@@ -30,6 +30,15 @@ pub struct Builder<'a, 'b> {
     catch: bool,
     /// Whether or not we're logging the error coming out of this intrinsic
     log_error: bool,
+    /// A warning to log with `console.warn` the first time this function is
+    /// called, if any.
+    warning: Option<String>,
+    /// The name of this export if calls to it should check that they come
+    /// from the thread which instantiated the module.
+    assert_context: Option<String>,
+    /// For `async` exports, the TypeScript type of the value which the
+    /// returned promise resolves to.
+    resolves_to: Option<String>,
 }
 
 /// Helper struct used to create JS to process all instructions in an adapter
@@ -64,6 +73,10 @@ pub struct JsBuilder<'a, 'b> {
     /// pushed to the stack. We're not super principled about this though, so
     /// improvements will likely happen here over time.
     stack: Vec<String>,
+
+    /// The class constructed by this constructor if it extends a JS class, in
+    /// which case the Rust object is stored on `this` rather than a new object.
+    construct_into_this: Option<String>,
 }
 
 pub struct TypescriptArg {
@@ -82,6 +95,9 @@ impl<'a, 'b> Builder<'a, 'b> {
             constructor: None,
             method: None,
             catch: false,
+            warning: None,
+            assert_context: None,
+            resolves_to: None,
         }
     }
 
@@ -101,19 +117,31 @@ impl<'a, 'b> Builder<'a, 'b> {
         self.log_error = log;
     }
 
+    pub fn warn_once(&mut self, warning: String) {
+        self.warning = Some(warning);
+    }
+
+    pub fn assert_context(&mut self, name: &str) {
+        self.assert_context = Some(name.to_string());
+    }
+
+    pub fn resolves_to(&mut self, ty: &str) {
+        self.resolves_to = Some(ty.to_string());
+    }
+
     pub fn process(
         &mut self,
         adapter: &Adapter,
         instructions: &[InstructionData],
         explicit_arg_names: &Option<Vec<String>>,
     ) -> Result<String, Error> {
-        if self
-            .cx
-            .aux
-            .imports_with_assert_no_shim
-            .contains(&adapter.id)
-        {
-            bail!("generating a shim for something asserted to have no shim");
+        if let Some(name) = self.cx.aux.imports_with_assert_no_shim.get(&adapter.id) {
+            bail!(
+                "the import `{}` was annotated with `assert_no_shim` but a JS \
+                 shim is required to convert its arguments or return value, or \
+                 to catch exceptions it throws",
+                name
+            );
         }
 
         let mut params = adapter.params.iter();
@@ -126,17 +154,18 @@ impl<'a, 'b> Builder<'a, 'b> {
         match self.method {
             Some(consumes_self) => {
                 drop(params.next());
+                js.cx.expose_ptr_slot();
                 if js.cx.config.debug {
                     js.prelude(
-                        "if (this.ptr == 0) throw new Error('Attempt to use a moved value');\n",
+                        "if (this[ptrSlot] == 0) throw new Error('Attempt to use a moved value');\n",
                     );
                 }
                 if consumes_self {
-                    js.prelude("var ptr = this.ptr;");
-                    js.prelude("this.ptr = 0;");
+                    js.prelude("var ptr = this[ptrSlot];");
+                    js.prelude("this[ptrSlot] = 0;");
                     js.args.push("ptr".to_string());
                 } else {
-                    js.args.push("this.ptr".to_string());
+                    js.args.push("this[ptrSlot]".to_string());
                 }
             }
             None => {}
@@ -150,6 +179,15 @@ impl<'a, 'b> Builder<'a, 'b> {
             function_args.push(arg);
         }
 
+        // Classes extending a JS class are constructed by calling the JS
+        // constructor with the same arguments as the Rust constructor.
+        if let Some(class) = &self.constructor {
+            if js.cx.class_extends_js(class) {
+                js.prelude(&format!("super({});", function_args.join(", ")));
+                js.construct_into_this = Some(class.clone());
+            }
+        }
+
         // Translate all instructions, the fun loop!
         //
         // This loop will process all instructions for this adapter function.
@@ -171,6 +209,10 @@ impl<'a, 'b> Builder<'a, 'b> {
             0 => {}
             1 => {
                 self.ts_ret = js.typescript.pop();
+                if let (Some(ret), Some(resolved)) = (&mut self.ts_ret, &self.resolves_to) {
+                    ret.ty = format!("Promise<{}>", resolved);
+                    ret.optional = false;
+                }
                 let val = js.pop();
                 js.prelude(&format!("return {};", val));
             }
@@ -220,6 +262,18 @@ impl<'a, 'b> Builder<'a, 'b> {
             call = format!("try {{\n{}}} catch (e) {{\n logError(e)\n}}\n", call);
         }
 
+        if let Some(warning) = &self.warning {
+            js.cx.expose_warn_once();
+            let warning = serde_json::to_string(warning).unwrap();
+            ret.push_str(&format!("warnOnce({});\n", warning));
+        }
+
+        if let Some(name) = &self.assert_context {
+            js.cx.expose_assert_instantiating_context();
+            let name = serde_json::to_string(name).unwrap();
+            ret.push_str(&format!("assertInstantiatingContext({});\n", name));
+        }
+
         ret.push_str(&call);
         ret.push_str("}");
 
@@ -245,7 +299,7 @@ impl<'a, 'b> Builder<'a, 'b> {
                 if omittable {
                     ts_args.push(format!("{}?: {}", arg.name, arg.ty));
                 } else {
-                    ts_args.push(format!("{}: {} | undefined", arg.name, arg.ty));
+                    ts_args.push(format!("{}: {}", arg.name, ts_optional(&arg.ty)));
                 }
             } else {
                 omittable = false;
@@ -259,9 +313,10 @@ impl<'a, 'b> Builder<'a, 'b> {
         if self.constructor.is_none() {
             ts.push_str(": ");
             if let Some(ty) = &self.ts_ret {
-                ts.push_str(&ty.ty);
                 if ty.optional {
-                    ts.push_str(" | undefined");
+                    ts.push_str(&ts_optional(&ty.ty));
+                } else {
+                    ts.push_str(&ty.ty);
                 }
             } else {
                 ts.push_str("void");
@@ -278,7 +333,7 @@ impl<'a, 'b> Builder<'a, 'b> {
             .iter()
             .map(|a| {
                 if a.optional {
-                    format!("@param {{{} | undefined}} {}\n", a.ty, a.name)
+                    format!("@param {{{}}} {}\n", ts_optional(&a.ty), a.name)
                 } else {
                     format!("@param {{{}}} {}\n", a.ty, a.name)
                 }
@@ -301,6 +356,7 @@ impl<'a, 'b> JsBuilder<'a, 'b> {
             prelude: String::new(),
             typescript: Vec::new(),
             stack: Vec::new(),
+            construct_into_this: None,
         }
     }
 
@@ -410,9 +466,10 @@ impl<'a, 'b> JsBuilder<'a, 'b> {
         if !self.cx.config.debug {
             return;
         }
+        self.cx.expose_ptr_slot();
         self.prelude(&format!(
             "\
-                if ({0}.ptr === 0) {{
+                if ({0}[ptrSlot] === 0) {{
                     throw new Error('Attempt to use a moved value');
                 }}
             ",
@@ -562,6 +619,7 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
         Instruction::StoreRetptr { ty, offset, mem } => {
             let (mem, size) = match ty {
                 AdapterType::I32 => (js.cx.expose_int32_memory(*mem), 4),
+                AdapterType::I64 => (js.cx.expose_int64_memory(*mem), 8),
                 AdapterType::F32 => (js.cx.expose_f32_memory(*mem), 4),
                 AdapterType::F64 => (js.cx.expose_f64_memory(*mem), 8),
                 other => bail!("invalid aggregate return type {:?}", other),
@@ -569,6 +627,12 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             // Note that we always assume the return pointer is argument 0,
             // which is currently the case for LLVM.
             let val = js.pop();
+            // Pointers into 64-bit memories are numbers in JS, but are stored
+            // as BigInts.
+            let val = match ty {
+                AdapterType::I64 if js.cx.config.memory64 => format!("BigInt({})", val),
+                _ => val,
+            };
             let expr = format!(
                 "{}()[{} / {} + {}] = {};",
                 mem,
@@ -583,6 +647,7 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
         Instruction::LoadRetptr { ty, offset, mem } => {
             let (mem, size) = match ty {
                 AdapterType::I32 => (js.cx.expose_int32_memory(*mem), 4),
+                AdapterType::I64 => (js.cx.expose_int64_memory(*mem), 8),
                 AdapterType::F32 => (js.cx.expose_f32_memory(*mem), 4),
                 AdapterType::F64 => (js.cx.expose_f64_memory(*mem), 8),
                 other => bail!("invalid aggregate return type {:?}", other),
@@ -591,6 +656,10 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             // it earlier, and we always push the same value, so load that value
             // here
             let expr = format!("{}()[{} / {} + {}]", mem, retptr_val, size, offset);
+            let expr = match ty {
+                AdapterType::I64 if js.cx.config.memory64 => format!("Number({})", expr),
+                _ => expr,
+            };
             js.prelude(&format!("var r{} = {};", offset, expr));
             js.push(format!("r{}", offset));
         }
@@ -622,7 +691,11 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             js.cx.expose_global_stack_pointer();
             let val = js.pop();
             js.push(format!("addBorrowedObject({})", val));
-            js.finally("heap[stack_pointer++] = undefined;");
+            if js.cx.config.shared_runtime.is_some() {
+                js.finally("popBorrowedObject();");
+            } else {
+                js.finally("heap[stack_pointer++] = undefined;");
+            }
         }
 
         Instruction::I32FromAnyrefRustOwned { class } => {
@@ -630,9 +703,10 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             let val = js.pop();
             js.assert_class(&val, &class);
             js.assert_not_moved(&val);
+            js.cx.expose_ptr_slot();
             let i = js.tmp();
-            js.prelude(&format!("var ptr{} = {}.ptr;", i, val));
-            js.prelude(&format!("{}.ptr = 0;", val));
+            js.prelude(&format!("var ptr{} = {}[ptrSlot];", i, val));
+            js.prelude(&format!("{}[ptrSlot] = 0;", val));
             js.push(format!("ptr{}", i));
         }
 
@@ -641,20 +715,22 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             let val = js.pop();
             js.assert_class(&val, &class);
             js.assert_not_moved(&val);
-            js.push(format!("{}.ptr", val));
+            js.cx.expose_ptr_slot();
+            js.push(format!("{}[ptrSlot]", val));
         }
 
         Instruction::I32FromOptionRust { class } => {
             js.typescript_optional(class);
             let val = js.pop();
             js.cx.expose_is_like_none();
+            js.cx.expose_ptr_slot();
             let i = js.tmp();
             js.prelude(&format!("let ptr{} = 0;", i));
             js.prelude(&format!("if (!isLikeNone({0})) {{", val));
             js.assert_class(&val, class);
             js.assert_not_moved(&val);
-            js.prelude(&format!("ptr{} = {}.ptr;", i, val));
-            js.prelude(&format!("{}.ptr = 0;", val));
+            js.prelude(&format!("ptr{} = {}[ptrSlot];", i, val));
+            js.prelude(&format!("{}[ptrSlot] = 0;", val));
             js.prelude("}");
             js.push(format!("ptr{}", i));
         }
@@ -682,6 +758,46 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             js.push(format!("high{}", i));
         }
 
+        Instruction::I32Split128 { signed } => {
+            js.typescript_required("BigInt");
+            let val = js.pop();
+            let f = js.cx.expose_split_bigint128();
+            let i = js.tmp();
+            js.prelude(&format!("const parts{} = {}({}, {});", i, f, val, signed));
+            for part in 0..4 {
+                js.push(format!("parts{}[{}]", i, part));
+            }
+        }
+
+        Instruction::ScalarsFromTuple { elements } => {
+            js.typescript_required(&tuple_typescript(elements));
+            let val = js.pop();
+            let i = js.tmp();
+            js.prelude(&format!("const tuple{} = {};", i, val));
+            js.prelude(&format!(
+                "\
+                    if (tuple{i}.length !== {len}) {{
+                        throw new Error(`expected an array of length {len}, got ${{tuple{i}.length}}`);
+                    }}
+                ",
+                i = i,
+                len = elements.len(),
+            ));
+            for (j, ty) in elements.iter().enumerate() {
+                let element = format!("tuple{}[{}]", i, j);
+                match ty {
+                    AdapterType::Bool => {
+                        js.assert_bool(&element);
+                        js.push(format!("{} ? 1 : 0", element));
+                    }
+                    _ => {
+                        js.assert_number(&element);
+                        js.push(element);
+                    }
+                }
+            }
+        }
+
         Instruction::I32SplitOption64 { signed } => {
             js.typescript_optional("BigInt");
             let val = js.pop();
@@ -875,7 +991,7 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
         }
 
         Instruction::BoolFromI32 => {
-            js.typescript_required("bool");
+            js.typescript_required("boolean");
             let val = js.pop();
             js.push(format!("{} !== 0", val));
         }
@@ -917,11 +1033,39 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             js.push(format!("n{}", i))
         }
 
+        Instruction::I128FromParts { signed } => {
+            js.typescript_required("BigInt");
+            let f = js.cx.expose_join_bigint128();
+            let d = js.pop();
+            let c = js.pop();
+            let b = js.pop();
+            let a = js.pop();
+            js.push(format!("{}([{}, {}, {}, {}], {})", f, a, b, c, d, signed));
+        }
+
+        Instruction::TupleFromScalars { elements } => {
+            js.typescript_required(&tuple_typescript(elements));
+            let mut values = Vec::new();
+            for ty in elements.iter().rev() {
+                let val = js.pop();
+                values.push(match ty {
+                    AdapterType::Bool => format!("{} !== 0", val),
+                    _ => val,
+                });
+            }
+            values.reverse();
+            js.push(format!("[{}]", values.join(", ")));
+        }
+
         Instruction::RustFromI32 { class } => {
             js.typescript_required(class);
             js.cx.require_class_wrap(class);
             let val = js.pop();
-            js.push(format!("{}.__wrap({})", class, val));
+            if js.construct_into_this.as_ref() == Some(class) {
+                js.push(format!("{}.__wrap({}, this)", class, val));
+            } else {
+                js.push(format!("{}.__wrap({})", class, val));
+            }
         }
 
         Instruction::OptionRustFromI32 { class } => {
@@ -978,50 +1122,113 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             adapter,
             nargs,
             mutable,
+            debug_name,
         } => {
-            js.typescript_optional("any");
+            let ts = js.cx.closure_typescript_of(*adapter);
+            js.typescript_optional(&ts);
             let i = js.tmp();
             let b = js.pop();
             let a = js.pop();
-            js.prelude(&format!("var state{} = {{a: {}, b: {}}};", i, a, b));
             let args = (0..*nargs)
                 .map(|i| format!("arg{}", i))
                 .collect::<Vec<_>>()
                 .join(", ");
             let wrapper = js.cx.adapter_name(*adapter);
-            if *mutable {
+            let invoked = js.cx.expose_closure_invoked_error();
+            let name = if js.cx.config.debug {
+                format!(", '{}'", debug_name)
+            } else {
+                String::new()
+            };
+
+            // Each call site gets a factory for JS functions which forward to
+            // the `(a, b)` pair in a `state` object, along with a cached
+            // `state` (and its function) which is reused across calls. This
+            // avoids allocating a new JS function every time a stack closure
+            // is passed to JS, for example in a tight loop.
+            let make = generate_identifier("makeStackClosure", &mut js.cx.defined_identifiers);
+            let cache = format!("{}Cache", make);
+            let body = if *mutable {
                 // Mutable closures need protection against being called
                 // recursively, so ensure that we clear out one of the
-                // internal pointers while it's being invoked.
-                js.prelude(&format!(
-                    "var cb{i} = ({args}) => {{
-                        const a = state{i}.a;
-                        state{i}.a = 0;
+                // internal pointers while it's being invoked. The closure is
+                // destroyed once the call it was passed to returns, after
+                // which it's no longer active.
+                format!(
+                    "
+                        if (state.a === 0) {invoked}(state.active{name});
+                        const a = state.a;
+                        state.a = 0;
                         try {{
-                            return {name}(a, state{i}.b, {args});
+                            return {wrapper}(a, state.b, {args});
                         }} finally {{
-                            state{i}.a = a;
+                            state.a = a;
                         }}
-                    }};",
-                    i = i,
+                    ",
                     args = args,
-                    name = wrapper,
-                ));
+                    wrapper = wrapper,
+                    invoked = invoked,
+                    name = name,
+                )
             } else {
-                js.prelude(&format!(
-                    "var cb{i} = ({args}) => {wrapper}(state{i}.a, state{i}.b, {args});",
-                    i = i,
+                format!(
+                    "
+                        if (state.a === 0) {invoked}(false{name});
+                        return {wrapper}(state.a, state.b, {args});
+                    ",
                     args = args,
                     wrapper = wrapper,
-                ));
-            }
+                    invoked = invoked,
+                    name = name,
+                )
+            };
+            js.cx.global(&format!(
+                "
+                    function {make}(state) {{
+                        return ({args}) => {{
+                            {body}
+                        }};
+                    }}
+
+                    let {cache} = null;
+                ",
+                make = make,
+                cache = cache,
+                args = args,
+                body = body,
+            ));
+
+            // If the cached state is already in use (the import was called
+            // re-entrantly while the closure is on the stack) then we fall
+            // back to allocating a fresh one.
+            js.prelude(&format!(
+                "
+                    var state{i} = {cache};
+                    if (state{i} === null || state{i}.active) {{
+                        state{i} = {{ a: 0, b: 0, active: false, cb: null }};
+                        state{i}.cb = {make}(state{i});
+                        if ({cache} === null) {cache} = state{i};
+                    }}
+                    state{i}.a = {a};
+                    state{i}.b = {b};
+                    state{i}.active = true;
+                ",
+                i = i,
+                a = a,
+                b = b,
+                make = make,
+                cache = cache,
+            ));
 
             // Make sure to null out our internal pointers when we return
             // back to Rust to ensure that any lingering references to the
             // closure will fail immediately due to null pointers passed in
             // to Rust.
-            js.finally(&format!("state{}.a = state{0}.b = 0;", i));
-            js.push(format!("cb{}", i));
+            js.finally(&format!(
+                "state{}.a = state{0}.b = 0; state{0}.active = false;",
+                i
+            ));
+            js.push(format!("state{}.cb", i));
         }
 
         Instruction::VectorLoad { kind, mem, free } => {
@@ -1042,6 +1249,22 @@ fn instruction(js: &mut JsBuilder, instr: &Instruction, log_error: &mut bool) ->
             js.push(format!("v{}", i))
         }
 
+        Instruction::OwnedView { kind, mem, free } => {
+            js.typescript_required(&format!("{} & {{ free(): void }}", kind.js_ty()));
+            let len = js.pop();
+            let ptr = js.pop();
+            let f = js.cx.expose_get_vector_from_wasm(*kind, *mem)?;
+            let take = js.cx.expose_take_owned_view(*free);
+            js.push(format!(
+                "{take}({f}({ptr}, {len}), {ptr}, {len} * {size})",
+                take = take,
+                f = f,
+                ptr = ptr,
+                len = len,
+                size = kind.size(),
+            ));
+        }
+
         Instruction::OptionVectorLoad { kind, mem, free } => {
             js.typescript_optional(kind.js_ty());
             let len = js.pop();
@@ -1253,3 +1476,72 @@ impl Invocation {
         }
     }
 }
+
+/// Returns the TypeScript type for a value of type `ty` which may also be
+/// `undefined`.
+///
+/// `any` already includes `undefined`, so it's left as-is rather than
+/// producing the redundant `any | undefined`.
+pub fn ts_optional(ty: &str) -> String {
+    if ty == "any" {
+        ty.to_string()
+    } else {
+        format!("{} | undefined", ty)
+    }
+}
+
+/// Returns the TypeScript function type of a closure called from JS with
+/// arguments of the types `args`, which returns `ret`.
+///
+/// A closure returning nothing is typed as returning `void`, like exported
+/// functions are.
+pub fn closure_typescript(args: &[TypescriptArg], ret: Option<&TypescriptArg>) -> String {
+    let ty = |arg: &TypescriptArg| {
+        if arg.optional {
+            ts_optional(&arg.ty)
+        } else {
+            arg.ty.clone()
+        }
+    };
+    let args = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| format!("arg{}: {}", i, ty(arg)))
+        .collect::<Vec<_>>();
+    let ret = ret.map(ty).unwrap_or_else(|| "void".to_string());
+    format!("({}) => {}", args.join(", "), ret)
+}
+
+/// Returns the TypeScript type of a tuple with elements of the given types.
+fn tuple_typescript(elements: &[AdapterType]) -> String {
+    let elements = elements
+        .iter()
+        .map(|ty| match ty {
+            AdapterType::Bool => "boolean",
+            _ => "number",
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", elements.join(", "))
+}
+
+#[test]
+fn test_closure_typescript() {
+    let arg = |ty: &str, optional| TypescriptArg {
+        ty: ty.to_string(),
+        name: "arg".to_string(),
+        optional,
+    };
+    assert_eq!(closure_typescript(&[], None), "() => void");
+    assert_eq!(
+        closure_typescript(&[arg("number", false), arg("string", false)], None),
+        "(arg0: number, arg1: string) => void"
+    );
+    assert_eq!(
+        closure_typescript(&[arg("Uint8Array", false)], Some(&arg("boolean", false))),
+        "(arg0: Uint8Array) => boolean"
+    );
+    assert_eq!(
+        closure_typescript(&[arg("string", true)], Some(&arg("any", true))),
+        "(arg0: string | undefined) => any"
+    );
+}
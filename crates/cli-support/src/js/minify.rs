@@ -0,0 +1,620 @@
+//! Minification of the generated JS glue, used for `--minify`.
+//!
+//! This isn't a general purpose JS minifier, it only needs to handle the JS we
+//! generate ourselves. Comments and indentation are removed, lines are joined
+//! where that can't change how semicolons are inserted, and identifiers
+//! declared in the glue are renamed to short names. Names which are exported
+//! from the module, along with all property names, are left alone so the
+//! public interface of the module is unchanged.
+
+use anyhow::{bail, Error};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Kind {
+    Ident,
+    Number,
+    String,
+    Template,
+    Regex,
+    Punct,
+    Newline,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+}
+
+impl Token<'_> {
+    fn is(&self, kind: Kind, text: &str) -> bool {
+        self.kind == kind && self.text == text
+    }
+}
+
+/// The kinds of brackets we keep track of while renaming, to tell property
+/// names apart from references to variables.
+#[derive(Copy, Clone, PartialEq)]
+enum Bracket {
+    Block,
+    Class,
+    Object,
+    /// The braces of a destructuring pattern in a `const`, `let` or `var`
+    /// declaration, whose keys are property names of the destructured value.
+    Pattern,
+    Other,
+}
+
+const PUNCTUATORS: &[&str] = &[
+    ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>", "==", "!=",
+    "<=", ">=", "&&", "||", "??", "?.", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    "**", "<<", ">>",
+];
+
+/// Words which can't be used as the name of a binding.
+pub(crate) const RESERVED: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "NaN",
+    "Infinity",
+];
+
+/// Keywords after which a `/` starts a regular expression rather than being a
+/// division.
+const REGEX_PREFIX_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+];
+
+/// Minifies `js`, in which each `placeholder(i)` stands for `verbatim[i]`.
+///
+/// Code passed verbatim, like the `js_prelude!`s of crates, is inserted back
+/// untouched, and the identifiers it mentions are neither renamed nor picked
+/// as new names in the rest of the glue.
+pub fn minify(js: &str, verbatim: &[&str]) -> Result<String, Error> {
+    let tokens = tokenize(js)?;
+    let kept = verbatim
+        .iter()
+        .flat_map(|code| code.split(|c: char| !is_ident_char_str(c)))
+        .filter(|name| !name.is_empty())
+        .collect::<HashSet<_>>();
+    let renames = renames(&tokens, &kept);
+    let mut minified = emit(&tokens, &renames);
+    for (i, code) in verbatim.iter().enumerate() {
+        minified = minified.replacen(&placeholder(i), &format!("\n{}\n", code.trim()), 1);
+    }
+    Ok(minified)
+}
+
+/// The identifier standing in for the `i`th piece of verbatim code passed to
+/// `minify`.
+pub fn placeholder(i: usize) -> String {
+    format!("__wbindgen_verbatim_{}_", i)
+}
+
+fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c == b'$' || c >= 0x80
+}
+
+fn is_ident_char(c: u8) -> bool {
+    is_ident_start(c) || c.is_ascii_digit()
+}
+
+fn is_ident_char_str(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn tokenize(js: &str) -> Result<Vec<Token<'_>>, Error> {
+    let bytes = js.as_bytes();
+    let mut tokens: Vec<Token> = Vec::new();
+    // Whether each open `{` is the `${` of a template literal, in which case
+    // the matching `}` continues the template.
+    let mut braces = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let next = bytes.get(i + 1).cloned();
+        let kind = match bytes[i] {
+            b'\n' => {
+                i += 1;
+                Kind::Newline
+            }
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'/' if next == Some(b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if next == Some(b'*') => {
+                let end = match js[i + 2..].find("*/") {
+                    Some(end) => i + 2 + end + 2,
+                    None => bail!("unterminated comment in generated JS"),
+                };
+                i = end;
+                // A comment spanning lines still ends a line as far as
+                // semicolon insertion is concerned.
+                if !js[start..end].contains('\n') {
+                    continue;
+                }
+                Kind::Newline
+            }
+            b'\'' | b'"' => {
+                let quote = bytes[i];
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        Some(b'\\') => i += 2,
+                        Some(c) if *c == quote => break,
+                        Some(b'\n') | None => bail!("unterminated string in generated JS"),
+                        Some(_) => i += 1,
+                    }
+                }
+                i += 1;
+                Kind::String
+            }
+            b'`' => {
+                i = template(bytes, i + 1, &mut braces)?;
+                Kind::Template
+            }
+            b'}' if braces.last() == Some(&true) => {
+                braces.pop();
+                i = template(bytes, i + 1, &mut braces)?;
+                Kind::Template
+            }
+            b'/' if regex_allowed(tokens.iter().rev().find(|t| t.kind != Kind::Newline)) => {
+                let mut in_class = false;
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        Some(b'\\') => i += 2,
+                        Some(b'[') => {
+                            in_class = true;
+                            i += 1;
+                        }
+                        Some(b']') => {
+                            in_class = false;
+                            i += 1;
+                        }
+                        Some(b'/') if !in_class => break,
+                        Some(b'\n') | None => bail!("unterminated regex in generated JS"),
+                        Some(_) => i += 1,
+                    }
+                }
+                i += 1;
+                while i < bytes.len() && is_ident_char(bytes[i]) {
+                    i += 1;
+                }
+                Kind::Regex
+            }
+            c if is_ident_start(c) => {
+                while i < bytes.len() && is_ident_char(bytes[i]) {
+                    i += 1;
+                }
+                Kind::Ident
+            }
+            c if c.is_ascii_digit()
+                || (c == b'.' && next.map_or(false, |c| c.is_ascii_digit())) =>
+            {
+                let hex = c == b'0' && (next == Some(b'x') || next == Some(b'X'));
+                i += 1;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        c if is_ident_char(c) || c == b'.' => i += 1,
+                        b'+' | b'-' if !hex && (bytes[i - 1] == b'e' || bytes[i - 1] == b'E') => {
+                            i += 1
+                        }
+                        _ => break,
+                    }
+                }
+                Kind::Number
+            }
+            c => {
+                i += PUNCTUATORS
+                    .iter()
+                    .find(|p| js[i..].starts_with(*p))
+                    .map_or(1, |p| p.len());
+                match c {
+                    b'{' => braces.push(false),
+                    b'}' => {
+                        braces.pop();
+                    }
+                    _ => {}
+                }
+                Kind::Punct
+            }
+        };
+        tokens.push(Token {
+            kind,
+            text: &js[start..i],
+        });
+    }
+    Ok(tokens)
+}
+
+/// Scans the rest of a template literal starting at `i`, returning the index
+/// just past its end or past the `${` of the next substitution.
+fn template(bytes: &[u8], mut i: usize, braces: &mut Vec<bool>) -> Result<usize, Error> {
+    loop {
+        match bytes.get(i) {
+            Some(b'\\') => i += 2,
+            Some(b'`') => return Ok(i + 1),
+            Some(b'$') if bytes.get(i + 1) == Some(&b'{') => {
+                braces.push(true);
+                return Ok(i + 2);
+            }
+            Some(_) => i += 1,
+            None => bail!("unterminated template literal in generated JS"),
+        }
+    }
+}
+
+fn regex_allowed(prev: Option<&Token>) -> bool {
+    let prev = match prev {
+        Some(prev) => prev,
+        None => return true,
+    };
+    match prev.kind {
+        Kind::Ident => REGEX_PREFIX_KEYWORDS.contains(&prev.text),
+        Kind::Template => prev.text.ends_with("${"),
+        Kind::Punct => match prev.text {
+            ")" | "]" | "}" => false,
+            _ => true,
+        },
+        Kind::Number | Kind::String | Kind::Regex | Kind::Newline => false,
+    }
+}
+
+/// Picks new names for all identifiers declared in the glue which aren't
+/// exported or in `kept`.
+fn renames(tokens: &[Token], kept: &HashSet<&str>) -> HashMap<String, String> {
+    let tokens = tokens
+        .iter()
+        .filter(|t| t.kind != Kind::Newline)
+        .collect::<Vec<_>>();
+    let mut declared = HashSet::new();
+    let mut exported = HashSet::new();
+    let mut used = HashSet::new();
+    let mut counts = HashMap::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != Kind::Ident {
+            continue;
+        }
+        used.insert(token.text);
+        *counts.entry(token.text).or_insert(0) += 1;
+        match token.text {
+            "function" | "const" | "let" | "var" => {}
+            _ => continue,
+        }
+        let name = match tokens.get(i + 1) {
+            Some(t) if t.kind == Kind::Ident => t.text,
+            _ => continue,
+        };
+        if i > 0 && tokens[i - 1].is(Kind::Ident, "export") {
+            exported.insert(name);
+        } else {
+            declared.insert(name);
+        }
+    }
+
+    // Give the shortest names to the most used identifiers.
+    let mut names = declared
+        .difference(&exported)
+        .filter(|name| !kept.contains(*name))
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort_by_key(|name| (-counts[name], *name));
+
+    let mut fresh = (0..).map(short_name).filter(|name| {
+        !used.contains(&name[..]) && !kept.contains(&name[..]) && !RESERVED.contains(&&name[..])
+    });
+    names
+        .into_iter()
+        .filter(|name| name.len() > 1)
+        .map(|name| (name.to_string(), fresh.next().unwrap()))
+        .collect()
+}
+
+/// Returns the `n`th short identifier: `a` through `Z`, then `aa` and so on.
+fn short_name(mut n: usize) -> String {
+    const FIRST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    const REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let mut name = String::new();
+    name.push(FIRST[n % FIRST.len()] as char);
+    n /= FIRST.len();
+    while n > 0 {
+        n -= 1;
+        name.push(REST[n % REST.len()] as char);
+        n /= REST.len();
+    }
+    name
+}
+
+fn emit(tokens: &[Token], renames: &HashMap<String, String>) -> String {
+    let mut dst = String::new();
+    let mut brackets = Vec::new();
+    let mut class_pending = false;
+    // Whether we're inside the braces of an `export { ... }` or
+    // `import { ... }` list, where some names refer to other modules.
+    let mut export_list = false;
+    let mut import_list = false;
+    let mut prev: Option<&Token> = None;
+    let mut newline = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind == Kind::Newline {
+            newline = true;
+            continue;
+        }
+        let next = tokens[i + 1..].iter().find(|t| t.kind != Kind::Newline);
+        let prev_is = |kind: Kind, s: &str| prev.map_or(false, |p| p.is(kind, s));
+        let next_is = |s: &str| next.map_or(false, |n| n.is(Kind::Punct, s));
+
+        if let Some(p) = prev {
+            // Only keep line breaks which might be significant for automatic
+            // semicolon insertion.
+            if newline && !joinable(p, token) {
+                dst.push('\n');
+            } else if needs_space(&dst, p, token) {
+                dst.push(' ');
+            }
+        }
+        newline = false;
+
+        let mut text = token.text;
+        if token.kind == Kind::Ident {
+            if let Some(new_name) = renames.get(text) {
+                let property = prev_is(Kind::Punct, ".") || prev_is(Kind::Punct, "?.");
+                let after_brace_or_comma = prev_is(Kind::Punct, "{") || prev_is(Kind::Punct, ",");
+                let after_modifier = prev_is(Kind::Ident, "static")
+                    || prev_is(Kind::Ident, "get")
+                    || prev_is(Kind::Ident, "set")
+                    || prev_is(Kind::Ident, "async");
+                let member = match brackets.last() {
+                    Some(Bracket::Class) => {
+                        after_brace_or_comma
+                            || after_modifier
+                            || prev_is(Kind::Punct, "}")
+                            || prev_is(Kind::Punct, ";")
+                            || prev_is(Kind::Punct, "*")
+                    }
+                    Some(Bracket::Object) => {
+                        (after_brace_or_comma && (next_is(":") || next_is("("))) || after_modifier
+                    }
+                    Some(Bracket::Pattern) => after_brace_or_comma && next_is(":"),
+                    _ => false,
+                };
+                let external = (import_list && next.map_or(false, |n| n.is(Kind::Ident, "as")))
+                    || (export_list && prev_is(Kind::Ident, "as"));
+                if !property && !member && !external {
+                    // Shorthand properties keep their name as the key.
+                    let object = match brackets.last() {
+                        Some(Bracket::Object) | Some(Bracket::Pattern) => true,
+                        _ => false,
+                    };
+                    if object && after_brace_or_comma && (next_is(",") || next_is("}")) {
+                        dst.push_str(text);
+                        dst.push(':');
+                    }
+                    text = new_name;
+                }
+            }
+            if text == "class" {
+                class_pending = true;
+            }
+        }
+
+        match token.kind {
+            Kind::Punct => match text {
+                "{" => {
+                    let kind = if class_pending {
+                        Bracket::Class
+                    } else if prev_is(Kind::Ident, "export") {
+                        export_list = true;
+                        Bracket::Other
+                    } else if prev_is(Kind::Ident, "import") {
+                        import_list = true;
+                        Bracket::Other
+                    } else if prev_is(Kind::Ident, "const")
+                        || prev_is(Kind::Ident, "let")
+                        || prev_is(Kind::Ident, "var")
+                    {
+                        Bracket::Pattern
+                    } else if prev.map_or(true, |p| object_follows(p)) {
+                        Bracket::Object
+                    } else {
+                        Bracket::Block
+                    };
+                    class_pending = false;
+                    brackets.push(kind);
+                }
+                "(" | "[" => brackets.push(Bracket::Other),
+                "}" | ")" | "]" => {
+                    brackets.pop();
+                    export_list = false;
+                    import_list = false;
+                }
+                _ => {}
+            },
+            Kind::Template => {
+                if text.starts_with('}') {
+                    brackets.pop();
+                }
+                if text.ends_with("${") {
+                    brackets.push(Bracket::Other);
+                }
+            }
+            _ => {}
+        }
+
+        dst.push_str(text);
+        prev = Some(token);
+    }
+    dst.push('\n');
+    dst
+}
+
+/// Whether a `{` after `prev` starts an object literal rather than a block.
+fn object_follows(prev: &Token) -> bool {
+    match (prev.kind, prev.text) {
+        (Kind::Punct, "=")
+        | (Kind::Punct, "(")
+        | (Kind::Punct, "[")
+        | (Kind::Punct, ",")
+        | (Kind::Punct, ":")
+        | (Kind::Punct, "?")
+        | (Kind::Punct, "||")
+        | (Kind::Punct, "&&")
+        | (Kind::Punct, "??")
+        | (Kind::Punct, "!")
+        | (Kind::Ident, "return")
+        | (Kind::Ident, "typeof")
+        | (Kind::Ident, "in")
+        | (Kind::Ident, "of") => true,
+        (Kind::Template, text) => text.ends_with("${"),
+        _ => false,
+    }
+}
+
+/// Whether the line break between `prev` and `next` can be removed without
+/// changing where semicolons are automatically inserted.
+fn joinable(prev: &Token, next: &Token) -> bool {
+    match (prev.kind, prev.text) {
+        (Kind::Punct, ")") | (Kind::Punct, "]") | (Kind::Punct, "}") => {}
+        (Kind::Punct, "++") | (Kind::Punct, "--") => {}
+        (Kind::Punct, _) => return true,
+        _ => {}
+    }
+    match (next.kind, next.text) {
+        (Kind::Punct, ")")
+        | (Kind::Punct, "]")
+        | (Kind::Punct, "}")
+        | (Kind::Punct, ",")
+        | (Kind::Punct, ";")
+        | (Kind::Punct, ".") => true,
+        _ => false,
+    }
+}
+
+/// Whether a space is needed between the output so far and `next` to keep
+/// them from running together.
+fn needs_space(dst: &str, prev: &Token, next: &Token) -> bool {
+    let ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$' || !c.is_ascii();
+    let last = match dst.chars().last() {
+        Some(c) => c,
+        None => return false,
+    };
+    let first = next.text.chars().next().unwrap();
+    if ident_char(last) && ident_char(first) {
+        return true;
+    }
+    if prev.kind == Kind::Number && first == '.' {
+        return true;
+    }
+    match (last, first) {
+        ('+', '+') | ('-', '-') | ('/', '/') | ('/', '*') => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{minify, placeholder};
+
+    #[test]
+    fn destructuring_keys_are_kept() {
+        let js = "
+            const { getObject: getObject2, takeObject } = require('foo');
+            function getObject(idx) { return getObject2(idx); }
+            exports.takeObject = takeObject;
+            exports.run = getObject;
+        ";
+        let minified = minify(js, &[]).unwrap();
+        assert!(
+            minified.contains("const{getObject:getObject2,takeObject}="),
+            "{}",
+            minified
+        );
+        assert!(!minified.contains("function getObject("), "{}", minified);
+    }
+
+    #[test]
+    fn verbatim_code_is_kept() {
+        let prelude = "function a() {\n    return helperName();\n}";
+        let js = format!(
+            "
+                function helperName() {{ return 1; }}
+                function otherHelper() {{ return helperName() + otherHelper(); }}
+                {}
+                exports.run = otherHelper;
+            ",
+            placeholder(0),
+        );
+        let minified = minify(&js, &[prelude]).unwrap();
+        assert!(minified.contains(prelude), "{}", minified);
+        assert!(minified.contains("function helperName()"), "{}", minified);
+        assert!(!minified.contains("function a(){"), "{}", minified);
+        assert!(!minified.contains("function otherHelper("), "{}", minified);
+    }
+}
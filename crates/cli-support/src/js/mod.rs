@@ -2,7 +2,9 @@ use crate::descriptor::VectorKind;
 use crate::intrinsic::Intrinsic;
 use crate::wit::{Adapter, AdapterId, AdapterJsImportKind, AuxValue};
 use crate::wit::{AdapterKind, Instruction, InstructionData};
-use crate::wit::{AuxEnum, AuxExport, AuxExportKind, AuxImport, AuxStruct};
+use crate::wit::{
+    AuxConst, AuxEnum, AuxEnumRepr, AuxExport, AuxExportKind, AuxImport, AuxStability, AuxStruct,
+};
 use crate::wit::{JsImport, JsImportName, NonstandardWitSection, WasmBindgenAux};
 use crate::{Bindgen, EncodeInto, OutputMode};
 use anyhow::{anyhow, bail, Context as _, Error};
@@ -10,10 +12,12 @@ use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::fs;
+use std::mem;
 use std::path::{Path, PathBuf};
 use walrus::{FunctionId, ImportId, MemoryId, Module, TableId};
 
 mod binding;
+pub(crate) mod minify;
 
 pub struct Context<'a> {
     globals: String,
@@ -45,6 +49,10 @@ pub struct Context<'a> {
 
     exported_classes: Option<BTreeMap<String, ExportedClass>>,
 
+    /// Free functions exported with `js_namespace`, keyed by the namespace
+    /// they're grouped under.
+    exported_namespaces: BTreeMap<String, ExportedNamespace>,
+
     /// A map of the name of npm dependencies we've loaded so far to the path
     /// they're defined in as well as their version specification.
     pub npm_dependencies: HashMap<String, (PathBuf, String)>,
@@ -53,23 +61,113 @@ pub struct Context<'a> {
     /// names.
     memory_indices: HashMap<MemoryId, usize>,
     table_indices: HashMap<TableId, usize>,
+
+    /// Contents of the shared runtime module, if `--shared-runtime` is
+    /// enabled, which the helpers below are imported from.
+    pub runtime: String,
+
+    /// A description of everything exported to JS, mapping a description of
+    /// each export (like `class Foo method bar`) to its TypeScript signature.
+    /// This is used to check a module's interface with `--check-interface`.
+    pub interface: BTreeMap<String, String>,
+
+    /// TypeScript declarations of exports gated on a Cargo feature with
+    /// `#[wasm_bindgen(cfg_feature = "...")]`, keyed by the feature, when
+    /// `--typescript-feature-files` is enabled. Each is written to its own
+    /// `.d.ts` file which the main one re-exports.
+    pub feature_typescript: BTreeMap<String, String>,
+
+    /// TypeScript declarations of classes and enums which aren't gated on a
+    /// Cargo feature, keyed by their name, when `--typescript-feature-files`
+    /// is enabled. Those the per-feature files refer to are written to a file
+    /// of their own so the main file, which re-exports the per-feature ones,
+    /// is never imported from.
+    shared_typescript: BTreeMap<String, String>,
+
+    /// The TypeScript function type of each adapter which JS calls Rust
+    /// closures through, as found when generating the adapter.
+    closure_typescript: HashMap<AdapterId, String>,
+
+    /// The name of the wasm import module which JS glue is provided under
+    /// when we instantiate the module ourselves, `wbg` unless `--module-hash`
+    /// is enabled.
+    import_namespace: String,
+}
+
+#[derive(Default)]
+struct ExportedNamespace {
+    /// Pairs of the JS name of each function in this namespace and the local
+    /// identifier it's defined under in the generated JS.
+    functions: Vec<(String, String)>,
+    typescript: String,
 }
 
 #[derive(Default)]
 pub struct ExportedClass {
     comments: String,
+    /// The Cargo feature the class was only compiled with
+    cfg_feature: Option<String>,
     contents: String,
     typescript: String,
     has_constructor: bool,
     wrap_needed: bool,
+    /// The JS class this class extends, if any
+    extends_js: Option<String>,
+    /// The tag name this class is defined as a custom element with, if any
+    custom_element: Option<String>,
+    /// Pairs of an observed attribute of this custom element and the JS name
+    /// of the method called when it changes
+    observed_attributes: Vec<(String, String)>,
     /// Whether to generate helper methods for inspecting the class
     is_inspectable: bool,
+    /// Whether a Rust method was exported as `toJSON` via
+    /// `#[wasm_bindgen(to_json)]`
+    has_to_json: bool,
+    /// The JS name and TypeScript item type of the `#[wasm_bindgen(iterator)]`
+    /// method of this class, if any, which `[Symbol.iterator]` is implemented
+    /// with
+    iterator: Option<(String, String)>,
+    /// Whether that method is named `next`, making instances of the class
+    /// iterators themselves
+    is_iterator: bool,
     /// All readable properties of the class
     readable_properties: Vec<String>,
+    /// Properties whose getter is `#[wasm_bindgen(cached)]`
+    cached_properties: Vec<String>,
     /// Map from field name to type as a string plus whether it has a setter
     typescript_fields: HashMap<String, (String, bool)>,
+    /// Methods and static methods of the class in the order they were first
+    /// exported. Rust methods sharing a JS name are grouped into one method
+    /// which dispatches to them based on its arguments.
+    methods: Vec<ExportedMethod>,
+}
+
+struct ExportedMethod {
+    name: String,
+    prefix: &'static str,
+    overloads: Vec<Overload>,
 }
 
+/// One of the Rust methods exported under the JS name of an `ExportedMethod`.
+struct Overload {
+    docs: String,
+    js: String,
+    ts: String,
+    /// JS expression in terms of `args` which tests whether the arguments of a
+    /// call can be passed to this overload.
+    matches: String,
+}
+
+/// The import module name used with `--module-hash` until the final wasm
+/// module is emitted, when it's replaced with a name of the same length derived
+/// from the hash of the module.
+pub(crate) const MODULE_HASH_PLACEHOLDER: &str = "wbg_HASHPLACEHOLDER_";
+
+/// Used in place of a feature's name for the file which
+/// `--typescript-feature-files` writes the types shared between the
+/// per-feature files to.
+const SHARED_TYPES_FILE: &str = "types";
+
 const INITIAL_HEAP_VALUES: &[&str] = &["undefined", "null", "true", "false"];
 // Must be kept in sync with `src/lib.rs` of the `wasm-bindgen` crate
 const INITIAL_HEAP_OFFSET: usize = 32;
@@ -81,6 +179,11 @@ impl<'a> Context<'a> {
         wit: &'a NonstandardWitSection,
         aux: &'a WasmBindgenAux,
     ) -> Result<Context<'a>, Error> {
+        let import_namespace = if config.module_hash {
+            MODULE_HASH_PLACEHOLDER.to_string()
+        } else {
+            "wbg".to_string()
+        };
         Ok(Context {
             globals: String::new(),
             imports_post: String::new(),
@@ -91,6 +194,7 @@ impl<'a> Context<'a> {
             defined_identifiers: Default::default(),
             wasm_import_definitions: Default::default(),
             exported_classes: Some(Default::default()),
+            exported_namespaces: Default::default(),
             config,
             module,
             npm_dependencies: Default::default(),
@@ -99,9 +203,53 @@ impl<'a> Context<'a> {
             aux,
             memory_indices: Default::default(),
             table_indices: Default::default(),
+            runtime: String::new(),
+            interface: Default::default(),
+            feature_typescript: Default::default(),
+            shared_typescript: Default::default(),
+            closure_typescript: Default::default(),
+            import_namespace,
         })
     }
 
+    /// Returns the JS modules imported by the generated bindings, along with
+    /// the names imported from each of them, for `--check-interface`.
+    pub fn imported_modules(&self) -> BTreeMap<String, Vec<String>> {
+        self.js_imports
+            .iter()
+            .map(|(module, names)| {
+                let mut names = names.iter().map(|(n, _)| n.clone()).collect::<Vec<_>>();
+                names.sort();
+                names.dedup();
+                (module.clone(), names)
+            })
+            .collect()
+    }
+
+    /// Returns where TypeScript for an export gated on `feature` is written,
+    /// which is a separate file per feature with `--typescript-feature-files`.
+    fn typescript_dst(&mut self, feature: Option<&String>) -> &mut String {
+        match feature {
+            Some(feature) if self.config.typescript_feature_files => self
+                .feature_typescript
+                .entry(feature.clone())
+                .or_insert_with(String::new),
+            _ => &mut self.typescript,
+        }
+    }
+
+    /// Returns where TypeScript for the class or enum `name` gated on `feature`
+    /// is written.
+    fn type_typescript_dst(&mut self, name: &str, feature: Option<&String>) -> &mut String {
+        if feature.is_none() && self.config.typescript_feature_files {
+            return self
+                .shared_typescript
+                .entry(name.to_string())
+                .or_insert_with(String::new);
+        }
+        self.typescript_dst(feature)
+    }
+
     fn should_write_global(&mut self, name: impl Into<Cow<'static, str>>) -> bool {
         self.exposed_globals.as_mut().unwrap().insert(name.into())
     }
@@ -122,23 +270,20 @@ impl<'a> Context<'a> {
             self.globals.push_str(c);
             self.typescript.push_str(c);
         }
+        let assign = |object: &str| {
+            if contents.starts_with("class") {
+                format!("{}\n{}.{2} = {2};\n", contents, object, export_name)
+            } else {
+                format!("{}.{} = {};\n", object, export_name, contents)
+            }
+        };
         let global = match self.config.mode {
             OutputMode::Node {
                 experimental_modules: false,
-            } => {
-                if contents.starts_with("class") {
-                    format!("{}\nmodule.exports.{1} = {1};\n", contents, export_name)
-                } else {
-                    format!("module.exports.{} = {};\n", export_name, contents)
-                }
-            }
-            OutputMode::NoModules { .. } => {
-                if contents.starts_with("class") {
-                    format!("{}\n__exports.{1} = {1};\n", contents, export_name)
-                } else {
-                    format!("__exports.{} = {};\n", export_name, contents)
-                }
-            }
+            } => assign("module.exports"),
+            OutputMode::NoModules { .. } => assign("__exports"),
+            // Each instance has exports of its own, see `finalize_js`.
+            OutputMode::Web if self.config.multiple_instances => assign("__exports"),
             OutputMode::Bundler { .. }
             | OutputMode::Node {
                 experimental_modules: true,
@@ -180,6 +325,17 @@ impl<'a> Context<'a> {
         // previously present).
         let needs_manual_start = self.unstart_start_function();
 
+        if self.config.memory64 {
+            self.expose_memory64_shims();
+            self.wrap_memory64_imports();
+        }
+
+        // The context is recorded at instantiation even if no export checks it,
+        // so it has to be declared.
+        if self.asserts_instantiating_context() {
+            self.expose_assert_instantiating_context();
+        }
+
         // Cause any future calls to `should_write_global` to panic, making sure
         // we don't ask for items which we can no longer emit.
         drop(self.exposed_globals.take().unwrap());
@@ -206,18 +362,35 @@ impl<'a> Context<'a> {
         let mut init = (String::new(), String::new());
         let mut footer = String::new();
         let mut imports = self.js_import_header()?;
+
+        // With `--multiple-instances` all state of the glue, including its
+        // exports, is created anew in `__wbg_instance` for each call to
+        // `init`, which resolves to the exports of that instance.
+        let instances = self.config.multiple_instances;
+        if instances && self.aux.structs.iter().any(|s| s.custom_element.is_some()) {
+            bail!(
+                "custom elements can only be defined once, so they can't be used with \
+                 `--multiple-instances`"
+            );
+        }
+        let mut state = String::new();
+        let new_instance = "function init(...args) {\nreturn __wbg_instance()(...args);\n}";
         match &self.config.mode {
             // In `--target no-modules` mode we need to both expose a name on
             // the global object as well as generate our own custom start
             // function.
             OutputMode::NoModules { global } => {
-                js.push_str("const __exports = {};\n");
-                js.push_str("let wasm;\n");
+                state.push_str("const __exports = {};\n");
+                state.push_str("let wasm;\n");
                 init = self.gen_init(needs_manual_start, None)?;
-                footer.push_str(&format!(
-                    "self.{} = Object.assign(init, __exports);\n",
-                    global
-                ));
+                if instances {
+                    footer.push_str(&format!("self.{} = {};\n", global, new_instance));
+                } else {
+                    footer.push_str(&format!(
+                        "self.{} = Object.assign(init, __exports);\n",
+                        global
+                    ));
+                }
             }
 
             // With normal CommonJS node we need to defer requiring the wasm
@@ -237,7 +410,9 @@ impl<'a> Context<'a> {
                     footer.push_str(";\n");
                 }
 
-                footer.push_str(&format!("wasm = require('./{}_bg');\n", module_name));
+                let exports = self.wasm_exports(&format!("require('./{}_bg')", module_name));
+                footer.push_str(&format!("wasm = {};\n", exports));
+                footer.push_str(self.instantiated_context());
                 if needs_manual_start {
                     footer.push_str("wasm.__wbindgen_start();\n");
                 }
@@ -263,6 +438,8 @@ impl<'a> Context<'a> {
                     footer.push_str(js.trim());
                     footer.push_str(";\n");
                 }
+                footer.push_str(self.instantiated_context());
+                footer.push_str(&self.custom_element_definitions());
                 if needs_manual_start {
                     footer.push_str("\nwasm.__wbindgen_start();\n");
                 }
@@ -275,24 +452,63 @@ impl<'a> Context<'a> {
             OutputMode::Web => {
                 self.imports_post.push_str("let wasm;\n");
                 init = self.gen_init(needs_manual_start, Some(&mut imports))?;
-                footer.push_str("export default init;\n");
+                if instances {
+                    state.push_str("const __exports = {};\n");
+                    footer.push_str(&format!("export default {}\n", new_instance));
+                } else {
+                    footer.push_str("export default init;\n");
+                }
             }
         }
 
         let (init_js, init_ts) = init;
 
+        // The declarations of exports describe the object `init` resolves to
+        // rather than the module itself.
+        if instances {
+            let header = "/* tslint:disable */\n/* eslint-disable */\n";
+            let decls = ts[header.len()..].to_string();
+            ts = format!(
+                "{}declare namespace __wbg_instance {{\n{}}}\n\n\
+                 export type Instance = typeof __wbg_instance;\n",
+                header, decls,
+            );
+        }
         ts.push_str(&init_ts);
 
+        if self.config.typescript_feature_files {
+            self.finalize_feature_typescript(module_name, &mut ts)?;
+        }
+
         // Emit all the JS for importing all our functionality
         assert!(
             !self.config.mode.uses_es_modules() || js.is_empty(),
             "ES modules require imports to be at the start of the file"
         );
+        if !instances {
+            js.push_str(&state);
+        }
         js.push_str(&imports);
         js.push_str("\n");
+        if instances {
+            js.push_str("function __wbg_instance() {\n");
+            js.push_str(&state);
+        }
         js.push_str(&self.imports_post);
         js.push_str("\n");
 
+        // Preludes registered by crates run before anything else in the module
+        // so the rest of the glue can rely on them. They're written by users
+        // rather than generated by us, so they're never minified.
+        for (i, prelude) in self.aux.js_preludes.iter().enumerate() {
+            if self.config.minify {
+                js.push_str(&minify::placeholder(i));
+            } else {
+                js.push_str(prelude.trim());
+            }
+            js.push_str("\n\n");
+        }
+
         // Emit all our exports from this module
         js.push_str(&self.globals);
         js.push_str("\n");
@@ -300,6 +516,9 @@ impl<'a> Context<'a> {
         // Generate the initialization glue, if there was any
         js.push_str(&init_js);
         js.push_str("\n");
+        if instances {
+            js.push_str("return init;\n}\n\n");
+        }
         js.push_str(&footer);
         js.push_str("\n");
         if self.config.mode.no_modules() {
@@ -310,9 +529,120 @@ impl<'a> Context<'a> {
             js = js.replace("\n\n\n", "\n\n");
         }
 
+        if self.config.minify {
+            let preludes = self
+                .aux
+                .js_preludes
+                .iter()
+                .map(|p| p.as_str())
+                .collect::<Vec<_>>();
+            js = minify::minify(&js, &preludes).context("failed to minify the generated JS")?;
+        }
+
         Ok((js, ts))
     }
 
+    /// Re-exports each per-feature TypeScript file from the main one, and
+    /// imports the types each of them refers to from the file defining it.
+    ///
+    /// Classes and enums without a feature which are referred to by a
+    /// per-feature file, directly or through other such types, are moved to
+    /// the `{module}_types.d.ts` file which the main one also re-exports.
+    fn finalize_feature_typescript(
+        &mut self,
+        module_name: &str,
+        ts: &mut String,
+    ) -> Result<(), Error> {
+        if self.feature_typescript.contains_key(SHARED_TYPES_FILE) {
+            bail!(
+                "exports can't be gated on a Cargo feature named `{}` with \
+                 `--typescript-feature-files`, as that file holds the types \
+                 shared between the per-feature files",
+                SHARED_TYPES_FILE
+            );
+        }
+        let header = "/* tslint:disable */\n/* eslint-disable */\n";
+        let feature_of_type = self
+            .aux
+            .structs
+            .iter()
+            .map(|s| (&s.name, &s.cfg_feature))
+            .chain(self.aux.enums.iter().map(|e| (&e.name, &e.cfg_feature)))
+            .collect::<Vec<_>>();
+
+        let mut shared = BTreeSet::new();
+        for contents in self.feature_typescript.values() {
+            for name in self.shared_typescript.keys() {
+                if mentions_identifier(contents, name) {
+                    shared.insert(name.clone());
+                }
+            }
+        }
+        loop {
+            let mut referenced = Vec::new();
+            for name in shared.iter() {
+                let decl = &self.shared_typescript[name];
+                referenced.extend(
+                    self.shared_typescript
+                        .keys()
+                        .filter(|other| !shared.contains(*other))
+                        .filter(|other| mentions_identifier(decl, other))
+                        .cloned(),
+                );
+            }
+            if referenced.is_empty() {
+                break;
+            }
+            shared.extend(referenced);
+        }
+
+        let mut shared_ts = String::new();
+        for (name, decl) in mem::replace(&mut self.shared_typescript, BTreeMap::new()) {
+            if shared.contains(&name) {
+                shared_ts.push_str(&decl);
+            } else {
+                ts.push_str(&decl);
+            }
+        }
+        if !shared.is_empty() {
+            self.feature_typescript
+                .insert(SHARED_TYPES_FILE.to_string(), shared_ts);
+        }
+
+        for (file, contents) in self.feature_typescript.iter_mut() {
+            ts.push_str(&format!("export * from './{}_{}';\n", module_name, file));
+
+            let mut imports = BTreeMap::new();
+            for (name, type_feature) in feature_of_type.iter() {
+                if !mentions_identifier(contents, name) {
+                    continue;
+                }
+                let defined_in = match type_feature {
+                    Some(f) => f.as_str(),
+                    None if shared.contains(name.as_str()) => SHARED_TYPES_FILE,
+                    None => continue,
+                };
+                if defined_in == file.as_str() {
+                    continue;
+                }
+                imports
+                    .entry(format!("./{}_{}", module_name, defined_in))
+                    .or_insert_with(Vec::new)
+                    .push(name.as_str());
+            }
+            let mut imports_ts = header.to_string();
+            for (module, names) in imports {
+                imports_ts.push_str(&format!(
+                    "import {{ {} }} from '{}';\n",
+                    names.join(", "),
+                    module
+                ));
+            }
+            contents.insert_str(0, &imports_ts);
+        }
+        Ok(())
+    }
+
     fn js_import_header(&self) -> Result<String, Error> {
         let mut imports = String::new();
         match &self.config.mode {
@@ -372,7 +702,7 @@ impl<'a> Context<'a> {
         Ok(imports)
     }
 
-    fn ts_for_init_fn(has_memory: bool, has_module_or_path_optional: bool) -> String {
+    fn ts_for_init_fn(has_memory: bool, has_module_or_path_optional: bool, output: &str) -> String {
         let (memory_doc, memory_param) = if has_memory {
             (
                 "* @param {WebAssembly.Memory} maybe_memory\n",
@@ -391,12 +721,12 @@ impl<'a> Context<'a> {
             * @param {{RequestInfo | BufferSource | WebAssembly.Module}} module_or_path\n\
             {}\
             *\n\
-            * @returns {{Promise<any>}}\n\
+            * @returns {{Promise<{3}>}}\n\
             */\n\
             export default function init \
-                (module_or_path{}: RequestInfo | BufferSource | WebAssembly.Module{}): Promise<any>;
+                (module_or_path{1}: RequestInfo | BufferSource | WebAssembly.Module{2}): Promise<{3}>;
         ",
-            memory_doc, arg_optional, memory_param
+            memory_doc, arg_optional, memory_param, output
         )
     }
 
@@ -405,7 +735,8 @@ impl<'a> Context<'a> {
         needs_manual_start: bool,
         mut imports: Option<&mut String>,
     ) -> Result<(String, String), Error> {
-        let module_name = "wbg";
+        let module_name = self.import_namespace.clone();
+        let module_name = &module_name[..];
         let mut init_memory_arg = "";
         let mut init_memory1 = String::new();
         let mut init_memory2 = String::new();
@@ -440,7 +771,13 @@ impl<'a> Context<'a> {
             _ => "",
         };
 
-        let ts = Self::ts_for_init_fn(has_memory, !default_module_path.is_empty());
+        let output = if self.config.multiple_instances {
+            "Instance"
+        } else {
+            "any"
+        };
+        let ts = Self::ts_for_init_fn(has_memory, !default_module_path.is_empty(), output);
+        let exports = self.wasm_exports("instance.exports");
 
         // Initialize the `imports` object for all import definitions that we're
         // directed to wire up.
@@ -534,10 +871,12 @@ impl<'a> Context<'a> {
                             }});
                     }}
                     return result.then(({{instance, module}}) => {{
-                        wasm = instance.exports;
+                        wasm = {exports};
+                        {context}
                         init.__wbindgen_wasm_module = module;
+                        {define}
                         {start}
-                        return wasm;
+                        return {ret};
                     }});
                 }}
             ",
@@ -545,17 +884,43 @@ impl<'a> Context<'a> {
             default_module_path = default_module_path,
             init_memory1 = init_memory1,
             init_memory2 = init_memory2,
+            context = self.instantiated_context(),
+            define = self.custom_element_definitions(),
+            ret = if self.config.multiple_instances {
+                "__exports"
+            } else {
+                "wasm"
+            },
             start = if needs_manual_start {
                 "wasm.__wbindgen_start();"
             } else {
                 ""
             },
             imports_init = imports_init,
+            exports = exports,
         );
 
         Ok((js, ts))
     }
 
+    /// Exports an object for each `js_namespace` used by exported functions,
+    /// along with a matching TypeScript `namespace` declaration.
+    fn write_namespaces(&mut self) -> Result<(), Error> {
+        for (name, ns) in mem::replace(&mut self.exported_namespaces, Default::default()) {
+            let mut object = "{\n".to_string();
+            for (js_name, local) in ns.functions.iter() {
+                object.push_str(&format!("{}: {},\n", js_name, local));
+            }
+            object.push_str("}");
+            self.export(&name, &object, None)?;
+            self.typescript
+                .push_str(&format!("export namespace {} {{\n", name));
+            self.typescript.push_str(&ns.typescript);
+            self.typescript.push_str("}\n");
+        }
+        Ok(())
+    }
+
     fn write_classes(&mut self) -> Result<(), Error> {
         for (class, exports) in self.exported_classes.take().unwrap() {
             self.write_class(&class, &exports)?;
@@ -564,32 +929,82 @@ impl<'a> Context<'a> {
     }
 
     fn write_class(&mut self, name: &str, class: &ExportedClass) -> Result<(), Error> {
-        let mut dst = format!("class {} {{\n", name);
+        // A cached value replaces the getter with a read-only property on the
+        // object, which would shadow the setter and make assignments throw.
+        for property in class.cached_properties.iter() {
+            if class.typescript_fields[property].1 {
+                bail!(
+                    "property `{}` of class `{}` has a `#[wasm_bindgen(cached)]` \
+                     getter and so can't also have a setter",
+                    property,
+                    name
+                );
+            }
+        }
+        let mut dst = match &class.extends_js {
+            Some(base) => format!("class {} extends {} {{\n", name, base),
+            None => format!("class {} {{\n", name),
+        };
         let mut ts_dst = format!("export {}", dst);
+        self.expose_ptr_slot();
 
-        if self.config.debug && !class.has_constructor {
-            dst.push_str(
+        // Without an exported constructor `new` would create an instance
+        // without a Rust object behind it, so it's only possible to create
+        // instances through `__wrap` in that case.
+        if class.custom_element.is_some() && self.config.mode.nodejs() {
+            bail!(
+                "custom element class `{}` can't be used with `--target nodejs` as it \
+                 has no `customElements` registry",
+                name
+            );
+        }
+        if !class.has_constructor {
+            // The browser creates custom elements with `new` itself.
+            if class.custom_element.is_some() {
+                bail!(
+                    "custom element class `{}` must have a `#[wasm_bindgen(constructor)]` \
+                     taking no arguments",
+                    name
+                );
+            }
+            dst.push_str(&format!(
                 "
-                    constructor() {
-                        throw new Error('cannot invoke `new` directly');
-                    }
+                    constructor() {{
+                        throw new Error('{} cannot be constructed from JS');
+                    }}
                 ",
-            );
+                name,
+            ));
+            ts_dst.push_str("  private constructor();\n");
         }
 
         if class.wrap_needed {
+            // Constructors of classes extending a JS class pass along the
+            // `this` created by `super()` to store the Rust object on, since
+            // instances of built-in classes like `HTMLElement` can't be
+            // created any other way.
+            let obj = format!("Object.create({}.prototype)", name);
+            let params = match &class.extends_js {
+                Some(_) => format!("ptr, obj = {}", obj),
+                None => "ptr".to_string(),
+            };
+            let create = match &class.extends_js {
+                Some(_) => String::new(),
+                None => format!("const obj = {};", obj),
+            };
             dst.push_str(&format!(
                 "
-                static __wrap(ptr) {{
-                    const obj = Object.create({}.prototype);
-                    obj.ptr = ptr;
+                static __wrap({}) {{
+                    {}
+                    Object.defineProperty(obj, ptrSlot, {{ value: ptr, writable: true }});
                     {}
                     return obj;
                 }}
                 ",
-                name,
+                params,
+                create,
                 if self.config.weak_refs {
-                    format!("{}FinalizationGroup.register(obj, obj.ptr, obj.ptr);", name)
+                    format!("{}FinalizationGroup.register(obj, ptr, ptr);", name)
                 } else {
                     String::new()
                 },
@@ -612,27 +1027,35 @@ impl<'a> Context<'a> {
 
         // If the class is inspectable, generate `toJSON` and `toString`
         // to expose all readable properties of the class. Otherwise,
-        // the class shows only the "ptr" property when logged or serialized
+        // the class shows only the "ptr" property when logged or serialized.
+        // A user-provided `#[wasm_bindgen(to_json)]` method takes precedence
+        // over the generated `toJSON`.
         if class.is_inspectable {
-            // Creates a `toJSON` method which returns an object of all readable properties
-            // This object looks like { a: this.a, b: this.b }
-            dst.push_str(&format!(
-                "
-                toJSON() {{
-                    return {{{}}};
-                }}
+            if !class.has_to_json {
+                // Creates a `toJSON` method which returns an object of all readable properties
+                // This object looks like { a: this.a, b: this.b }
+                dst.push_str(&format!(
+                    "
+                    toJSON() {{
+                        return {{{}}};
+                    }}
+                    ",
+                    class.readable_properties.iter().fold(
+                        String::from("\n"),
+                        |fields, field_name| {
+                            format!("{}{name}: this.{name},\n", fields, name = field_name)
+                        }
+                    )
+                ));
+            }
 
-                toString() {{
+            dst.push_str(
+                "
+                toString() {
                     return JSON.stringify(this);
-                }}
+                }
                 ",
-                class
-                    .readable_properties
-                    .iter()
-                    .fold(String::from("\n"), |fields, field_name| {
-                        format!("{}{name}: this.{name},\n", fields, name = field_name)
-                    })
-            ));
+            );
 
             if self.config.mode.nodejs() {
                 // `util.inspect` must be imported in Node.js to define [inspect.custom]
@@ -658,11 +1081,35 @@ impl<'a> Context<'a> {
             }
         }
 
+        // Every class is branded with its name under a well-known symbol so
+        // instances can be recognized even when they were created by another
+        // wasm-bindgen module (where `instanceof` will fail).
+        dst.push_str(&format!(
+            "
+            get [Symbol.for('wasm-bindgen.brand')]() {{
+                return '{}';
+            }}
+            ",
+            name
+        ));
+
+        // The pointer is only exposed for debugging.
+        if self.config.debug {
+            dst.push_str(
+                "
+                __wbg_ptr() {
+                    return this[ptrSlot];
+                }
+                ",
+            );
+            ts_dst.push_str("  __wbg_ptr(): number;\n");
+        }
+
         dst.push_str(&format!(
             "
             free() {{
-                const ptr = this.ptr;
-                this.ptr = 0;
+                const ptr = this[ptrSlot];
+                this[ptrSlot] = 0;
                 {}
                 wasm.{}(ptr);
             }}
@@ -675,9 +1122,149 @@ impl<'a> Context<'a> {
             wasm_bindgen_shared::free_function(&name),
         ));
         ts_dst.push_str("  free(): void;\n");
+
+        // Implement the JS iteration protocol by repeatedly calling the
+        // iterator method until it returns `None`, which shows up in JS as
+        // `undefined`.
+        if let (Some((iterator, item_ty)), true) = (&class.iterator, class.is_iterator) {
+            dst.push_str(&format!(
+                "
+                next() {{
+                    const value = this.{}();
+                    if (value === undefined) {{
+                        return {{ value: undefined, done: true }};
+                    }}
+                    return {{ value, done: false }};
+                }}
+
+                [Symbol.iterator]() {{
+                    return this;
+                }}
+                ",
+                iterator,
+            ));
+            ts_dst.push_str(&format!("  next(): IteratorResult<{}>;\n", item_ty));
+            ts_dst.push_str(&format!("  [Symbol.iterator](): Iterator<{}>;\n", item_ty));
+        } else if let Some((iterator, item_ty)) = &class.iterator {
+            dst.push_str(&format!(
+                "
+                [Symbol.iterator]() {{
+                    return {{
+                        next: () => {{
+                            const value = this.{}();
+                            if (value === undefined) {{
+                                return {{ value: undefined, done: true }};
+                            }}
+                            return {{ value, done: false }};
+                        }},
+                        [Symbol.iterator]() {{
+                            return this;
+                        }},
+                    }};
+                }}
+                ",
+                iterator,
+            ));
+            ts_dst.push_str(&format!("  [Symbol.iterator](): Iterator<{}>;\n", item_ty));
+        }
+        // Changes to observed attributes of a custom element are forwarded to
+        // the methods observing them, called with the new and old value.
+        if !class.observed_attributes.is_empty() {
+            if class
+                .methods
+                .iter()
+                .any(|m| m.name == "attributeChangedCallback")
+            {
+                bail!(
+                    "class `{}` can't export an `attributeChangedCallback` method as well as \
+                     `#[wasm_bindgen(observed_attribute)]` methods",
+                    name
+                );
+            }
+            let mut observers = BTreeMap::new();
+            for (attribute, method) in class.observed_attributes.iter() {
+                observers
+                    .entry(attribute)
+                    .or_insert_with(Vec::new)
+                    .push(method);
+            }
+            let mut cases = String::new();
+            for (attribute, methods) in observers.iter() {
+                cases.push_str(&format!("case {}:\n", serde_json::to_string(attribute)?));
+                for method in methods {
+                    cases.push_str(&format!("this.{}(newValue, oldValue);\n", method));
+                }
+                cases.push_str("break;\n");
+            }
+            dst.push_str(&format!(
+                "
+                static get observedAttributes() {{
+                    return {};
+                }}
+
+                attributeChangedCallback(name, oldValue, newValue) {{
+                    switch (name) {{
+                        {}
+                    }}
+                }}
+                ",
+                serde_json::to_string(&observers.keys().collect::<Vec<_>>())?,
+                cases,
+            ));
+            ts_dst.push_str("  static readonly observedAttributes: string[];\n");
+            ts_dst.push_str(
+                "  attributeChangedCallback(name: string, oldValue: string | null, \
+                 newValue: string | null): void;\n",
+            );
+        }
         dst.push_str(&class.contents);
         ts_dst.push_str(&class.typescript);
 
+        for method in class.methods.iter() {
+            if let [overload] = &method.overloads[..] {
+                dst.push_str(&overload.docs);
+                dst.push_str(method.prefix);
+                dst.push_str(&method.name);
+                dst.push_str(&overload.js);
+                dst.push_str("\n");
+                ts_dst.push_str(&overload.docs);
+                ts_dst.push_str(&format!(
+                    "  {}{}{};\n",
+                    method.prefix, method.name, overload.ts
+                ));
+                continue;
+            }
+
+            // Each overload is defined under a name of its own, and the
+            // method itself calls the first one accepting its arguments.
+            let receiver = if method.prefix.is_empty() {
+                "this"
+            } else {
+                name
+            };
+            let mut dispatch = String::new();
+            for (i, overload) in method.overloads.iter().enumerate() {
+                let overload_name = format!("__wbg_{}_{}", method.name, i);
+                dst.push_str(&format!(
+                    "{}{}{}\n",
+                    method.prefix, overload_name, overload.js
+                ));
+                dispatch.push_str(&format!(
+                    "if ({}) {{\nreturn {}.{}(...args);\n}}\n",
+                    overload.matches, receiver, overload_name
+                ));
+                ts_dst.push_str(&overload.docs);
+                ts_dst.push_str(&format!(
+                    "  {}{}{};\n",
+                    method.prefix, method.name, overload.ts
+                ));
+            }
+            dst.push_str(&format!(
+                "{}{}(...args) {{\n{}throw new TypeError('no overload of `{}.{}` accepts the given arguments');\n}}\n",
+                method.prefix, method.name, dispatch, name, method.name,
+            ));
+        }
+
         let mut fields = class.typescript_fields.keys().collect::<Vec<_>>();
         fields.sort(); // make sure we have deterministic output
         for name in fields {
@@ -694,8 +1281,11 @@ impl<'a> Context<'a> {
         dst.push_str("}\n");
         ts_dst.push_str("}\n");
 
-        self.export(&name, &dst, Some(class.comments.clone()))?;
-        self.typescript.push_str(&ts_dst);
+        self.globals.push_str(&class.comments);
+        self.export(&name, &dst, None)?;
+        let ts = self.type_typescript_dst(name, class.cfg_feature.as_ref());
+        ts.push_str(&class.comments);
+        ts.push_str(&ts_dst);
 
         Ok(())
     }
@@ -715,16 +1305,19 @@ impl<'a> Context<'a> {
         //
         // Otherwise the free operation here is pretty simple, just appending to
         // the linked list of heap slots that are free.
-        self.global(&format!(
-            "
+        self.runtime_global(
+            Some("dropObject"),
+            &format!(
+                "
             function dropObject(idx) {{
                 if (idx < {}) return;
                 heap[idx] = heap_next;
                 heap_next = idx;
             }}
             ",
-            INITIAL_HEAP_OFFSET + INITIAL_HEAP_VALUES.len(),
-        ));
+                INITIAL_HEAP_OFFSET + INITIAL_HEAP_VALUES.len(),
+            ),
+        );
     }
 
     fn expose_global_heap(&mut self) {
@@ -732,9 +1325,15 @@ impl<'a> Context<'a> {
             return;
         }
         assert!(!self.config.anyref);
-        self.global(&format!("const heap = new Array({});", INITIAL_HEAP_OFFSET));
-        self.global("heap.fill(undefined);");
-        self.global(&format!("heap.push({});", INITIAL_HEAP_VALUES.join(", ")));
+        self.runtime_global(
+            Some("heap"),
+            &format!("const heap = new Array({});", INITIAL_HEAP_OFFSET),
+        );
+        self.runtime_global(None, "heap.fill(undefined);");
+        self.runtime_global(
+            None,
+            &format!("heap.push({});", INITIAL_HEAP_VALUES.join(", ")),
+        );
     }
 
     fn expose_global_heap_next(&mut self) {
@@ -742,7 +1341,17 @@ impl<'a> Context<'a> {
             return;
         }
         self.expose_global_heap();
-        self.global("let heap_next = heap.length;");
+        self.runtime_global(None, "let heap_next = heap.length;");
+
+        // With a shared runtime `heap_next` stays private to it since names
+        // destructured from a CommonJS `require` are snapshots which wouldn't
+        // see later changes, so it's only read and written through functions.
+        if self.config.shared_runtime.is_some() {
+            self.runtime_global(
+                Some("getHeapNext"),
+                "function getHeapNext() { return heap_next; }",
+            );
+        }
     }
 
     fn expose_get_object(&mut self) {
@@ -753,7 +1362,10 @@ impl<'a> Context<'a> {
 
         // Accessing a heap object is just a simple index operation due to how
         // the stack/heap are laid out.
-        self.global("function getObject(idx) { return heap[idx]; }");
+        self.runtime_global(
+            Some("getObject"),
+            "function getObject(idx) { return heap[idx]; }",
+        );
     }
 
     fn expose_not_defined(&mut self) {
@@ -803,14 +1415,24 @@ impl<'a> Context<'a> {
     fn expose_pass_string_to_wasm(&mut self, memory: MemoryId) -> Result<MemView, Error> {
         self.expose_wasm_vector_len();
 
-        let debug = if self.config.debug {
+        let mut debug = if self.config.debug {
             "
                 if (typeof(arg) !== 'string') throw new Error('expected a string argument');
             "
+            .to_string()
         } else {
-            ""
+            String::new()
         };
 
+        // JS strings are sequences of UTF-16 code units and may contain
+        // unpaired surrogates, which have no UTF-8 encoding. By default these
+        // are replaced with U+FFFD by the encoders below, but in strict mode
+        // such strings are rejected with an exception instead.
+        if self.config.strict_strings {
+            self.expose_assert_well_formed_string();
+            debug.push_str("assertWellFormedString(arg);");
+        }
+
         // If we are targeting Node.js, it doesn't have `encodeInto` yet
         // but it does have `Buffer::write` which has similar semantics but
         // doesn't require creating intermediate view using `subarray`
@@ -976,6 +1598,31 @@ impl<'a> Context<'a> {
         Ok(ret)
     }
 
+    fn expose_assert_well_formed_string(&mut self) {
+        if !self.should_write_global("assert_well_formed_string") {
+            return;
+        }
+        self.global(
+            "
+            function assertWellFormedString(arg) {
+                if (!/[\\uD800-\\uDFFF]/.test(arg)) return;
+                for (let i = 0; i < arg.length; i++) {
+                    const code = arg.charCodeAt(i);
+                    if (code < 0xD800 || code > 0xDFFF) continue;
+                    if (code <= 0xDBFF) {
+                        const next = arg.charCodeAt(i + 1);
+                        if (next >= 0xDC00 && next <= 0xDFFF) {
+                            i++;
+                            continue;
+                        }
+                    }
+                    throw new TypeError(`string contains an unpaired surrogate at index ${i}`);
+                }
+            }
+            ",
+        );
+    }
+
     fn expose_pass_array8_to_wasm(&mut self, memory: MemoryId) -> Result<MemView, Error> {
         let view = self.expose_uint8_memory(memory);
         self.pass_array_to_wasm("passArray8ToWasm", view, 1)
@@ -1099,38 +1746,60 @@ impl<'a> Context<'a> {
             return Ok(());
         }
 
+        // Strings coming out of Rust are always valid UTF-8, so decoding them
+        // is never lossy. (It's the opposite direction, JS to Rust, which needs
+        // care; see `expose_pass_string_to_wasm`.)
+        //
         // `ignoreBOM` is needed so that the BOM will be preserved when sending a string from Rust to JS
         // `fatal` is needed to catch any weird encoding bugs when sending a string from Rust to JS
         self.expose_text_processor("TextDecoder", "('utf-8', { ignoreBOM: true, fatal: true })")?;
 
         // This is needed to workaround a bug in Safari
         // See: https://github.com/rustwasm/wasm-bindgen/issues/1825
-        self.global("cachedTextDecoder.decode();");
+        self.runtime_global(None, "cachedTextDecoder.decode();");
 
         Ok(())
     }
 
     fn expose_text_processor(&mut self, s: &str, args: &str) -> Result<(), Error> {
+        let cached = format!("cached{}", s);
         if self.config.mode.nodejs() {
-            let name = self.import_name(&JsImport {
-                name: JsImportName::Module {
-                    module: "util".to_string(),
-                    name: s.to_string(),
-                },
-                fields: Vec::new(),
-            })?;
-            self.global(&format!("let cached{} = new {}{};", s, name, args));
+            let name = if self.config.shared_runtime.is_some() {
+                self.runtime_global(None, &format!("import {{ {} }} from 'util';", s));
+                s.to_string()
+            } else {
+                self.import_name(&JsImport {
+                    name: JsImportName::Module {
+                        module: "util".to_string(),
+                        name: s.to_string(),
+                    },
+                    fields: Vec::new(),
+                })?
+            };
+            self.runtime_global(
+                Some(&cached),
+                &format!("let {} = new {}{};", cached, name, args),
+            );
         } else if !self.config.mode.always_run_in_browser() {
-            self.global(&format!(
-                "
+            self.runtime_global(
+                None,
+                &format!(
+                    "
                     const l{0} = typeof {0} === 'undefined' ? \
                         require('util').{0} : {0};\
                 ",
-                s
-            ));
-            self.global(&format!("let cached{0} = new l{0}{1};", s, args));
+                    s
+                ),
+            );
+            self.runtime_global(
+                Some(&cached),
+                &format!("let {} = new l{}{};", cached, s, args),
+            );
         } else {
-            self.global(&format!("let cached{0} = new {0}{1};", s, args));
+            self.runtime_global(
+                Some(&cached),
+                &format!("let {} = new {}{};", cached, s, args),
+            );
         }
 
         Ok(())
@@ -1436,23 +2105,51 @@ impl<'a> Context<'a> {
         if !self.should_write_global("assert_class") {
             return;
         }
+        self.expose_ptr_slot();
         self.global(
             "
             function _assertClass(instance, klass) {
                 if (!(instance instanceof klass)) {
+                    const brand = Symbol.for('wasm-bindgen.brand');
+                    if (instance != null && instance[brand] === klass.prototype[brand]) {
+                        throw new Error(`expected instance of ${klass.name}, but it was created by a different wasm module`);
+                    }
                     throw new Error(`expected instance of ${klass.name}`);
                 }
-                return instance.ptr;
+                return instance[ptrSlot];
             }
             ",
         );
     }
 
-    fn expose_global_stack_pointer(&mut self) {
-        if !self.should_write_global("stack_pointer") {
+    /// The pointer of an exported Rust object is stored under a module-local
+    /// symbol in a non-enumerable property, so it isn't serialized, copied,
+    /// or easily mutated by JS. It's only exposed for debugging through the
+    /// `__wbg_ptr()` method generated with `--debug`.
+    fn expose_ptr_slot(&mut self) {
+        if !self.should_write_global("ptr_slot") {
             return;
         }
-        self.global(&format!("let stack_pointer = {};", INITIAL_HEAP_OFFSET));
+        self.global("const ptrSlot = Symbol('wasm-bindgen.ptr');");
+    }
+
+    fn expose_global_stack_pointer(&mut self) {
+        if !self.should_write_global("stack_pointer") {
+            return;
+        }
+        self.runtime_global(
+            None,
+            &format!("let stack_pointer = {};", INITIAL_HEAP_OFFSET),
+        );
+
+        // Like `heap_next` the stack pointer stays private to a shared
+        // runtime, so borrowed objects are released through a function.
+        if self.config.shared_runtime.is_some() {
+            self.runtime_global(
+                Some("popBorrowedObject"),
+                "function popBorrowedObject() { heap[stack_pointer++] = undefined; }",
+            );
+        }
     }
 
     fn expose_borrowed_objects(&mut self) {
@@ -1466,7 +2163,8 @@ impl<'a> Context<'a> {
         // after executing this. Once we've reserved stack space we write the
         // value. Eventually underflow will throw an exception, but JS sort of
         // just handles it today...
-        self.global(
+        self.runtime_global(
+            Some("addBorrowedObject"),
             "
             function addBorrowedObject(obj) {
                 if (stack_pointer == 1) throw new Error('out of js stack');
@@ -1483,7 +2181,8 @@ impl<'a> Context<'a> {
         }
         self.expose_get_object();
         self.expose_drop_ref();
-        self.global(
+        self.runtime_global(
+            Some("takeObject"),
             "
             function takeObject(idx) {
                 const ret = getObject(idx);
@@ -1500,7 +2199,7 @@ impl<'a> Context<'a> {
         }
         self.expose_global_heap();
         self.expose_global_heap_next();
-        let set_heap_next = if self.config.debug {
+        let set_heap_next = if self.config.debug && self.config.shared_runtime.is_none() {
             String::from(
                 "
                 if (typeof(heap_next) !== 'number') throw new Error('corrupt heap');
@@ -1514,8 +2213,10 @@ impl<'a> Context<'a> {
         // (starting at `heap_next`). Once that linked list is exhausted we'll
         // be pointing beyond the end of the array, at which point we'll reserve
         // one more slot and use that.
-        self.global(&format!(
-            "
+        self.runtime_global(
+            Some("addHeapObject"),
+            &format!(
+                "
             function addHeapObject(obj) {{
                 if (heap_next === heap.length) heap.push(heap.length + 1);
                 const idx = heap_next;
@@ -1525,8 +2226,300 @@ impl<'a> Context<'a> {
                 return idx;
             }}
             ",
-            set_heap_next
+                set_heap_next
+            ),
+        );
+    }
+
+    fn expose_warn_once(&mut self) {
+        if !self.should_write_global("warn_once") {
+            return;
+        }
+        self.global(
+            "
+            const warned = new Set();
+
+            function warnOnce(message) {
+                if (warned.has(message)) return;
+                warned.add(message);
+                console.warn(message);
+            }
+            ",
+        );
+    }
+
+    fn expose_assert_instantiating_context(&mut self) {
+        if !self.should_write_global("assert_instantiating_context") {
+            return;
+        }
+        self.global(
+            "
+            let instantiatingContext;
+
+            function assertInstantiatingContext(name) {
+                if (instantiatingContext === globalThis) return;
+                throw new Error(
+                    `export ${name} was called on a thread which didn't instantiate the ` +
+                    'wasm module, which is only supported by modules built with threads support'
+                );
+            }
+            ",
+        );
+    }
+
+    /// Returns whether exports check that they're called on the thread which
+    /// instantiated the module, which is done in debug mode unless the module
+    /// was built with threads support.
+    fn asserts_instantiating_context(&self) -> bool {
+        self.config.debug && !self.is_threaded()
+    }
+
+    /// Returns the JS recording the global object of the thread which
+    /// instantiated the module, run as soon as `wasm` is assigned.
+    ///
+    /// Every thread loads its own copy of the glue, so in a copy which didn't
+    /// instantiate the module this is never run and exports throw.
+    fn instantiated_context(&self) -> &'static str {
+        if self.asserts_instantiating_context() {
+            "instantiatingContext = globalThis;\n"
+        } else {
+            ""
+        }
+    }
+
+    /// Returns whether the module was built with threads support, in which
+    /// case its memory is shared between threads.
+    fn is_threaded(&self) -> bool {
+        self.module.memories.iter().any(|m| m.shared)
+    }
+
+    /// Returns the JS defining every exported custom element, which is run
+    /// once the wasm module is instantiated, just before its start function.
+    fn custom_element_definitions(&self) -> String {
+        let mut js = String::new();
+        for struct_ in self.aux.structs.iter() {
+            if let Some(tag) = &struct_.custom_element {
+                // The macro only checks tags loosely, so they're quoted as
+                // JS strings rather than spliced in as they are.
+                let tag = serde_json::to_string(tag).unwrap();
+                js.push_str(&format!(
+                    "customElements.define({}, {});\n",
+                    tag, struct_.name
+                ));
+            }
+        }
+        js
+    }
+
+    /// Returns whether the exported class `name` extends a JS class.
+    fn class_extends_js(&self, name: &str) -> bool {
+        self.aux
+            .structs
+            .iter()
+            .any(|s| s.name == name && s.extends_js.is_some())
+    }
+
+    fn expose_closure_finalization(&mut self) -> Result<(), Error> {
+        if !self.should_write_global("closure_finalization") {
+            return Ok(());
+        }
+        let dtor = self.closure_dtor_call("state.a")?;
+        // Closures which Rust has given up ownership of, for example with
+        // `Closure::forget`, are destroyed once JS garbage collects them.
+        // Closures dropped by Rust have already been destroyed, which is
+        // indicated by a zeroed `a`.
+        self.global(&format!(
+            "
+            const closureFinalization = typeof FinalizationRegistry === 'undefined'
+                ? {{ register: () => {{}}, unregister: () => {{}} }}
+                : new FinalizationRegistry(state => {{
+                    if (state.a !== 0) {{
+                        {dtor};
+                    }}
+                }});
+            ",
+            dtor = dtor,
+        ));
+        Ok(())
+    }
+
+    /// Returns the table lookup and pointer arguments for calling a closure's
+    /// destructor from `state`. The destructor is called straight from the
+    /// table, so it needs BigInts for the pointers of a 64-bit memory.
+    fn closure_dtor_call(&mut self, a: &str) -> Result<String, Error> {
+        let table = self.export_function_table()?;
+        let (a, b) = if self.config.memory64 {
+            (format!("BigInt({})", a), "BigInt(state.b)")
+        } else {
+            (a.to_string(), "state.b")
+        };
+        Ok(format!("wasm.{}.get(state.dtor)({}, {})", table, a, b))
+    }
+
+    /// `FnMut` closures of every type are manufactured by this factory, which
+    /// wraps the adapter `f` for invoking them in a JS function owning the
+    /// state of the Rust closure.
+    fn expose_make_mut_closure(&mut self) -> Result<&'static str, Error> {
+        if !self.should_write_global("make_mut_closure") {
+            return Ok("makeMutClosure");
+        }
+        let dtor = self.closure_dtor_call("a")?;
+        let register = self.register_closure_finalization()?;
+        let invoked = self.expose_closure_invoked_error();
+        // Mutable closures can't be invoked recursively. To handle that we
+        // swap out the `state.a` pointer with zero while we invoke it. If we
+        // finish and the closure wasn't destroyed, then we put back the
+        // pointer so a future invocation can succeed. This is done in a
+        // `finally` block so a closure returning `Err`, which throws from the
+        // call, can still be invoked again afterwards.
+        //
+        // A zeroed pointer is either a recursive call or a destroyed closure,
+        // which are told apart by the reference count as destroyed closures
+        // have none left.
+        //
+        // The reference count is incremented for the duration of the call so
+        // the Rust closure environment won't be deallocated while it's being
+        // invoked.
+        //
+        // Stateless closures are passed a zero data pointer, leaving only the
+        // vtable with their function pointer, as their data pointer is always
+        // the dangling pointer 1. They're still destroyed once dropped by Rust
+        // in case capturing a zero-sized value gave them drop glue, but aren't
+        // registered for finalization.
+        self.global(&format!(
+            "
+            function makeMutClosure(arg0, arg1, dtor, f, name, wrap) {{
+                const state = {{ a: arg0 || 1, b: arg1, cnt: 1, stateless: arg0 === 0, dtor }};
+                let real = (...args) => {{
+                    if (state.a === 0) {invoked}(state.cnt > 0, name);
+                    state.cnt++;
+                    const a = state.a;
+                    state.a = 0;
+                    try {{
+                        return f(a, state.b, ...args);
+                    }} finally {{
+                        if (--state.cnt === 0) {{
+                            {dtor};
+                        }} else {{
+                            state.a = a;
+                        }}
+                    }}
+                }};
+                if (wrap !== undefined) real = wrap(real);
+                real.original = state;
+                {register}
+                return real;
+            }}
+            ",
+            dtor = dtor,
+            register = register,
+            invoked = invoked,
         ));
+        Ok("makeMutClosure")
+    }
+
+    /// Like `makeMutClosure`, but for `Fn` closures.
+    fn expose_make_closure(&mut self) -> Result<&'static str, Error> {
+        if !self.should_write_global("make_closure") {
+            return Ok("makeClosure");
+        }
+        let dtor = self.closure_dtor_call("state.a")?;
+        let register = self.register_closure_finalization()?;
+        let invoked = self.expose_closure_invoked_error();
+        // Shared closures can be invoked recursively so we just immediately
+        // pass through `state.a`. If we end up executing the destructor,
+        // however, we clear out the `state.a` pointer to prevent it being used
+        // again the future.
+        self.global(&format!(
+            "
+            function makeClosure(arg0, arg1, dtor, f, name, wrap) {{
+                const state = {{ a: arg0 || 1, b: arg1, cnt: 1, stateless: arg0 === 0, dtor }};
+                let real = (...args) => {{
+                    if (state.a === 0) {invoked}(false, name);
+                    state.cnt++;
+                    try {{
+                        return f(state.a, state.b, ...args);
+                    }} finally {{
+                        if (--state.cnt === 0) {{
+                            {dtor};
+                            state.a = 0;
+                        }}
+                    }}
+                }};
+                if (wrap !== undefined) real = wrap(real);
+                real.original = state;
+                {register}
+                return real;
+            }}
+            ",
+            dtor = dtor,
+            register = register,
+            invoked = invoked,
+        ));
+        Ok("makeClosure")
+    }
+
+    /// Exposes the function throwing the error for a closure invoked while
+    /// it's already running, for `FnMut` closures, or after it's destroyed.
+    /// The closure's type is included if `name` is given.
+    fn expose_closure_invoked_error(&mut self) -> &'static str {
+        if !self.should_write_global("closure_invoked_error") {
+            return "closureInvokedError";
+        }
+        self.global(
+            "
+            function closureInvokedError(recursive, name) {
+                const what = recursive ? 'recursively' : 'after being destroyed';
+                const type = name === undefined ? '' : ` \\`${name}\\``;
+                throw new Error(`closure${type} invoked ${what}`);
+            }
+            ",
+        );
+        "closureInvokedError"
+    }
+
+    /// Returns the statement registering a new closure `real` to be destroyed
+    /// once it's garbage collected, if `--weak-closures` is enabled.
+    fn register_closure_finalization(&mut self) -> Result<&'static str, Error> {
+        if !self.config.weak_closures {
+            return Ok("");
+        }
+        self.expose_closure_finalization()?;
+        // Stateless closures have nothing to free, so they aren't registered.
+        Ok("if (!state.stateless) closureFinalization.register(real, state, state);")
+    }
+
+    fn expose_take_owned_view(&mut self, free: FunctionId) -> &'static str {
+        if !self.should_write_global("take_owned_view") {
+            return "takeOwnedView";
+        }
+        let free = self.export_name_of(free);
+        // The memory behind the view is freed either explicitly through its
+        // `free` method or, where supported, once the view is garbage
+        // collected.
+        self.global(&format!(
+            "
+            const ownedViewFinalization = typeof FinalizationRegistry === 'undefined'
+                ? {{ register: () => {{}}, unregister: () => {{}} }}
+                : new FinalizationRegistry(([ptr, len]) => wasm.{free}(ptr, len));
+
+            function takeOwnedView(view, ptr, len) {{
+                let freed = false;
+                ownedViewFinalization.register(view, [ptr, len], view);
+                Object.defineProperty(view, 'free', {{
+                    value: () => {{
+                        if (freed) return;
+                        freed = true;
+                        ownedViewFinalization.unregister(view);
+                        wasm.{free}(ptr, len);
+                    }},
+                }});
+                return view;
+            }}
+            ",
+            free = free,
+        ));
+        "takeOwnedView"
     }
 
     fn expose_handle_error(&mut self) -> Result<(), Error> {
@@ -1664,6 +2657,45 @@ impl<'a> Context<'a> {
         name
     }
 
+    fn expose_split_bigint128(&mut self) -> &'static str {
+        let name = "splitBigInt128";
+        if !self.should_write_global(name) {
+            return name;
+        }
+        self.global(
+            "
+            function splitBigInt128(n, signed) {
+                const truncated = signed ? BigInt.asIntN(128, n) : BigInt.asUintN(128, n);
+                if (truncated !== n) {
+                    throw new RangeError(`${n} is out of range for ${signed ? 'i128' : 'u128'}`);
+                }
+                const bits = BigInt.asUintN(128, n);
+                return [0, 32, 64, 96].map(shift => Number(BigInt.asUintN(32, bits >> BigInt(shift))));
+            }
+            ",
+        );
+        name
+    }
+
+    fn expose_join_bigint128(&mut self) -> &'static str {
+        let name = "joinBigInt128";
+        if !self.should_write_global(name) {
+            return name;
+        }
+        self.global(
+            "
+            function joinBigInt128(parts, signed) {
+                let n = BigInt(0);
+                for (let i = parts.length - 1; i >= 0; i--) {
+                    n = (n << BigInt(32)) | BigInt(parts[i] >>> 0);
+                }
+                return signed ? BigInt.asIntN(128, n) : n;
+            }
+            ",
+        );
+        name
+    }
+
     fn expose_int64_cvt_shim(&mut self) -> &'static str {
         let name = "int64CvtShim";
         if !self.should_write_global(name) {
@@ -1703,6 +2735,45 @@ impl<'a> Context<'a> {
         );
     }
 
+    /// Defines a helper which can be shared between modules.
+    ///
+    /// By default this is the same as `global`, but with `--shared-runtime`
+    /// the definition is instead placed in the shared runtime module and the
+    /// `export`ed name, if any, is imported from there.
+    fn runtime_global(&mut self, export: Option<&str>, s: &str) {
+        let module = match &self.config.shared_runtime {
+            Some(module) => module.clone(),
+            None => return self.global(s),
+        };
+        if let Some(name) = export {
+            self.js_imports
+                .entry(module)
+                .or_insert_with(Vec::new)
+                .push((name.to_string(), None));
+            self.defined_identifiers.insert(name.to_string(), 1);
+            self.runtime.push_str("export ");
+        }
+        self.runtime.push_str(s.trim());
+        self.runtime.push_str("\n\n");
+    }
+
+    /// With `--shared-runtime`, defines every shareable helper up front so
+    /// the runtime module is the same for all modules sharing it.
+    fn expose_shared_runtime(&mut self) -> Result<(), Error> {
+        if self.config.shared_runtime.is_none() {
+            return Ok(());
+        }
+        if !self.config.anyref {
+            self.expose_get_object();
+            self.expose_add_heap_object();
+            self.expose_take_object();
+            self.expose_borrowed_objects();
+        }
+        self.expose_text_encoder()?;
+        self.expose_text_decoder()?;
+        Ok(())
+    }
+
     fn global(&mut self, s: &str) {
         let s = s.trim();
 
@@ -1852,6 +2923,8 @@ impl<'a> Context<'a> {
     }
 
     pub fn generate(&mut self) -> Result<(), Error> {
+        self.expose_shared_runtime()?;
+
         for (id, adapter) in crate::sorted_iter(&self.wit.adapters) {
             let instrs = match &adapter.kind {
                 AdapterKind::Import { .. } => continue,
@@ -1863,6 +2936,11 @@ impl<'a> Context<'a> {
         let mut pairs = self.aux.export_map.iter().collect::<Vec<_>>();
         pairs.sort_by_key(|(k, _)| *k);
         check_duplicated_getter_and_setter_names(&pairs)?;
+        if self.config.camel_case {
+            check_camel_case_collisions(&pairs)?;
+        }
+
+        self.write_namespaces()?;
 
         for e in self.aux.enums.iter() {
             self.generate_enum(e)?;
@@ -1872,6 +2950,10 @@ impl<'a> Context<'a> {
             self.generate_struct(s)?;
         }
 
+        for c in self.aux.consts.iter() {
+            self.generate_const(c)?;
+        }
+
         self.typescript.push_str(&self.aux.extra_typescript);
 
         for path in self.aux.package_jsons.iter() {
@@ -1923,6 +3005,21 @@ impl<'a> Context<'a> {
         match kind {
             Kind::Export(export) => {
                 arg_names = &export.arg_names;
+                if let Some(ty) = &export.resolves_to {
+                    builder.resolves_to(ty);
+                }
+                // Without threads support the module's state is only valid on
+                // the thread which instantiated it, so in debug mode calls from
+                // elsewhere are caught before they corrupt it.
+                if builder.cx.asserts_instantiating_context() {
+                    builder.assert_context(&export.debug_name);
+                }
+                if builder.cx.config.warn_deprecated {
+                    let camel_case = builder.cx.config.camel_case;
+                    if let Some(warning) = stability_warning(export, camel_case) {
+                        builder.warn_once(warning);
+                    }
+                }
                 match &export.kind {
                     AuxExportKind::Function(_) => {}
                     AuxExportKind::StaticFunction { .. } => {}
@@ -1952,23 +3049,125 @@ impl<'a> Context<'a> {
                 Kind::Adapter => format!("failed to generates bindings for adapter"),
             })?;
         let ts = builder.typescript_signature();
-        let js_doc = builder.js_doc_comments();
+        let mut js_doc = builder.js_doc_comments();
+        // The builder borrows `self`, so take the TypeScript types it found
+        // before the rest of the export is generated.
+        let ts_ret = builder.ts_ret.take();
+        let ts_args = mem::replace(&mut builder.ts_args, Vec::new());
+        // Getters returning and setters taking an `Option` are typed as
+        // properties which may be `undefined`.
+        let ts_property = |arg: &binding::TypescriptArg| {
+            if arg.optional {
+                binding::ts_optional(&arg.ty)
+            } else {
+                arg.ty.clone()
+            }
+        };
+        if let Kind::Export(export) = kind {
+            match &export.stability {
+                AuxStability::Stable => {}
+                AuxStability::Unstable => js_doc.push_str("@experimental\n"),
+                AuxStability::Deprecated(Some(note)) => {
+                    // The note can't be allowed to end the doc comment early.
+                    let note = note.replace("*/", "*\\/");
+                    js_doc.push_str(&format!("@deprecated {}\n", note.trim()))
+                }
+                AuxStability::Deprecated(None) => js_doc.push_str("@deprecated\n"),
+            }
+        }
 
         // Once we've got all the JS then put it in the right location depending
         // on what's being exported.
         match kind {
             Kind::Export(export) => {
                 let docs = format_doc_comments(&export.comments, Some(js_doc));
+                let camel_case = self.config.camel_case && !export.renamed_via_js_name;
+                let js_name = |name: &str| {
+                    if camel_case {
+                        to_camel_case(name)
+                    } else {
+                        name.to_string()
+                    }
+                };
+                let (item, signature) = match &export.kind {
+                    AuxExportKind::Function(name) => match &export.js_namespace {
+                        Some(ns) => (format!("function {}.{}", ns, js_name(name)), ts.clone()),
+                        None => (format!("function {}", js_name(name)), ts.clone()),
+                    },
+                    AuxExportKind::Constructor(class) => {
+                        (format!("class {} constructor", class), ts.clone())
+                    }
+                    AuxExportKind::Getter { class, field, .. } => (
+                        format!("class {} get {}", class, js_name(field)),
+                        ts_property(ts_ret.as_ref().unwrap()),
+                    ),
+                    AuxExportKind::Setter { class, field } => (
+                        format!("class {} set {}", class, js_name(field)),
+                        ts_property(&ts_args[0]),
+                    ),
+                    AuxExportKind::StaticFunction { class, name } => (
+                        format!("class {} static {}", class, js_name(name)),
+                        ts.clone(),
+                    ),
+                    AuxExportKind::Method { class, name, .. } => (
+                        format!("class {} method {}", class, js_name(name)),
+                        ts.clone(),
+                    ),
+                };
+                // Overloads of a method are listed together under its name.
+                self.interface
+                    .entry(item)
+                    .and_modify(|prev| {
+                        prev.push_str("; ");
+                        prev.push_str(&signature);
+                    })
+                    .or_insert(signature);
                 match &export.kind {
+                    AuxExportKind::Function(name) if export.js_namespace.is_some() => {
+                        let name = js_name(name);
+                        let namespace = export.js_namespace.as_ref().unwrap();
+                        let local = generate_identifier(
+                            &format!("{}_{}", namespace, name),
+                            &mut self.defined_identifiers,
+                        );
+                        self.globals.push_str(&docs);
+                        self.globals.push_str(&format!("function {}{}\n", local, js));
+                        let ns = self
+                            .exported_namespaces
+                            .entry(namespace.clone())
+                            .or_default();
+                        ns.typescript.push_str(&docs);
+                        ns.typescript
+                            .push_str(&format!("export function {}{};\n", name, ts));
+                        ns.functions.push((name, local));
+                    }
                     AuxExportKind::Function(name) => {
-                        self.export(&name, &format!("function{}", js), Some(docs))?;
+                        let name = js_name(name);
+                        self.globals.push_str(&docs);
+                        self.export(&name, &format!("function{}", js), None)?;
                         self.globals.push_str("\n");
-                        self.typescript.push_str("export function ");
-                        self.typescript.push_str(&name);
-                        self.typescript.push_str(&ts);
-                        self.typescript.push_str(";\n");
+                        let dst = self.typescript_dst(export.cfg_feature.as_ref());
+                        dst.push_str(&docs);
+                        dst.push_str("export function ");
+                        dst.push_str(&name);
+                        dst.push_str(&ts);
+                        dst.push_str(";\n");
                     }
                     AuxExportKind::Constructor(class) => {
+                        // The browser constructs custom elements itself, without
+                        // passing any arguments.
+                        let custom_element = self
+                            .aux
+                            .structs
+                            .iter()
+                            .any(|s| s.name == *class && s.custom_element.is_some());
+                        if custom_element && !ts_args.is_empty() {
+                            bail!(
+                                "custom element class `{}` must have a \
+                                 `#[wasm_bindgen(constructor)]` taking no arguments",
+                                class
+                            );
+                        }
                         let exported = require_class(&mut self.exported_classes, class);
                         if exported.has_constructor {
                             bail!("found duplicate constructor for class `{}`", class);
@@ -1976,23 +3175,90 @@ impl<'a> Context<'a> {
                         exported.has_constructor = true;
                         exported.push(&docs, "constructor", "", &js, &ts);
                     }
-                    AuxExportKind::Getter { class, field } => {
-                        let ret_ty = builder.ts_ret.as_ref().unwrap().ty.clone();
+                    AuxExportKind::Getter {
+                        class,
+                        field,
+                        cached,
+                    } => {
+                        let ret_ty = ts_property(ts_ret.as_ref().unwrap());
+                        let name = js_name(field);
                         let exported = require_class(&mut self.exported_classes, class);
-                        exported.push_getter(&docs, field, &js, &ret_ty);
+                        if *cached {
+                            exported.cached_properties.push(name.clone());
+                            // The first read replaces the getter with a plain
+                            // property on the object holding the value, so
+                            // later reads don't call into wasm at all.
+                            let getter = format!("__wbg_cached_{}", name);
+                            exported.contents.push_str(&format!("{}{}\n", getter, js));
+                            let js = format!(
+                                "() {{
+                                    const value = this.{}();
+                                    Object.defineProperty(this, '{}', {{ value }});
+                                    return value;
+                                }}",
+                                getter, name,
+                            );
+                            exported.push_getter(&docs, &name, &js, &ret_ty);
+                        } else {
+                            exported.push_getter(&docs, &name, &js, &ret_ty);
+                        }
                     }
                     AuxExportKind::Setter { class, field } => {
-                        let arg_ty = builder.ts_args[0].ty.clone();
+                        let arg_ty = ts_property(&ts_args[0]);
                         let exported = require_class(&mut self.exported_classes, class);
-                        exported.push_setter(&docs, field, &js, &arg_ty);
+                        exported.push_setter(&docs, &js_name(field), &js, &arg_ty);
                     }
                     AuxExportKind::StaticFunction { class, name } => {
                         let exported = require_class(&mut self.exported_classes, class);
-                        exported.push(&docs, name, "static ", &js, &ts);
+                        let matches = overload_matches(&ts_args);
+                        exported.push_method(&docs, &js_name(name), "static ", &js, &ts, matches);
                     }
-                    AuxExportKind::Method { class, name, .. } => {
+                    AuxExportKind::Method {
+                        class,
+                        name,
+                        iterator,
+                        observed_attribute,
+                        ..
+                    } => {
                         let exported = require_class(&mut self.exported_classes, class);
-                        exported.push(&docs, name, "", &js, &ts);
+                        if name == "toJSON" {
+                            exported.has_to_json = true;
+                        }
+                        if let Some(attribute) = observed_attribute {
+                            exported
+                                .observed_attributes
+                                .push((attribute.clone(), js_name(name)));
+                        }
+                        // An iterator method named `next` makes instances of the
+                        // class iterators themselves, so the Rust method is kept
+                        // under another name and `next` follows the JS protocol.
+                        let is_iterator = *iterator && js_name(name) == "next";
+                        if *iterator {
+                            if exported.iterator.is_some() {
+                                bail!(
+                                    "class `{}` has more than one `#[wasm_bindgen(iterator)]` \
+                                     method",
+                                    class
+                                );
+                            }
+                            let item_ty = match &ts_ret {
+                                Some(ret) => ret.ty.clone(),
+                                None => "any".to_string(),
+                            };
+                            let method = if is_iterator {
+                                "__wbg_next".to_string()
+                            } else {
+                                js_name(name)
+                            };
+                            exported.iterator = Some((method, item_ty));
+                            exported.is_iterator = is_iterator;
+                        }
+                        if is_iterator {
+                            exported.contents.push_str(&format!("__wbg_next{}\n", js));
+                        } else {
+                            let matches = overload_matches(&ts_args);
+                            exported.push_method(&docs, &js_name(name), "", &js, &ts, matches);
+                        }
                     }
                 }
             }
@@ -2001,6 +3267,10 @@ impl<'a> Context<'a> {
                     .insert(core, format!("function{}", js));
             }
             Kind::Adapter => {
+                // The leading two arguments are the closure's data and vtable
+                // pointers which are filled in by the JS glue.
+                let ts = binding::closure_typescript(&ts_args[2..], ts_ret.as_ref());
+                self.closure_typescript.insert(id, ts);
                 self.globals.push_str("function ");
                 self.globals.push_str(&self.adapter_name(id));
                 self.globals.push_str(&js);
@@ -2313,73 +3583,49 @@ impl<'a> Context<'a> {
                 dtor,
                 mutable,
                 adapter,
-                nargs,
+                debug_name,
+                creator,
             } => {
                 assert!(kind == AdapterJsImportKind::Normal);
                 assert!(!variadic);
                 assert_eq!(args.len(), 3);
-                let arg_names = (0..*nargs)
-                    .map(|i| format!("arg{}", i))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                let mut js = format!("({}) => {{\n", arg_names);
-                // First up with a closure we increment the internal reference
-                // count. This ensures that the Rust closure environment won't
-                // be deallocated while we're invoking it.
-                js.push_str("state.cnt++;\n");
-
-                let table = self.export_function_table()?;
-                let dtor = format!("wasm.{}.get({})", table, dtor);
-                let call = self.adapter_name(*adapter);
-
-                if *mutable {
-                    // For mutable closures they can't be invoked recursively.
-                    // To handle that we swap out the `this.a` pointer with zero
-                    // while we invoke it. If we finish and the closure wasn't
-                    // destroyed, then we put back the pointer so a future
-                    // invocation can succeed.
-                    js.push_str("const a = state.a;\n");
-                    js.push_str("state.a = 0;\n");
-                    js.push_str("try {\n");
-                    js.push_str(&format!("return {}(a, state.b, {});\n", call, arg_names));
-                    js.push_str("} finally {\n");
-                    js.push_str("if (--state.cnt === 0) ");
-                    js.push_str(&dtor);
-                    js.push_str("(a, state.b);\n");
-                    js.push_str("else state.a = a;\n");
-                    js.push_str("}\n");
+                // All closures are manufactured by one of two shared
+                // factories, so each closure type only costs a call here.
+                let make = if *mutable {
+                    self.expose_make_mut_closure()?
                 } else {
-                    // For shared closures they can be invoked recursively so we
-                    // just immediately pass through `this.a`. If we end up
-                    // executing the destructor, however, we clear out the
-                    // `this.a` pointer to prevent it being used again the
-                    // future.
-                    js.push_str("try {\n");
-                    js.push_str(&format!(
-                        "return {}(state.a, state.b, {});\n",
-                        call, arg_names
-                    ));
-                    js.push_str("} finally {\n");
-                    js.push_str("if (--state.cnt === 0) {\n");
-                    js.push_str(&dtor);
-                    js.push_str("(state.a, state.b);\n");
-                    js.push_str("state.a = 0;\n");
-                    js.push_str("}\n");
-                    js.push_str("}\n");
-                }
-                js.push_str("}\n");
-
-                prelude.push_str(&format!(
-                    "
-                        const state = {{ a: {arg0}, b: {arg1}, cnt: 1 }};
-                        const real = {body};
-                        real.original = state;
-                    ",
-                    body = js,
-                    arg0 = &args[0],
-                    arg1 = &args[1],
-                ));
-                Ok("real".to_string())
+                    self.expose_make_closure()?
+                };
+                // In debug mode errors from invoking the closure name its
+                // type to help track down which closure it was. The JS
+                // function is also wrapped in one named after the Rust
+                // function creating it, defined right here so each creator
+                // has its own function in the source and therefore its own
+                // entry in profiles.
+                let name = if self.config.debug {
+                    let wrap = match creator {
+                        Some(creator) => {
+                            let creator = serde_json::to_string(creator)?;
+                            format!(
+                                "real => ({{ {0}: (...args) => real(...args) }})[{0}]",
+                                creator
+                            )
+                        }
+                        None => "undefined".to_string(),
+                    };
+                    format!(", '{}', {}", debug_name, wrap)
+                } else {
+                    String::new()
+                };
+                Ok(format!(
+                    "{}({}, {}, {}, {}{})",
+                    make,
+                    args[0],
+                    args[1],
+                    dtor,
+                    self.adapter_name(*adapter),
+                    name,
+                ))
             }
 
             AuxImport::StructuralMethod(name) => {
@@ -2474,6 +3720,33 @@ impl<'a> Context<'a> {
                 Ok(format!("{}.__wrap({})", class, args[0]))
             }
 
+            AuxImport::InstanceofExportedClass(class) => {
+                assert!(kind == AdapterJsImportKind::Normal);
+                assert!(!variadic);
+                assert_eq!(args.len(), 1);
+                require_class(&mut self.exported_classes, class);
+                Ok(format!("{} instanceof {}", args[0], class))
+            }
+
+            AuxImport::LinkTo(module) => {
+                assert!(kind == AdapterJsImportKind::Normal);
+                assert!(!variadic);
+                assert_eq!(args.len(), 0);
+                let path = self.config.local_module_name(module);
+                match self.config.mode {
+                    OutputMode::Node {
+                        experimental_modules: false,
+                    } => Ok(format!(
+                        "require('url').pathToFileURL(require('path').join(__dirname, '{}')).toString()",
+                        path
+                    )),
+                    OutputMode::NoModules { .. } => {
+                        bail!("`link_to!` is not supported with `--target no-modules`")
+                    }
+                    _ => Ok(format!("new URL('{}', import.meta.url).toString()", path)),
+                }
+            }
+
             AuxImport::Intrinsic(intrinsic) => {
                 assert!(kind == AdapterJsImportKind::Normal);
                 assert!(!variadic);
@@ -2547,6 +3820,10 @@ impl<'a> Context<'a> {
                 prelude.push_str(&format!("const obj = {}.original;\n", args[0]));
                 prelude.push_str("if (obj.cnt-- == 1) {\n");
                 prelude.push_str("obj.a = 0;\n");
+                if self.config.weak_closures {
+                    self.expose_closure_finalization()?;
+                    prelude.push_str("closureFinalization.unregister(obj);\n");
+                }
                 prelude.push_str("return true;\n");
                 prelude.push_str("}\n");
                 "false".to_string()
@@ -2557,6 +3834,56 @@ impl<'a> Context<'a> {
                 args[0].clone()
             }
 
+            // With `--weak-closures` every closure is already registered when
+            // it's created, so it must not be registered a second time.
+            Intrinsic::CallbackForgetWeak => {
+                assert_eq!(args.len(), 1);
+                if self.config.weak_closures {
+                    args[0].clone()
+                } else {
+                    self.expose_closure_finalization()?;
+                    prelude.push_str(&format!("const f = {};\n", args[0]));
+                    "closureFinalization.register(f, f.original, f.original)".to_string()
+                }
+            }
+
+            Intrinsic::QueueMicrotask => {
+                assert_eq!(args.len(), 1);
+                self.expose_queue_microtask();
+                format!("queueMicrotaskShim({})", args[0])
+            }
+
+            Intrinsic::QueueMacrotask => {
+                assert_eq!(args.len(), 1);
+                self.expose_queue_macrotask();
+                format!("queueMacrotaskShim({})", args[0])
+            }
+
+            Intrinsic::PromiseThen => {
+                assert_eq!(args.len(), 3);
+                format!(
+                    "Promise.resolve({}).then({}, {})",
+                    args[0], args[1], args[2]
+                )
+            }
+
+            Intrinsic::PerformanceNow => {
+                assert_eq!(args.len(), 0);
+                self.expose_performance_now();
+                "performanceNow()".to_string()
+            }
+
+            Intrinsic::DateNow => {
+                assert_eq!(args.len(), 0);
+                "Date.now()".to_string()
+            }
+
+            Intrinsic::RandomFill => {
+                assert_eq!(args.len(), 1);
+                self.expose_random_fill()?;
+                format!("randomFill({})", args[0])
+            }
+
             Intrinsic::NumberNew => {
                 assert_eq!(args.len(), 1);
                 args[0].clone()
@@ -2589,6 +3916,32 @@ impl<'a> Context<'a> {
                 format!("typeof(obj) === 'string' ? obj : undefined")
             }
 
+            Intrinsic::BigIntGetAsString => {
+                assert_eq!(args.len(), 1);
+                prelude.push_str(&format!("const obj = {};\n", args[0]));
+                format!("typeof(obj) === 'bigint' ? obj.toString() : undefined")
+            }
+
+            Intrinsic::StringGetUtf16 => {
+                assert_eq!(args.len(), 1);
+                // The code units are copied out of the string in one pass, and
+                // the array is then copied into wasm memory in one go.
+                prelude.push_str(&format!(
+                    "
+                    const obj = {};
+                    let units;
+                    if (typeof(obj) === 'string') {{
+                        units = new Uint16Array(obj.length);
+                        for (let i = 0; i < obj.length; i++) {{
+                            units[i] = obj.charCodeAt(i);
+                        }}
+                    }}
+                    ",
+                    args[0]
+                ));
+                format!("units")
+            }
+
             Intrinsic::BooleanGet => {
                 assert_eq!(args.len(), 1);
                 prelude.push_str(&format!("const v = {};\n", args[0]));
@@ -2605,6 +3958,19 @@ impl<'a> Context<'a> {
                 format!("throw {}", args[0])
             }
 
+            Intrinsic::TryCatch => {
+                assert_eq!(args.len(), 3);
+                format!(
+                    "Reflect.apply({}, {}, {2} === undefined ? [] : {2})",
+                    args[0], args[1], args[2]
+                )
+            }
+
+            Intrinsic::FunctionCall => {
+                assert_eq!(args.len(), 2);
+                format!("{}(...{})", args[0], args[1])
+            }
+
             Intrinsic::Module => {
                 assert_eq!(args.len(), 0);
                 if !self.config.mode.no_modules() && !self.config.mode.web() {
@@ -2645,6 +4011,59 @@ impl<'a> Context<'a> {
                 format!("debugString({})", args[0])
             }
 
+            Intrinsic::ObjectId => {
+                assert_eq!(args.len(), 1);
+                self.expose_object_id();
+                format!("objectId({})", args[0])
+            }
+
+            Intrinsic::WeakRefNew => {
+                assert_eq!(args.len(), 1);
+                prelude.push_str(&format!("const val = {};\n", args[0]));
+                prelude.push_str(
+                    "const isObject = typeof(val) === 'function' || \
+                     (typeof(val) === 'object' && val !== null);\n",
+                );
+                "typeof(WeakRef) === 'function' && isObject ? new WeakRef(val) : undefined"
+                    .to_string()
+            }
+
+            Intrinsic::WeakRefDeref => {
+                assert_eq!(args.len(), 1);
+                format!("{}.deref()", args[0])
+            }
+
+            Intrinsic::ObjectGet => {
+                assert_eq!(args.len(), 2);
+                format!("{}[{}]", args[0], args[1])
+            }
+
+            Intrinsic::ObjectSet => {
+                assert_eq!(args.len(), 3);
+                format!("{}[{}] = {}", args[0], args[1], args[2])
+            }
+
+            Intrinsic::ObjectGetPath => {
+                assert_eq!(args.len(), 3);
+                prelude.push_str(&format!("let cur = {};\n", args[0]));
+                prelude.push_str(&format!("const keys = {};\n", args[1]));
+                prelude.push_str(&format!("const lens = {};\n", args[2]));
+                prelude.push_str(
+                    "
+                        let start = 0;
+                        for (let i = 0; i < lens.length; i++) {
+                            if (cur === undefined || cur === null) {
+                                break;
+                            }
+                            const end = start + lens[i];
+                            cur = cur[keys.slice(start, end)];
+                            start = end;
+                        }
+                    ",
+                );
+                "cur".to_string()
+            }
+
             Intrinsic::JsonParse => {
                 assert_eq!(args.len(), 1);
                 format!("JSON.parse({})", args[0])
@@ -2661,17 +4080,23 @@ impl<'a> Context<'a> {
 
             Intrinsic::AnyrefHeapLiveCount => {
                 assert_eq!(args.len(), 0);
-                self.expose_global_heap();
-                prelude.push_str(
+                self.expose_global_heap_next();
+                let heap_next = if self.config.shared_runtime.is_some() {
+                    "getHeapNext()"
+                } else {
+                    "heap_next"
+                };
+                prelude.push_str(&format!(
                     "
                         let free_count = 0;
-                        let next = heap_next;
-                        while (next < heap.length) {
+                        let next = {};
+                        while (next < heap.length) {{
                             free_count += 1;
                             next = heap[next];
-                        }
+                        }}
                     ",
-                );
+                    heap_next,
+                ));
                 format!(
                     "heap.length - free_count - {} - {}",
                     INITIAL_HEAP_OFFSET,
@@ -2709,27 +4134,67 @@ impl<'a> Context<'a> {
 
     fn generate_enum(&mut self, enum_: &AuxEnum) -> Result<(), Error> {
         let mut variants = String::new();
-
-        self.typescript
-            .push_str(&format!("export enum {} {{", enum_.name));
+        let mut ts = format!("export enum {} {{", enum_.name);
+        let mut signature = Vec::new();
         for (name, value) in enum_.variants.iter() {
-            variants.push_str(&format!("{}:{},", name, value));
-            self.typescript.push_str(&format!("\n  {},", name));
+            match enum_.repr {
+                AuxEnumRepr::Number => {
+                    variants.push_str(&format!("{}:{},", name, value));
+                    ts.push_str(&format!("\n  {},", name));
+                    signature.push(format!("{} = {}", name, value));
+                }
+                AuxEnumRepr::String => {
+                    variants.push_str(&format!("{}:\"{}\",", name, name));
+                    ts.push_str(&format!("\n  {} = \"{}\",", name, name));
+                    signature.push(format!("{} = \"{}\"", name, name));
+                }
+                // TypeScript's numeric enums already map values back to
+                // names, so only the JS object needs the extra entries.
+                AuxEnumRepr::Both => {
+                    variants.push_str(&format!("{}:{},\"{}\":\"{}\",", name, value, value, name));
+                    ts.push_str(&format!("\n  {} = {},", name, value));
+                    signature.push(format!("{} = {}", name, value));
+                }
+            }
         }
-        self.typescript.push_str("\n}\n");
+        self.interface
+            .insert(format!("enum {}", enum_.name), signature.join(", "));
+        ts.push_str("\n}\n");
+
+        let docs = format_doc_comments(&enum_.comments, None);
+        let dst = self.type_typescript_dst(&enum_.name, enum_.cfg_feature.as_ref());
+        dst.push_str(&ts);
+        dst.push_str(&docs);
+        self.globals.push_str(&docs);
         self.export(
             &enum_.name,
             &format!("Object.freeze({{ {} }})", variants),
-            Some(format_doc_comments(&enum_.comments, None)),
+            None,
         )?;
 
         Ok(())
     }
 
+    fn generate_const(&mut self, const_: &AuxConst) -> Result<(), Error> {
+        self.interface
+            .insert(format!("const {}", const_.name), const_.ts_type.to_string());
+        let docs = format_doc_comments(&const_.comments, None);
+        self.typescript.push_str(&docs);
+        self.typescript.push_str(&format!(
+            "export const {}: {};\n",
+            const_.name, const_.ts_type
+        ));
+        self.globals.push_str(&docs);
+        self.export(&const_.name, &const_.value, None)
+    }
+
     fn generate_struct(&mut self, struct_: &AuxStruct) -> Result<(), Error> {
         let class = require_class(&mut self.exported_classes, &struct_.name);
         class.comments = format_doc_comments(&struct_.comments, None);
         class.is_inspectable = struct_.is_inspectable;
+        class.cfg_feature = struct_.cfg_feature.clone();
+        class.extends_js = struct_.extends_js.clone();
+        class.custom_element = struct_.custom_element.clone();
         Ok(())
     }
 
@@ -2872,6 +4337,258 @@ impl<'a> Context<'a> {
         );
     }
 
+    /// Wraps each import taking or returning pointers into a 64-bit memory,
+    /// which are BigInts in JS, to work with them as numbers instead like the
+    /// rest of the generated JS does.
+    fn wrap_memory64_imports(&mut self) {
+        let mut wrapped = Vec::new();
+        for (id, js) in self.wasm_import_definitions.iter() {
+            let func = match self.module.imports.get(*id).kind {
+                walrus::ImportKind::Function(f) => f,
+                _ => continue,
+            };
+            let ty = self.module.types.get(self.module.funcs.get(func).ty());
+            let is_word = |ty: &walrus::ValType| *ty == walrus::ValType::I64;
+            if !ty.params().iter().any(is_word) && !ty.results().iter().any(is_word) {
+                continue;
+            }
+            let returns_word = ty.results().iter().any(is_word);
+            wrapped.push((
+                *id,
+                format!("memory64Import({}, {})", js.trim(), returns_word),
+            ));
+        }
+        self.wasm_import_definitions.extend(wrapped);
+    }
+
+    /// Returns the JS expression for the wasm module's exports given the
+    /// `exports` of its instance, wrapping the functions which take or return
+    /// pointers into a 64-bit memory so they can be called with numbers.
+    fn wasm_exports(&mut self, exports: &str) -> String {
+        if !self.config.memory64 {
+            return exports.to_string();
+        }
+        let mut words = Vec::new();
+        for export in self.module.exports.iter() {
+            let func = match export.item {
+                walrus::ExportItem::Function(f) => f,
+                _ => continue,
+            };
+            let ty = self.module.types.get(self.module.funcs.get(func).ty());
+            let params = ty
+                .params()
+                .iter()
+                .enumerate()
+                .filter(|(_, ty)| **ty == walrus::ValType::I64)
+                .map(|(i, _)| i.to_string())
+                .collect::<Vec<_>>();
+            let returns_word = ty.results().iter().any(|ty| *ty == walrus::ValType::I64);
+            if params.is_empty() && !returns_word {
+                continue;
+            }
+            words.push(format!(
+                "{}: [[{}], {}]",
+                export.name,
+                params.join(", "),
+                returns_word
+            ));
+        }
+        if words.is_empty() {
+            return exports.to_string();
+        }
+        format!("memory64Exports({}, {{ {} }})", exports, words.join(", "))
+    }
+
+    fn expose_memory64_shims(&mut self) {
+        if !self.should_write_global("memory64_shims") {
+            return;
+        }
+
+        // Pointers into 64-bit memories are always far below
+        // `Number.MAX_SAFE_INTEGER`, so they're converted to and from numbers
+        // at the boundary.
+        self.global(
+            "
+            function memory64Import(f, returnsWord) {
+                return function() {
+                    const args = Array.prototype.map.call(arguments, arg => {
+                        return typeof arg === 'bigint' ? Number(arg) : arg;
+                    });
+                    const ret = f.apply(this, args);
+                    return returnsWord ? BigInt(ret) : ret;
+                };
+            }
+
+            function memory64Exports(exports, words) {
+                const wrapped = Object.assign({}, exports);
+                for (const name in words) {
+                    const f = exports[name];
+                    const [params, returnsWord] = words[name];
+                    wrapped[name] = function() {
+                        const args = Array.prototype.slice.call(arguments);
+                        for (const i of params) {
+                            args[i] = BigInt(args[i]);
+                        }
+                        const ret = f.apply(null, args);
+                        return returnsWord ? Number(ret) : ret;
+                    };
+                }
+                return wrapped;
+            }
+            ",
+        );
+    }
+
+    fn expose_queue_microtask(&mut self) {
+        if !self.should_write_global("queue_microtask") {
+            return;
+        }
+
+        self.global(
+            "
+            const queueMicrotaskShim = typeof queueMicrotask === 'function'
+                ? f => queueMicrotask(f)
+                : f => { Promise.resolve().then(f); };
+            ",
+        );
+    }
+
+    fn expose_performance_now(&mut self) {
+        if !self.should_write_global("performance_now") {
+            return;
+        }
+
+        // Some environments, like older versions of Node.js, don't have a
+        // global `performance`, in which case the wall clock is the best we
+        // can do.
+        self.global(
+            "
+            const performanceNow = typeof performance === 'object' && typeof performance.now === 'function'
+                ? () => performance.now()
+                : () => Date.now();
+            ",
+        );
+    }
+
+    fn expose_random_fill(&mut self) -> Result<(), Error> {
+        if !self.should_write_global("random_fill") {
+            return Ok(());
+        }
+
+        // Node.js only has `crypto.getRandomValues` as a global in recent
+        // versions, but has always had `randomFillSync` in its `crypto`
+        // module.
+        let fill = if self.config.mode.nodejs() {
+            let name = self.import_name(&JsImport {
+                name: JsImportName::Module {
+                    module: "crypto".to_string(),
+                    name: "randomFillSync".to_string(),
+                },
+                fields: Vec::new(),
+            })?;
+            format!("buf => {}(buf)", name)
+        } else if !self.config.mode.always_run_in_browser() {
+            // Bundlers would try to resolve a literal `require('crypto')`, so
+            // as with the `getrandom` crate it's only loaded through
+            // `module.require` when running in Node.js.
+            "typeof crypto === 'object' && typeof crypto.getRandomValues === 'function' \
+                ? buf => crypto.getRandomValues(buf) \
+                : typeof module === 'object' && typeof module.require === 'function' \
+                ? module.require('crypto').randomFillSync \
+                : () => { throw new Error('no source of randomness is available'); }"
+                .to_string()
+        } else {
+            "buf => crypto.getRandomValues(buf)".to_string()
+        };
+
+        // `getRandomValues` fills at most 65536 bytes at a time and can't fill
+        // views of shared memory, so the bytes are generated in chunks in a
+        // separate buffer. Errors are returned rather than thrown so Rust can
+        // handle a missing source of randomness.
+        self.global(&format!(
+            "
+            function randomFill(buf) {{
+                try {{
+                    const fill = {};
+                    const chunk = new Uint8Array(Math.min(buf.length, 65536));
+                    for (let i = 0; i < buf.length; i += chunk.length) {{
+                        const view = chunk.subarray(0, Math.min(chunk.length, buf.length - i));
+                        fill(view);
+                        buf.set(view, i);
+                    }}
+                }} catch (e) {{
+                    return e;
+                }}
+            }}
+            ",
+            fill
+        ));
+        Ok(())
+    }
+
+    fn expose_queue_macrotask(&mut self) {
+        if !self.should_write_global("queue_macrotask") {
+            return;
+        }
+
+        // `setImmediate` is only in Node.js, and `setTimeout` is clamped to a
+        // minimum delay in browsers, so prefer posting to a `MessageChannel`
+        // there to run the next task as soon as possible.
+        self.global(
+            "
+            const queueMacrotaskShim = (() => {
+                if (typeof setImmediate === 'function') {
+                    return f => { setImmediate(f); };
+                }
+                if (typeof MessageChannel === 'function') {
+                    const channel = new MessageChannel();
+                    const tasks = [];
+                    channel.port1.onmessage = () => tasks.shift()();
+                    return f => {
+                        tasks.push(f);
+                        channel.port2.postMessage(undefined);
+                    };
+                }
+                return f => { setTimeout(f, 0); };
+            })();
+            ",
+        );
+    }
+
+    fn expose_object_id(&mut self) {
+        if !self.should_write_global("object_id") {
+            return;
+        }
+
+        self.global(
+            "
+            const objectIds = new WeakMap();
+            let nextObjectId = 1;
+
+            function objectId(val) {
+                const type = typeof val;
+                if ((type == 'object' && val !== null) || type == 'function') {
+                    let id = objectIds.get(val);
+                    if (id === undefined) {
+                        id = nextObjectId;
+                        nextObjectId = (nextObjectId + 1) | 0;
+                        objectIds.set(val, id);
+                    }
+                    return id;
+                }
+                // Primitives can't be stored in a `WeakMap`, but are compared
+                // by value anyway, so hash their string representation.
+                const s = type + ':' + String(val);
+                let hash = 0;
+                for (let i = 0; i < s.length; i++) {
+                    hash = (Math.imul(hash, 31) + s.charCodeAt(i)) | 0;
+                }
+                return hash;
+            }
+            ",
+        );
+    }
+
     fn export_function_table(&mut self) -> Result<String, Error> {
         match self.module.tables.main_function_table()? {
             Some(id) => Ok(self.export_name_of(id)),
@@ -2924,6 +4641,25 @@ impl<'a> Context<'a> {
     fn adapter_name(&self, id: AdapterId) -> String {
         format!("__wbg_adapter_{}", id.0)
     }
+
+    /// Returns the TypeScript function type of the Rust closures which JS calls
+    /// through the adapter `id`.
+    ///
+    /// A closure's adapter is registered before the adapters it's passed to JS
+    /// from, so it's always generated first.
+    fn closure_typescript_of(&self, id: AdapterId) -> String {
+        self.closure_typescript[&id].clone()
+    }
+}
+
+/// Returns whether `ts` mentions the identifier `name`, rather than just some
+/// identifier containing it.
+fn mentions_identifier(ts: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    ts.match_indices(name).any(|(i, _)| {
+        !ts[..i].chars().next_back().map_or(false, is_ident)
+            && !ts[i + name.len()..].chars().next().map_or(false, is_ident)
+    })
 }
 
 fn check_duplicated_getter_and_setter_names(
@@ -2948,10 +4684,12 @@ fn check_duplicated_getter_and_setter_names(
                     AuxExportKind::Getter {
                         class: first_class,
                         field: first_field,
+                        ..
                     },
                     AuxExportKind::Getter {
                         class: second_class,
                         field: second_field,
+                        ..
                     },
                 ) => verify_exports(first_class, first_field, second_class, second_field)?,
                 (
@@ -2971,6 +4709,47 @@ fn check_duplicated_getter_and_setter_names(
     Ok(())
 }
 
+/// Checks that `--camel-case` doesn't give two exports which are named
+/// differently in Rust the same JS name, like `foo_bar` and `fooBar`, as one
+/// would silently replace the other.
+fn check_camel_case_collisions(exports: &[(&AdapterId, &AuxExport)]) -> Result<(), Error> {
+    let mut names = HashMap::new();
+    for (_, export) in exports {
+        let (scope, name) = match &export.kind {
+            AuxExportKind::Function(name) => match &export.js_namespace {
+                Some(ns) => (format!("namespace `{}`", ns), name),
+                None => ("the module".to_string(), name),
+            },
+            AuxExportKind::StaticFunction { class, name } => {
+                (format!("the static methods of `{}`", class), name)
+            }
+            AuxExportKind::Method { class, name, .. } => (format!("class `{}`", class), name),
+            AuxExportKind::Getter { class, field, .. } | AuxExportKind::Setter { class, field } => {
+                (format!("class `{}`", class), field)
+            }
+            AuxExportKind::Constructor(_) => continue,
+        };
+        let js_name = if export.renamed_via_js_name {
+            name.clone()
+        } else {
+            to_camel_case(name)
+        };
+        let prev = names.insert((scope.clone(), js_name.clone()), name);
+        if let Some(prev) = prev {
+            if prev != name {
+                bail!(
+                    "`{}` and `{}` are both exported as `{}` from {} with `--camel-case`",
+                    prev,
+                    name,
+                    js_name,
+                    scope
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 fn generate_identifier(name: &str, used_names: &mut HashMap<String, usize>) -> String {
     let cnt = used_names.entry(name.to_string()).or_insert(0);
     *cnt += 1;
@@ -2983,10 +4762,116 @@ fn generate_identifier(name: &str, used_names: &mut HashMap<String, usize>) -> S
     }
 }
 
+/// Converts a snake_case identifier to camelCase, preserving any leading or
+/// trailing underscores.
+fn to_camel_case(name: &str) -> String {
+    let start = name.len() - name.trim_start_matches('_').len();
+    let end = name.trim_end_matches('_').len().max(start);
+    let mut ret = name[..start].to_string();
+    let mut upper = false;
+    for c in name[start..end].chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            ret.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            ret.push(c);
+        }
+    }
+    ret.push_str(&name[end..]);
+    ret
+}
+
+/// Returns the message to warn with the first time the export is called if it
+/// has been marked as unstable or deprecated.
+fn stability_warning(export: &AuxExport, camel_case: bool) -> Option<String> {
+    let js_name = |name: &str| {
+        if camel_case && !export.renamed_via_js_name {
+            to_camel_case(name)
+        } else {
+            name.to_string()
+        }
+    };
+    let name = match &export.kind {
+        AuxExportKind::Function(name) => match &export.js_namespace {
+            Some(ns) => format!("{}.{}", ns, js_name(name)),
+            None => js_name(name),
+        },
+        AuxExportKind::Constructor(class) => format!("new {}", class),
+        AuxExportKind::Getter { class, field, .. } | AuxExportKind::Setter { class, field } => {
+            format!("{}.prototype.{}", class, js_name(field))
+        }
+        AuxExportKind::StaticFunction { class, name } => format!("{}.{}", class, js_name(name)),
+        AuxExportKind::Method { class, name, .. } => {
+            format!("{}.prototype.{}", class, js_name(name))
+        }
+    };
+    match &export.stability {
+        AuxStability::Stable => None,
+        AuxStability::Unstable => Some(format!(
+            "`{}` is unstable and may change without notice",
+            name
+        )),
+        AuxStability::Deprecated(Some(note)) => Some(format!("`{}` is deprecated: {}", name, note)),
+        AuxStability::Deprecated(None) => Some(format!("`{}` is deprecated", name)),
+    }
+}
+
+/// Returns a JS expression which tests whether the arguments of a call, found
+/// in `args`, can be passed to a function with the TypeScript arguments
+/// `ts_args`. This is used to pick between overloaded methods.
+fn overload_matches(ts_args: &[binding::TypescriptArg]) -> String {
+    // Trailing optional arguments may be omitted by the caller.
+    let required = ts_args
+        .iter()
+        .rposition(|arg| !arg.optional)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let mut checks = Vec::new();
+    if required == ts_args.len() {
+        checks.push(format!("args.length === {}", ts_args.len()));
+    } else {
+        if required > 0 {
+            checks.push(format!("args.length >= {}", required));
+        }
+        checks.push(format!("args.length <= {}", ts_args.len()));
+    }
+    for (i, arg) in ts_args.iter().enumerate() {
+        let val = format!("args[{}]", i);
+        let ty = arg.ty.trim_end_matches(" | undefined");
+        let check = match ty {
+            "number" | "string" | "boolean" | "symbol" => {
+                format!("typeof({}) === '{}'", val, ty)
+            }
+            "BigInt" => format!("typeof({}) === 'bigint'", val),
+            "Function" => format!("typeof({}) === 'function'", val),
+            _ if ty.ends_with("[]") => format!("Array.isArray({})", val),
+            // Typed arrays and exported classes
+            _ if ty.starts_with(|c: char| c.is_ascii_uppercase())
+                && ty.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                format!("{} instanceof {}", val, ty)
+            }
+            _ => continue,
+        };
+        if arg.optional {
+            checks.push(format!(
+                "({v} === undefined || {v} === null || {})",
+                check,
+                v = val
+            ));
+        } else {
+            checks.push(check);
+        }
+    }
+    checks.join(" && ")
+}
+
 fn format_doc_comments(comments: &str, js_doc_comments: Option<String>) -> String {
     let body: String = comments.lines().map(|c| format!("*{}\n", c)).collect();
     let doc = if let Some(docs) = js_doc_comments {
-        docs.lines().map(|l| format!("* {} \n", l)).collect()
+        docs.lines().map(|l| format!("* {}\n", l)).collect()
     } else {
         String::new()
     };
@@ -3019,6 +4904,37 @@ impl ExportedClass {
         self.typescript.push_str(";\n");
     }
 
+    /// Used for adding a method or static method to a class, which becomes an
+    /// overload of any previously added method with the same JS name.
+    fn push_method(
+        &mut self,
+        docs: &str,
+        name: &str,
+        prefix: &'static str,
+        js: &str,
+        ts: &str,
+        matches: String,
+    ) {
+        let overload = Overload {
+            docs: docs.to_string(),
+            js: js.to_string(),
+            ts: ts.to_string(),
+            matches,
+        };
+        let existing = self
+            .methods
+            .iter_mut()
+            .find(|m| m.name == name && m.prefix == prefix);
+        match existing {
+            Some(method) => method.overloads.push(overload),
+            None => self.methods.push(ExportedMethod {
+                name: name.to_string(),
+                prefix,
+                overloads: vec![overload],
+            }),
+        }
+    }
+
     /// Used for adding a getter to a class, mainly to ensure that TypeScript
     /// generation is handled specially.
     fn push_getter(&mut self, docs: &str, field: &str, js: &str, ret_ty: &str) {
@@ -3050,7 +4966,12 @@ impl ExportedClass {
             .typescript_fields
             .entry(field.to_string())
             .or_insert_with(Default::default);
-        *ty = ret_ty.to_string();
+        // The property is typed by what reading it returns if a setter accepts
+        // a different type, such as a getter returning `T` whose setter takes
+        // an `Option<T>`.
+        if ty.is_empty() || prefix == "get " {
+            *ty = ret_ty.to_string();
+        }
         has_setter
     }
 }
@@ -3076,6 +4997,17 @@ fn test_generate_identifier() {
     );
 }
 
+#[test]
+fn test_to_camel_case() {
+    assert_eq!(to_camel_case("foo"), "foo");
+    assert_eq!(to_camel_case("foo_bar"), "fooBar");
+    assert_eq!(to_camel_case("foo_bar_baz"), "fooBarBaz");
+    assert_eq!(to_camel_case("alreadyCamel"), "alreadyCamel");
+    assert_eq!(to_camel_case("_private_thing"), "_privateThing");
+    assert_eq!(to_camel_case("trailing_"), "trailing_");
+    assert_eq!(to_camel_case("__"), "__");
+}
+
 struct MemView {
     name: &'static str,
     num: usize,
@@ -1,6 +1,7 @@
 #![doc(html_root_url = "https://docs.rs/wasm-bindgen-cli-support/0.2")]
 
 use anyhow::{bail, Context, Error};
+use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env;
 use std::fs;
@@ -9,12 +10,16 @@ use std::path::{Path, PathBuf};
 use std::str;
 use walrus::Module;
 
+use crate::manifest::Manifest;
+
 mod anyref;
 mod decode;
 mod descriptor;
 mod descriptors;
+mod interface;
 mod intrinsic;
 mod js;
+mod manifest;
 mod multivalue;
 mod throw2unreachable;
 pub mod wasm2es6js;
@@ -26,27 +31,49 @@ pub struct Bindgen {
     mode: OutputMode,
     debug: bool,
     typescript: bool,
+    typescript_feature_files: bool,
     demangle: bool,
     keep_debug: bool,
     remove_name_section: bool,
     remove_producers_section: bool,
     emit_start: bool,
+    emit_wat: bool,
+    strict_strings: bool,
+    module_hash: bool,
+    warn_deprecated: bool,
+    warn_unused_imports: bool,
+    deny_warnings: bool,
+    minify: bool,
     // Experimental support for weakrefs, an upcoming ECMAScript feature.
     // Currently only enable-able through an env var.
     weak_refs: bool,
+    // Destroy closures given up by Rust once JS garbage collects them
+    weak_closures: bool,
     // Support for the wasm threads proposal, transforms the wasm module to be
     // "ready to be instantiated on any thread"
     threads: wasm_bindgen_threads_xform::Config,
     anyref: bool,
     multi_value: bool,
     wasm_interface_types: bool,
+    // Experimental support for modules with 64-bit memories, where pointers
+    // and lengths are passed across the boundary as BigInts.
+    memory64: bool,
     encode_into: EncodeInto,
+    camel_case: bool,
+    shared_runtime: Option<String>,
+    multiple_instances: bool,
+    write_interface: Option<PathBuf>,
+    check_interface: Option<PathBuf>,
+    manifest: bool,
 }
 
 pub struct Output {
     module: walrus::Module,
     stem: String,
     generated: Generated,
+    emit_wat: bool,
+    manifest: bool,
+    warnings: Vec<String>,
 }
 
 enum Generated {
@@ -58,10 +85,15 @@ struct JsGenerated {
     mode: OutputMode,
     js: String,
     ts: String,
+    feature_ts: BTreeMap<String, String>,
     snippets: HashMap<String, Vec<String>>,
     local_modules: HashMap<String, String>,
     npm_dependencies: HashMap<String, (PathBuf, String)>,
     typescript: bool,
+    minify: bool,
+    module_hash: bool,
+    shared_runtime: Option<(String, String)>,
+    interface: String,
 }
 
 #[derive(Clone)]
@@ -97,17 +129,33 @@ impl Bindgen {
             },
             debug: false,
             typescript: false,
+            typescript_feature_files: false,
             demangle: true,
             keep_debug: false,
             remove_name_section: false,
             remove_producers_section: false,
             emit_start: true,
+            emit_wat: false,
+            strict_strings: false,
+            module_hash: false,
+            warn_deprecated: false,
+            warn_unused_imports: false,
+            deny_warnings: false,
+            minify: false,
             weak_refs: env::var("WASM_BINDGEN_WEAKREF").is_ok(),
+            weak_closures: false,
             threads: threads_config(),
             anyref: anyref || wasm_interface_types,
             multi_value: multi_value || wasm_interface_types,
             wasm_interface_types,
+            memory64: false,
             encode_into: EncodeInto::Test,
+            camel_case: false,
+            shared_runtime: None,
+            multiple_instances: false,
+            write_interface: None,
+            check_interface: None,
+            manifest: false,
         }
     }
 
@@ -222,6 +270,14 @@ impl Bindgen {
         self
     }
 
+    /// Writes the TypeScript declarations of exports gated on a Cargo feature
+    /// with `#[wasm_bindgen(cfg_feature = "...")]` to a separate `.d.ts` file
+    /// per feature, which the main `.d.ts` file re-exports.
+    pub fn typescript_feature_files(&mut self, enable: bool) -> &mut Bindgen {
+        self.typescript_feature_files = enable;
+        self
+    }
+
     pub fn demangle(&mut self, demangle: bool) -> &mut Bindgen {
         self.demangle = demangle;
         self
@@ -247,13 +303,308 @@ impl Bindgen {
         self
     }
 
+    /// Enables support for the upcoming weak references proposal, which is
+    /// otherwise only enabled through the `WASM_BINDGEN_WEAKREF` env var.
+    pub fn weak_refs(&mut self, enable: bool) -> &mut Bindgen {
+        self.weak_refs = self.weak_refs || enable;
+        self
+    }
+
+    /// Registers JS functions created for `Closure`s with a
+    /// `FinalizationRegistry`, so the Rust closure is destroyed once the JS
+    /// function is garbage collected after `Closure::forget` or
+    /// `Closure::into_js_value`. This is skipped at runtime in JS engines
+    /// without `FinalizationRegistry`, which leak such closures as before.
+    pub fn weak_closures(&mut self, enable: bool) -> &mut Bindgen {
+        self.weak_closures = enable;
+        self
+    }
+
+    /// Enables support for the reference types proposal (`anyref`), which is
+    /// otherwise only enabled through the `WASM_BINDGEN_ANYREF` env var.
+    pub fn reference_types(&mut self, enable: bool) -> &mut Bindgen {
+        self.anyref = self.anyref || enable;
+        self
+    }
+
+    /// Enables experimental support for wasm modules using a 64-bit memory,
+    /// such as those built for `wasm64-unknown-unknown`.
+    pub fn memory64(&mut self, enable: bool) -> &mut Bindgen {
+        self.memory64 = enable;
+        self
+    }
+
+    /// Also write a `.wat` disassembly of the final wasm module next to it.
+    pub fn emit_wat(&mut self, emit: bool) -> &mut Bindgen {
+        self.emit_wat = emit;
+        self
+    }
+
+    /// Throw a `TypeError` when a JS string containing unpaired surrogates is
+    /// passed to Rust, rather than replacing them with U+FFFD.
+    pub fn strict_strings(&mut self, strict: bool) -> &mut Bindgen {
+        self.strict_strings = strict;
+        self
+    }
+
+    /// Provide the JS glue to the wasm module under an import module name
+    /// which includes a hash of the module's contents, rather than `wbg`, so
+    /// that the glue of several wasm modules on one page can't collide.
+    ///
+    /// This only affects targets where the generated JS instantiates the
+    /// wasm module itself, `web` and `no-modules`; other targets import the
+    /// glue from the generated JS file's own path.
+    pub fn module_hash(&mut self, module_hash: bool) -> &mut Bindgen {
+        self.module_hash = module_hash;
+        self
+    }
+
+    /// Log a warning with `console.warn` the first time each export marked
+    /// with `#[wasm_bindgen(deprecated)]` or `#[wasm_bindgen(unstable)]` is
+    /// called.
+    pub fn warn_deprecated(&mut self, warn: bool) -> &mut Bindgen {
+        self.warn_deprecated = warn;
+        self
+    }
+
+    /// Report imported functions which are declared in an `extern` block but
+    /// never used by the final module. Off by default since crates providing
+    /// bindings for whole JS APIs only ever have a fraction of them used.
+    pub fn warn_unused_imports(&mut self, warn: bool) -> &mut Bindgen {
+        self.warn_unused_imports = warn;
+        self
+    }
+
+    /// Fail instead of only reporting warnings about the module's bindings,
+    /// such as exports for which no JS glue could be generated.
+    pub fn deny_warnings(&mut self, deny: bool) -> &mut Bindgen {
+        self.deny_warnings = deny;
+        self
+    }
+
+    /// Emit the generated JS with short internal identifiers and without
+    /// comments or indentation. Exported names are kept as they are.
+    pub fn minify(&mut self, minify: bool) -> &mut Bindgen {
+        self.minify = minify;
+        self
+    }
+
     pub fn encode_into(&mut self, mode: EncodeInto) -> &mut Bindgen {
         self.encode_into = mode;
         self
     }
 
+    /// Converts the JS names of all exported snake_case functions, methods,
+    /// and fields to camelCase. Names explicitly configured with `js_name` are
+    /// left as-is.
+    pub fn camel_case(&mut self, camel_case: bool) -> &mut Bindgen {
+        self.camel_case = camel_case;
+        self
+    }
+
+    /// Keeps all state of the generated JS, like the JS heap and cached views
+    /// of wasm memory, in a closure created for each call to `init`, so a
+    /// module can be instantiated any number of times without instances
+    /// interfering with each other. Each call to `init` resolves to an object
+    /// holding the exports of its instance.
+    ///
+    /// This is only supported with `--target web` and `--target no-modules`,
+    /// as other targets instantiate the module once when it's imported.
+    pub fn multiple_instances(&mut self, enable: bool) -> &mut Bindgen {
+        self.multiple_instances = enable;
+        self
+    }
+
+    /// Imports the JS heap, text codecs and other helpers which don't depend
+    /// on a particular wasm module from the module `specifier`, so that they
+    /// can be shared by multiple generated modules loaded on the same page.
+    ///
+    /// If `specifier` is a relative path the shared runtime module is written
+    /// to that path relative to the output directory. All modules sharing a
+    /// runtime must be generated with the same target.
+    pub fn shared_runtime(&mut self, specifier: Option<String>) -> &mut Bindgen {
+        self.shared_runtime = specifier;
+        self
+    }
+
+    /// Writes a description of the module's JS interface, its exports with
+    /// their TypeScript signatures and the JS modules it imports, to `path`.
+    pub fn write_interface(&mut self, path: Option<PathBuf>) -> &mut Bindgen {
+        self.write_interface = path;
+        self
+    }
+
+    /// Instead of writing out any bindings, checks that the module's JS
+    /// interface matches the description previously written to `path` with
+    /// `write_interface`, failing with a list of differences if it doesn't.
+    pub fn check_interface(&mut self, path: Option<PathBuf>) -> &mut Bindgen {
+        self.check_interface = path;
+        self
+    }
+
+    /// Writes a `<name>.manifest.json` file to the output directory listing
+    /// every file emitted along with its role, size and SHA-256 hash.
+    pub fn manifest(&mut self, enable: bool) -> &mut Bindgen {
+        self.manifest = enable;
+        self
+    }
+
     pub fn generate<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
-        self.generate_output()?.emit(path.as_ref())
+        let mut output = self.generate_output()?;
+        for warning in output.warnings() {
+            eprintln!("warning: {}", warning);
+        }
+        if let Some(interface_path) = &self.write_interface {
+            fs::write(interface_path, output.interface())
+                .with_context(|| format!("failed to write `{}`", interface_path.display()))?;
+        }
+        if let Some(interface_path) = &self.check_interface {
+            return interface::check(interface_path, output.interface());
+        }
+        output.emit(path.as_ref())
+    }
+
+    /// Writes out a JS entry point named `name` into `out_dir` which bundles
+    /// together the bindings for all of the `stems` listed, each of which is
+    /// expected to have already been generated into `out_dir`.
+    ///
+    /// Each module is exposed from the entry point under a namespace named
+    /// after its stem and is only instantiated the first time it's accessed.
+    /// This is intended for projects which split their code across multiple
+    /// `cdylib` crates, all processed in one invocation of `wasm-bindgen`.
+    pub fn generate_entry<P: AsRef<Path>>(
+        &self,
+        out_dir: P,
+        name: &str,
+        stems: &[String],
+    ) -> Result<(), Error> {
+        let out_dir = out_dir.as_ref();
+        let extension = if self.mode.nodejs_experimental_modules() {
+            "mjs"
+        } else {
+            "js"
+        };
+        let idents = self.entry_idents(name, stems)?;
+        let mut js = String::new();
+        let mut ts = String::new();
+        for (stem, ident) in stems.iter().zip(&idents) {
+            match &self.mode {
+                OutputMode::Bundler { .. }
+                | OutputMode::Node {
+                    experimental_modules: true,
+                } => {
+                    js.push_str(&format!(
+                        "
+                            let cached_{0} = null;
+                            export function {0}() {{
+                                if (cached_{0} === null) cached_{0} = import('./{1}.{2}');
+                                return cached_{0};
+                            }}
+                        ",
+                        ident, stem, extension,
+                    ));
+                    ts.push_str(&format!(
+                        "export function {}(): Promise<typeof import('./{}')>;\n",
+                        ident, stem,
+                    ));
+                }
+                OutputMode::Web => {
+                    js.push_str(&format!(
+                        "
+                            let cached_{0} = null;
+                            export function {0}(module_or_path) {{
+                                if (cached_{0} === null) {{
+                                    cached_{0} = import('./{1}.js').then(async m => {{
+                                        await m.default(module_or_path);
+                                        return m;
+                                    }});
+                                }}
+                                return cached_{0};
+                            }}
+                        ",
+                        ident, stem,
+                    ));
+                    ts.push_str(&format!(
+                        "export function {}(module_or_path?: RequestInfo | BufferSource | \
+                         WebAssembly.Module): Promise<typeof import('./{}')>;\n",
+                        ident, stem,
+                    ));
+                }
+                OutputMode::Node {
+                    experimental_modules: false,
+                } => {
+                    js.push_str(&format!(
+                        "
+                            Object.defineProperty(module.exports, '{0}', {{
+                                enumerable: true,
+                                get() {{ return require('./{1}.js'); }},
+                            }});
+                        ",
+                        ident, stem,
+                    ));
+                    ts.push_str(&format!(
+                        "export const {}: typeof import('./{}');\n",
+                        ident, stem,
+                    ));
+                }
+                OutputMode::NoModules { .. } => unreachable!(),
+            }
+        }
+
+        let js_path = out_dir.join(name).with_extension(extension);
+        fs::write(&js_path, reset_indentation(&js))
+            .with_context(|| format!("failed to write `{}`", js_path.display()))?;
+        if self.typescript {
+            let ts_path = js_path.with_extension("d.ts");
+            fs::write(&ts_path, ts)
+                .with_context(|| format!("failed to write `{}`", ts_path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Checks that an entry point named `name` can be generated for the
+    /// `stems` listed with [`Bindgen::generate_entry`], without writing
+    /// anything.
+    ///
+    /// This is intended to be called before generating the bindings for each
+    /// module so that an invalid combination of inputs doesn't overwrite any
+    /// existing output.
+    pub fn check_entry(&self, name: &str, stems: &[String]) -> Result<(), Error> {
+        self.entry_idents(name, stems).map(drop)
+    }
+
+    /// Returns the names that each of the `stems` is exported under from the
+    /// entry point named `name`.
+    fn entry_idents(&self, name: &str, stems: &[String]) -> Result<Vec<String>, Error> {
+        if let OutputMode::NoModules { .. } = self.mode {
+            bail!("multiple input modules are not supported with `--target no-modules`")
+        }
+        let mut used = HashSet::new();
+        let mut idents = Vec::new();
+        for stem in stems {
+            if stem == name {
+                bail!(
+                    "the bundle entry `{}` has the same name as one of its \
+                     input modules, pick a different `--out-name`",
+                    name
+                );
+            }
+            if !used.insert(stem) {
+                bail!("multiple input modules are named `{}`", stem);
+            }
+            let mut ident = stem.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+            if ident.starts_with(|c: char| c.is_ascii_digit()) {
+                ident.insert(0, '_');
+            }
+            if js::minify::RESERVED.contains(&&ident[..]) {
+                ident.push('_');
+            }
+            if idents.contains(&ident) {
+                bail!("multiple input modules would be exported as `{}`", ident);
+            }
+            idents.push(ident);
+        }
+        Ok(idents)
     }
 
     pub fn generate_output(&mut self) -> Result<Output, Error> {
@@ -307,6 +658,22 @@ impl Bindgen {
             );
         }
 
+        if self.memory64 {
+            if self.wasm_interface_types || self.anyref {
+                bail!(
+                    "64-bit memories aren't supported with wasm interface types \
+                     or reference types yet"
+                );
+            }
+            if self.mode.uses_es_modules() && !self.mode.web() {
+                bail!(
+                    "64-bit memories require wasm-bindgen to instantiate the \
+                     module, use `--target web`, `--target no-modules` or \
+                     `--target nodejs`"
+                );
+            }
+        }
+
         self.threads
             .run(&mut module)
             .with_context(|| "failed to prepare module for threading")?;
@@ -327,7 +694,7 @@ impl Bindgen {
         // exports by executing `__wbindgen_describe_*` functions. This'll
         // effectively move all the descriptor functions to their own custom
         // sections.
-        descriptors::execute(&mut module)?;
+        descriptors::execute(&mut module, self.debug)?;
 
         // Process and remove our raw custom sections emitted by the
         // #[wasm_bindgen] macro and the compiler. In their stead insert a
@@ -340,8 +707,27 @@ impl Bindgen {
             self.anyref,
             self.wasm_interface_types,
             self.emit_start,
+            self.memory64,
+            self.warn_unused_imports,
         )?;
 
+        let warnings = module
+            .customs
+            .get_typed::<wit::WasmBindgenAux>()
+            .unwrap()
+            .warnings
+            .clone();
+        if self.deny_warnings && !warnings.is_empty() {
+            let mut msg = format!(
+                "found {} warning(s) about the module's bindings, which are denied:\n",
+                warnings.len()
+            );
+            for warning in warnings.iter() {
+                msg.push_str(&format!("\n    * {}", warning));
+            }
+            bail!("{}", msg);
+        }
+
         // Now that we've got type information from the webidl processing pass,
         // touch up the output of rustc to insert anyref shims where necessary.
         // This is only done if the anyref pass is enabled, which it's
@@ -398,6 +784,9 @@ impl Bindgen {
         // Otherwise we execute the JS generation passes to actually emit
         // JS/TypeScript/etc. The output here is unused in wasm interfac
         let generated = if self.wasm_interface_types {
+            if self.write_interface.is_some() || self.check_interface.is_some() {
+                bail!("checking a module's interface isn't supported with wasm interface types");
+            }
             wit::section::add(&mut module)
                 .context("failed to generate a standard interface types section")?;
             Generated::InterfaceTypes
@@ -410,15 +799,48 @@ impl Bindgen {
                 .customs
                 .delete_typed::<wit::NonstandardWitSection>()
                 .unwrap();
+            if self.shared_runtime.is_some() && !self.mode.uses_es_modules() {
+                bail!("`--shared-runtime` requires a target which uses ES modules");
+            }
+            if self.multiple_instances {
+                if !self.mode.web() && !self.mode.no_modules() {
+                    bail!(
+                        "`--multiple-instances` requires `--target web` or \
+                         `--target no-modules`"
+                    );
+                }
+                if self.shared_runtime.is_some() {
+                    bail!(
+                        "`--multiple-instances` can't be used with `--shared-runtime`, \
+                         which shares the JS heap between modules"
+                    );
+                }
+                if self.typescript_feature_files {
+                    bail!(
+                        "`--multiple-instances` can't be used with \
+                         `--typescript-feature-files`"
+                    );
+                }
+            }
             let mut cx = js::Context::new(&mut module, self, &adapters, &aux)?;
             cx.generate()?;
             let (js, ts) = cx.finalize(stem)?;
+            let interface = interface::describe(&cx.interface, &cx.imported_modules())?;
+            let shared_runtime = self
+                .shared_runtime
+                .clone()
+                .map(|specifier| (specifier, cx.runtime.clone()));
             Generated::Js(JsGenerated {
+                shared_runtime,
                 snippets: aux.snippets.clone(),
                 local_modules: aux.local_modules.clone(),
                 mode: self.mode.clone(),
                 typescript: self.typescript,
+                minify: self.minify,
+                module_hash: self.module_hash,
                 npm_dependencies: cx.npm_dependencies.clone(),
+                interface,
+                feature_ts: cx.feature_typescript.clone(),
                 js,
                 ts,
             })
@@ -428,6 +850,9 @@ impl Bindgen {
             module,
             stem: stem.to_string(),
             generated,
+            emit_wat: self.emit_wat,
+            manifest: self.manifest,
+            warnings,
         })
     }
 
@@ -447,6 +872,21 @@ impl Bindgen {
     }
 }
 
+/// Replaces every occurrence of `from` in `bytes` with `to`, which must have
+/// the same length so that wasm sections stay valid.
+fn replace_bytes(bytes: &mut [u8], from: &[u8], to: &[u8]) {
+    assert_eq!(from.len(), to.len());
+    let mut i = 0;
+    while i + from.len() <= bytes.len() {
+        if bytes[i..].starts_with(from) {
+            bytes[i..i + to.len()].copy_from_slice(to);
+            i += to.len();
+        } else {
+            i += 1;
+        }
+    }
+}
+
 fn reset_indentation(s: &str) -> String {
     let mut indent: u32 = 0;
     let mut dst = String::new();
@@ -579,6 +1019,12 @@ fn unexported_unused_lld_things(module: &mut Module) {
 }
 
 impl Output {
+    /// Returns warnings about the module's bindings found while generating
+    /// them, such as imported functions which are declared but never used.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     pub fn js(&self) -> &str {
         match &self.generated {
             Generated::InterfaceTypes => panic!("no js with interface types output"),
@@ -586,6 +1032,15 @@ impl Output {
         }
     }
 
+    /// Returns a JSON description of the JS interface of this module, as
+    /// written by `Bindgen::write_interface`.
+    pub fn interface(&self) -> &str {
+        match &self.generated {
+            Generated::InterfaceTypes => panic!("no js with interface types output"),
+            Generated::Js(gen) => &gen.interface,
+        }
+    }
+
     pub fn wasm(&self) -> &walrus::Module {
         &self.module
     }
@@ -599,15 +1054,43 @@ impl Output {
     }
 
     fn _emit(&mut self, out_dir: &Path) -> Result<(), Error> {
+        fs::create_dir_all(out_dir)?;
+        let mut manifest = Manifest::new(out_dir);
+        self.emit_files(out_dir, &mut manifest)?;
+        if self.manifest {
+            let path = out_dir.join(format!("{}.manifest.json", self.stem));
+            fs::write(&path, manifest.render()?)
+                .with_context(|| format!("failed to write `{}`", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn emit_files(&mut self, out_dir: &Path, manifest: &mut Manifest) -> Result<(), Error> {
         let wasm_name = match &self.generated {
             Generated::InterfaceTypes => self.stem.clone(),
             Generated::Js(_) => format!("{}_bg", self.stem),
         };
         let wasm_path = out_dir.join(wasm_name).with_extension("wasm");
-        fs::create_dir_all(out_dir)?;
-        let wasm_bytes = self.module.emit_wasm();
-        fs::write(&wasm_path, wasm_bytes)
-            .with_context(|| format!("failed to write `{}`", wasm_path.display()))?;
+        let mut wasm_bytes = self.module.emit_wasm();
+        if let Generated::Js(gen) = &mut self.generated {
+            if gen.module_hash {
+                let hash = format!("{:x}", Sha256::digest(&wasm_bytes));
+                let namespace = format!("wbg_{}", &hash[..16]);
+                replace_bytes(
+                    &mut wasm_bytes,
+                    js::MODULE_HASH_PLACEHOLDER.as_bytes(),
+                    namespace.as_bytes(),
+                );
+                gen.js = gen.js.replace(js::MODULE_HASH_PLACEHOLDER, &namespace);
+            }
+        }
+        if self.emit_wat {
+            let wat_path = wasm_path.with_extension("wat");
+            let wat = wasmprinter::print_bytes(&wasm_bytes)
+                .context("failed to disassemble the final wasm module")?;
+            manifest.write(&wat_path, "wat", wat)?;
+        }
+        manifest.write(&wasm_path, "wasm", wasm_bytes)?;
 
         let gen = match &self.generated {
             Generated::InterfaceTypes => return Ok(()),
@@ -620,17 +1103,20 @@ impl Output {
             for (i, js) in list.iter().enumerate() {
                 let name = format!("inline{}.js", i);
                 let path = out_dir.join("snippets").join(identifier).join(name);
-                fs::create_dir_all(path.parent().unwrap())?;
-                fs::write(&path, js)
-                    .with_context(|| format!("failed to write `{}`", path.display()))?;
+                manifest.write(&path, "snippet", js)?;
             }
         }
 
         for (path, contents) in gen.local_modules.iter() {
             let path = out_dir.join("snippets").join(path);
-            fs::create_dir_all(path.parent().unwrap())?;
-            fs::write(&path, contents)
-                .with_context(|| format!("failed to write `{}`", path.display()))?;
+            manifest.write(&path, "snippet", contents)?;
+        }
+
+        if let Some((specifier, contents)) = &gen.shared_runtime {
+            if specifier.starts_with("./") || specifier.starts_with("../") {
+                let path = out_dir.join(specifier);
+                manifest.write(&path, "shared-runtime", reset_indentation(contents))?;
+            }
         }
 
         if gen.npm_dependencies.len() > 0 {
@@ -640,7 +1126,7 @@ impl Output {
                 .map(|(k, v)| (k, &v.1))
                 .collect::<BTreeMap<_, _>>();
             let json = serde_json::to_string_pretty(&map)?;
-            fs::write(out_dir.join("package.json"), json)?;
+            manifest.write(&out_dir.join("package.json"), "package-json", json)?;
         }
 
         // And now that we've got all our JS and TypeScript, actually write it
@@ -651,27 +1137,33 @@ impl Output {
             "js"
         };
         let js_path = out_dir.join(&self.stem).with_extension(extension);
-        fs::write(&js_path, reset_indentation(&gen.js))
-            .with_context(|| format!("failed to write `{}`", js_path.display()))?;
+        let js = if gen.minify {
+            gen.js.clone()
+        } else {
+            reset_indentation(&gen.js)
+        };
+        manifest.write(&js_path, "js", js)?;
 
         if gen.typescript {
             let ts_path = js_path.with_extension("d.ts");
-            fs::write(&ts_path, &gen.ts)
-                .with_context(|| format!("failed to write `{}`", ts_path.display()))?;
+            manifest.write(&ts_path, "typescript", &gen.ts)?;
+
+            for (feature, ts) in gen.feature_ts.iter() {
+                let ts_path = out_dir.join(format!("{}_{}.d.ts", self.stem, feature));
+                manifest.write(&ts_path, "typescript", ts)?;
+            }
         }
 
         if gen.mode.nodejs() {
             let js_path = wasm_path.with_extension(extension);
             let shim = gen.generate_node_wasm_import(&self.module, &wasm_path);
-            fs::write(&js_path, shim)
-                .with_context(|| format!("failed to write `{}`", js_path.display()))?;
+            manifest.write(&js_path, "wasm-js", shim)?;
         }
 
         if gen.typescript {
             let ts_path = wasm_path.with_extension("d.ts");
             let ts = wasm2es6js::typescript(&self.module)?;
-            fs::write(&ts_path, ts)
-                .with_context(|| format!("failed to write `{}`", ts_path.display()))?;
+            manifest.write(&ts_path, "wasm-typescript", ts)?;
         }
 
         Ok(())
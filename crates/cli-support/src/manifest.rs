@@ -0,0 +1,93 @@
+//! Support for `--manifest`, which writes a JSON description of every file
+//! emitted into the output directory.
+//!
+//! Each file is listed with its path relative to the output directory, its
+//! role, its size in bytes and the SHA-256 hash of its contents, so tools
+//! packaging the output don't have to guess which files belong to it.
+
+use anyhow::{Context, Error};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Component, Path};
+
+/// The files written to an output directory, in the order they were written.
+pub struct Manifest<'a> {
+    out_dir: &'a Path,
+    files: Vec<File>,
+}
+
+struct File {
+    path: String,
+    role: &'static str,
+    size: usize,
+    sha256: String,
+}
+
+impl<'a> Manifest<'a> {
+    pub fn new(out_dir: &'a Path) -> Manifest<'a> {
+        Manifest {
+            out_dir,
+            files: Vec::new(),
+        }
+    }
+
+    /// Writes `contents` to `path`, which must be within the output
+    /// directory, and records it with the given role.
+    pub fn write(
+        &mut self,
+        path: &Path,
+        role: &'static str,
+        contents: impl AsRef<[u8]>,
+    ) -> Result<(), Error> {
+        let contents = contents.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+            .with_context(|| format!("failed to write `{}`", path.display()))?;
+
+        // Paths are always written with `/` separators so the manifest is the
+        // same on every platform.
+        let relative = path.strip_prefix(self.out_dir).unwrap_or(path);
+        let path = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => Some(s.to_string_lossy()),
+                Component::ParentDir => Some("..".into()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        self.files.push(File {
+            path,
+            role,
+            size: contents.len(),
+            sha256: format!("{:x}", Sha256::digest(contents)),
+        });
+        Ok(())
+    }
+
+    /// Renders the manifest, listing files sorted by their path.
+    pub fn render(&self) -> Result<String, Error> {
+        let mut files = self.files.iter().collect::<Vec<_>>();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let files = files
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "path": f.path,
+                    "role": f.role,
+                    "size": f.size,
+                    "sha256": f.sha256,
+                })
+            })
+            .collect::<Vec<_>>();
+        let manifest = serde_json::json!({
+            "generator": format!("wasm-bindgen {}", env!("CARGO_PKG_VERSION")),
+            "files": files,
+        });
+        let mut json = serde_json::to_string_pretty(&manifest)?;
+        json.push('\n');
+        Ok(json)
+    }
+}
@@ -73,7 +73,7 @@ impl InstructionBuilder<'_, '_> {
                     Instruction::I32FromAnyrefRustOwned {
                         class: class.clone(),
                     },
-                    &[AdapterType::I32],
+                    &[self.cx.pointer()],
                 );
             }
             Descriptor::I8 => self.number(WitVT::S8, WasmVT::I32),
@@ -84,6 +84,8 @@ impl InstructionBuilder<'_, '_> {
             Descriptor::U32 => self.number(WitVT::U32, WasmVT::I32),
             Descriptor::I64 => self.number64(true),
             Descriptor::U64 => self.number64(false),
+            Descriptor::I128 => self.number128(true),
+            Descriptor::U128 => self.number128(false),
             Descriptor::F32 => {
                 self.get(AdapterType::F32);
                 self.output.push(AdapterType::F32);
@@ -105,7 +107,7 @@ impl InstructionBuilder<'_, '_> {
                         realloc: self.cx.realloc(),
                         mem: self.cx.memory()?,
                     },
-                    &[AdapterType::I32, AdapterType::I32],
+                    &[self.cx.pointer(); 2],
                 );
             }
 
@@ -120,7 +122,7 @@ impl InstructionBuilder<'_, '_> {
                         malloc: self.cx.malloc()?,
                         mem: self.cx.memory()?,
                     },
-                    &[AdapterType::I32, AdapterType::I32],
+                    &[self.cx.pointer(); 2],
                 );
             }
 
@@ -129,7 +131,8 @@ impl InstructionBuilder<'_, '_> {
             Descriptor::Closure(_) |
 
             // Always behind a `Ref`
-            Descriptor::Slice(_) => bail!(
+            Descriptor::Slice(_) |
+            Descriptor::Tuple(_) => bail!(
                 "unsupported argument type for calling Rust function from JS: {:?}",
                 arg
             ),
@@ -138,7 +141,7 @@ impl InstructionBuilder<'_, '_> {
             Descriptor::Unit => {}
 
             // Largely synthetic and can't show up
-            Descriptor::ClampedU8 => unreachable!(),
+            Descriptor::ClampedU8 | Descriptor::Promise(_) => unreachable!(),
         }
         Ok(())
     }
@@ -151,10 +154,13 @@ impl InstructionBuilder<'_, '_> {
                     Instruction::I32FromAnyrefRustBorrow {
                         class: class.clone(),
                     },
-                    &[AdapterType::I32],
+                    &[self.cx.pointer()],
                 );
             }
-            Descriptor::Anyref => {
+            // JS functions passed to closures taking `&dyn Fn(..)` are
+            // borrowed like any other JS value, and called from Rust through
+            // an intrinsic.
+            Descriptor::Anyref | Descriptor::Function(_) => {
                 self.instruction(
                     &[AdapterType::Anyref],
                     Instruction::I32FromAnyrefBorrow,
@@ -170,7 +176,7 @@ impl InstructionBuilder<'_, '_> {
                         realloc: self.cx.realloc(),
                         mem: self.cx.memory()?,
                     },
-                    &[AdapterType::I32, AdapterType::I32],
+                    &[self.cx.pointer(); 2],
                 );
             }
             Descriptor::Slice(_) => {
@@ -191,7 +197,7 @@ impl InstructionBuilder<'_, '_> {
                             mem: self.cx.memory()?,
                             free: self.cx.free()?,
                         },
-                        &[AdapterType::I32, AdapterType::I32],
+                        &[self.cx.pointer(); 2],
                     );
                 } else {
                     self.instruction(
@@ -201,10 +207,20 @@ impl InstructionBuilder<'_, '_> {
                             malloc: self.cx.malloc()?,
                             mem: self.cx.memory()?,
                         },
-                        &[AdapterType::I32, AdapterType::I32],
+                        &[self.cx.pointer(); 2],
                     );
                 }
             }
+            Descriptor::Tuple(elements) => {
+                let elements = tuple_elements(elements)?;
+                self.instruction(
+                    &[AdapterType::Anyref],
+                    Instruction::ScalarsFromTuple {
+                        elements: elements.clone(),
+                    },
+                    &vec![AdapterType::F64; elements.len()],
+                );
+            }
             _ => bail!(
                 "unsupported reference argument type for calling Rust function from JS: {:?}",
                 arg
@@ -270,7 +286,7 @@ impl InstructionBuilder<'_, '_> {
                     Instruction::I32FromOptionRust {
                         class: name.to_string(),
                     },
-                    &[AdapterType::I32],
+                    &[self.cx.pointer()],
                 );
             }
 
@@ -285,7 +301,7 @@ impl InstructionBuilder<'_, '_> {
                         mem,
                         realloc,
                     },
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                 );
             }
 
@@ -301,7 +317,7 @@ impl InstructionBuilder<'_, '_> {
                 self.instruction(
                     &[AdapterType::Anyref],
                     Instruction::OptionVector { kind, malloc, mem },
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                 );
             }
 
@@ -384,6 +400,14 @@ impl InstructionBuilder<'_, '_> {
         );
     }
 
+    fn number128(&mut self, signed: bool) {
+        self.instruction(
+            &[AdapterType::Anyref],
+            Instruction::I32Split128 { signed },
+            &[AdapterType::I32; 4],
+        );
+    }
+
     fn in_option_native(&mut self, wasm: ValType) {
         self.instruction(
             &[AdapterType::Anyref],
@@ -400,3 +424,28 @@ impl InstructionBuilder<'_, '_> {
         );
     }
 }
+
+/// Returns the JS types of the elements of a tuple, each of which is passed as
+/// an `f64` to and from wasm.
+pub fn tuple_elements(elements: &[Descriptor]) -> Result<Vec<AdapterType>, Error> {
+    elements
+        .iter()
+        .map(|element| {
+            Ok(match element {
+                Descriptor::I8 => AdapterType::S8,
+                Descriptor::U8 => AdapterType::U8,
+                Descriptor::I16 => AdapterType::S16,
+                Descriptor::U16 => AdapterType::U16,
+                Descriptor::I32 => AdapterType::S32,
+                Descriptor::U32 => AdapterType::U32,
+                Descriptor::F32 => AdapterType::F32,
+                Descriptor::F64 => AdapterType::F64,
+                Descriptor::Boolean => AdapterType::Bool,
+                other => bail!(
+                    "tuple elements must be numbers or booleans, found `{}`",
+                    other.rust_name()
+                ),
+            })
+        })
+        .collect()
+}
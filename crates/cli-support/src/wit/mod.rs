@@ -1,9 +1,10 @@
 use crate::decode;
-use crate::descriptor::{Descriptor, Function};
+use crate::descriptor::{Descriptor, Function, VectorKind};
 use crate::descriptors::WasmBindgenDescriptorsSection;
 use crate::intrinsic::Intrinsic;
 use anyhow::{anyhow, bail, Error};
 use std::collections::HashMap;
+use std::mem;
 use std::str;
 use walrus::MemoryId;
 use walrus::{ExportId, FunctionId, ImportId, Module};
@@ -33,6 +34,8 @@ struct Context<'a> {
     anyref_enabled: bool,
     wasm_interface_types: bool,
     support_start: bool,
+    memory64: bool,
+    warn_unused_imports: bool,
 }
 
 struct InstructionBuilder<'a, 'b> {
@@ -48,6 +51,8 @@ pub fn process(
     anyref_enabled: bool,
     wasm_interface_types: bool,
     support_start: bool,
+    memory64: bool,
+    warn_unused_imports: bool,
 ) -> Result<(NonstandardWitSectionId, WasmBindgenAuxId), Error> {
     let mut storage = Vec::new();
     let programs = extract_programs(module, &mut storage)?;
@@ -66,6 +71,8 @@ pub fn process(
         anyref_enabled,
         wasm_interface_types,
         support_start,
+        memory64,
+        warn_unused_imports,
     };
     cx.init()?;
 
@@ -133,6 +140,7 @@ impl<'a> Context<'a> {
             let WasmBindgenDescriptorsSection {
                 descriptors,
                 closure_imports,
+                mut closure_creators,
             } = *custom;
             // Store all the executed descriptors in our own field so we have
             // access to them while processing programs.
@@ -158,12 +166,13 @@ impl<'a> Context<'a> {
                     arguments: vec![Descriptor::I32; 3],
                     ret: Descriptor::Anyref,
                 };
+                let creator = closure_creators.remove(&id);
                 let id = self.import_adapter(id, signature, AdapterJsImportKind::Normal)?;
                 // Synthesize the two integer pointers we pass through which
                 // aren't present in the signature but are present in the wasm
                 // signature.
+                let debug_name = descriptor.function.closure_name(descriptor.mutable);
                 let mut function = descriptor.function.clone();
-                let nargs = function.arguments.len();
                 function.arguments.insert(0, Descriptor::I32);
                 function.arguments.insert(0, Descriptor::I32);
                 let adapter = self.table_element_adapter(descriptor.shim_idx, function)?;
@@ -172,8 +181,9 @@ impl<'a> Context<'a> {
                     AuxImport::Closure {
                         dtor: descriptor.dtor_idx,
                         mutable: descriptor.mutable,
-                        nargs,
                         adapter,
+                        debug_name,
+                        creator,
                     },
                 );
             }
@@ -253,6 +263,16 @@ impl<'a> Context<'a> {
 
     fn bind_intrinsic(&mut self, id: ImportId, intrinsic: Intrinsic) -> Result<(), Error> {
         let id = self.import_adapter(id, intrinsic.signature(), AdapterJsImportKind::Normal)?;
+
+        // `__wbindgen_try_catch` behaves like a `catch` import, so its shim
+        // needs to store exceptions for Rust to pick up afterwards.
+        if let Intrinsic::TryCatch = intrinsic {
+            let adapter = self.adapters.implements.last().unwrap().2;
+            self.aux.imports_with_catch.insert(adapter);
+            if self.aux.exn_store.is_none() {
+                self.find_exn_store();
+            }
+        }
         self.aux
             .import_map
             .insert(id, AuxImport::Intrinsic(intrinsic));
@@ -271,6 +291,9 @@ impl<'a> Context<'a> {
             inline_js,
             unique_crate_identifier,
             package_json,
+            linked_modules,
+            js_preludes,
+            consts,
         } = program;
 
         for module in local_modules {
@@ -288,6 +311,9 @@ impl<'a> Context<'a> {
         if let Some(s) = package_json {
             self.aux.package_jsons.insert(s.into());
         }
+        for module in linked_modules {
+            self.linked_module(module)?;
+        }
         for export in exports {
             self.export(export)?;
         }
@@ -316,6 +342,9 @@ impl<'a> Context<'a> {
         for struct_ in structs {
             self.struct_(struct_)?;
         }
+        for const_ in consts {
+            self.const_(const_);
+        }
         for section in typescript_custom_sections {
             self.aux.extra_typescript.push_str(section);
             self.aux.extra_typescript.push_str("\n\n");
@@ -325,19 +354,44 @@ impl<'a> Context<'a> {
             .entry(unique_crate_identifier.to_string())
             .or_insert(Vec::new())
             .extend(inline_js.iter().map(|s| s.to_string()));
+        for prelude in js_preludes {
+            if !self.aux.js_preludes.iter().any(|p| p == prelude) {
+                self.aux.js_preludes.push(prelude.to_string());
+            }
+        }
         Ok(())
     }
 
     fn export(&mut self, export: decode::Export<'_>) -> Result<(), Error> {
         let wasm_name = match &export.class {
-            Some(class) => struct_function_export_name(class, export.function.name),
+            Some(class) => struct_function_export_name(class, export.rust_name),
             None => export.function.name.to_string(),
         };
         let mut descriptor = match self.descriptors.remove(&wasm_name) {
-            None => return Ok(()),
+            None => {
+                self.aux.warnings.push(format!(
+                    "no JS glue was generated for the export `{}` because its \
+                     type information is missing from the module",
+                    wasm_name
+                ));
+                return Ok(());
+            }
             Some(d) => d.unwrap_function(),
         };
         let (export_id, id) = self.function_exports[&wasm_name];
+
+        // The promise returned from `async` exports is passed to JS like any
+        // other `JsValue`, but typed after the value it resolves to.
+        let resolves_to = match mem::replace(&mut descriptor.ret, Descriptor::Anyref) {
+            Descriptor::Promise(resolved) => match *resolved {
+                Descriptor::Unit => Some("void".to_string()),
+                _ => Some("any".to_string()),
+            },
+            ret => {
+                descriptor.ret = ret;
+                None
+            }
+        };
         if export.start {
             self.add_start_function(id)?;
         }
@@ -353,6 +407,7 @@ impl<'a> Context<'a> {
                             AuxExportKind::Getter {
                                 class,
                                 field: f.to_string(),
+                                cached: export.cached,
                             }
                         }
                         decode::OperationKind::Setter(f) => {
@@ -372,6 +427,10 @@ impl<'a> Context<'a> {
                                 class,
                                 name: export.function.name.to_string(),
                                 consumed: export.consumed,
+                                iterator: export.iterator,
+                                observed_attribute: export
+                                    .observed_attribute
+                                    .map(|s| s.to_string()),
                             }
                         }
                     },
@@ -381,13 +440,27 @@ impl<'a> Context<'a> {
         };
 
         let id = self.export_adapter(export_id, descriptor)?;
+        if export.no_copy {
+            self.return_owned_view(id, &wasm_name)?;
+        }
         self.aux.export_map.insert(
             id,
             AuxExport {
                 debug_name: wasm_name,
                 comments: concatenate_comments(&export.comments),
                 arg_names: Some(export.function.arg_names),
+                renamed_via_js_name: export.function.renamed_via_js_name,
+                resolves_to,
+                js_namespace: export.js_namespace.map(|s| s.to_string()),
+                stability: match export.stability {
+                    decode::Stability::Stable => AuxStability::Stable,
+                    decode::Stability::Unstable => AuxStability::Unstable,
+                    decode::Stability::Deprecated(note) => {
+                        AuxStability::Deprecated(note.map(|s| s.to_string()))
+                    }
+                },
                 kind,
+                cfg_feature: export.cfg_feature.map(|s| s.to_string()),
             },
         );
         Ok(())
@@ -448,7 +521,10 @@ impl<'a> Context<'a> {
         } = function;
         let (import_id, _id) = match self.function_imports.get(*shim) {
             Some(pair) => *pair,
-            None => return Ok(()),
+            None => {
+                self.warn_unused_import(method.as_ref().map(|m| m.class), function.name);
+                return Ok(());
+            }
         };
         let descriptor = match self.descriptors.remove(*shim) {
             None => return Ok(()),
@@ -518,7 +594,11 @@ impl<'a> Context<'a> {
             }
         }
         if *assert_no_shim {
-            self.aux.imports_with_assert_no_shim.insert(adapter);
+            let name = match method {
+                Some(data) => format!("{}.{}", data.class, function.name),
+                None => function.name.to_string(),
+            };
+            self.aux.imports_with_assert_no_shim.insert(adapter, name);
         }
 
         self.aux.import_map.insert(id, import);
@@ -629,6 +709,32 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Records a warning about an imported function that's declared in an
+    /// `extern` block but never called, so the compiler didn't import it.
+    fn warn_unused_import(&mut self, class: Option<&str>, name: &str) {
+        if !self.warn_unused_imports {
+            return;
+        }
+        // `js-sys` and `web-sys` declare bindings for entire JS and Web APIs
+        // of which any one program only uses a fraction, so warning about
+        // those would drown out everything else.
+        let crate_name = match self.unique_crate_identifier.rfind('-') {
+            Some(i) => &self.unique_crate_identifier[..i],
+            None => self.unique_crate_identifier,
+        };
+        if crate_name == "js-sys" || crate_name == "web-sys" {
+            return;
+        }
+        let name = match class {
+            Some(class) => format!("{}.{}", class, name),
+            None => name.to_string(),
+        };
+        self.aux.warnings.push(format!(
+            "the imported function `{}` from crate `{}` is never used",
+            name, crate_name
+        ));
+    }
+
     fn import_static(
         &mut self,
         import: &decode::Import<'_>,
@@ -701,11 +807,31 @@ impl<'a> Context<'a> {
                 .iter()
                 .map(|v| (v.name.to_string(), v.value))
                 .collect(),
+            cfg_feature: enum_.cfg_feature.map(|s| s.to_string()),
+            repr: match enum_.repr {
+                decode::EnumRepr::Number => AuxEnumRepr::Number,
+                decode::EnumRepr::String => AuxEnumRepr::String,
+                decode::EnumRepr::Both => AuxEnumRepr::Both,
+            },
         };
         self.aux.enums.push(aux);
         Ok(())
     }
 
+    fn const_(&mut self, const_: decode::Const<'_>) {
+        let (value, ts_type) = match const_.value {
+            decode::ConstValue::String(s) => (serde_json::to_string(s).unwrap(), "string"),
+            decode::ConstValue::Boolean(b) => (b.to_string(), "boolean"),
+            decode::ConstValue::Number(n) => (n.to_string(), "number"),
+        };
+        self.aux.consts.push(AuxConst {
+            name: const_.name.to_string(),
+            comments: concatenate_comments(&const_.comments),
+            value,
+            ts_type,
+        });
+    }
+
     fn struct_(&mut self, struct_: decode::Struct<'_>) -> Result<(), Error> {
         for field in struct_.fields {
             let getter = wasm_bindgen_shared::struct_field_get(&struct_.name, &field.name);
@@ -728,11 +854,17 @@ impl<'a> Context<'a> {
                 AuxExport {
                     debug_name: format!("getter for `{}::{}`", struct_.name, field.name),
                     arg_names: None,
+                    renamed_via_js_name: false,
+                    resolves_to: None,
+                    js_namespace: None,
+                    stability: AuxStability::Stable,
                     comments: concatenate_comments(&field.comments),
                     kind: AuxExportKind::Getter {
                         class: struct_.name.to_string(),
                         field: field.name.to_string(),
+                        cached: field.cached,
                     },
+                    cfg_feature: struct_.cfg_feature.map(|s| s.to_string()),
                 },
             );
 
@@ -741,6 +873,15 @@ impl<'a> Context<'a> {
                 continue;
             }
 
+            // Fields marked `typed_array = view` are read as a borrowed slice
+            // of wasm memory, but written with an owned vector.
+            let descriptor = match descriptor {
+                Descriptor::Ref(d) => match *d {
+                    Descriptor::Slice(elem) => Descriptor::Vector(elem),
+                    d => Descriptor::Ref(Box::new(d)),
+                },
+                d => d,
+            };
             let (setter_id, _) = self.function_exports[&setter];
             let setter_descriptor = Function {
                 arguments: vec![Descriptor::I32, descriptor],
@@ -753,11 +894,16 @@ impl<'a> Context<'a> {
                 AuxExport {
                     debug_name: format!("setter for `{}::{}`", struct_.name, field.name),
                     arg_names: None,
+                    renamed_via_js_name: false,
+                    resolves_to: None,
+                    js_namespace: None,
+                    stability: AuxStability::Stable,
                     comments: concatenate_comments(&field.comments),
                     kind: AuxExportKind::Setter {
                         class: struct_.name.to_string(),
                         field: field.name.to_string(),
                     },
+                    cfg_feature: struct_.cfg_feature.map(|s| s.to_string()),
                 },
             );
         }
@@ -765,6 +911,9 @@ impl<'a> Context<'a> {
             name: struct_.name.to_string(),
             comments: concatenate_comments(&struct_.comments),
             is_inspectable: struct_.is_inspectable,
+            cfg_feature: struct_.cfg_feature.map(|s| s.to_string()),
+            extends_js: struct_.extends_js.map(|s| s.to_string()),
+            custom_element: struct_.custom_element.map(|s| s.to_string()),
         };
         self.aux.structs.push(aux);
 
@@ -781,6 +930,37 @@ impl<'a> Context<'a> {
                 .insert(id, AuxImport::WrapInExportedClass(struct_.name.to_string()));
         }
 
+        let instanceof = wasm_bindgen_shared::instanceof_function(struct_.name);
+        if let Some((import_id, _id)) = self.function_imports.get(&instanceof).cloned() {
+            let signature = Function {
+                shim_idx: 0,
+                arguments: vec![Descriptor::Ref(Box::new(Descriptor::Anyref))],
+                ret: Descriptor::Boolean,
+            };
+            let id = self.import_adapter(import_id, signature, AdapterJsImportKind::Normal)?;
+            self.aux.import_map.insert(
+                id,
+                AuxImport::InstanceofExportedClass(struct_.name.to_string()),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn linked_module(&mut self, module: decode::LinkedModule<'_>) -> Result<(), Error> {
+        let (import_id, _id) = match self.function_imports.get(module.link_function_name) {
+            Some(pair) => *pair,
+            None => return Ok(()),
+        };
+        let signature = Function {
+            shim_idx: 0,
+            arguments: Vec::new(),
+            ret: Descriptor::Anyref,
+        };
+        let id = self.import_adapter(import_id, signature, AdapterJsImportKind::Normal)?;
+        self.aux
+            .import_map
+            .insert(id, AuxImport::LinkTo(module.identifier.to_string()));
         Ok(())
     }
 
@@ -981,7 +1161,12 @@ impl<'a> Context<'a> {
                 debug_name: format!("standard export {:?}", id),
                 comments: String::new(),
                 arg_names: None,
+                renamed_via_js_name: true,
+                resolves_to: None,
+                js_namespace: None,
+                stability: AuxStability::Stable,
                 kind,
+                cfg_feature: None,
             };
             assert!(self.aux.export_map.insert(id, export).is_none());
         }
@@ -1102,7 +1287,8 @@ impl<'a> Context<'a> {
         // usage of closures going out to the import.
         let mut args = ret.cx.instruction_builder(false);
         if uses_retptr {
-            args.input.push(AdapterType::I32);
+            let ptr = args.cx.pointer();
+            args.input.push(ptr);
         }
         for arg in signature.arguments.iter() {
             args.outgoing(arg)?;
@@ -1175,6 +1361,34 @@ impl<'a> Context<'a> {
         Ok(id)
     }
 
+    /// Changes the adapter `id` to hand its returned vector to JS as a view of
+    /// wasm memory which JS takes ownership of, rather than copying it out and
+    /// freeing it immediately. Used for `#[wasm_bindgen(no_copy)]`.
+    fn return_owned_view(&mut self, id: AdapterId, name: &str) -> Result<(), Error> {
+        let instructions = match &mut self.adapters.adapters.get_mut(&id).unwrap().kind {
+            AdapterKind::Local { instructions } => instructions,
+            AdapterKind::Import { .. } => unreachable!(),
+        };
+        for instr in instructions.iter_mut() {
+            match instr.instr {
+                Instruction::VectorLoad {
+                    kind: VectorKind::Anyref,
+                    ..
+                } => break,
+                Instruction::VectorLoad { kind, mem, free } => {
+                    instr.instr = Instruction::OwnedView { kind, mem, free };
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        bail!(
+            "`#[wasm_bindgen(no_copy)]` on `{}` requires returning a `Vec<T>` or \
+             `Box<[T]>` of numbers",
+            name
+        )
+    }
+
     fn table_element_adapter(&mut self, idx: u32, signature: Function) -> Result<AdapterId, Error> {
         let call = Instruction::CallTableElement(idx);
         // like above, largely just defer the work elsewhere
@@ -1249,6 +1463,16 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// The adapter type pointers and lengths are passed as, which is an `i64`
+    /// for 64-bit memories.
+    fn pointer(&self) -> AdapterType {
+        if self.memory64 {
+            AdapterType::I64
+        } else {
+            AdapterType::I32
+        }
+    }
+
     fn malloc(&self) -> Result<FunctionId, Error> {
         self.function_exports
             .get("__wbindgen_malloc")
@@ -23,6 +23,10 @@ pub struct WasmBindgenAux {
     /// that crate identifier.
     pub snippets: HashMap<String, Vec<String>>,
 
+    /// JS registered through `js_prelude!` to be inserted at the top of the
+    /// generated JS, before any bindings run.
+    pub js_preludes: Vec<String>,
+
     /// A list of all `package.json` files that are intended to be included in
     /// the final build.
     pub package_jsons: HashSet<PathBuf>,
@@ -37,7 +41,9 @@ pub struct WasmBindgenAux {
     /// Small bits of metadata about imports.
     pub imports_with_catch: HashSet<AdapterId>,
     pub imports_with_variadic: HashSet<AdapterId>,
-    pub imports_with_assert_no_shim: HashSet<AdapterId>,
+    /// Imports annotated with `assert_no_shim`, along with their names for
+    /// error messages.
+    pub imports_with_assert_no_shim: HashMap<AdapterId, String>,
 
     /// Auxiliary information to go into JS/TypeScript bindings describing the
     /// exported enums from Rust.
@@ -47,6 +53,9 @@ pub struct WasmBindgenAux {
     /// exported structs from Rust and their fields they've got exported.
     pub structs: Vec<AuxStruct>,
 
+    /// Constants exported from Rust.
+    pub consts: Vec<AuxConst>,
+
     /// Information about various internal functions used to manage the `anyref`
     /// table, later used to process JS bindings.
     pub anyref_table: Option<walrus::TableId>,
@@ -56,6 +65,10 @@ pub struct WasmBindgenAux {
     /// Various intrinsics used for JS glue generation
     pub exn_store: Option<walrus::FunctionId>,
     pub shadow_stack_pointer: Option<walrus::GlobalId>,
+
+    /// Warnings about bindings which are declared in Rust but don't end up
+    /// being hooked up, reported once the module has been processed.
+    pub warnings: Vec<String>,
 }
 
 pub type WasmBindgenAuxId = TypedCustomSectionId<WasmBindgenAux>;
@@ -70,8 +83,32 @@ pub struct AuxExport {
     /// Argument names in Rust forwarded here to configure the names that show
     /// up in TypeScript bindings.
     pub arg_names: Option<Vec<String>>,
+    /// Whether the name of this export was explicitly configured with
+    /// `js_name`, in which case it's never changed by `--camel-case`.
+    pub renamed_via_js_name: bool,
+    /// For `async` exports, the TypeScript type of the value which the
+    /// returned promise resolves to.
+    pub resolves_to: Option<String>,
+    /// The JS namespace object that a free function is exported under, if
+    /// any, via `#[wasm_bindgen(js_namespace = foo)]`.
+    pub js_namespace: Option<String>,
+    /// Whether this export was marked as unstable or deprecated, which is
+    /// documented in JS and TypeScript and optionally warned about at runtime.
+    pub stability: AuxStability,
     /// What kind of function this is and where it shows up
     pub kind: AuxExportKind,
+    /// The Cargo feature this export was only compiled with, given with
+    /// `#[wasm_bindgen(cfg_feature = "...")]`
+    pub cfg_feature: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum AuxStability {
+    Stable,
+    /// Marked with `#[wasm_bindgen(unstable)]`
+    Unstable,
+    /// Marked with `#[wasm_bindgen(deprecated)]`, with an optional message
+    Deprecated(Option<String>),
 }
 
 /// All possible kinds of exports from a wasm module.
@@ -102,7 +139,13 @@ pub enum AuxExportKind {
     /// This function is intended to be a getter for a field on a class. The
     /// first argument is the internal pointer and the returned value is
     /// expected to be the field.
-    Getter { class: String, field: String },
+    Getter {
+        class: String,
+        field: String,
+        /// Whether the value is cached on the JS object after it's first read,
+        /// with `#[wasm_bindgen(cached)]`.
+        cached: bool,
+    },
 
     /// This function is intended to be a setter for a field on a class. The
     /// first argument is the internal pointer and the second argument is
@@ -120,6 +163,12 @@ pub enum AuxExportKind {
         /// Whether or not this is calling a by-value method in Rust and should
         /// clear the internal pointer in JS automatically.
         consumed: bool,
+        /// Whether this method drives the `[Symbol.iterator]` implementation
+        /// of its class.
+        iterator: bool,
+        /// The attribute of a custom element whose changes this method is
+        /// called with, if any.
+        observed_attribute: Option<String>,
     },
 }
 
@@ -131,6 +180,33 @@ pub struct AuxEnum {
     pub comments: String,
     /// A list of variants with their name and value
     pub variants: Vec<(String, u32)>,
+    /// The Cargo feature this enum was only compiled with
+    pub cfg_feature: Option<String>,
+    /// How the values of this enum are represented in JS
+    pub repr: AuxEnumRepr,
+}
+
+#[derive(Debug)]
+pub struct AuxConst {
+    /// The name of this constant
+    pub name: String,
+    /// The copied Rust comments to forward to JS
+    pub comments: String,
+    /// The value of this constant as a JS literal
+    pub value: String,
+    /// The TypeScript type of this constant
+    pub ts_type: &'static str,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum AuxEnumRepr {
+    /// The numeric value of each variant
+    Number,
+    /// The name of each variant, with `#[wasm_bindgen(js_repr = string)]`
+    String,
+    /// The numeric value of each variant, with the exported object mapping
+    /// values back to names too, with `#[wasm_bindgen(js_repr = both)]`
+    Both,
 }
 
 #[derive(Debug)]
@@ -141,6 +217,14 @@ pub struct AuxStruct {
     pub comments: String,
     /// Whether to generate helper methods for inspecting the class
     pub is_inspectable: bool,
+    /// The Cargo feature this struct was only compiled with
+    pub cfg_feature: Option<String>,
+    /// The JS class the generated class extends, from
+    /// `#[wasm_bindgen(extends_js = ...)]`
+    pub extends_js: Option<String>,
+    /// The tag name this class is defined as a custom element with, from
+    /// `#[wasm_bindgen(custom_element = "...")]`
+    pub custom_element: Option<String>,
 }
 
 /// All possible types of imports that can be imported by a wasm module.
@@ -182,10 +266,11 @@ pub enum AuxImport {
     /// This import is intended to manufacture a JS closure with the given
     /// signature and then return that back to Rust.
     Closure {
-        mutable: bool,      // whether or not this was a `FnMut` closure
-        dtor: u32,          // table element index of the destructor function
-        adapter: AdapterId, // the adapter which translates the types for this closure
-        nargs: usize,
+        mutable: bool,           // whether or not this was a `FnMut` closure
+        dtor: u32,               // table element index of the destructor function
+        adapter: AdapterId,      // the adapter which translates the types for this closure
+        debug_name: String,      // the closure's type, for error messages in debug mode
+        creator: Option<String>, // the function creating the closure, to name it in debug mode
     },
 
     /// This import is expected to be a shim that simply calls the `foo` method
@@ -286,6 +371,15 @@ pub enum AuxImport {
     /// requiring an intrinsic here to do so.
     WrapInExportedClass(String),
 
+    /// This import is an `instanceof` check against the named exported class
+    /// defined in this module.
+    InstanceofExportedClass(String),
+
+    /// This import returns the URL of the local file, identified here, which
+    /// was requested with `link_to!` and is copied into the output directory
+    /// alongside the other local JS snippets.
+    LinkTo(String),
+
     /// This is an intrinsic function expected to be implemented with a JS glue
     /// shim. Each intrinsic has its own expected signature and implementation.
     Intrinsic(Intrinsic),
@@ -1,4 +1,5 @@
 use crate::descriptor::Descriptor;
+use crate::wit::incoming::tuple_elements;
 use crate::wit::{AdapterType, Instruction, InstructionBuilder};
 use crate::wit::{InstructionData, StackChange};
 use anyhow::{bail, format_err, Error};
@@ -79,9 +80,21 @@ impl InstructionBuilder<'_, '_> {
                 );
             }
 
+            Descriptor::I128 | Descriptor::U128 => {
+                let signed = match arg {
+                    Descriptor::I128 => true,
+                    _ => false,
+                };
+                self.instruction(
+                    &[AdapterType::I32; 4],
+                    Instruction::I128FromParts { signed },
+                    &[AdapterType::Anyref],
+                );
+            }
+
             Descriptor::RustStruct(class) => {
                 self.instruction(
-                    &[AdapterType::I32],
+                    &[self.cx.pointer()],
                     Instruction::RustFromI32 {
                         class: class.to_string(),
                     },
@@ -95,8 +108,9 @@ impl InstructionBuilder<'_, '_> {
 
             Descriptor::String => {
                 // fetch the ptr/length ...
-                self.get(AdapterType::I32);
-                self.get(AdapterType::I32);
+                let ptr = self.cx.pointer();
+                self.get(ptr);
+                self.get(ptr);
 
                 // ... then defer a call to `free` to happen later
                 let free = self.cx.free()?;
@@ -131,7 +145,7 @@ impl InstructionBuilder<'_, '_> {
                 let mem = self.cx.memory()?;
                 let free = self.cx.free()?;
                 self.instruction(
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                     Instruction::VectorLoad { kind, mem, free },
                     &[AdapterType::Vector(kind)],
                 );
@@ -139,6 +153,15 @@ impl InstructionBuilder<'_, '_> {
 
             Descriptor::Option(d) => self.outgoing_option(d)?,
 
+            Descriptor::Tuple(elements) => {
+                let elements = tuple_elements(elements)?;
+                self.instruction(
+                    &vec![AdapterType::F64; elements.len()],
+                    Instruction::TupleFromScalars { elements },
+                    &[AdapterType::Anyref],
+                );
+            }
+
             Descriptor::Function(_) | Descriptor::Closure(_) | Descriptor::Slice(_) => bail!(
                 "unsupported argument type for calling JS function from Rust: {:?}",
                 arg
@@ -148,7 +171,7 @@ impl InstructionBuilder<'_, '_> {
             Descriptor::Unit => {}
 
             // Largely synthetic and can't show up
-            Descriptor::ClampedU8 => unreachable!(),
+            Descriptor::ClampedU8 | Descriptor::Promise(_) => unreachable!(),
         }
         Ok(())
     }
@@ -167,7 +190,7 @@ impl InstructionBuilder<'_, '_> {
             Descriptor::String => {
                 let std = wit_walrus::Instruction::MemoryToString(self.cx.memory()?);
                 self.instruction(
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                     Instruction::Standard(std),
                     &[AdapterType::String],
                 );
@@ -181,7 +204,7 @@ impl InstructionBuilder<'_, '_> {
                 })?;
                 let mem = self.cx.memory()?;
                 self.instruction(
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                     Instruction::View { kind, mem },
                     &[AdapterType::Vector(kind)],
                 );
@@ -190,6 +213,7 @@ impl InstructionBuilder<'_, '_> {
             Descriptor::Function(descriptor) => {
                 // synthesize the a/b arguments that aren't present in the
                 // signature from wasm-bindgen but are present in the wasm file.
+                let debug_name = descriptor.closure_name(mutable);
                 let mut descriptor = (**descriptor).clone();
                 let nargs = descriptor.arguments.len();
                 descriptor.arguments.insert(0, Descriptor::I32);
@@ -198,11 +222,12 @@ impl InstructionBuilder<'_, '_> {
                     .cx
                     .table_element_adapter(descriptor.shim_idx, descriptor)?;
                 self.instruction(
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                     Instruction::StackClosure {
                         adapter,
                         nargs,
                         mutable,
+                        debug_name,
                     },
                     &[AdapterType::Anyref],
                 );
@@ -269,7 +294,7 @@ impl InstructionBuilder<'_, '_> {
             }
             Descriptor::RustStruct(name) => {
                 self.instruction(
-                    &[AdapterType::I32],
+                    &[self.cx.pointer()],
                     Instruction::OptionRustFromI32 {
                         class: name.to_string(),
                     },
@@ -291,7 +316,7 @@ impl InstructionBuilder<'_, '_> {
                 let mem = self.cx.memory()?;
                 let free = self.cx.free()?;
                 self.instruction(
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                     Instruction::OptionVectorLoad { kind, mem, free },
                     &[AdapterType::Anyref],
                 );
@@ -326,7 +351,7 @@ impl InstructionBuilder<'_, '_> {
                 })?;
                 let mem = self.cx.memory()?;
                 self.instruction(
-                    &[AdapterType::I32; 2],
+                    &[self.cx.pointer(); 2],
                     Instruction::OptionView { kind, mem },
                     &[AdapterType::Anyref],
                 );
@@ -352,7 +377,7 @@ impl InstructionBuilder<'_, '_> {
         let mem = self.cx.memory()?;
         let free = self.cx.free()?;
         self.instruction(
-            &[AdapterType::I32; 2],
+            &[self.cx.pointer(); 2],
             Instruction::CachedStringLoad {
                 owned,
                 optional,
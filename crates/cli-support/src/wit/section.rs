@@ -35,6 +35,7 @@ pub fn add(module: &mut Module) -> Result<(), Error> {
         extra_typescript: _, // ignore this even if it's specified
         local_modules,
         snippets,
+        js_preludes,
         package_jsons,
         export_map,
         import_map,
@@ -156,6 +157,14 @@ pub fn add(module: &mut Module) -> Result<(), Error> {
         );
     }
 
+    if !js_preludes.is_empty() {
+        bail!(
+            "generating a bindings section is currently incompatible with \
+             `js_prelude!` being used as well since a standalone wasm file is \
+             being generated"
+        );
+    }
+
     if let Some(path) = package_jsons.iter().next() {
         bail!(
             "generating a bindings section is currently incompatible with \
@@ -258,6 +267,12 @@ fn translate_instruction(
         I32Split64 { .. } | I64FromLoHi { .. } => {
             bail!("64-bit integers aren't supported in wasm-bindgen");
         }
+        I32Split128 { .. } | I128FromParts { .. } => {
+            bail!("128-bit integers aren't supported in wasm interface types");
+        }
+        ScalarsFromTuple { .. } | TupleFromScalars { .. } => {
+            bail!("tuples aren't supported in wasm interface types");
+        }
         I32SplitOption64 { .. }
         | I32FromOptionAnyref { .. }
         | I32FromOptionU32Sentinel
@@ -279,7 +294,11 @@ fn translate_instruction(
         | Option64FromI32 { .. } => {
             bail!("optional types aren't supported in wasm bindgen");
         }
-        MutableSliceToMemory { .. } | VectorToMemory { .. } | VectorLoad { .. } | View { .. } => {
+        MutableSliceToMemory { .. }
+        | VectorToMemory { .. }
+        | VectorLoad { .. }
+        | OwnedView { .. }
+        | View { .. } => {
             bail!("vector slices aren't supported in wasm interface types yet");
         }
         CachedStringLoad { .. } => {
@@ -353,10 +372,14 @@ fn check_standard_import(import: &AuxImport) -> Result<(), Error> {
         AuxImport::WrapInExportedClass(name) => {
             format!("wrapping a pointer in a `{}` js class wrapper", name)
         }
+        AuxImport::InstanceofExportedClass(name) => {
+            format!("an `instanceof` check of the `{}` js class", name)
+        }
         AuxImport::Intrinsic(intrinsic) => {
             format!("wasm-bindgen specific intrinsic `{}`", intrinsic.name())
         }
         AuxImport::Closure { .. } => format!("creating a `Closure` wrapper"),
+        AuxImport::LinkTo(path) => format!("the URL of the linked file `{}`", path),
     };
     bail!("import of {} requires JS glue", item);
 }
@@ -373,7 +396,7 @@ fn check_standard_export(export: &AuxExport) -> Result<(), Error> {
                 name,
             );
         }
-        AuxExportKind::Getter { class, field } => {
+        AuxExportKind::Getter { class, field, .. } => {
             bail!(
                 "cannot export `{}::{}` getter function when generating \
                  a standalone WebAssembly module with no JS glue",
@@ -142,6 +142,16 @@ pub enum Instruction {
     I32Split64 {
         signed: bool,
     },
+    /// Pops a `BigInt` from the stack, pushing the four `i32` values making up
+    /// a 128-bit integer, least significant first.
+    I32Split128 {
+        signed: bool,
+    },
+    /// Pops an array from the stack, pushing each of its elements, whose types
+    /// are `elements`, as an `f64`.
+    ScalarsFromTuple {
+        elements: Vec<AdapterType>,
+    },
     /// Pops an `s64` or `u64` from the stack, pushing three `i32` values.
     /// First is the "some/none" bit, and the next is the low bits, and the
     /// next is the high bits.
@@ -221,6 +231,14 @@ pub enum Instruction {
     I64FromLoHi {
         signed: bool,
     },
+    /// pops four `i32`, least significant first, pushes a 128-bit `BigInt`
+    I128FromParts {
+        signed: bool,
+    },
+    /// pops one `f64` for each of `elements`, pushes an array of them
+    TupleFromScalars {
+        elements: Vec<AdapterType>,
+    },
     /// pops `i32`, pushes an anyref for the wrapped rust class
     RustFromI32 {
         class: String,
@@ -241,6 +259,13 @@ pub enum Instruction {
         mem: walrus::MemoryId,
         free: walrus::FunctionId,
     },
+    /// pops ptr/length, pushes a view of the vector which takes ownership of
+    /// the original data
+    OwnedView {
+        kind: VectorKind,
+        mem: walrus::MemoryId,
+        free: walrus::FunctionId,
+    },
     /// pops ptr/length, pushes a vector, frees the original data
     OptionVectorLoad {
         kind: VectorKind,
@@ -254,6 +279,7 @@ pub enum Instruction {
         adapter: AdapterId,
         nargs: usize,
         mutable: bool,
+        debug_name: String,
     },
     /// pops two i32 data pointers, pushes a vector view
     View {
@@ -456,6 +482,7 @@ impl walrus::CustomSection for NonstandardWitSection {
                         roots.push_func(free);
                     }
                     VectorLoad { free, mem, .. }
+                    | OwnedView { free, mem, .. }
                     | OptionVectorLoad { free, mem, .. }
                     | CachedStringLoad { free, mem, .. } => {
                         roots.push_memory(mem);
@@ -0,0 +1,153 @@
+//! Support for HTTP fixture routes on the browser test server.
+//!
+//! Tests exercising networking APIs like `fetch`, `XMLHttpRequest`, or
+//! `WebSocket` need something on the other end of the connection. The
+//! `WASM_BINDGEN_TEST_FIXTURES` environment variable can be set to a JSON file
+//! listing routes that the test server will answer in addition to serving
+//! static files, and otherwise `tests/fixtures.json` in the crate being tested
+//! is used if it exists. The file contains an array of routes like:
+//!
+//! ```json
+//! [
+//!     { "path": "/fixtures/hello", "body": "hello!" },
+//!     { "path": "/fixtures/data", "file": "data.json", "headers": { "Content-Type": "application/json" } },
+//!     { "path": "/fixtures/missing", "status": 404 },
+//!     { "path": "/fixtures/echo", "method": "POST", "echo": true },
+//!     { "path": "/fixtures/ws", "websocket-echo": true }
+//! ]
+//! ```
+//!
+//! Paths of `file` entries are relative to the fixtures file itself.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use anyhow::{bail, Context, Error};
+use rouille::websocket::{self, Message};
+use rouille::{Request, Response};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Route {
+    path: String,
+    method: Option<String>,
+    status: Option<u16>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    file: Option<PathBuf>,
+    #[serde(default)]
+    echo: bool,
+    #[serde(default)]
+    websocket_echo: bool,
+}
+
+/// Loads the routes listed in the file named by `WASM_BINDGEN_TEST_FIXTURES`,
+/// or `tests/fixtures.json`, if any.
+pub fn load() -> Result<Vec<Route>, Error> {
+    let path = match std::env::var_os("WASM_BINDGEN_TEST_FIXTURES") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let path = PathBuf::from("tests/fixtures.json");
+            if !path.exists() {
+                return Ok(Vec::new());
+            }
+            path
+        }
+    };
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read fixtures file {:?}", path))?;
+    let mut routes: Vec<Route> = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse fixtures file {:?}", path))?;
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    for route in routes.iter_mut() {
+        let kinds = route.body.is_some() as u8
+            + route.file.is_some() as u8
+            + route.echo as u8
+            + route.websocket_echo as u8;
+        if kinds > 1 {
+            bail!(
+                "fixture route `{}` may only have one of `body`, `file`, \
+                 `echo`, or `websocket-echo`",
+                route.path
+            );
+        }
+        if let Some(file) = &mut route.file {
+            *file = dir.join(&*file);
+        }
+    }
+    Ok(routes)
+}
+
+/// Attempts to answer `request` with one of `routes`, returning `None` if no
+/// route matches.
+pub fn respond(routes: &[Route], request: &Request) -> Option<Response> {
+    let route = routes.iter().find(|route| {
+        route.path == request.url()
+            && route
+                .method
+                .as_ref()
+                .map_or(true, |m| m.eq_ignore_ascii_case(request.method()))
+    })?;
+
+    let mut response = if route.websocket_echo {
+        websocket_echo(request)
+    } else if route.echo {
+        let mut body = Vec::new();
+        if let Some(mut data) = request.data() {
+            if let Err(e) = data.read_to_end(&mut body) {
+                return Some(Response::text(e.to_string()).with_status_code(500));
+            }
+        }
+        let content_type = request
+            .header("Content-Type")
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        Response::from_data(content_type, body)
+    } else if let Some(file) = &route.file {
+        match fs::read(file) {
+            Ok(data) => Response::from_data("application/octet-stream", data),
+            Err(e) => {
+                Response::text(format!("failed to read {:?}: {}", file, e)).with_status_code(500)
+            }
+        }
+    } else {
+        Response::text(route.body.clone().unwrap_or_default())
+    };
+
+    if let Some(status) = route.status {
+        response = response.with_status_code(status);
+    }
+    for (name, value) in route.headers.iter() {
+        response = response.with_unique_header(name.clone(), value.clone());
+    }
+    Some(response)
+}
+
+fn websocket_echo(request: &Request) -> Response {
+    let (response, websocket) = match websocket::start(request, None::<&str>) {
+        Ok(pair) => pair,
+        Err(e) => return Response::text(format!("{:?}", e)).with_status_code(400),
+    };
+    thread::spawn(move || {
+        let mut websocket = match websocket.recv() {
+            Ok(ws) => ws,
+            Err(_) => return,
+        };
+        while let Some(message) = websocket.next() {
+            let result = match message {
+                Message::Text(text) => websocket.send_text(&text),
+                Message::Binary(data) => websocket.send_binary(&data),
+            };
+            if result.is_err() {
+                break;
+            }
+        }
+    });
+    response
+}
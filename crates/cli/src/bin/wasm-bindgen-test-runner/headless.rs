@@ -39,6 +39,104 @@ fn first_match_default() -> Vec<Capabilities> {
     vec![Capabilities::default()]
 }
 
+/// The entry of `webdriver.json` configuring the test runner itself, which
+/// isn't passed along to the driver. It's named like an extension capability
+/// so it can't clash with any standard capability.
+const OPTIONS_KEY: &str = "wasm-bindgen:options";
+
+/// Options for the test runner read from `webdriver.json`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Options {
+    /// Extra arguments for a local WebDriver binary, passed after those in env
+    /// vars like `GECKODRIVER_ARGS`.
+    #[serde(default)]
+    driver_args: Vec<String>,
+    /// Whether the browser is passed the arguments the runner normally adds,
+    /// such as the one making it headless. These can be disabled to pass
+    /// different ones through the browser's capabilities instead.
+    #[serde(default = "default_args_default")]
+    default_args: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            driver_args: Vec::new(),
+            default_args: true,
+        }
+    }
+}
+
+fn default_args_default() -> bool {
+    true
+}
+
+/// Loads the capabilities in the file named by the
+/// `WASM_BINDGEN_TEST_WEBDRIVER_JSON` environment variable, or
+/// `webdriver.json`, if any, along with the runner's own options in them.
+fn load_config(status: &dyn Fn(&str)) -> Result<(Capabilities, Options), Error> {
+    let (path, required) = match env::var_os("WASM_BINDGEN_TEST_WEBDRIVER_JSON") {
+        Some(path) => (PathBuf::from(path), true),
+        None => (PathBuf::from("webdriver.json"), false),
+    };
+    status(&format!(
+        "Try find `{}` for configure browser's capabilities:",
+        path.display()
+    ));
+    let mut capabilities: Capabilities = match File::open(&path) {
+        Ok(file) => {
+            status("Ok");
+            serde_json::from_reader(file)
+                .with_context(|| format!("failed to parse WebDriver config {:?}", path))?
+        }
+        Err(e) if required => {
+            return Err(e).with_context(|| format!("failed to read WebDriver config {:?}", path));
+        }
+        Err(_) => {
+            status("Not found");
+            Capabilities::new()
+        }
+    };
+    let options = take_options(&mut capabilities)
+        .with_context(|| format!("invalid `{}` in {:?}", OPTIONS_KEY, path))?;
+    Ok((capabilities, options))
+}
+
+/// Removes the runner's own options from `capabilities`, returning the
+/// defaults if there aren't any.
+fn take_options(capabilities: &mut Capabilities) -> Result<Options, Error> {
+    match capabilities.remove(OPTIONS_KEY) {
+        Some(options) => Ok(serde_json::from_value(options)?),
+        None => Ok(Options::default()),
+    }
+}
+
+/// Adds `args` to the browser arguments in the `key` capability, skipping any
+/// which are already present.
+fn add_browser_args(cap: &mut Capabilities, key: &str, args: &[&str]) -> Result<(), Error> {
+    let options = match cap
+        .entry(key.to_string())
+        .or_insert_with(|| Json::Object(Map::new()))
+    {
+        Json::Object(options) => options,
+        _ => bail!("the `{}` capability must be a JSON object", key),
+    };
+    let list = match options
+        .entry("args".to_string())
+        .or_insert_with(|| Json::Array(vec![]))
+    {
+        Json::Array(list) => list,
+        _ => bail!("`args` in the `{}` capability must be a JSON array", key),
+    };
+    for arg in args {
+        if !list.iter().any(|a| a.as_str() == Some(*arg)) {
+            list.push(Json::String(arg.to_string()));
+        }
+    }
+    Ok(())
+}
+
 /// Wrapper for [`Capabilities`] used in `--legacy` mode.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct LegacyNewSessionParameters {
@@ -55,7 +153,24 @@ pub struct LegacyNewSessionParameters {
 /// binary, controlling it, running tests, scraping output, displaying output,
 /// etc. It will return `Ok` if all tests finish successfully, and otherwise it
 /// will return an error if some tests failed.
-pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
+///
+/// If `report` is set, a machine-readable report was requested with
+/// `--format`, in which case it's the only thing printed to stdout and all
+/// other output goes to stderr.
+///
+/// If the tests collect coverage data, the page sends it to the server once
+/// they're done, which writes it to `coverage`.
+pub fn run(server: &SocketAddr, shell: &Shell, report: bool, coverage: &Path) -> Result<(), Error> {
+    // Keep stdout clean for the report, if one was requested
+    let status = |s: &str| {
+        if report {
+            eprintln!("{}", s);
+        } else {
+            println!("{}", s);
+        }
+    };
+
+    let (capabilities, options) = load_config(&status)?;
     let driver = Driver::find()?;
     let mut drop_log: Box<dyn FnMut()> = Box::new(|| ());
     let driver_url = match driver.location() {
@@ -70,6 +185,7 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
             // threads. We'll print this output later.
             let mut cmd = Command::new(path);
             cmd.args(args)
+                .args(&options.driver_args)
                 .arg(format!("--port={}", driver_addr.port().to_string()));
             let mut child = BackgroundChild::spawn(&path, &mut cmd, shell)?;
             drop_log = Box::new(move || child.print_stdio_on_drop = false);
@@ -92,32 +208,21 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
             Url::parse(&format!("http://{}", driver_addr)).map_err(Error::from)
         }
     }?;
-    println!(
+    status(&format!(
         "Running headless tests in {} on `{}`",
         driver.browser(),
         driver_url.as_str(),
-    );
+    ));
 
     let mut client = Client {
         handle: Easy::new(),
         driver_url,
         session: None,
     };
-    println!("Try find `webdriver.json` for configure browser's capabilities:");
-    let capabilities: Capabilities = match File::open("webdriver.json") {
-        Ok(file) => {
-            println!("Ok");
-            serde_json::from_reader(file)
-        }
-        Err(_) => {
-            println!("Not found");
-            Ok(Capabilities::new())
-        }
-    }?;
     shell.status("Starting new webdriver session...");
     // Allocate a new session with the webdriver protocol, and once we've done
     // so schedule the browser to get closed with a call to `close_window`.
-    let id = client.new_session(&driver, capabilities)?;
+    let id = client.new_session(&driver, capabilities, options.default_args)?;
     client.session = Some(id.clone());
 
     // Visit our local server to open up the page that runs tests, and then get
@@ -127,6 +232,8 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
     client.goto(&id, &url)?;
     shell.status("Loading page elements...");
     let output = client.element(&id, "#output")?;
+    let report_element = client.element(&id, "#report")?;
+    let coverage_element = client.element(&id, "#coverage")?;
     let logs = client.element(&id, "#console_log")?;
     let errors = client.element(&id, "#console_error")?;
 
@@ -158,28 +265,50 @@ pub fn run(server: &SocketAddr, shell: &Shell) -> Result<(), Error> {
     // what happened on the console. Currently we just do this by scraping the
     // output of various fields and printing them out, hopefully providing
     // enough diagnostic info to see what went wrong (if anything).
+    // Coverage data is sent after the tests finish, so give the page a moment
+    // to deliver it. The page marks when it's done, either having sent the
+    // data or having found that the tests don't collect any.
+    if client.text(&id, &output)?.contains("test result: ") {
+        let start = Instant::now();
+        while start.elapsed() < Duration::new(5, 0) {
+            if !client.text(&id, &coverage_element)?.is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        if coverage.exists() {
+            status(&format!("Wrote coverage data to {}", coverage.display()));
+        }
+    }
+
     let output = client.text(&id, &output)?;
+    let report_text = client.text(&id, &report_element)?;
     let logs = client.text(&id, &logs)?;
     let errors = client.text(&id, &errors)?;
 
     if output.contains("test result: ") {
-        println!("{}", output);
+        if report {
+            eprintln!("{}", output);
+            println!("{}", report_text);
+        } else {
+            println!("{}", output);
+        }
 
         // If the tests harness finished (either successfully or unsuccessfully)
         // then in theory all the info needed to debug the failure is in its own
         // output, so we shouldn't need the driver logs to get printed.
         drop_log();
     } else {
-        println!("failed to detect test as having been run");
+        status("failed to detect test as having been run");
         if output.len() > 0 {
-            println!("output div contained:\n{}", tab(&output));
+            status(&format!("output div contained:\n{}", tab(&output)));
         }
     }
     if logs.len() > 0 {
-        println!("console.log div contained:\n{}", tab(&logs));
+        status(&format!("console.log div contained:\n{}", tab(&logs)));
     }
     if errors.len() > 0 {
-        println!("console.log div contained:\n{}", tab(&errors));
+        status(&format!("console.log div contained:\n{}", tab(&errors)));
     }
 
     if !output.contains("test result: ok") {
@@ -330,7 +459,12 @@ enum Method<'a> {
 // copied the `webdriver-client` crate when writing the below bindings.
 
 impl Client {
-    fn new_session(&mut self, driver: &Driver, mut cap: Capabilities) -> Result<String, Error> {
+    fn new_session(
+        &mut self,
+        driver: &Driver,
+        mut cap: Capabilities,
+        default_args: bool,
+    ) -> Result<String, Error> {
         match driver {
             Driver::Gecko(_) => {
                 #[derive(Deserialize)]
@@ -343,15 +477,9 @@ impl Client {
                     #[serde(rename = "sessionId")]
                     session_id: String,
                 }
-                cap.entry("moz:firefoxOptions".to_string())
-                    .or_insert_with(|| Json::Object(serde_json::Map::new()))
-                    .as_object_mut()
-                    .expect("moz:firefoxOptions wasn't a JSON object")
-                    .entry("args".to_string())
-                    .or_insert_with(|| Json::Array(vec![]))
-                    .as_array_mut()
-                    .expect("args wasn't a JSON array")
-                    .extend(vec![Json::String("-headless".to_string())]);
+                if default_args {
+                    add_browser_args(&mut cap, "moz:firefoxOptions", &["-headless"])?;
+                }
                 let session_config = SpecNewSessionParameters {
                     always_match: cap,
                     first_match: vec![Capabilities::new()],
@@ -378,13 +506,17 @@ impl Client {
                     #[serde(rename = "sessionId")]
                     session_id: Option<String>,
                 }
+                // Safari can't run headless, so there are no default
+                // arguments to add here.
+                let session_config = SpecNewSessionParameters {
+                    always_match: cap.clone(),
+                    first_match: vec![Capabilities::new()],
+                };
                 let request = json!({
                     // this is needed for the now `--legacy` mode
-                    "desiredCapabilities": {
-                    },
+                    "desiredCapabilities": cap,
                     // this is needed for the now `--w3c` (default) mode
-                    "capabilities": {
-                    }
+                    "capabilities": session_config,
                 });
                 let x: Response = self.post("/session", &request)?;
                 Ok(x.clone()
@@ -398,22 +530,20 @@ impl Client {
                     #[serde(rename = "sessionId")]
                     session_id: String,
                 }
-                cap.entry("goog:chromeOptions".to_string())
-                    .or_insert_with(|| Json::Object(serde_json::Map::new()))
-                    .as_object_mut()
-                    .expect("goog:chromeOptions wasn't a JSON object")
-                    .entry("args".to_string())
-                    .or_insert_with(|| Json::Array(vec![]))
-                    .as_array_mut()
-                    .expect("args wasn't a JSON array")
-                    .extend(vec![
-                        Json::String("headless".to_string()),
-                        // See https://stackoverflow.com/questions/50642308/
-                        // for what this funky `disable-dev-shm-usage`
-                        // option is
-                        Json::String("disable-dev-shm-usage".to_string()),
-                        Json::String("no-sandbox".to_string()),
-                    ]);
+                if default_args {
+                    add_browser_args(
+                        &mut cap,
+                        "goog:chromeOptions",
+                        &[
+                            "headless",
+                            // See https://stackoverflow.com/questions/50642308/
+                            // for what this funky `disable-dev-shm-usage`
+                            // option is
+                            "disable-dev-shm-usage",
+                            "no-sandbox",
+                        ],
+                    )?;
+                }
                 let request = LegacyNewSessionParameters {
                     desired: cap,
                     required: Capabilities::new(),
@@ -633,3 +763,55 @@ impl<'a> Drop for BackgroundChild<'a> {
         }
     }
 }
+
+#[cfg(test)]
+fn capabilities(json: Json) -> Capabilities {
+    match json {
+        Json::Object(map) => map,
+        _ => panic!("not a JSON object"),
+    }
+}
+
+#[test]
+fn test_take_options() {
+    let mut cap = capabilities(json!({
+        "goog:chromeOptions": {},
+        "wasm-bindgen:options": {
+            "driver-args": ["--verbose"],
+            "default-args": false,
+        },
+    }));
+    let options = take_options(&mut cap).unwrap();
+    assert_eq!(options.driver_args, ["--verbose"]);
+    assert!(!options.default_args);
+    assert!(!cap.contains_key(OPTIONS_KEY));
+    assert!(cap.contains_key("goog:chromeOptions"));
+
+    let options = take_options(&mut cap).unwrap();
+    assert!(options.driver_args.is_empty());
+    assert!(options.default_args);
+
+    let mut cap = capabilities(json!({ "wasm-bindgen:options": { "driver-arg": [] } }));
+    assert!(take_options(&mut cap).is_err());
+}
+
+#[test]
+fn test_add_browser_args() {
+    let mut cap =
+        capabilities(json!({ "goog:chromeOptions": { "args": ["headless", "mute-audio"] } }));
+    add_browser_args(&mut cap, "goog:chromeOptions", &["headless", "no-sandbox"]).unwrap();
+    assert_eq!(
+        cap["goog:chromeOptions"]["args"],
+        json!(["headless", "mute-audio", "no-sandbox"])
+    );
+
+    let mut cap = Capabilities::new();
+    add_browser_args(&mut cap, "moz:firefoxOptions", &["-headless"]).unwrap();
+    assert_eq!(cap["moz:firefoxOptions"], json!({ "args": ["-headless"] }));
+
+    let mut cap = capabilities(json!({ "moz:firefoxOptions": true }));
+    assert!(add_browser_args(&mut cap, "moz:firefoxOptions", &["-headless"]).is_err());
+
+    let mut cap = capabilities(json!({ "goog:chromeOptions": { "args": "headless" } }));
+    assert!(add_browser_args(&mut cap, "goog:chromeOptions", &["headless"]).is_err());
+}
@@ -13,8 +13,9 @@
 
 use anyhow::{anyhow, bail, Context};
 use std::env;
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread;
 use wasm_bindgen_cli_support::Bindgen;
 
@@ -22,6 +23,7 @@ use wasm_bindgen_cli_support::Bindgen;
 #[global_allocator]
 static ALLOC: std::alloc::System = std::alloc::System;
 
+mod fixtures;
 mod headless;
 mod node;
 mod server;
@@ -54,6 +56,11 @@ fn main() -> anyhow::Result<()> {
 
     let module = "wasm-bindgen-test";
 
+    // If the tests were built to collect coverage data, it's written here once
+    // they finish. Remove any stale data from a previous run first.
+    let coverage = coverage_output(&wasm_file_to_test)?;
+    drop(fs::remove_file(&coverage));
+
     // Collect all tests that the test harness is supposed to run. We assume
     // that any exported function with the prefix `__wbg_test` is a test we need
     // to execute.
@@ -129,9 +136,11 @@ integration test.\
         .context("executing `wasm-bindgen` over the wasm file")?;
     shell.clear();
 
+    let args = args.collect::<Vec<_>>();
+
     // If we're executing in node.js, that module will take it from here.
     if node {
-        return node::execute(&module, &tmpdir, &args.collect::<Vec<_>>(), &tests);
+        return node::execute(&module, &tmpdir, &args, &tests, &coverage);
     }
 
     // Otherwise we're executing in a browser. Spawn a server which serves up
@@ -145,8 +154,9 @@ integration test.\
         headless,
         &module,
         &tmpdir,
-        &args.collect::<Vec<_>>(),
+        &args,
         &tests,
+        coverage.clone(),
     )
     .context("failed to spawn server")?;
     let addr = srv.server_addr();
@@ -167,6 +177,41 @@ integration test.\
     }
 
     thread::spawn(|| srv.run());
-    headless::run(&addr, &shell)?;
+    headless::run(&addr, &shell, report_requested(&args), &coverage)?;
     Ok(())
 }
+
+/// Returns where the `.profraw` coverage data for the wasm file being tested
+/// is written: a file named after it in the directory configured with
+/// `WASM_BINDGEN_TEST_PROFRAW_OUT`, or the current directory.
+fn coverage_output(wasm: &Path) -> anyhow::Result<PathBuf> {
+    let dir = match env::var_os("WASM_BINDGEN_TEST_PROFRAW_OUT") {
+        Some(dir) => PathBuf::from(dir),
+        None => env::current_dir()?,
+    };
+    let stem = wasm
+        .file_stem()
+        .ok_or_else(|| anyhow!("file to test has no file name"))?;
+    let mut name = stem.to_os_string();
+    name.push(".profraw");
+    Ok(dir.join(name))
+}
+
+/// Returns whether `--format` requested a machine-readable report of the test
+/// run rather than the default human-readable output.
+fn report_requested(args: &[OsString]) -> bool {
+    let mut args = args.iter().map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        let format = if arg == "--format" {
+            args.next()
+        } else if arg.starts_with("--format=") {
+            Some(arg["--format=".len()..].to_string().into())
+        } else {
+            None
+        };
+        if let Some(format) = format {
+            return format != "pretty";
+        }
+    }
+    false
+}
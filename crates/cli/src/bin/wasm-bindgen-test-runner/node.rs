@@ -11,6 +11,7 @@ pub fn execute(
     tmpdir: &Path,
     args: &[OsString],
     tests: &[String],
+    coverage: &Path,
 ) -> Result<(), Error> {
     let mut js_to_execute = format!(
         r#"
@@ -58,13 +59,20 @@ pub fn execute(
             cx.args(process.argv.slice(2));
 
             const ok = await cx.run(tests.map(n => wasm[n]));
+
+            // Write out coverage data, if the tests were built to collect it
+            const coverage = support.__wbgtest_cov_dump();
+            if (coverage !== undefined)
+                require('fs').writeFileSync({1:?}, coverage);
+
             if (!ok)
                 exit(1);
         }}
 
         const tests = [];
     "#,
-        module
+        module,
+        coverage.display().to_string(),
     );
 
     // Note that we're collecting *JS objects* that represent the functions to
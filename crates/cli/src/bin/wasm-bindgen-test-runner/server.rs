@@ -1,11 +1,14 @@
 use std::ffi::OsString;
-use std::fs;
+use std::fs::{self, File};
+use std::io;
 use std::net::SocketAddr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Error};
 use rouille::{Request, Response, Server};
 
+use crate::fixtures;
+
 pub fn spawn(
     addr: &SocketAddr,
     headless: bool,
@@ -13,6 +16,7 @@ pub fn spawn(
     tmpdir: &Path,
     args: &[OsString],
     tests: &[String],
+    coverage: PathBuf,
 ) -> Result<Server<impl Fn(&Request) -> Response + Send + Sync>, Error> {
     let mut js_to_execute = format!(
         r#"
@@ -23,6 +27,7 @@ pub fn spawn(
             __wbgtest_console_info,
             __wbgtest_console_warn,
             __wbgtest_console_error,
+            __wbgtest_cov_dump,
             default as init,
         }} from './{0}';
 
@@ -48,6 +53,15 @@ pub fn spawn(
             cx.args({1:?});
 
             await cx.run(test.map(s => wasm[s]));
+
+            // Send coverage data back to the test runner, if the tests were
+            // built to collect it
+            const coverage = __wbgtest_cov_dump();
+            if (coverage !== undefined) {{
+                await fetch('/__wasm_bindgen/coverage', {{ method: 'POST', body: coverage }});
+            }}
+            document.getElementById('coverage').textContent =
+                coverage === undefined ? 'none' : 'sent';
         }}
 
         const tests = [];
@@ -62,6 +76,10 @@ pub fn spawn(
     let js_path = tmpdir.join("run.js");
     fs::write(&js_path, js_to_execute).context("failed to write JS file")?;
 
+    // Any fixture routes registered for networking tests are answered before
+    // we fall back to serving files.
+    let routes = fixtures::load()?;
+
     // For now, always run forever on this port. We may update this later!
     let tmpdir = tmpdir.to_path_buf();
     let srv = Server::new(addr, move |request| {
@@ -78,6 +96,19 @@ pub fn spawn(
             return Response::from_data("text/html", s);
         }
 
+        if request.method() == "POST" && request.url() == "/__wasm_bindgen/coverage" {
+            return match save_coverage(request, &coverage) {
+                Ok(()) => Response::empty_204(),
+                Err(e) => {
+                    Response::text(format!("failed to save coverage: {}", e)).with_status_code(500)
+                }
+            };
+        }
+
+        if let Some(response) = fixtures::respond(&routes, request) {
+            return response;
+        }
+
         // Otherwise we need to find the asset here. It may either be in our
         // temporary directory (generated files) or in the main directory
         // (relative import paths to JS). Try to find both locations.
@@ -93,6 +124,14 @@ pub fn spawn(
     .map_err(|e| anyhow!("{}", e))?;
     return Ok(srv);
 
+    fn save_coverage(request: &Request, path: &Path) -> io::Result<()> {
+        let mut body = request
+            .data()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "request body already read"))?;
+        io::copy(&mut body, &mut File::create(path)?)?;
+        Ok(())
+    }
+
     fn try_asset(request: &Request, dir: &Path) -> Response {
         let response = rouille::match_assets(request, dir);
         if response.is_success() {
@@ -1,7 +1,10 @@
-use anyhow::{bail, Error};
+use anyhow::{bail, Context, Error};
 use docopt::Docopt;
 use serde::Deserialize;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process;
 use wasm_bindgen_cli_support::{Bindgen, EncodeInto};
 
@@ -13,14 +16,15 @@ const USAGE: &'static str = "
 Generating JS bindings for a wasm file
 
 Usage:
-    wasm-bindgen [options] <input>
+    wasm-bindgen [options] <input>...
     wasm-bindgen -h | --help
     wasm-bindgen -V | --version
 
 Options:
     -h --help                    Show this screen.
     --out-dir DIR                Output directory
-    --out-name VAR               Set a custom output filename (Without extension. Defaults to crate name)
+    --out-name VAR               Set a custom output filename (Without extension. Defaults to crate name,
+                                 or `index` for the entry point when given multiple inputs)
     --target TARGET              What type of output to generate, valid
                                  values are [web, bundler, nodejs, no-modules],
                                  and the default is [bundler]
@@ -28,20 +32,67 @@ Options:
     --browser                    Hint that JS should only be compatible with a browser
     --typescript                 Output a TypeScript definition file (on by default)
     --no-typescript              Don't emit a *.d.ts file
+    --typescript-feature-files   Write the TypeScript of exports gated with
+                                 `cfg_feature` to a *.d.ts file per feature
     --debug                      Include otherwise-extraneous debug checks in output
+    --no-debug                   Don't include debug checks, even if configured to
     --no-demangle                Don't demangle Rust symbol names
     --keep-debug                 Keep debug sections in wasm files
     --remove-name-section        Remove the debugging `name` section of the file
     --remove-producers-section   Remove the telemetry `producers` section
+    --emit-wat                   Also write a `.wat` disassembly of the output wasm
+    --weak-refs                  Enable usage of the JS weak references proposal
+    --no-weak-refs               Don't use weak references, even if configured to
+    --weak-closures              Free forgotten closures once JS garbage collects them
+    --no-weak-closures           Don't free forgotten closures, even if configured to
+    --reference-types            Enable usage of the wasm reference types proposal
+    --no-reference-types         Don't use reference types, even if configured to
+    --experimental-memory64      Generate bindings for a wasm64 module with a 64-bit memory
+    --no-config                  Don't read options from `wasm-bindgen.toml` or
+                                 `[package.metadata.wasm-bindgen]` in Cargo.toml
+    --camel-case                 Convert exported snake_case names to camelCase in JS
+    --strict-strings             Throw when JS strings with unpaired surrogates are
+                                 passed to Rust instead of replacing them
+    --module-hash                Name the glue's wasm import module after a hash of
+                                 the module to avoid collisions between modules
+    --warn-deprecated            Warn in the console the first time deprecated or
+                                 unstable exports are called
+    --warn-unused-imports        Warn about imported functions which are declared
+                                 but never used
+    --deny-warnings              Fail if there are warnings about the module's
+                                 bindings, like exports without JS glue
+    --minify                     Emit the generated JS with short internal names and
+                                 no comments or indentation
+    --shared-runtime MODULE      Import JS helpers from a runtime module shared
+                                 with other generated modules
+    --multiple-instances         Allow `init` to be called more than once, each call
+                                 creating an independent instance of the module
+    --write-interface FILE       Write a JSON description of the exports and
+                                 imports of the module to FILE
+    --check-interface FILE       Don't generate anything, only check that the
+                                 module's interface matches the one in FILE
+    --manifest                   Also write a JSON manifest listing every emitted
+                                 file with its role and SHA-256 hash
     --encode-into MODE           Whether or not to use TextEncoder#encodeInto,
                                  valid values are [test, always, never]
     --nodejs                     Deprecated, use `--target nodejs`
     --web                        Deprecated, use `--target web`
     --no-modules                 Deprecated, use `--target no-modules`
     -V --version                 Print the version number of wasm-bindgen
+
+Environment:
+    WASM_BINDGEN_EXPECTED_VERSION  Version of wasm-bindgen the project requires. When
+                                   it differs from this binary's version, a binary of
+                                   that version in the cache directory is run instead
+    WASM_BINDGEN_CACHE_DIR         Directory holding binaries of other versions, by
+                                   default the `wasm-bindgen` user cache directory
+    WASM_BINDGEN_DOWNLOAD          Set to `1` to download binaries of other versions
+                                   into the cache directory when they're missing
+    WASM_BINDGEN_EXPECTED_SHA256   SHA-256 hash of the release tarball to download,
+                                   required with `WASM_BINDGEN_DOWNLOAD`
 ";
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 struct Args {
     flag_nodejs: bool,
     flag_browser: bool,
@@ -49,22 +100,53 @@ struct Args {
     flag_no_modules: bool,
     flag_typescript: bool,
     flag_no_typescript: bool,
+    flag_typescript_feature_files: bool,
     flag_out_dir: Option<PathBuf>,
     flag_out_name: Option<String>,
     flag_debug: bool,
+    flag_no_debug: bool,
     flag_version: bool,
     flag_no_demangle: bool,
     flag_no_modules_global: Option<String>,
     flag_remove_name_section: bool,
     flag_remove_producers_section: bool,
+    flag_emit_wat: bool,
+    flag_weak_refs: bool,
+    flag_no_weak_refs: bool,
+    flag_weak_closures: bool,
+    flag_no_weak_closures: bool,
+    flag_reference_types: bool,
+    flag_no_reference_types: bool,
+    flag_experimental_memory64: bool,
+    flag_no_config: bool,
     flag_keep_debug: bool,
+    flag_camel_case: bool,
+    flag_strict_strings: bool,
+    flag_module_hash: bool,
+    flag_warn_deprecated: bool,
+    flag_warn_unused_imports: bool,
+    flag_deny_warnings: bool,
+    flag_minify: bool,
+    flag_shared_runtime: Option<String>,
+    flag_multiple_instances: bool,
+    flag_write_interface: Option<PathBuf>,
+    flag_check_interface: Option<PathBuf>,
+    flag_manifest: bool,
     flag_encode_into: Option<String>,
     flag_target: Option<String>,
-    arg_input: Option<PathBuf>,
+    arg_input: Vec<PathBuf>,
 }
 
 fn main() {
     env_logger::init();
+    match dispatch_to_expected_version() {
+        Ok(None) => {}
+        Ok(Some(code)) => process::exit(code),
+        Err(e) => {
+            eprintln!("error: {:?}", e);
+            process::exit(1);
+        }
+    }
     let args: Args = Docopt::new(USAGE)
         .and_then(|d| d.deserialize())
         .unwrap_or_else(|e| e.exit());
@@ -81,12 +163,331 @@ fn main() {
     process::exit(1);
 }
 
+/// Set for binaries run by `dispatch_to_expected_version`, so that a cached
+/// binary of the wrong version can't cause an endless chain of dispatches.
+const DISPATCHED_ENV: &str = "__WASM_BINDGEN_DISPATCHED";
+
+/// Checks this binary's version against `WASM_BINDGEN_EXPECTED_VERSION`, which
+/// wrappers like `wasm-pack` set to the version of the `wasm-bindgen` crate a
+/// project depends on. On a mismatch the binary of the expected version in the
+/// cache directory, downloaded first if enabled, is run in our place and its
+/// exit code is returned.
+fn dispatch_to_expected_version() -> Result<Option<i32>, Error> {
+    let expected = match env::var("WASM_BINDGEN_EXPECTED_VERSION") {
+        Ok(v) if !v.is_empty() => v,
+        _ => return Ok(None),
+    };
+    // The version ends up in paths and URLs, so only accept plain versions.
+    if !is_valid_version(&expected) {
+        bail!(
+            "`WASM_BINDGEN_EXPECTED_VERSION` is set to `{}` which isn't a valid \
+             version, expected something like `0.2.56`",
+            expected
+        );
+    }
+    let ours = env!("CARGO_PKG_VERSION");
+    if expected == ours {
+        return Ok(None);
+    }
+    if env::var_os(DISPATCHED_ENV).is_some() {
+        bail!(
+            "expected wasm-bindgen {} but the cached binary is version {}",
+            expected,
+            ours
+        );
+    }
+
+    let dir = cache_dir()?.join(&expected);
+    let bin = dir.join(format!("wasm-bindgen{}", env::consts::EXE_SUFFIX));
+    if !bin.is_file() {
+        let download = match env::var("WASM_BINDGEN_DOWNLOAD") {
+            Ok(v) => v == "1" || v == "true",
+            Err(_) => false,
+        };
+        if !download {
+            bail!(
+                "this project requires wasm-bindgen {expected} but this binary is \
+                 version {ours}\n\n\
+                 Either install the matching version with\n\n    \
+                 cargo install -f wasm-bindgen-cli --version {expected}\n\n\
+                 or set `WASM_BINDGEN_DOWNLOAD=1` to download it to `{dir}`",
+                expected = expected,
+                ours = ours,
+                dir = dir.display(),
+            );
+        }
+        let sha256 = match env::var("WASM_BINDGEN_EXPECTED_SHA256") {
+            Ok(v) => v.to_lowercase(),
+            Err(_) => bail!(
+                "`WASM_BINDGEN_EXPECTED_SHA256` must be set to the SHA-256 hash of \
+                 the release tarball to download wasm-bindgen {}",
+                expected
+            ),
+        };
+        download_release(&expected, &sha256, &dir)
+            .with_context(|| format!("failed to download wasm-bindgen {}", expected))?;
+    }
+
+    log::info!("dispatching to `{}`", bin.display());
+    let status = process::Command::new(&bin)
+        .args(env::args_os().skip(1))
+        .env(DISPATCHED_ENV, "1")
+        .status()
+        .with_context(|| format!("failed to run `{}`", bin.display()))?;
+    Ok(Some(status.code().unwrap_or(1)))
+}
+
+/// Whether `version` is a valid semver version without build metadata, like
+/// `0.2.56` or `0.3.0-rc.1`.
+fn is_valid_version(version: &str) -> bool {
+    let (core, pre) = match version.find('-') {
+        Some(i) => (&version[..i], Some(&version[i + 1..])),
+        None => (version, None),
+    };
+    let numbers = core.split('.').collect::<Vec<_>>();
+    let numbers_ok = numbers.len() == 3
+        && numbers.iter().all(|n| {
+            !n.is_empty()
+                && n.bytes().all(|b| b.is_ascii_digit())
+                && (n.len() == 1 || !n.starts_with('0'))
+        });
+    let pre_ok = pre.map_or(true, |pre| {
+        pre.split('.')
+            .all(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-'))
+    });
+    numbers_ok && pre_ok
+}
+
+/// The directory binaries of other versions are cached in, each under a
+/// directory named after its version.
+fn cache_dir() -> Result<PathBuf, Error> {
+    if let Some(dir) = env::var_os("WASM_BINDGEN_CACHE_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    let base = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Caches"))
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+    match base {
+        Some(base) => Ok(base.join("wasm-bindgen")),
+        None => bail!("failed to find a cache directory, set `WASM_BINDGEN_CACHE_DIR`"),
+    }
+}
+
+/// Downloads the prebuilt release of wasm-bindgen `version` for this platform
+/// from GitHub and unpacks its `wasm-bindgen` binary into `dir`, as long as the
+/// tarball's SHA-256 hash matches `sha256`.
+fn download_release(version: &str, sha256: &str, dir: &Path) -> Result<(), Error> {
+    let target = if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        "x86_64-unknown-linux-musl"
+    } else if cfg!(all(target_os = "macos", target_arch = "x86_64")) {
+        "x86_64-apple-darwin"
+    } else if cfg!(all(windows, target_arch = "x86_64")) {
+        "x86_64-pc-windows-msvc"
+    } else {
+        bail!("no prebuilt releases of wasm-bindgen are available for this platform");
+    };
+    let name = format!("wasm-bindgen-{}-{}", version, target);
+    let url = format!(
+        "https://github.com/rustwasm/wasm-bindgen/releases/download/{}/{}.tar.gz",
+        version, name
+    );
+    eprintln!("downloading {}", url);
+
+    let mut tarball = Vec::new();
+    let mut easy = curl::easy::Easy::new();
+    easy.url(&url)?;
+    easy.follow_location(true)?;
+    easy.fail_on_error(true)?;
+    {
+        let mut transfer = easy.transfer();
+        transfer.write_function(|data| {
+            tarball.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
+    }
+
+    let actual = format!("{:x}", Sha256::digest(&tarball));
+    if actual != sha256 {
+        bail!(
+            "SHA-256 hash of `{}` is {} but {} was expected",
+            url,
+            actual,
+            sha256
+        );
+    }
+
+    // Unpack into a temporary directory next to the final one so a partial
+    // download never shows up in the cache. It's unique to this process so
+    // concurrent downloads of the same version can't clobber each other.
+    let parent = dir.parent().unwrap_or(dir);
+    let tmp = parent.join(format!("{}.partial.{}", version, process::id()));
+    drop(fs::remove_dir_all(&tmp));
+    fs::create_dir_all(&tmp)?;
+    let archive = tmp.join(format!("{}.tar.gz", name));
+    fs::write(&archive, &tarball)?;
+    let status = process::Command::new("tar")
+        .arg("xzf")
+        .arg(&archive)
+        .current_dir(&tmp)
+        .status()
+        .context("failed to run `tar`")?;
+    if !status.success() {
+        bail!("failed to unpack `{}`", archive.display());
+    }
+    let exe = format!("wasm-bindgen{}", env::consts::EXE_SUFFIX);
+    fs::create_dir_all(dir)?;
+    fs::rename(tmp.join(&name).join(&exe), dir.join(&exe))?;
+    drop(fs::remove_dir_all(&tmp));
+    Ok(())
+}
+
+/// Options which can be set in a `wasm-bindgen.toml` file or in the
+/// `[package.metadata.wasm-bindgen]` table of `Cargo.toml`, so every build of a
+/// project uses the same settings. Flags passed on the command line take
+/// precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    target: Option<String>,
+    typescript: Option<bool>,
+    debug: Option<bool>,
+    weak_refs: Option<bool>,
+    weak_closures: Option<bool>,
+    reference_types: Option<bool>,
+    out_name: Option<String>,
+}
+
+impl Config {
+    /// Searches the current directory and its ancestors for configuration,
+    /// stopping at the first `wasm-bindgen.toml` or the first `Cargo.toml`
+    /// that has wasm-bindgen metadata.
+    fn find() -> Result<Config, Error> {
+        let cwd = env::current_dir()?;
+        for dir in cwd.ancestors() {
+            let path = dir.join("wasm-bindgen.toml");
+            if path.is_file() {
+                let contents = fs::read_to_string(&path)?;
+                return toml::from_str(&contents)
+                    .with_context(|| format!("failed to parse `{}`", path.display()));
+            }
+            let manifest = dir.join("Cargo.toml");
+            if manifest.is_file() {
+                let contents = fs::read_to_string(&manifest)?;
+                let manifest_value = toml::from_str::<toml::Value>(&contents)
+                    .with_context(|| format!("failed to parse `{}`", manifest.display()))?;
+                let table = manifest_value
+                    .get("package")
+                    .and_then(|p| p.get("metadata"))
+                    .and_then(|m| m.get("wasm-bindgen"));
+                if let Some(table) = table {
+                    return table.clone().try_into().with_context(|| {
+                        format!(
+                            "invalid `package.metadata.wasm-bindgen` in `{}`",
+                            manifest.display()
+                        )
+                    });
+                }
+            }
+        }
+        Ok(Config::default())
+    }
+
+    fn apply(self, args: &mut Args) {
+        let mode_flag =
+            args.flag_target.is_some() || args.flag_nodejs || args.flag_web || args.flag_no_modules;
+        if !mode_flag {
+            args.flag_target = self.target;
+        }
+        if args.flag_out_name.is_none() {
+            args.flag_out_name = self.out_name;
+        }
+        if !args.flag_typescript && !args.flag_no_typescript {
+            args.flag_no_typescript = self.typescript == Some(false);
+        }
+        apply_flag(&mut args.flag_debug, args.flag_no_debug, self.debug);
+        apply_flag(
+            &mut args.flag_weak_refs,
+            args.flag_no_weak_refs,
+            self.weak_refs,
+        );
+        apply_flag(
+            &mut args.flag_weak_closures,
+            args.flag_no_weak_closures,
+            self.weak_closures,
+        );
+        apply_flag(
+            &mut args.flag_reference_types,
+            args.flag_no_reference_types,
+            self.reference_types,
+        );
+    }
+}
+
+/// Takes a boolean option from the configuration unless either the flag
+/// enabling it or the one disabling it was passed on the command line.
+fn apply_flag(flag: &mut bool, negated: bool, config: Option<bool>) {
+    if !*flag && !negated {
+        *flag = config.unwrap_or(false);
+    }
+}
+
 fn rmain(args: &Args) -> Result<(), Error> {
-    let input = match args.arg_input {
-        Some(ref s) => s,
-        None => bail!("input file expected"),
+    let mut args = args.clone();
+    if !args.flag_no_config {
+        Config::find()?.apply(&mut args);
+    }
+    let args = &args;
+
+    let out_dir = match args.flag_out_dir {
+        Some(ref p) => p,
+        None => bail!("the `--out-dir` argument is now required"),
     };
 
+    match &args.arg_input[..] {
+        [] => bail!("input file expected"),
+        [input] => configure(args, input)?.generate(out_dir),
+        inputs => {
+            if args.flag_write_interface.is_some() || args.flag_check_interface.is_some() {
+                bail!("interfaces can only be written or checked for a single input module");
+            }
+            // With multiple inputs each module gets bindings named after its
+            // own file, and `--out-name` instead names the combined entry
+            // point which lazily loads each of them. All inputs are checked
+            // before generating anything to avoid overwriting existing output
+            // if they can't be bundled together.
+            let mut stems = Vec::new();
+            for input in inputs {
+                let stem = match input.file_stem().and_then(|s| s.to_str()) {
+                    Some(stem) => stem.to_string(),
+                    None => bail!("input file `{}` has no valid UTF-8 name", input.display()),
+                };
+                stems.push(stem);
+            }
+            let name = match &args.flag_out_name {
+                Some(name) => name.as_str(),
+                None => "index",
+            };
+            let entry = configure(args, &inputs[0])?;
+            entry.check_entry(name, &stems)?;
+            for (input, stem) in inputs.iter().zip(&stems) {
+                configure(args, input)?
+                    .out_name(stem)
+                    .generate(out_dir)
+                    .with_context(|| format!("failed to process `{}`", input.display()))?;
+            }
+            entry.generate_entry(out_dir, name, &stems)
+        }
+    }
+}
+
+fn configure(args: &Args, input: &Path) -> Result<Bindgen, Error> {
     let typescript = args.flag_typescript || !args.flag_no_typescript;
 
     let mut b = Bindgen::new();
@@ -109,7 +510,25 @@ fn rmain(args: &Args) -> Result<(), Error> {
         .keep_debug(args.flag_keep_debug)
         .remove_name_section(args.flag_remove_name_section)
         .remove_producers_section(args.flag_remove_producers_section)
-        .typescript(typescript);
+        .emit_wat(args.flag_emit_wat)
+        .weak_refs(args.flag_weak_refs)
+        .weak_closures(args.flag_weak_closures)
+        .reference_types(args.flag_reference_types)
+        .memory64(args.flag_experimental_memory64)
+        .camel_case(args.flag_camel_case)
+        .strict_strings(args.flag_strict_strings)
+        .module_hash(args.flag_module_hash)
+        .warn_deprecated(args.flag_warn_deprecated)
+        .warn_unused_imports(args.flag_warn_unused_imports)
+        .deny_warnings(args.flag_deny_warnings)
+        .minify(args.flag_minify)
+        .shared_runtime(args.flag_shared_runtime.clone())
+        .multiple_instances(args.flag_multiple_instances)
+        .write_interface(args.flag_write_interface.clone())
+        .check_interface(args.flag_check_interface.clone())
+        .manifest(args.flag_manifest)
+        .typescript(typescript)
+        .typescript_feature_files(args.flag_typescript_feature_files);
     if let Some(ref name) = args.flag_no_modules_global {
         b.no_modules_global(name)?;
     }
@@ -124,11 +543,5 @@ fn rmain(args: &Args) -> Result<(), Error> {
             s => bail!("invalid encode-into mode: `{}`", s),
         };
     }
-
-    let out_dir = match args.flag_out_dir {
-        Some(ref p) => p,
-        None => bail!("the `--out-dir` argument is now required"),
-    };
-
-    b.generate(out_dir)
+    Ok(b)
 }
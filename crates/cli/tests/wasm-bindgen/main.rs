@@ -12,6 +12,7 @@
 //! large!
 
 use assert_cmd::prelude::*;
+use predicates::prelude::*;
 use predicates::str;
 use std::env;
 use std::fs;
@@ -38,6 +39,7 @@ fn repo_root() -> PathBuf {
 struct Project {
     root: PathBuf,
     name: &'static str,
+    target: &'static str,
 }
 
 impl Project {
@@ -45,7 +47,18 @@ impl Project {
         let root = target_dir().join("cli-tests").join(name);
         drop(fs::remove_dir_all(&root));
         fs::create_dir_all(&root).unwrap();
-        Project { root, name }
+        Project {
+            root,
+            name,
+            target: "wasm32-unknown-unknown",
+        }
+    }
+
+    /// Builds the project for `wasm64-unknown-unknown`, which has no
+    /// precompiled standard library and so needs `-Zbuild-std`.
+    fn memory64(&mut self) -> &mut Project {
+        self.target = "wasm64-unknown-unknown";
+        self
     }
 
     fn file(&mut self, name: &str, contents: &str) -> &mut Project {
@@ -95,17 +108,20 @@ impl Project {
         }
 
         let target_dir = target_dir();
-        Command::new("cargo")
-            .current_dir(&self.root)
+        let mut cmd = Command::new("cargo");
+        cmd.current_dir(&self.root)
             .arg("build")
             .arg("--target")
-            .arg("wasm32-unknown-unknown")
-            .env("CARGO_TARGET_DIR", &target_dir)
-            .assert()
-            .success();
+            .arg(self.target)
+            .env("CARGO_TARGET_DIR", &target_dir);
+        if self.target != "wasm32-unknown-unknown" {
+            cmd.arg("-Zbuild-std=std,panic_abort")
+                .env("RUSTC_BOOTSTRAP", "1");
+        }
+        cmd.assert().success();
 
         target_dir
-            .join("wasm32-unknown-unknown")
+            .join(self.target)
             .join("debug")
             .join(self.name)
             .with_extension("wasm")
@@ -334,3 +350,1601 @@ $",
     )?);
     Ok(())
 }
+
+#[test]
+fn multiple_inputs_get_an_entry() {
+    let mut project = Project::new("multiple_inputs_get_an_entry");
+    project.file(
+        "src/lib.rs",
+        r#"
+            use wasm_bindgen::prelude::*;
+            #[wasm_bindgen]
+            pub fn foo() -> u32 { 3 }
+        "#,
+    );
+    let wasm = project.build();
+    let a = project.root.join("a.wasm");
+    let b = project.root.join("b-c.wasm");
+    let c = project.root.join("class.wasm");
+    let d = project.root.join("3d.wasm");
+    for path in &[&a, &b, &c, &d] {
+        fs::copy(&wasm, path).unwrap();
+    }
+    let out_dir = project.root.join("pkg");
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg("--target")
+        .arg("nodejs")
+        .args(&[&a, &b, &c, &d])
+        .assert()
+        .success();
+    assert!(out_dir.join("index.d.ts").is_file());
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const m = require('./index.js'); \
+             console.log(m.a.foo() + m.b_c.foo() + m.class_.foo() + m._3d.foo());",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("12\n");
+}
+
+#[test]
+fn multiple_inputs_checked_before_generating() -> anyhow::Result<()> {
+    let mut project = Project::new("multiple_inputs_checked_before_generating");
+    project.file(
+        "src/lib.rs",
+        r#"
+            use wasm_bindgen::prelude::*;
+            #[wasm_bindgen]
+            pub fn foo() -> u32 { 3 }
+        "#,
+    );
+    let wasm = project.build();
+    let a = project.root.join("a.wasm");
+    let b = project.root.join("b/a.wasm");
+    fs::create_dir_all(b.parent().unwrap()).unwrap();
+    fs::copy(&wasm, &a).unwrap();
+    fs::copy(&wasm, &b).unwrap();
+    let out_dir = project.root.join("pkg");
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg("--target")
+        .arg("nodejs")
+        .arg(&a)
+        .arg(&b)
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(str::is_match("multiple input modules are named `a`")?);
+    assert!(!out_dir.join("a.js").exists());
+    Ok(())
+}
+
+#[test]
+fn assert_no_shim_rejects_shims() -> anyhow::Result<()> {
+    let (mut cmd, _out_dir) = Project::new("assert_no_shim_rejects_shims")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    #[wasm_bindgen(assert_no_shim)]
+                    fn needs_shim(s: &str);
+                }
+
+                #[wasm_bindgen]
+                pub fn foo() {
+                    needs_shim("x");
+                }
+            "#,
+        )
+        .wasm_bindgen("");
+    cmd.assert().failure().code(1).stderr(str::is_match(
+        "the import `needs_shim` was annotated with `assert_no_shim`",
+    )?);
+    Ok(())
+}
+
+#[test]
+fn cached_getter_rejects_setter() -> anyhow::Result<()> {
+    let (mut cmd, _out_dir) = Project::new("cached_getter_rejects_setter")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub struct Counter(u32);
+
+                #[wasm_bindgen]
+                impl Counter {
+                    #[wasm_bindgen(getter, cached)]
+                    pub fn value(&self) -> u32 {
+                        self.0
+                    }
+                }
+
+                #[wasm_bindgen]
+                impl Counter {
+                    #[wasm_bindgen(setter)]
+                    pub fn set_value(&mut self, value: u32) {
+                        self.0 = value;
+                    }
+                }
+            "#,
+        )
+        .wasm_bindgen("");
+    cmd.assert().failure().code(1).stderr(str::is_match(
+        "property `value` of class `Counter` has a `#\\[wasm_bindgen\\(cached\\)\\]` getter",
+    )?);
+    Ok(())
+}
+
+#[test]
+fn shared_runtime_is_imported() {
+    let (mut cmd, out_dir) = Project::new("shared_runtime_is_imported")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn greet(name: &str) -> JsValue {
+                    JsValue::from(format!("hi {}", name))
+                }
+            "#,
+        )
+        .wasm_bindgen("--target web --shared-runtime ../runtime.js");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("shared_runtime_is_imported.js")).unwrap();
+    assert!(js.contains("from '../runtime.js'"));
+    assert!(!js.contains("function addHeapObject"));
+    let runtime = fs::read_to_string(out_dir.join("../runtime.js")).unwrap();
+    assert!(runtime.contains("export function addHeapObject"));
+    assert!(runtime.contains("export let cachedTextDecoder"));
+    // The heap state is only changed through functions of the runtime.
+    assert!(runtime.contains("export function getHeapNext"));
+    assert!(!runtime.contains("export let heap_next"));
+    assert!(!runtime.contains("export let stack_pointer"));
+}
+
+#[test]
+fn random_fill_hides_require_from_bundlers() {
+    let (mut cmd, out_dir) = Project::new("random_fill_hides_require_from_bundlers")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn fill(buf: &mut [u8]) -> Result<(), JsValue> {
+                    wasm_bindgen::random_fill(buf)
+                }
+            "#,
+        )
+        .wasm_bindgen("");
+    cmd.assert().success();
+    let js =
+        fs::read_to_string(out_dir.join("random_fill_hides_require_from_bundlers.js")).unwrap();
+    assert!(js.contains("function randomFill"));
+    assert!(js.contains("module.require('crypto')"));
+    assert!(!js.replace("module.require(", "").contains("require("));
+}
+
+#[test]
+fn shared_runtime_requires_es_modules() {
+    let (mut cmd, _out_dir) = Project::new("shared_runtime_requires_es_modules")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --shared-runtime ./runtime.js");
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("requires a target which uses ES modules"));
+}
+
+#[test]
+fn check_interface() {
+    let mut project = Project::new("check_interface");
+    project.file(
+        "src/lib.rs",
+        r#"
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            pub fn greet(name: &str) -> String {
+                format!("hi {}", name)
+            }
+
+            #[wasm_bindgen]
+            pub struct Counter {
+                pub count: u32,
+            }
+        "#,
+    );
+    let interface = project.root.join("interface.json");
+    let (mut cmd, _out_dir) =
+        project.wasm_bindgen(&format!("--write-interface {}", interface.display()));
+    cmd.assert().success();
+    let contents = fs::read_to_string(&interface).unwrap();
+    assert!(contents.contains(r#""function greet": "(name: string): string""#));
+    assert!(contents.contains(r#""class Counter get count": "number""#));
+
+    let check = format!("--check-interface {}", interface.display());
+    let (mut cmd, _out_dir) = project.wasm_bindgen(&check);
+    cmd.assert().success();
+
+    fs::write(
+        &interface,
+        contents.replace("(name: string): string", "(name: string): number"),
+    )
+    .unwrap();
+    let (mut cmd, _out_dir) = project.wasm_bindgen(&check);
+    cmd.assert().failure().stderr(str::contains(
+        "~ changed export `function greet`: \"(name: string): number\" -> \
+         \"(name: string): string\"",
+    ));
+}
+
+#[test]
+fn emit_wat() {
+    let (mut cmd, out_dir) = Project::new("emit_wat")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--emit-wat");
+    cmd.assert().success();
+    let wat = fs::read_to_string(out_dir.join("emit_wat_bg.wat")).unwrap();
+    assert!(wat.starts_with("(module"));
+    assert!(wat.contains("(export \"add\""));
+}
+
+#[test]
+fn config_file() {
+    let mut project = Project::new("config_file");
+    project.file(
+        "src/lib.rs",
+        r#"
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            pub fn add(a: u32, b: u32) -> u32 {
+                a + b
+            }
+        "#,
+    );
+    project.file(
+        "wasm-bindgen.toml",
+        r#"
+            target = "nodejs"
+            typescript = false
+            out-name = "configured"
+        "#,
+    );
+    let (mut cmd, out_dir) = project.wasm_bindgen("");
+    cmd.current_dir(&project.root).assert().success();
+    let js = fs::read_to_string(out_dir.join("configured.js")).unwrap();
+    assert!(js.contains("module.exports.add"));
+    assert!(!out_dir.join("configured.d.ts").exists());
+
+    // Flags on the command line take precedence over the config file.
+    let (mut cmd, out_dir) = project.wasm_bindgen("--out-name explicit --typescript");
+    cmd.current_dir(&project.root).assert().success();
+    assert!(out_dir.join("explicit.js").is_file());
+    assert!(out_dir.join("explicit.d.ts").is_file());
+
+    // ... and the config can be ignored entirely.
+    let (mut cmd, out_dir) = project.wasm_bindgen("--no-config");
+    cmd.current_dir(&project.root).assert().success();
+    assert!(out_dir.join("config_file.d.ts").is_file());
+}
+
+#[test]
+fn config_cargo_metadata_applied() {
+    let mut project = Project::new("config_cargo_metadata_applied");
+    project
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .file(
+            "Cargo.toml",
+            &format!(
+                "
+                    [package]
+                    name = \"config_cargo_metadata_applied\"
+                    authors = []
+                    version = \"1.0.0\"
+                    edition = '2018'
+
+                    [lib]
+                    crate-type = ['cdylib']
+
+                    [dependencies]
+                    wasm-bindgen = {{ path = '{}' }}
+
+                    [package.metadata.wasm-bindgen]
+                    out-name = \"from_metadata\"
+                    typescript = false
+                    debug = true
+
+                    [workspace]
+                ",
+                repo_root().display(),
+            ),
+        );
+    let (mut cmd, out_dir) = project.wasm_bindgen("");
+    cmd.current_dir(&project.root).assert().success();
+    let js = fs::read_to_string(out_dir.join("from_metadata.js")).unwrap();
+    assert!(js.contains("_assertNum("));
+    assert!(!out_dir.join("from_metadata.d.ts").exists());
+
+    // Explicit flags override the metadata in both directions.
+    let (mut cmd, out_dir) = project.wasm_bindgen("--out-name explicit --typescript --no-debug");
+    cmd.current_dir(&project.root).assert().success();
+    let js = fs::read_to_string(out_dir.join("explicit.js")).unwrap();
+    assert!(!js.contains("_assertNum("));
+    assert!(out_dir.join("explicit.d.ts").is_file());
+}
+
+#[test]
+fn config_cargo_metadata() {
+    let (mut cmd, out_dir) = Project::new("config_cargo_metadata")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .file(
+            "Cargo.toml",
+            &format!(
+                "
+                    [package]
+                    name = \"config_cargo_metadata\"
+                    authors = []
+                    version = \"1.0.0\"
+                    edition = '2018'
+
+                    [lib]
+                    crate-type = ['cdylib']
+
+                    [dependencies]
+                    wasm-bindgen = {{ path = '{}' }}
+
+                    [package.metadata.wasm-bindgen]
+                    out-name = \"from_metadata\"
+                    bogus = true
+
+                    [workspace]
+                ",
+                repo_root().display(),
+            ),
+        )
+        .wasm_bindgen("");
+    cmd.current_dir(out_dir.parent().unwrap())
+        .assert()
+        .failure()
+        .stderr(str::contains("invalid `package.metadata.wasm-bindgen`"));
+}
+
+#[test]
+fn strict_strings() {
+    let (mut cmd, out_dir) = Project::new("strict_strings")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn len(s: &str) -> usize {
+                    s.len()
+                }
+            "#,
+        )
+        .wasm_bindgen("--strict-strings");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("strict_strings.js")).unwrap();
+    assert!(js.contains("function assertWellFormedString(arg)"));
+    assert!(js.contains("assertWellFormedString(arg);"));
+}
+
+#[test]
+fn module_hash() {
+    let (mut cmd, out_dir) = Project::new("module_hash")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn greet(s: &str) -> String {
+                    format!("hello {}", s)
+                }
+            "#,
+        )
+        .wasm_bindgen("--target web --module-hash");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("module_hash.js")).unwrap();
+    assert!(js.contains("imports.wbg_"));
+    assert!(!js.contains("imports.wbg."));
+    let wasm = fs::read(out_dir.join("module_hash_bg.wasm")).unwrap();
+    let module = walrus::Module::from_buffer(&wasm).unwrap();
+    assert!(module.imports.iter().count() > 0);
+    for import in module.imports.iter() {
+        let hash = import.module.trim_start_matches("wbg_");
+        assert_eq!(hash.len(), 16);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(js.contains(&format!("imports.{}.{}", import.module, import.name)));
+    }
+}
+
+#[test]
+fn typescript_feature_files() {
+    let mut project = Project::new("typescript_feature_files");
+    project
+        .file(
+            "Cargo.toml",
+            &format!(
+                "
+                    [package]
+                    name = \"typescript_feature_files\"
+                    authors = []
+                    version = \"1.0.0\"
+                    edition = '2018'
+
+                    [dependencies]
+                    wasm-bindgen = {{ path = '{}' }}
+
+                    [features]
+                    default = ['extra']
+                    extra = []
+                    missing = []
+
+                    [lib]
+                    crate-type = ['cdylib']
+
+                    [workspace]
+                ",
+                repo_root().display(),
+            ),
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub struct Base;
+
+                #[wasm_bindgen]
+                pub struct Other;
+
+                #[wasm_bindgen]
+                pub fn base() -> Base {
+                    Base
+                }
+
+                #[wasm_bindgen(cfg_feature = "extra")]
+                pub fn extra(_base: &Base) -> u32 {
+                    1
+                }
+
+                #[wasm_bindgen(cfg_feature = "missing")]
+                pub fn missing() {}
+            "#,
+        );
+    let (mut cmd, out_dir) = project.wasm_bindgen("--typescript-feature-files");
+    cmd.assert().success();
+
+    let ts = fs::read_to_string(out_dir.join("typescript_feature_files.d.ts")).unwrap();
+    assert!(ts.contains("export function base(): Base;"));
+    assert!(!ts.contains("export function extra"));
+    assert!(!ts.contains("missing"));
+    assert!(ts.contains("export * from './typescript_feature_files_extra';"));
+    assert!(ts.contains("export * from './typescript_feature_files_types';"));
+    assert!(ts.contains("export class Other"));
+    assert!(!ts.contains("export class Base"));
+
+    let types = fs::read_to_string(out_dir.join("typescript_feature_files_types.d.ts")).unwrap();
+    assert!(types.contains("export class Base"));
+    assert!(!types.contains("import"));
+
+    let extra = fs::read_to_string(out_dir.join("typescript_feature_files_extra.d.ts")).unwrap();
+    assert!(extra.contains("import { Base } from './typescript_feature_files_types';"));
+    assert!(!extra.contains("Other"));
+    assert!(extra.contains("export function extra(_base: Base): number;"));
+    assert!(!out_dir
+        .join("typescript_feature_files_missing.d.ts")
+        .exists());
+
+    let js = fs::read_to_string(out_dir.join("typescript_feature_files.js")).unwrap();
+    assert!(js.contains("export function extra("));
+    assert!(!js.contains("missing"));
+}
+
+#[test]
+fn deprecated_exports() {
+    let (mut cmd, out_dir) = Project::new("deprecated_exports")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(deprecated = "use `add` instead")]
+                pub fn sum(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+
+                #[wasm_bindgen(unstable)]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+
+                #[wasm_bindgen(deprecated = "multiply with `a */ b` instead")]
+                pub fn product(a: u32, b: u32) -> u32 {
+                    a * b
+                }
+
+                #[wasm_bindgen]
+                pub struct Counter(u32);
+
+                #[wasm_bindgen]
+                impl Counter {
+                    #[wasm_bindgen(deprecated)]
+                    pub fn get(&self) -> u32 {
+                        self.0
+                    }
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --warn-deprecated");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("deprecated_exports.js")).unwrap();
+    assert!(js.contains("* @deprecated multiply with `a *\\/ b` instead\n"));
+    assert!(js.contains("* @deprecated use `add` instead"));
+    assert!(js.contains("* @experimental"));
+    assert!(js.contains("function warnOnce(message)"));
+    assert!(js.contains("warnOnce(\"`sum` is deprecated: use `add` instead\");"));
+    assert!(js.contains("warnOnce(\"`add` is unstable and may change without notice\");"));
+    assert!(js.contains("warnOnce(\"`Counter.prototype.get` is deprecated\");"));
+    let ts = fs::read_to_string(out_dir.join("deprecated_exports.d.ts")).unwrap();
+    assert!(ts.contains("* @deprecated use `add` instead"));
+    assert!(ts.contains("* @deprecated\n"));
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const m = require('./deprecated_exports.js'); \\
+             console.log(m.sum(1, 2), m.sum(3, 4), m.product(2, 3));",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("3 7 6\n")
+        .stderr(
+            "`sum` is deprecated: use `add` instead\n\
+             `product` is deprecated: multiply with `a */ b` instead\n",
+        );
+}
+
+#[test]
+fn unused_imports_warn() {
+    let (mut cmd, _out_dir) = Project::new("unused_imports_warn")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    fn used();
+                    fn unused();
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {
+                    used();
+                }
+            "#,
+        )
+        .wasm_bindgen("--warn-unused-imports");
+    cmd.assert().success().stderr(str::contains(
+        "warning: the imported function `unused` from crate `unused_imports_warn` is never used",
+    ));
+}
+
+#[test]
+fn unused_imports_quiet_by_default() {
+    let (mut cmd, _out_dir) = Project::new("unused_imports_quiet_by_default")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    fn unused();
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {}
+            "#,
+        )
+        .wasm_bindgen("--deny-warnings");
+    cmd.assert()
+        .success()
+        .stderr(str::contains("is never used").not());
+}
+
+#[test]
+fn deny_warnings() -> anyhow::Result<()> {
+    let (mut cmd, _out_dir) = Project::new("deny_warnings")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    type Foo;
+                    #[wasm_bindgen(method)]
+                    fn bar(this: &Foo);
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {}
+            "#,
+        )
+        .wasm_bindgen("--warn-unused-imports --deny-warnings");
+    cmd.assert().failure().code(1).stderr(str::is_match(
+        "the imported function `Foo.bar` from crate `deny_warnings` is never used",
+    )?);
+    Ok(())
+}
+
+#[test]
+fn no_copy_requires_vector_return() -> anyhow::Result<()> {
+    let (mut cmd, _out_dir) = Project::new("no_copy_requires_vector_return")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(no_copy)]
+                pub fn name() -> String {
+                    String::from("x")
+                }
+            "#,
+        )
+        .wasm_bindgen("");
+    cmd.assert().failure().code(1).stderr(str::is_match(
+        "`#\\[wasm_bindgen\\(no_copy\\)\\]` on `name` requires",
+    )?);
+    Ok(())
+}
+
+#[test]
+fn minify() {
+    let (mut cmd, out_dir) = Project::new("minify")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                /// Greets someone.
+                #[wasm_bindgen]
+                pub fn greet(name: &str) -> String {
+                    format!("hello {}", name)
+                }
+
+                #[wasm_bindgen]
+                pub struct Counter(u32);
+
+                #[wasm_bindgen]
+                impl Counter {
+                    #[wasm_bindgen(constructor)]
+                    pub fn new() -> Counter {
+                        Counter(0)
+                    }
+
+                    pub fn increment(&mut self, by: JsValue) -> u32 {
+                        self.0 += by.as_f64().unwrap() as u32;
+                        self.0
+                    }
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --minify");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("minify.js")).unwrap();
+    assert!(!js.contains("Greets someone"));
+    assert!(!js.contains("function getStringFromWasm"));
+    assert!(!js.contains("\n    "));
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const m = require('./minify.js'); \
+             const c = new m.Counter(); \
+             c.increment(2); \
+             console.log(m.greet('world'), c.increment(3));",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("hello world 5\n");
+}
+
+#[test]
+fn memory64_runs() {
+    let (mut cmd, out_dir) = Project::new("memory64_runs")
+        .memory64()
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn greet(name: &str) -> String {
+                    format!("hello {}", name)
+                }
+
+                #[wasm_bindgen]
+                pub fn sum(values: &[u32]) -> Option<u32> {
+                    values.iter().copied().reduce(|a, b| a + b)
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --experimental-memory64");
+    cmd.assert().success();
+    Command::new("node")
+        .arg("--experimental-wasm-memory64")
+        .arg("-e")
+        .arg(
+            "const m = require('./memory64_runs.js'); \
+             const sum = m.sum(new Uint32Array([1, 2, 3])); \
+             console.log(m.greet('world'), sum, m.sum(new Uint32Array(0)));",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("hello world 6 undefined\n");
+}
+
+#[test]
+fn closures_survive_fat_lto() {
+    let (mut cmd, out_dir) = Project::new("closures_survive_fat_lto")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(inline_js = "
+                    export function call(f, arg) { return f(arg); }
+                    export function call_str(f, arg) { return f(arg); }
+                ")]
+                extern "C" {
+                    fn call(f: &Closure<dyn FnMut(u32) -> u32>, arg: u32) -> u32;
+                    fn call_str(f: &Closure<dyn FnMut(String) -> String>, arg: &str) -> String;
+                }
+
+                #[wasm_bindgen]
+                pub fn run() -> String {
+                    let double = Closure::wrap(Box::new(|x| x * 2) as Box<dyn FnMut(u32) -> u32>);
+                    let add = Closure::wrap(Box::new(|x| x + 3) as Box<dyn FnMut(u32) -> u32>);
+                    let shout = Closure::wrap(
+                        Box::new(|s: String| s.to_uppercase()) as Box<dyn FnMut(String) -> String>
+                    );
+                    let n = call(&add, call(&double, 4));
+                    format!("{} {}", call_str(&shout, "hi"), n)
+                }
+            "#,
+        )
+        .file(
+            "Cargo.toml",
+            &format!(
+                "
+                    [package]
+                    name = \"closures_survive_fat_lto\"
+                    authors = []
+                    version = \"1.0.0\"
+                    edition = '2018'
+
+                    [dependencies]
+                    wasm-bindgen = {{ path = '{}' }}
+
+                    [lib]
+                    crate-type = ['cdylib']
+
+                    [profile.dev]
+                    opt-level = 'z'
+                    lto = 'fat'
+                    codegen-units = 1
+
+                    [workspace]
+                ",
+                repo_root().display(),
+            ),
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().success();
+    Command::new("node")
+        .arg("-e")
+        .arg("console.log(require('./closures_survive_fat_lto.js').run());")
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("HI 11\n");
+}
+
+#[test]
+fn camel_case_keeps_explicit_names() {
+    let (mut cmd, out_dir) = Project::new("camel_case_keeps_explicit_names")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub struct Point {
+                    pub x_coord: u32,
+                }
+
+                #[wasm_bindgen]
+                impl Point {
+                    #[wasm_bindgen(constructor)]
+                    pub fn new() -> Point {
+                        Point { x_coord: 1 }
+                    }
+
+                    pub fn double_x(&self) -> u32 {
+                        self.x_coord * 2
+                    }
+
+                    #[wasm_bindgen(js_name = triple_x)]
+                    pub fn triple(&self) -> u32 {
+                        self.x_coord * 3
+                    }
+
+                    #[wasm_bindgen(getter = raw_x)]
+                    pub fn raw(&self) -> u32 {
+                        self.x_coord
+                    }
+
+                    #[wasm_bindgen(getter)]
+                    pub fn half_x(&self) -> u32 {
+                        self.x_coord / 2
+                    }
+                }
+
+                #[wasm_bindgen]
+                pub fn make_point() -> Point {
+                    Point::new()
+                }
+
+                #[wasm_bindgen(js_name = keep_snake)]
+                pub fn renamed() -> u32 {
+                    7
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --camel-case");
+    cmd.assert().success();
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const m = require('./camel_case_keeps_explicit_names.js'); \
+             const p = m.makePoint(); \
+             console.log(p.xCoord, p.doubleX(), p.triple_x(), p.raw_x, p.halfX, m.keep_snake());",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("1 2 3 1 0 7\n");
+}
+
+#[test]
+fn camel_case_rejects_collisions() -> anyhow::Result<()> {
+    let (mut cmd, out_dir) = Project::new("camel_case_rejects_collisions")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn foo_bar() {}
+
+                #[wasm_bindgen]
+                #[allow(non_snake_case)]
+                pub fn fooBar() {}
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --camel-case");
+    cmd.assert().failure().code(1).stderr(str::is_match(
+        "`foo_bar` and `fooBar` are both exported as `fooBar` from the module",
+    )?);
+    assert!(!out_dir.join("camel_case_rejects_collisions.js").exists());
+    Ok(())
+}
+
+#[test]
+fn weak_closures() {
+    // Forgotten closures are destroyed with either the CLI flag or the
+    // `weak-closures` feature of the `wasm-bindgen` crate.
+    for &(name, features, flags) in &[
+        ("weak_closures", "[]", "--target nodejs --weak-closures"),
+        (
+            "weak_closures_feature",
+            "['weak-closures']",
+            "--target nodejs",
+        ),
+    ] {
+        let (mut cmd, out_dir) = Project::new(name)
+            .file(
+                "src/lib.rs",
+                r#"
+                    use std::sync::atomic::{AtomicBool, Ordering};
+                    use wasm_bindgen::prelude::*;
+
+                    static DROPPED: AtomicBool = AtomicBool::new(false);
+
+                    struct SetOnDrop;
+
+                    impl Drop for SetOnDrop {
+                        fn drop(&mut self) {
+                            DROPPED.store(true, Ordering::SeqCst);
+                        }
+                    }
+
+                    #[wasm_bindgen]
+                    pub fn make_callback() -> JsValue {
+                        let guard = SetOnDrop;
+                        Closure::wrap(Box::new(move || {
+                            let _ = &guard;
+                        }) as Box<dyn Fn()>)
+                        .into_js_value()
+                    }
+
+                    #[wasm_bindgen]
+                    pub fn dropped() -> bool {
+                        DROPPED.load(Ordering::SeqCst)
+                    }
+                "#,
+            )
+            .file(
+                "Cargo.toml",
+                &format!(
+                    "
+                        [package]
+                        name = \"{}\"
+                        authors = []
+                        version = \"1.0.0\"
+                        edition = '2018'
+
+                        [dependencies]
+                        wasm-bindgen = {{ path = '{}', features = {} }}
+
+                        [lib]
+                        crate-type = ['cdylib']
+
+                        [workspace]
+                    ",
+                    name,
+                    repo_root().display(),
+                    features,
+                ),
+            )
+            .wasm_bindgen(flags);
+        cmd.assert().success();
+        let js = fs::read_to_string(out_dir.join(format!("{}.js", name))).unwrap();
+        assert_eq!(js.matches("closureFinalization.register(").count(), 1);
+        Command::new("node")
+            .arg("--expose-gc")
+            .arg("-e")
+            .arg(format!(
+                "const m = require('./{}.js'); \
+                 m.make_callback()(); \
+                 (async () => {{ \
+                     for (let i = 0; i < 100 && !m.dropped(); i++) {{ \
+                         global.gc(); \
+                         await new Promise(resolve => setTimeout(resolve, 10)); \
+                     }} \
+                     console.log(m.dropped()); \
+                 }})();",
+                name
+            ))
+            .current_dir(&out_dir)
+            .assert()
+            .success()
+            .stdout("true\n");
+    }
+}
+
+#[test]
+fn closure_factories_are_shared() {
+    let (mut cmd, out_dir) = Project::new("closure_factories_are_shared")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn adder(n: u32) -> JsValue {
+                    Closure::wrap(Box::new(move |x: u32| x + n) as Box<dyn FnMut(u32) -> u32>)
+                        .into_js_value()
+                }
+
+                #[wasm_bindgen]
+                pub fn multiplier(n: u32) -> JsValue {
+                    Closure::wrap(Box::new(move |x: u32| x * n) as Box<dyn FnMut(u32) -> u32>)
+                        .into_js_value()
+                }
+
+                #[wasm_bindgen]
+                pub fn greeter() -> JsValue {
+                    Closure::wrap(Box::new(|| "hi".to_string()) as Box<dyn Fn() -> String>)
+                        .into_js_value()
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("closure_factories_are_shared.js")).unwrap();
+    assert_eq!(js.matches("function makeMutClosure(").count(), 1);
+    assert_eq!(js.matches("function makeClosure(").count(), 1);
+    // One import for each of the two closure types.
+    assert_eq!(js.matches("__wbindgen_closure_wrapper").count(), 2);
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const m = require('./closure_factories_are_shared.js'); \
+             console.log(m.adder(2)(3), m.multiplier(2)(3), m.greeter()());",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("5 6 hi\n");
+}
+
+#[test]
+fn debug_closure_names() {
+    let (mut cmd, out_dir) = Project::new("debug_closure_names")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn make_ticker() -> JsValue {
+                    let mut ticks = 0;
+                    Closure::wrap(Box::new(move || ticks += 1) as Box<dyn FnMut()>)
+                        .into_js_value()
+                }
+
+                #[wasm_bindgen]
+                pub fn make_other_ticker() -> JsValue {
+                    let mut ticks = 1;
+                    Closure::wrap(Box::new(move || ticks *= 2) as Box<dyn FnMut()>)
+                        .into_js_value()
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --debug");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("debug_closure_names.js")).unwrap();
+    assert!(js.contains("\"debug_closure_names::make_ticker\": (...args)"));
+    assert!(js.contains("\"debug_closure_names::make_other_ticker\": (...args)"));
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const m = require('./debug_closure_names.js'); \
+             const a = m.make_ticker(); \
+             const b = m.make_other_ticker(); \
+             a(); b(); \
+             console.log(a.name); \
+             console.log(b.name);",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("debug_closure_names::make_ticker\ndebug_closure_names::make_other_ticker\n");
+}
+
+#[test]
+fn debug_exports_assert_instantiating_context() {
+    let (mut cmd, out_dir) = Project::new("debug_exports_assert_instantiating_context")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --debug");
+    cmd.assert().success();
+    let js =
+        fs::read_to_string(out_dir.join("debug_exports_assert_instantiating_context.js")).unwrap();
+    assert!(js.contains("assertInstantiatingContext(\"add\");"));
+    Command::new("node")
+        .arg("-e")
+        .arg("console.log(require('./debug_exports_assert_instantiating_context.js').add(1, 2))")
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("3\n");
+
+    // A worker loading its own copy of the glue without instantiating the
+    // module, stood in for by a separate context.
+    let (mut cmd, out_dir) = Project::new("debug_exports_other_thread")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--target no-modules --debug");
+    cmd.assert().success();
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const vm = require('vm'); \
+             const fs = require('fs'); \
+             const glue = fs.readFileSync('./debug_exports_other_thread.js', 'utf8'); \
+             global.self = global; \
+             vm.runInThisContext(glue); \
+             const worker = vm.createContext({ TextDecoder, TextEncoder }); \
+             worker.self = worker; \
+             vm.runInContext(glue, worker); \
+             const bytes = fs.readFileSync('./debug_exports_other_thread_bg.wasm'); \
+             wasm_bindgen(bytes).then(() => { \
+                 console.log(wasm_bindgen.add(1, 2)); \
+                 try { \
+                     worker.wasm_bindgen.add(1, 2); \
+                 } catch (e) { \
+                     console.log(e.message); \
+                 } \
+             });",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout(
+            "3\nexport add was called on a thread which didn't instantiate the wasm module, \
+             which is only supported by modules built with threads support\n",
+        );
+
+    let (mut cmd, out_dir) = Project::new("exports_skip_instantiating_context")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("exports_skip_instantiating_context.js")).unwrap();
+    assert!(!js.contains("assertInstantiatingContext"));
+}
+
+#[test]
+fn exported_consts() {
+    // `cargo build` inherits this, and the variable is unique to this test.
+    env::set_var(
+        "WASM_BINDGEN_TEST_EXPORTED_CONSTS_API_URL",
+        "https://example.com",
+    );
+    let (mut cmd, out_dir) = Project::new("exported_consts")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                /// The version of this crate.
+                #[wasm_bindgen]
+                pub const VERSION: &str = "1.2.3";
+
+                #[wasm_bindgen(js_name = minTemperature)]
+                pub const MIN_TEMPERATURE: f64 = -273.15;
+
+                #[cfg(feature = "fancy")]
+                #[wasm_bindgen]
+                pub const FANCY: bool = true;
+
+                #[cfg(not(feature = "fancy"))]
+                #[wasm_bindgen]
+                pub const FANCY: bool = false;
+
+                #[wasm_bindgen(env = "WASM_BINDGEN_TEST_EXPORTED_CONSTS_API_URL")]
+                pub const API_URL: &str = "http://localhost";
+
+                #[wasm_bindgen(env = "WASM_BINDGEN_TEST_EXPORTED_CONSTS_UNSET")]
+                pub const MODE: &str = "development";
+
+                #[wasm_bindgen]
+                pub fn api_url() -> String {
+                    API_URL.to_string()
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().success();
+    let dts = fs::read_to_string(out_dir.join("exported_consts.d.ts")).unwrap();
+    assert!(dts.contains("* The version of this crate.\n*/\nexport const VERSION: string;"));
+    assert!(dts.contains("export const minTemperature: number;"));
+    assert!(dts.contains("export const FANCY: boolean;"));
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "const m = require('./exported_consts.js');
+             console.log(m.VERSION, m.minTemperature, m.FANCY, m.API_URL, m.api_url(), m.MODE)",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("1.2.3 -273.15 false https://example.com https://example.com development\n");
+}
+
+#[test]
+fn manifest() {
+    let (mut cmd, out_dir) = Project::new("manifest")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(inline_js = "export function answer() { return 42; }")]
+                extern "C" {
+                    fn answer() -> u32;
+                }
+
+                #[wasm_bindgen]
+                pub fn double_answer() -> u32 {
+                    answer() * 2
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --manifest");
+    cmd.assert().success();
+    let contents = fs::read_to_string(out_dir.join("manifest.manifest.json")).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    let files = manifest["files"].as_array().unwrap();
+    let roles = files
+        .iter()
+        .map(|f| (f["path"].as_str().unwrap(), f["role"].as_str().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(roles.len(), 6);
+    assert!(roles.contains(&("manifest.js", "js")));
+    assert!(roles.contains(&("manifest.d.ts", "typescript")));
+    assert!(roles.contains(&("manifest_bg.wasm", "wasm")));
+    assert!(roles.contains(&("manifest_bg.js", "wasm-js")));
+    assert!(roles.contains(&("manifest_bg.d.ts", "wasm-typescript")));
+    assert!(roles
+        .iter()
+        .any(|(path, role)| path.starts_with("snippets/") && *role == "snippet"));
+
+    for file in files {
+        let contents = fs::read(out_dir.join(file["path"].as_str().unwrap())).unwrap();
+        assert_eq!(file["size"].as_u64().unwrap(), contents.len() as u64);
+        assert_eq!(file["sha256"].as_str().unwrap().len(), 64);
+    }
+}
+
+#[test]
+fn custom_element() {
+    let (mut cmd, out_dir) = Project::new("custom_element")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(extends_js = HTMLElement, custom_element = "my-counter")]
+                pub struct Counter {
+                    count: u32,
+                    connected: bool,
+                }
+
+                #[wasm_bindgen]
+                impl Counter {
+                    #[wasm_bindgen(constructor)]
+                    pub fn new() -> Counter {
+                        Counter { count: 0, connected: false }
+                    }
+
+                    #[wasm_bindgen(observed_attribute = "count")]
+                    pub fn set_count(&mut self, value: Option<String>) {
+                        self.count = value.and_then(|v| v.parse().ok()).unwrap_or(0);
+                    }
+
+                    #[wasm_bindgen(observed_attribute = "it's")]
+                    pub fn set_quoted(&mut self, value: Option<String>) {
+                        self.count = value.map(|v| v.len() as u32).unwrap_or(0);
+                    }
+
+                    #[wasm_bindgen(js_name = connectedCallback)]
+                    pub fn connected_callback(&mut self) {
+                        self.connected = true;
+                    }
+
+                    #[wasm_bindgen(getter)]
+                    pub fn count(&self) -> u32 {
+                        self.count
+                    }
+
+                    #[wasm_bindgen(getter)]
+                    pub fn connected(&self) -> bool {
+                        self.connected
+                    }
+                }
+            "#,
+        )
+        .wasm_bindgen("--target no-modules");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("custom_element.js")).unwrap();
+    assert!(js.contains("customElements.define(\"my-counter\", Counter);"));
+    let ts = fs::read_to_string(out_dir.join("custom_element.d.ts")).unwrap();
+    assert!(ts.contains("static readonly observedAttributes: string[];"));
+
+    // Node has no DOM, so stand in for the parts custom elements rely on.
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "global.self = global; \
+             const defined = new Map(); \
+             global.HTMLElement = class {}; \
+             global.customElements = { define: (name, cls) => defined.set(name, cls) }; \
+             require('./custom_element.js'); \
+             const bytes = require('fs').readFileSync('./custom_element_bg.wasm'); \
+             wasm_bindgen(bytes).then(() => { \
+                 const Counter = defined.get('my-counter'); \
+                 const el = new Counter(); \
+                 el.attributeChangedCallback('count', null, '3'); \
+                 el.connectedCallback(); \
+                 const count = el.count; \
+                 el.attributeChangedCallback(\"it's\", null, 'four'); \
+                 console.log(Counter === wasm_bindgen.Counter, \
+                             Counter.observedAttributes.join(), \
+                             el instanceof HTMLElement, count, el.count, el.connected); \
+             });",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("true count,it's true 3 4 true\n");
+}
+
+#[test]
+fn custom_element_rejected() -> anyhow::Result<()> {
+    let lib = |constructor_args: &str| {
+        format!(
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(extends_js = HTMLElement, custom_element = "my-element")]
+                pub struct Element {{}}
+
+                #[wasm_bindgen]
+                impl Element {{
+                    #[wasm_bindgen(constructor)]
+                    pub fn new({}) -> Element {{
+                        Element {{}}
+                    }}
+                }}
+            "#,
+            constructor_args
+        )
+    };
+
+    let (mut cmd, _out_dir) = Project::new("custom_element_nodejs")
+        .file("src/lib.rs", &lib(""))
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().failure().code(1).stderr(str::is_match(
+        "custom element class `Element` can't be used with `--target nodejs`",
+    )?);
+
+    let (mut cmd, _out_dir) = Project::new("custom_element_constructor_args")
+        .file("src/lib.rs", &lib("_size: u32"))
+        .wasm_bindgen("--target web");
+    cmd.assert().failure().code(1).stderr(str::is_match(
+        "custom element class `Element` must have a `#\\[wasm_bindgen\\(constructor\\)\\]` \
+         taking no arguments",
+    )?);
+    Ok(())
+}
+
+#[test]
+fn multiple_instances() {
+    let (mut cmd, out_dir) = Project::new("multiple_instances")
+        .file(
+            "src/lib.rs",
+            r#"
+                use std::cell::RefCell;
+                use wasm_bindgen::prelude::*;
+
+                thread_local!(static STORED: RefCell<JsValue> = RefCell::new(JsValue::NULL));
+
+                #[wasm_bindgen]
+                pub fn store(value: JsValue) {
+                    STORED.with(|s| *s.borrow_mut() = value);
+                }
+
+                #[wasm_bindgen]
+                pub fn load() -> JsValue {
+                    STORED.with(|s| s.borrow().clone())
+                }
+            "#,
+        )
+        .wasm_bindgen("--target no-modules --multiple-instances");
+    cmd.assert().success();
+    let ts = fs::read_to_string(out_dir.join("multiple_instances.d.ts")).unwrap();
+    assert!(ts.contains("export type Instance = typeof __wbg_instance;"));
+    Command::new("node")
+        .arg("-e")
+        .arg(
+            "global.self = global; \
+             require('./multiple_instances.js'); \
+             const bytes = require('fs').readFileSync('./multiple_instances_bg.wasm'); \
+             (async () => { \
+                 const a = await wasm_bindgen(bytes); \
+                 const b = await wasm_bindgen(bytes); \
+                 a.store('a'); \
+                 b.store({ b: 1 }); \
+                 console.log(a.load(), b.load().b, a === b, typeof wasm_bindgen.load); \
+             })();",
+        )
+        .current_dir(&out_dir)
+        .assert()
+        .success()
+        .stdout("a 1 false undefined\n");
+
+    let (mut cmd, _out_dir) = Project::new("multiple_instances_bundler")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--target bundler --multiple-instances");
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("`--multiple-instances` requires"));
+}
+
+#[test]
+fn expected_version_mismatch() {
+    let cache = target_dir()
+        .join("cli-tests")
+        .join("expected_version_mismatch");
+    drop(fs::remove_dir_all(&cache));
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("-V")
+        .env("WASM_BINDGEN_EXPECTED_VERSION", "0.0.1")
+        .env("WASM_BINDGEN_CACHE_DIR", &cache)
+        .env_remove("WASM_BINDGEN_DOWNLOAD")
+        .assert()
+        .failure()
+        .stderr(str::contains("this project requires wasm-bindgen 0.0.1"));
+
+    // The running version is always accepted.
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("-V")
+        .env("WASM_BINDGEN_EXPECTED_VERSION", env!("CARGO_PKG_VERSION"))
+        .env("WASM_BINDGEN_CACHE_DIR", &cache)
+        .assert()
+        .success();
+}
+
+#[test]
+fn expected_version_is_validated() {
+    let cache = target_dir()
+        .join("cli-tests")
+        .join("expected_version_is_validated");
+    drop(fs::remove_dir_all(&cache));
+    for version in &[
+        "../../evil",
+        "0.2",
+        "0.2.x",
+        "01.2.3",
+        "0.2.3+build",
+        "0.2.3-",
+    ] {
+        Command::cargo_bin("wasm-bindgen")
+            .unwrap()
+            .arg("-V")
+            .env("WASM_BINDGEN_EXPECTED_VERSION", version)
+            .env("WASM_BINDGEN_CACHE_DIR", &cache)
+            .env("WASM_BINDGEN_DOWNLOAD", "1")
+            .assert()
+            .failure()
+            .stderr(str::contains("isn't a valid version"));
+    }
+
+    // Downloads are refused unless the tarball's hash is pinned.
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("-V")
+        .env("WASM_BINDGEN_EXPECTED_VERSION", "0.0.1-rc.1")
+        .env("WASM_BINDGEN_CACHE_DIR", &cache)
+        .env("WASM_BINDGEN_DOWNLOAD", "1")
+        .env_remove("WASM_BINDGEN_EXPECTED_SHA256")
+        .assert()
+        .failure()
+        .stderr(str::contains("`WASM_BINDGEN_EXPECTED_SHA256` must be set"));
+    assert!(!cache.exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn expected_version_dispatches_to_cache() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cache = target_dir()
+        .join("cli-tests")
+        .join("expected_version_dispatches_to_cache");
+    drop(fs::remove_dir_all(&cache));
+    let bin = cache.join("0.0.1").join("wasm-bindgen");
+    fs::create_dir_all(bin.parent().unwrap()).unwrap();
+    fs::write(&bin, "#!/bin/sh\necho \"cached $@\"\n").unwrap();
+    fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("-V")
+        .env("WASM_BINDGEN_EXPECTED_VERSION", "0.0.1")
+        .env("WASM_BINDGEN_CACHE_DIR", &cache)
+        .assert()
+        .success()
+        .stdout("cached -V\n");
+}
@@ -0,0 +1,190 @@
+//! Using `AbortController` and `AbortSignal` as cancellation tokens for
+//! futures, enabled with the `abort` feature.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{AbortController, AbortSignal};
+
+/// A Rust `Future` which resolves once an `AbortSignal` is aborted.
+///
+/// This resolves immediately if the signal was already aborted when it's
+/// first polled.
+pub struct Aborted {
+    signal: AbortSignal,
+    waker: Rc<RefCell<Option<Waker>>>,
+    listener: Option<Closure<dyn FnMut()>>,
+}
+
+impl Aborted {
+    /// Creates a future which resolves once `signal` is aborted.
+    pub fn new(signal: &AbortSignal) -> Aborted {
+        Aborted {
+            signal: signal.clone(),
+            waker: Rc::new(RefCell::new(None)),
+            listener: None,
+        }
+    }
+}
+
+impl fmt::Debug for Aborted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Aborted {{ ... }}")
+    }
+}
+
+impl From<AbortSignal> for Aborted {
+    fn from(signal: AbortSignal) -> Aborted {
+        Aborted::new(&signal)
+    }
+}
+
+impl Future for Aborted {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.signal.aborted() {
+            return Poll::Ready(());
+        }
+
+        *self.waker.borrow_mut() = Some(cx.waker().clone());
+
+        // Only start listening for the `abort` event once we're actually
+        // waiting on it, and keep the listener around until we're dropped.
+        if self.listener.is_none() {
+            let waker = self.waker.clone();
+            let listener = Closure::wrap(Box::new(move || {
+                if let Some(waker) = waker.borrow_mut().take() {
+                    waker.wake();
+                }
+            }) as Box<dyn FnMut()>);
+            self.signal
+                .add_event_listener_with_callback("abort", listener.as_ref().unchecked_ref())
+                .unwrap_throw();
+            self.listener = Some(listener);
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Aborted {
+    fn drop(&mut self) {
+        if let Some(listener) = &self.listener {
+            drop(
+                self.signal.remove_event_listener_with_callback(
+                    "abort",
+                    listener.as_ref().unchecked_ref(),
+                ),
+            );
+        }
+    }
+}
+
+/// Wraps `future` so that it's cancelled once `signal` is aborted.
+///
+/// The returned future resolves to `Some` with the output of `future` if it
+/// completes first, or `None` if `signal` is aborted first, in which case
+/// `future` is dropped without being polled again.
+pub fn abortable<F>(future: F, signal: &AbortSignal) -> Abortable<F>
+where
+    F: Future,
+{
+    Abortable {
+        future: Box::pin(future),
+        aborted: Aborted::new(signal),
+    }
+}
+
+/// A future which can be cancelled through an `AbortSignal`, created with
+/// [`abortable`](./fn.abortable.html).
+pub struct Abortable<F> {
+    future: Pin<Box<F>>,
+    aborted: Aborted,
+}
+
+impl<F> fmt::Debug for Abortable<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Abortable {{ ... }}")
+    }
+}
+
+impl<F: Future> Future for Abortable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if let Poll::Ready(()) = Pin::new(&mut self.aborted).poll(cx) {
+            return Poll::Ready(None);
+        }
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// An `AbortController` which is aborted when it's dropped.
+///
+/// This can be held by a Rust future while it awaits JS operations that
+/// accept an `AbortSignal`, such as `fetch`, so that they're cancelled if the
+/// future is dropped before they finish.
+///
+/// ```no_run
+/// use wasm_bindgen_futures::AbortOnDrop;
+///
+/// # async fn request(signal: &web_sys::AbortSignal) {}
+/// async fn run() {
+///     let controller = AbortOnDrop::new();
+///     // If this future is dropped while awaiting the request, the request is
+///     // aborted.
+///     request(&controller.signal()).await;
+/// }
+/// ```
+pub struct AbortOnDrop {
+    controller: Option<AbortController>,
+}
+
+impl AbortOnDrop {
+    /// Creates a new `AbortController` to be aborted on drop.
+    pub fn new() -> AbortOnDrop {
+        AbortOnDrop {
+            controller: Some(AbortController::new().unwrap_throw()),
+        }
+    }
+
+    /// Returns the signal of the underlying `AbortController`.
+    pub fn signal(&self) -> AbortSignal {
+        self.controller().signal()
+    }
+
+    /// Returns the underlying `AbortController`.
+    pub fn controller(&self) -> &AbortController {
+        self.controller.as_ref().unwrap_throw()
+    }
+
+    /// Releases the underlying `AbortController` without aborting it.
+    pub fn disarm(mut self) -> AbortController {
+        self.controller.take().unwrap_throw()
+    }
+}
+
+impl Default for AbortOnDrop {
+    fn default() -> AbortOnDrop {
+        AbortOnDrop::new()
+    }
+}
+
+impl fmt::Debug for AbortOnDrop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AbortOnDrop {{ ... }}")
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(controller) = &self.controller {
+            controller.abort();
+        }
+    }
+}
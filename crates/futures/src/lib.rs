@@ -29,6 +29,12 @@
 //! These three items should provide enough of a bridge to interoperate the two
 //! systems and make sure that Rust/JavaScript can work together with
 //! asynchronous and I/O work.
+//!
+//! With the `abort` feature enabled, `AbortSignal`s can additionally be used as
+//! cancellation tokens for Rust futures through [`Aborted`](./struct.Aborted.html)
+//! and [`abortable`](./fn.abortable.html), and
+//! [`AbortOnDrop`](./struct.AbortOnDrop.html) provides an `AbortController`
+//! which is aborted when a Rust future holding it is dropped.
 
 #![cfg_attr(target_feature = "atomics", feature(stdsimd))]
 #![deny(missing_docs)]
@@ -44,6 +50,11 @@ use wasm_bindgen::prelude::*;
 
 mod queue;
 
+#[cfg(feature = "abort")]
+mod abort;
+#[cfg(feature = "abort")]
+pub use abort::{abortable, AbortOnDrop, Abortable, Aborted};
+
 mod task {
     use cfg_if::cfg_if;
 
@@ -1,4 +1,3 @@
-use js_sys::Promise;
 use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
 use std::rc::Rc;
@@ -40,8 +39,7 @@ impl QueueState {
 
 pub(crate) struct Queue {
     state: Rc<QueueState>,
-    promise: Promise,
-    closure: Closure<dyn FnMut(JsValue)>,
+    closure: Closure<dyn FnMut()>,
 }
 
 impl Queue {
@@ -51,12 +49,8 @@ impl Queue {
         // If we're already inside the `run_all` loop then that'll pick up the
         // task we just enqueued. If we're not in `run_all`, though, then we need
         // to schedule a microtask.
-        //
-        // Note that we currently use a promise and a closure to do this, but
-        // eventually we should probably use something like `queueMicrotask`:
-        // https://developer.mozilla.org/en-US/docs/Web/API/WindowOrWorkerGlobalScope/queueMicrotask
         if !self.state.is_spinning.replace(true) {
-            self.promise.then(&self.closure);
+            wasm_bindgen::schedule::queue_microtask(self.closure.as_ref());
         }
     }
 }
@@ -69,14 +63,12 @@ impl Queue {
         });
 
         Self {
-            promise: Promise::resolve(&JsValue::undefined()),
-
             closure: {
                 let state = Rc::clone(&state);
 
                 // This closure will only be called on the next microtask event
                 // tick
-                Closure::wrap(Box::new(move |_| state.run_all()))
+                Closure::wrap(Box::new(move || state.run_all()))
             },
 
             state,
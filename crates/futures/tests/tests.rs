@@ -88,3 +88,40 @@ async fn can_create_multiple_futures_from_same_promise() {
     a.await.unwrap();
     b.await.unwrap();
 }
+
+#[cfg(feature = "abort")]
+mod abort {
+    use super::*;
+    use wasm_bindgen_futures::{abortable, AbortOnDrop, Aborted};
+
+    #[wasm_bindgen_test]
+    async fn aborted_resolves_on_abort() {
+        let controller = AbortOnDrop::new();
+        let signal = controller.signal();
+        spawn_local(async move {
+            drop(controller);
+        });
+        Aborted::new(&signal).await;
+        assert!(signal.aborted());
+    }
+
+    #[wasm_bindgen_test]
+    async fn abortable_completes_without_abort() {
+        let controller = AbortOnDrop::new();
+        let p = js_sys::Promise::resolve(&JsValue::from(42));
+        let x = abortable(JsFuture::from(p), &controller.signal()).await;
+        assert_eq!(x.unwrap().unwrap(), 42);
+        assert!(!controller.disarm().signal().aborted());
+    }
+
+    #[wasm_bindgen_test]
+    async fn abortable_is_cancelled() {
+        let controller = AbortOnDrop::new();
+        let signal = controller.signal();
+        let (_tx, rx) = oneshot::channel::<u32>();
+        spawn_local(async move {
+            drop(controller);
+        });
+        assert!(abortable(rx, &signal).await.is_none());
+    }
+}
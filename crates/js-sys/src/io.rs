@@ -0,0 +1,253 @@
+//! Adapters between `std::io` and JS byte sources and sinks.
+//!
+//! This module is only available with the `std-io` feature of this crate
+//! enabled. It allows existing Rust libraries written against `Read` and
+//! `Write` to consume and produce bytes stored in JS without first copying
+//! everything into a `Vec<u8>`.
+//!
+//! Note that a `Blob` cannot be read synchronously on the main thread. To read
+//! from a `Blob`, first resolve its `arrayBuffer()` promise and then wrap the
+//! result with `Uint8Array::new(&buffer)` and a [`Uint8ArrayReader`].
+
+use std::cmp;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Promise, Uint8Array};
+
+/// A cursor implementing `Read` and `Seek` over the contents of a
+/// `Uint8Array`.
+///
+/// Each call to `read` copies at most `buf.len()` bytes out of the JS array
+/// into wasm memory; the array itself is never copied wholesale.
+#[derive(Clone, Debug)]
+pub struct Uint8ArrayReader {
+    array: Uint8Array,
+    pos: u64,
+}
+
+impl Uint8ArrayReader {
+    /// Creates a new reader positioned at the start of `array`.
+    pub fn new(array: Uint8Array) -> Uint8ArrayReader {
+        Uint8ArrayReader { array, pos: 0 }
+    }
+
+    /// Returns the current position of this cursor, in bytes.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns a reference to the underlying array.
+    pub fn get_ref(&self) -> &Uint8Array {
+        &self.array
+    }
+
+    /// Consumes this reader, returning the underlying array.
+    pub fn into_inner(self) -> Uint8Array {
+        self.array
+    }
+}
+
+impl Read for Uint8ArrayReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = u64::from(self.array.length());
+        if self.pos >= len {
+            return Ok(0);
+        }
+        let amt = cmp::min(len - self.pos, buf.len() as u64) as usize;
+        let start = self.pos as u32;
+        self.array
+            .subarray(start, start + amt as u32)
+            .copy_to(&mut buf[..amt]);
+        self.pos += amt as u64;
+        Ok(amt)
+    }
+}
+
+impl Seek for Uint8ArrayReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            SeekFrom::End(n) => (u64::from(self.array.length()), n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+        let new_pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.wrapping_neg() as u64)
+        };
+        match new_pos {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+/// A growable JS byte buffer implementing `Write`.
+///
+/// Every write is copied into a new JS `Uint8Array` chunk, so the written data
+/// lives in JS rather than in wasm memory. Use [`Uint8ArrayWriter::finish`] to
+/// join all chunks into one contiguous `Uint8Array`.
+#[derive(Clone, Debug, Default)]
+pub struct Uint8ArrayWriter {
+    chunks: Vec<Uint8Array>,
+    len: u32,
+}
+
+impl Uint8ArrayWriter {
+    /// Creates a new, empty writer.
+    pub fn new() -> Uint8ArrayWriter {
+        Uint8ArrayWriter::default()
+    }
+
+    /// Returns the total number of bytes written so far.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns whether nothing has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Concatenates everything written so far into a single `Uint8Array`.
+    pub fn finish(self) -> Uint8Array {
+        if self.chunks.len() == 1 {
+            return self.chunks.into_iter().next().unwrap();
+        }
+        let ret = Uint8Array::new_with_length(self.len);
+        let mut offset = 0;
+        for chunk in self.chunks.iter() {
+            ret.set(chunk, offset);
+            offset += chunk.length();
+        }
+        ret
+    }
+}
+
+impl Write for Uint8ArrayWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let amt = cmp::min(buf.len(), (u32::max_value() - self.len) as usize);
+        if amt == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "JS byte buffer cannot grow beyond 4GiB",
+            ));
+        }
+        self.chunks.push(Uint8Array::from(&buf[..amt]));
+        self.len += amt as u32;
+        Ok(amt)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// Any JS object with a `write(chunk)` method returning a `Promise`, such
+    /// as a `WritableStreamDefaultWriter`.
+    #[derive(Clone, Debug)]
+    pub type WritableSink;
+
+    #[wasm_bindgen(method, structural, catch, js_name = write)]
+    fn write_chunk(this: &WritableSink, chunk: &Uint8Array) -> Result<Promise, JsValue>;
+}
+
+/// A `Write` adapter that forwards bytes to a [`WritableSink`] in chunks.
+///
+/// Writes are buffered in wasm memory until `chunk_size` bytes have
+/// accumulated, at which point they're copied into a `Uint8Array` and handed
+/// to the sink's `write` method. This never blocks: the promise returned by
+/// the sink isn't waited on, but the most recent one is available through
+/// [`SinkWriter::last_write`] so callers can await completion (or observe a
+/// rejection) from async code.
+#[derive(Debug)]
+pub struct SinkWriter {
+    sink: WritableSink,
+    buf: Vec<u8>,
+    chunk_size: usize,
+    last_write: Option<Promise>,
+}
+
+impl SinkWriter {
+    /// Creates a new writer flushing to `sink` every 8KiB.
+    pub fn new(sink: WritableSink) -> SinkWriter {
+        SinkWriter::with_chunk_size(sink, 8 * 1024)
+    }
+
+    /// Creates a new writer flushing to `sink` every `chunk_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn with_chunk_size(sink: WritableSink, chunk_size: usize) -> SinkWriter {
+        assert!(chunk_size > 0, "chunk size must be nonzero");
+        SinkWriter {
+            sink,
+            buf: Vec::with_capacity(chunk_size),
+            chunk_size,
+            last_write: None,
+        }
+    }
+
+    /// Returns the promise returned by the most recent call to the sink's
+    /// `write` method, if any chunk has been sent yet.
+    pub fn last_write(&self) -> Option<&Promise> {
+        self.last_write.as_ref()
+    }
+
+    /// Returns a reference to the underlying sink.
+    pub fn get_ref(&self) -> &WritableSink {
+        &self.sink
+    }
+
+    fn send_buffered(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = Uint8Array::from(&self.buf[..]);
+        self.buf.clear();
+        let promise = self.sink.write_chunk(&chunk).map_err(|e| {
+            let msg = format!("JS sink threw while writing: {:?}", e);
+            io::Error::new(io::ErrorKind::Other, msg)
+        })?;
+        self.last_write = Some(promise);
+        Ok(())
+    }
+}
+
+impl Write for SinkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let amt = cmp::min(buf.len(), self.chunk_size - self.buf.len());
+        self.buf.extend_from_slice(&buf[..amt]);
+        if self.buf.len() == self.chunk_size {
+            self.send_buffered()?;
+        }
+        Ok(amt)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buffered()
+    }
+}
+
+impl Drop for SinkWriter {
+    fn drop(&mut self) {
+        drop(self.send_buffered());
+    }
+}
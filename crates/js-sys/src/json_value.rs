@@ -0,0 +1,144 @@
+//! Conversions between `serde_json::Value` and trees of JS values.
+//!
+//! This module is only available with the `serde-json` feature of this crate
+//! enabled. Unlike `JsValue::from_serde` and `JsValue::into_serde`, which go
+//! through `JSON.stringify` and `JSON.parse`, these functions walk the value
+//! directly, building JS objects and arrays (or `serde_json` maps and vectors)
+//! as they go.
+//!
+//! JS numbers are always `f64`, so integers in a `serde_json::Value` outside
+//! of the range `-(2^53 - 1)..=2^53 - 1` lose precision when converted to JS.
+
+use serde_json::{Map as JsonMap, Number, Value};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::{Array, Map, Object, Reflect, TypeError};
+
+/// The largest integer which JS numbers can represent exactly, as in
+/// `Number.MAX_SAFE_INTEGER`.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// Converts a `serde_json::Value` to the equivalent JS value.
+///
+/// Objects become plain JS objects, arrays become `Array`s, and numbers,
+/// strings, booleans, and `null` become the corresponding JS primitives.
+pub fn to_js(value: &Value) -> JsValue {
+    match value {
+        Value::Null => JsValue::NULL,
+        Value::Bool(b) => JsValue::from_bool(*b),
+        Value::Number(n) => JsValue::from_f64(n.as_f64().unwrap_or(std::f64::NAN)),
+        Value::String(s) => JsValue::from_str(s),
+        Value::Array(values) => values.iter().map(to_js).collect::<Array>().into(),
+        Value::Object(map) => {
+            let object = Object::new();
+            for (key, value) in map {
+                Reflect::set(&object, &JsValue::from_str(key), &to_js(value)).unwrap_throw();
+            }
+            object.into()
+        }
+    }
+}
+
+/// Converts a tree of JS values to a `serde_json::Value`.
+///
+/// Arrays become JSON arrays, and `Map`s with string keys as well as any other
+/// object become JSON objects, using their own enumerable string-keyed
+/// properties. Integral numbers which JS can represent exactly become integer
+/// JSON numbers.
+///
+/// # Errors
+///
+/// Returns a `TypeError` if `value` contains anything without a JSON
+/// representation: `undefined`, functions, symbols, non-finite numbers, `Map`
+/// keys which aren't strings, or cycles.
+pub fn from_js(value: &JsValue) -> Result<Value, JsValue> {
+    Converter {
+        parents: Vec::new(),
+    }
+    .convert(value)
+}
+
+struct Converter {
+    /// The objects and arrays currently being converted, used to detect
+    /// cycles.
+    parents: Vec<JsValue>,
+}
+
+impl Converter {
+    fn convert(&mut self, value: &JsValue) -> Result<Value, JsValue> {
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        if let Some(b) = value.as_bool() {
+            return Ok(Value::Bool(b));
+        }
+        if let Some(n) = value.as_f64() {
+            return number(n).map(Value::Number);
+        }
+        if let Some(s) = value.as_string() {
+            return Ok(Value::String(s));
+        }
+        if value.is_undefined() || value.is_function() || !value.is_object() {
+            return Err(error("value has no JSON representation"));
+        }
+
+        if self.parents.iter().any(|parent| parent == value) {
+            return Err(error("cannot convert a cyclic structure to JSON"));
+        }
+        self.parents.push(value.clone());
+        let ret = self.convert_object(value);
+        self.parents.pop();
+        ret
+    }
+
+    fn convert_object(&mut self, value: &JsValue) -> Result<Value, JsValue> {
+        if Array::is_array(value) {
+            let array = value.unchecked_ref::<Array>();
+            let mut values = Vec::with_capacity(array.length() as usize);
+            for item in array.iter() {
+                values.push(self.convert(&item)?);
+            }
+            return Ok(Value::Array(values));
+        }
+
+        let mut map = JsonMap::new();
+        if let Some(js_map) = value.dyn_ref::<Map>() {
+            let mut result = Ok(());
+            js_map.for_each(&mut |value, key| {
+                if result.is_err() {
+                    return;
+                }
+                result = match key.as_string() {
+                    Some(key) => self.convert(&value).map(|value| {
+                        map.insert(key, value);
+                    }),
+                    None => Err(error("`Map` keys must be strings to convert to JSON")),
+                };
+            });
+            result?;
+        } else {
+            for entry in Object::entries(value.unchecked_ref()).iter() {
+                let entry = entry.unchecked_into::<Array>();
+                let key = entry.get(0).as_string().unwrap_throw();
+                map.insert(key, self.convert(&entry.get(1))?);
+            }
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+fn number(n: f64) -> Result<Number, JsValue> {
+    if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER {
+        return Ok(if n < 0.0 {
+            Number::from(n as i64)
+        } else {
+            Number::from(n as u64)
+        });
+    }
+    Number::from_f64(n).ok_or_else(|| error("non-finite numbers have no JSON representation"))
+}
+
+fn error(msg: &str) -> JsValue {
+    TypeError::new(msg).into()
+}
@@ -24,6 +24,11 @@ use std::mem;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
+#[cfg(feature = "std-io")]
+pub mod io;
+#[cfg(feature = "serde-json")]
+pub mod json_value;
+
 // When adding new imports:
 //
 // * Keep imports in alphabetical order.
@@ -1100,6 +1105,70 @@ extern "C" {
     pub fn new(message: &str) -> EvalError;
 }
 
+// FinalizationRegistry
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub type FinalizationRegistry;
+
+    /// The `FinalizationRegistry` object lets you request a callback when an
+    /// object is garbage-collected.
+    ///
+    /// The callback is called with the held value of each registered object
+    /// that's collected, and its `Closure` has to be kept alive for as long as
+    /// the registry is in use:
+    ///
+    /// ```no_run
+    /// use js_sys::{FinalizationRegistry, Object};
+    /// use wasm_bindgen::prelude::*;
+    ///
+    /// let cleanup = Closure::wrap(Box::new(|held: JsValue| {
+    ///     // `held` was passed to `register` along with the collected object.
+    /// }) as Box<dyn FnMut(JsValue)>);
+    /// let registry = FinalizationRegistry::new(&cleanup);
+    /// registry.register(&Object::new(), &"some resource".into());
+    /// cleanup.forget();
+    /// ```
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/FinalizationRegistry)
+    #[wasm_bindgen(constructor)]
+    pub fn new(cleanup: &Closure<dyn FnMut(JsValue)>) -> FinalizationRegistry;
+
+    /// The `register()` method registers an object with a
+    /// `FinalizationRegistry` instance so that if the object is
+    /// garbage-collected, the registry's callback may get called with
+    /// `held_value`.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/FinalizationRegistry/register)
+    #[wasm_bindgen(method)]
+    pub fn register(this: &FinalizationRegistry, target: &Object, held_value: &JsValue);
+
+    /// The `register()` method registers an object with a
+    /// `FinalizationRegistry` instance so that if the object is
+    /// garbage-collected, the registry's callback may get called with
+    /// `held_value`.
+    ///
+    /// The registration can later be removed with `unregister` by passing it
+    /// the same `unregister_token`.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/FinalizationRegistry/register)
+    #[wasm_bindgen(method, js_name = register)]
+    pub fn register_with_unregister_token(
+        this: &FinalizationRegistry,
+        target: &Object,
+        held_value: &JsValue,
+        unregister_token: &Object,
+    );
+
+    /// The `unregister()` method unregisters all objects registered with
+    /// `unregister_token`, returning whether at least one was unregistered.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/FinalizationRegistry/unregister)
+    #[wasm_bindgen(method)]
+    pub fn unregister(this: &FinalizationRegistry, unregister_token: &Object) -> bool;
+}
+
 // Function
 #[wasm_bindgen]
 extern "C" {
@@ -3243,6 +3312,28 @@ extern "C" {
     pub fn delete(this: &WeakMap, key: &Object) -> bool;
 }
 
+// WeakRef
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub type WeakRef;
+
+    /// The `WeakRef` object lets you hold a weak reference to another object,
+    /// without preventing that object from getting garbage-collected.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WeakRef)
+    #[wasm_bindgen(constructor)]
+    pub fn new(target: &Object) -> WeakRef;
+
+    /// The `deref()` method returns the target object of the `WeakRef`, or
+    /// `None` if it has been garbage-collected.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WeakRef/deref)
+    #[wasm_bindgen(method)]
+    pub fn deref(this: &WeakRef) -> Option<Object>;
+}
+
 // WeakSet
 #[wasm_bindgen]
 extern "C" {
@@ -4128,6 +4219,31 @@ impl JsString {
         (0..self.length()).map(move |i| self.char_code_at(i) as u16)
     }
 
+    /// Returns the raw UTF-16 code units of this JS string.
+    ///
+    /// Unlike `String::from`, this is always a lossless representation of the
+    /// string, even if it contains unpaired surrogates.
+    pub fn to_utf16(&self) -> Vec<u16> {
+        self.as_utf16().unwrap_throw()
+    }
+
+    /// Converts this JS string to a Rust `String`, returning an error if it
+    /// isn't valid UTF-16.
+    ///
+    /// This is the strict counterpart to `String::from`, which replaces any
+    /// unpaired surrogates with U+FFFD.
+    pub fn try_to_string(&self) -> Result<String, std::string::FromUtf16Error> {
+        String::from_utf16(&self.to_utf16())
+    }
+
+    /// Converts this JS string to a Rust `String`, replacing any unpaired
+    /// surrogates with U+FFFD.
+    ///
+    /// This is the same conversion as `String::from`, spelled out explicitly.
+    pub fn to_string_lossy(&self) -> String {
+        String::from(self)
+    }
+
     /// If this string consists of a single Unicode code point, then this method
     /// converts it into a Rust `char` without doing any allocations.
     ///
@@ -4560,6 +4676,714 @@ pub mod Intl {
     }
 }
 
+/// Bindings to the [`Temporal` proposal][proposal], a modern date and time
+/// API including time zone and calendar support.
+///
+/// These are only available with the `unstable-temporal` feature, since the
+/// proposal isn't finalized and the bindings may change along with it. Not
+/// all JS engines implement `Temporal` yet either.
+///
+/// [proposal]: https://tc39.es/proposal-temporal/docs/
+#[cfg(feature = "unstable-temporal")]
+#[allow(non_snake_case)]
+pub mod Temporal {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // Temporal.Duration
+    #[wasm_bindgen]
+    extern "C" {
+        /// A `Temporal.Duration` represents a length of time, with separate
+        /// fields for each unit from years to nanoseconds.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration)
+        #[wasm_bindgen(extends = Object, js_namespace = Temporal)]
+        #[derive(Clone, Debug)]
+        pub type Duration;
+
+        /// The `Temporal.Duration.from()` static method creates a duration
+        /// from an ISO 8601 string, an object with duration fields, or another
+        /// duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/from)
+        #[wasm_bindgen(catch, static_method_of = Duration, js_namespace = Temporal)]
+        pub fn from(item: &JsValue) -> Result<Duration, JsValue>;
+
+        /// The `years` accessor property returns the number of years in the
+        /// duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/years)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn years(this: &Duration) -> f64;
+
+        /// The `months` accessor property returns the number of months in the
+        /// duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/months)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn months(this: &Duration) -> f64;
+
+        /// The `weeks` accessor property returns the number of weeks in the
+        /// duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/weeks)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn weeks(this: &Duration) -> f64;
+
+        /// The `days` accessor property returns the number of days in the
+        /// duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/days)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn days(this: &Duration) -> f64;
+
+        /// The `hours` accessor property returns the number of hours in the
+        /// duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/hours)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn hours(this: &Duration) -> f64;
+
+        /// The `minutes` accessor property returns the number of minutes in
+        /// the duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/minutes)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn minutes(this: &Duration) -> f64;
+
+        /// The `seconds` accessor property returns the number of seconds in
+        /// the duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/seconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn seconds(this: &Duration) -> f64;
+
+        /// The `milliseconds` accessor property returns the number of
+        /// milliseconds in the duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/milliseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn milliseconds(this: &Duration) -> f64;
+
+        /// The `microseconds` accessor property returns the number of
+        /// microseconds in the duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/microseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn microseconds(this: &Duration) -> f64;
+
+        /// The `nanoseconds` accessor property returns the number of
+        /// nanoseconds in the duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/nanoseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn nanoseconds(this: &Duration) -> f64;
+
+        /// The `sign` accessor property returns 1 if the duration is
+        /// positive, -1 if it's negative, and 0 if it's zero.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/sign)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn sign(this: &Duration) -> i32;
+
+        /// The `blank` accessor property returns whether the duration is
+        /// zero.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/blank)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Duration")]
+        pub fn blank(this: &Duration) -> bool;
+
+        /// The `add()` method returns the sum of this duration and another,
+        /// balanced between units.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/add)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.Duration")]
+        pub fn add(this: &Duration, other: &Duration) -> Result<Duration, JsValue>;
+
+        /// The `subtract()` method returns the difference of this duration
+        /// and another, balanced between units.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/subtract)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.Duration")]
+        pub fn subtract(this: &Duration, other: &Duration) -> Result<Duration, JsValue>;
+
+        /// The `negated()` method returns a new duration with the opposite
+        /// sign.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/negated)
+        #[wasm_bindgen(method, js_class = "Temporal.Duration")]
+        pub fn negated(this: &Duration) -> Duration;
+
+        /// The `abs()` method returns a new duration with the absolute value
+        /// of this one.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/abs)
+        #[wasm_bindgen(method, js_class = "Temporal.Duration")]
+        pub fn abs(this: &Duration) -> Duration;
+
+        /// The `total()` method returns the length of this duration in the
+        /// given unit, like `"second"`, or an options object.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/total)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.Duration")]
+        pub fn total(this: &Duration, unit: &JsValue) -> Result<f64, JsValue>;
+
+        /// The `toString()` method returns the duration as an ISO 8601
+        /// string, like `"PT1H30M"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Duration/toString)
+        #[wasm_bindgen(method, js_class = "Temporal.Duration", js_name = toString)]
+        pub fn to_string(this: &Duration) -> JsString;
+    }
+
+    // Temporal.Instant
+    #[wasm_bindgen]
+    extern "C" {
+        /// A `Temporal.Instant` represents a fixed point in time with
+        /// nanosecond precision, without regard to calendar or time zone.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant)
+        #[wasm_bindgen(extends = Object, js_namespace = Temporal)]
+        #[derive(Clone, Debug)]
+        pub type Instant;
+
+        /// The `Temporal.Instant()` constructor creates an instant from a
+        /// number of nanoseconds since the Unix epoch.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/Instant)
+        #[wasm_bindgen(catch, constructor, js_namespace = Temporal)]
+        pub fn new(epoch_nanoseconds: i128) -> Result<Instant, JsValue>;
+
+        /// The `Temporal.Instant.from()` static method creates an instant
+        /// from an ISO 8601 string with an offset, or another instant.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/from)
+        #[wasm_bindgen(catch, static_method_of = Instant, js_namespace = Temporal)]
+        pub fn from(item: &JsValue) -> Result<Instant, JsValue>;
+
+        /// The `Temporal.Instant.fromEpochMilliseconds()` static method
+        /// creates an instant from a number of milliseconds since the Unix
+        /// epoch.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/fromEpochMilliseconds)
+        #[wasm_bindgen(catch, static_method_of = Instant, js_namespace = Temporal, js_name = fromEpochMilliseconds)]
+        pub fn from_epoch_milliseconds(epoch_milliseconds: f64) -> Result<Instant, JsValue>;
+
+        /// The `Temporal.Instant.fromEpochNanoseconds()` static method
+        /// creates an instant from a number of nanoseconds since the Unix
+        /// epoch.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/fromEpochNanoseconds)
+        #[wasm_bindgen(catch, static_method_of = Instant, js_namespace = Temporal, js_name = fromEpochNanoseconds)]
+        pub fn from_epoch_nanoseconds(epoch_nanoseconds: i128) -> Result<Instant, JsValue>;
+
+        /// The `Temporal.Instant.compare()` static method returns -1, 0, or 1
+        /// depending on whether the first instant comes before, is the same
+        /// as, or comes after the second.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/compare)
+        #[wasm_bindgen(static_method_of = Instant, js_namespace = Temporal)]
+        pub fn compare(one: &Instant, two: &Instant) -> i32;
+
+        /// The `epochMilliseconds` accessor property returns the number of
+        /// whole milliseconds since the Unix epoch.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/epochMilliseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Instant", js_name = epochMilliseconds)]
+        pub fn epoch_milliseconds(this: &Instant) -> f64;
+
+        /// The `epochNanoseconds` accessor property returns the number of
+        /// nanoseconds since the Unix epoch.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/epochNanoseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.Instant", js_name = epochNanoseconds)]
+        pub fn epoch_nanoseconds(this: &Instant) -> i128;
+
+        /// The `add()` method returns a new instant moved forward by the
+        /// given duration, which can't contain calendar units.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/add)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.Instant")]
+        pub fn add(this: &Instant, duration: &Duration) -> Result<Instant, JsValue>;
+
+        /// The `subtract()` method returns a new instant moved backward by
+        /// the given duration, which can't contain calendar units.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/subtract)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.Instant")]
+        pub fn subtract(this: &Instant, duration: &Duration) -> Result<Instant, JsValue>;
+
+        /// The `until()` method returns the duration from this instant until
+        /// another one.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/until)
+        #[wasm_bindgen(method, js_class = "Temporal.Instant")]
+        pub fn until(this: &Instant, other: &Instant) -> Duration;
+
+        /// The `since()` method returns the duration from another instant
+        /// until this one.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/since)
+        #[wasm_bindgen(method, js_class = "Temporal.Instant")]
+        pub fn since(this: &Instant, other: &Instant) -> Duration;
+
+        /// The `equals()` method returns whether two instants are the same
+        /// point in time.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/equals)
+        #[wasm_bindgen(method, js_class = "Temporal.Instant")]
+        pub fn equals(this: &Instant, other: &Instant) -> bool;
+
+        /// The `toZonedDateTimeISO()` method returns the date and time of
+        /// this instant in the given time zone, using the ISO 8601 calendar.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/toZonedDateTimeISO)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.Instant", js_name = toZonedDateTimeISO)]
+        pub fn to_zoned_date_time_iso(
+            this: &Instant,
+            time_zone: &str,
+        ) -> Result<ZonedDateTime, JsValue>;
+
+        /// The `toString()` method returns the instant as an ISO 8601 string
+        /// in UTC, like `"2020-01-01T00:00:00Z"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Instant/toString)
+        #[wasm_bindgen(method, js_class = "Temporal.Instant", js_name = toString)]
+        pub fn to_string(this: &Instant) -> JsString;
+    }
+
+    impl Instant {
+        /// Creates an instant from a `SystemTime`.
+        ///
+        /// Returns a `RangeError` if `time` is outside the range of
+        /// `Temporal.Instant`, which is 10<sup>8</sup> days around the Unix
+        /// epoch.
+        pub fn from_system_time(time: SystemTime) -> Result<Instant, JsValue> {
+            let nanos = match time.duration_since(UNIX_EPOCH) {
+                Ok(d) => d.as_nanos() as i128,
+                Err(e) => -(e.duration().as_nanos() as i128),
+            };
+            Instant::from_epoch_nanoseconds(nanos)
+        }
+
+        /// Returns this instant as a `SystemTime`.
+        ///
+        /// Returns `None` if the instant can't be represented as a
+        /// `SystemTime`, which on `wasm32-unknown-unknown` is the case for
+        /// every instant before the Unix epoch.
+        pub fn to_system_time(&self) -> Option<SystemTime> {
+            let nanos = self.epoch_nanoseconds();
+            let duration = std::time::Duration::new(
+                (nanos.abs() / 1_000_000_000) as u64,
+                (nanos.abs() % 1_000_000_000) as u32,
+            );
+            if nanos >= 0 {
+                UNIX_EPOCH.checked_add(duration)
+            } else {
+                UNIX_EPOCH.checked_sub(duration)
+            }
+        }
+    }
+
+    // Temporal.Now
+    #[wasm_bindgen]
+    extern "C" {
+        /// The `Temporal.Now` namespace contains methods returning the
+        /// current time in various formats.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Now)
+        #[wasm_bindgen(js_namespace = Temporal)]
+        #[derive(Clone, Debug)]
+        pub type Now;
+
+        /// The `Temporal.Now.instant()` static method returns the current
+        /// time as an instant.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Now/instant)
+        #[wasm_bindgen(static_method_of = Now, js_namespace = Temporal)]
+        pub fn instant() -> Instant;
+
+        /// The `Temporal.Now.timeZoneId()` static method returns the
+        /// identifier of the system's current time zone.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Now/timeZoneId)
+        #[wasm_bindgen(static_method_of = Now, js_namespace = Temporal, js_name = timeZoneId)]
+        pub fn time_zone_id() -> JsString;
+
+        /// The `Temporal.Now.plainDateISO()` static method returns the
+        /// current date in the system's time zone, using the ISO 8601
+        /// calendar.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Now/plainDateISO)
+        #[wasm_bindgen(static_method_of = Now, js_namespace = Temporal, js_name = plainDateISO)]
+        pub fn plain_date_iso() -> PlainDate;
+
+        /// The `Temporal.Now.zonedDateTimeISO()` static method returns the
+        /// current date and time in the system's time zone, using the ISO
+        /// 8601 calendar.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/Now/zonedDateTimeISO)
+        #[wasm_bindgen(static_method_of = Now, js_namespace = Temporal, js_name = zonedDateTimeISO)]
+        pub fn zoned_date_time_iso() -> ZonedDateTime;
+    }
+
+    // Temporal.PlainDate
+    #[wasm_bindgen]
+    extern "C" {
+        /// A `Temporal.PlainDate` represents a calendar date, without a time
+        /// or time zone.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate)
+        #[wasm_bindgen(extends = Object, js_namespace = Temporal)]
+        #[derive(Clone, Debug)]
+        pub type PlainDate;
+
+        /// The `Temporal.PlainDate()` constructor creates a date in the ISO
+        /// 8601 calendar, with a 1-based month.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/PlainDate)
+        #[wasm_bindgen(catch, constructor, js_namespace = Temporal)]
+        pub fn new(year: i32, month: u32, day: u32) -> Result<PlainDate, JsValue>;
+
+        /// The `Temporal.PlainDate.from()` static method creates a date from
+        /// an ISO 8601 string, an object with date fields, or another date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/from)
+        #[wasm_bindgen(catch, static_method_of = PlainDate, js_namespace = Temporal)]
+        pub fn from(item: &JsValue) -> Result<PlainDate, JsValue>;
+
+        /// The `Temporal.PlainDate.compare()` static method returns -1, 0, or
+        /// 1 depending on whether the first date comes before, is the same
+        /// as, or comes after the second.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/compare)
+        #[wasm_bindgen(static_method_of = PlainDate, js_namespace = Temporal)]
+        pub fn compare(one: &PlainDate, two: &PlainDate) -> i32;
+
+        /// The `year` accessor property returns the year of the date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/year)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate")]
+        pub fn year(this: &PlainDate) -> i32;
+
+        /// The `month` accessor property returns the 1-based month of the
+        /// date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/month)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate")]
+        pub fn month(this: &PlainDate) -> u32;
+
+        /// The `monthCode` accessor property returns a calendar specific code
+        /// for the month of the date, like `"M01"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/monthCode)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate", js_name = monthCode)]
+        pub fn month_code(this: &PlainDate) -> JsString;
+
+        /// The `day` accessor property returns the 1-based day of the month
+        /// of the date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/day)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate")]
+        pub fn day(this: &PlainDate) -> u32;
+
+        /// The `dayOfWeek` accessor property returns the day of the week of
+        /// the date, from 1 for Monday to 7 for Sunday.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/dayOfWeek)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate", js_name = dayOfWeek)]
+        pub fn day_of_week(this: &PlainDate) -> u32;
+
+        /// The `dayOfYear` accessor property returns the 1-based day of the
+        /// year of the date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/dayOfYear)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate", js_name = dayOfYear)]
+        pub fn day_of_year(this: &PlainDate) -> u32;
+
+        /// The `daysInMonth` accessor property returns the number of days in
+        /// the month of the date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/daysInMonth)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate", js_name = daysInMonth)]
+        pub fn days_in_month(this: &PlainDate) -> u32;
+
+        /// The `daysInYear` accessor property returns the number of days in
+        /// the year of the date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/daysInYear)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate", js_name = daysInYear)]
+        pub fn days_in_year(this: &PlainDate) -> u32;
+
+        /// The `inLeapYear` accessor property returns whether the year of the
+        /// date is a leap year.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/inLeapYear)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate", js_name = inLeapYear)]
+        pub fn in_leap_year(this: &PlainDate) -> bool;
+
+        /// The `calendarId` accessor property returns the identifier of the
+        /// calendar of the date, like `"iso8601"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/calendarId)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.PlainDate", js_name = calendarId)]
+        pub fn calendar_id(this: &PlainDate) -> JsString;
+
+        /// The `add()` method returns a new date moved forward by the given
+        /// duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/add)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.PlainDate")]
+        pub fn add(this: &PlainDate, duration: &Duration) -> Result<PlainDate, JsValue>;
+
+        /// The `subtract()` method returns a new date moved backward by the
+        /// given duration.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/subtract)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.PlainDate")]
+        pub fn subtract(this: &PlainDate, duration: &Duration) -> Result<PlainDate, JsValue>;
+
+        /// The `until()` method returns the duration from this date until
+        /// another one.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/until)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.PlainDate")]
+        pub fn until(this: &PlainDate, other: &PlainDate) -> Result<Duration, JsValue>;
+
+        /// The `since()` method returns the duration from another date until
+        /// this one.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/since)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.PlainDate")]
+        pub fn since(this: &PlainDate, other: &PlainDate) -> Result<Duration, JsValue>;
+
+        /// The `with()` method returns a new date with the fields given in
+        /// an object replaced.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/with)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.PlainDate", js_name = with)]
+        pub fn with(this: &PlainDate, fields: &Object) -> Result<PlainDate, JsValue>;
+
+        /// The `equals()` method returns whether two dates are the same date
+        /// in the same calendar.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/equals)
+        #[wasm_bindgen(method, js_class = "Temporal.PlainDate")]
+        pub fn equals(this: &PlainDate, other: &PlainDate) -> bool;
+
+        /// The `toString()` method returns the date as an ISO 8601 string,
+        /// like `"2020-01-31"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/PlainDate/toString)
+        #[wasm_bindgen(method, js_class = "Temporal.PlainDate", js_name = toString)]
+        pub fn to_string(this: &PlainDate) -> JsString;
+    }
+
+    // Temporal.ZonedDateTime
+    #[wasm_bindgen]
+    extern "C" {
+        /// A `Temporal.ZonedDateTime` represents a date and time in a
+        /// particular time zone and calendar.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime)
+        #[wasm_bindgen(extends = Object, js_namespace = Temporal)]
+        #[derive(Clone, Debug)]
+        pub type ZonedDateTime;
+
+        /// The `Temporal.ZonedDateTime()` constructor creates a date and time
+        /// from a number of nanoseconds since the Unix epoch and a time zone
+        /// identifier, like `"Europe/Paris"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/ZonedDateTime)
+        #[wasm_bindgen(catch, constructor, js_namespace = Temporal)]
+        pub fn new(epoch_nanoseconds: i128, time_zone: &str) -> Result<ZonedDateTime, JsValue>;
+
+        /// The `Temporal.ZonedDateTime.from()` static method creates a date
+        /// and time from an ISO 8601 string with a time zone annotation, an
+        /// object with date, time and time zone fields, or another date and
+        /// time.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/from)
+        #[wasm_bindgen(catch, static_method_of = ZonedDateTime, js_namespace = Temporal)]
+        pub fn from(item: &JsValue) -> Result<ZonedDateTime, JsValue>;
+
+        /// The `Temporal.ZonedDateTime.compare()` static method returns -1,
+        /// 0, or 1 depending on whether the first date and time is before,
+        /// at the same instant as, or after the second.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/compare)
+        #[wasm_bindgen(static_method_of = ZonedDateTime, js_namespace = Temporal)]
+        pub fn compare(one: &ZonedDateTime, two: &ZonedDateTime) -> i32;
+
+        /// The `year` accessor property returns the year of the date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/year)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn year(this: &ZonedDateTime) -> i32;
+
+        /// The `month` accessor property returns the 1-based month of the
+        /// date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/month)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn month(this: &ZonedDateTime) -> u32;
+
+        /// The `day` accessor property returns the 1-based day of the month
+        /// of the date.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/day)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn day(this: &ZonedDateTime) -> u32;
+
+        /// The `dayOfWeek` accessor property returns the day of the week of
+        /// the date, from 1 for Monday to 7 for Sunday.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/dayOfWeek)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime", js_name = dayOfWeek)]
+        pub fn day_of_week(this: &ZonedDateTime) -> u32;
+
+        /// The `hour` accessor property returns the hour of the time, from 0
+        /// to 23.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/hour)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn hour(this: &ZonedDateTime) -> u32;
+
+        /// The `minute` accessor property returns the minute of the time,
+        /// from 0 to 59.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/minute)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn minute(this: &ZonedDateTime) -> u32;
+
+        /// The `second` accessor property returns the second of the time,
+        /// from 0 to 59.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/second)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn second(this: &ZonedDateTime) -> u32;
+
+        /// The `millisecond` accessor property returns the millisecond of
+        /// the time, from 0 to 999.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/millisecond)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn millisecond(this: &ZonedDateTime) -> u32;
+
+        /// The `nanosecond` accessor property returns the nanosecond within
+        /// the microsecond of the time, from 0 to 999.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/nanosecond)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn nanosecond(this: &ZonedDateTime) -> u32;
+
+        /// The `timeZoneId` accessor property returns the identifier of the
+        /// time zone of the date and time.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/timeZoneId)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime", js_name = timeZoneId)]
+        pub fn time_zone_id(this: &ZonedDateTime) -> JsString;
+
+        /// The `offset` accessor property returns the UTC offset of the date
+        /// and time, like `"+01:00"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/offset)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime")]
+        pub fn offset(this: &ZonedDateTime) -> JsString;
+
+        /// The `offsetNanoseconds` accessor property returns the UTC offset
+        /// of the date and time in nanoseconds.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/offsetNanoseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime", js_name = offsetNanoseconds)]
+        pub fn offset_nanoseconds(this: &ZonedDateTime) -> f64;
+
+        /// The `epochMilliseconds` accessor property returns the number of
+        /// whole milliseconds since the Unix epoch.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/epochMilliseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime", js_name = epochMilliseconds)]
+        pub fn epoch_milliseconds(this: &ZonedDateTime) -> f64;
+
+        /// The `epochNanoseconds` accessor property returns the number of
+        /// nanoseconds since the Unix epoch.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/epochNanoseconds)
+        #[wasm_bindgen(method, getter, js_class = "Temporal.ZonedDateTime", js_name = epochNanoseconds)]
+        pub fn epoch_nanoseconds(this: &ZonedDateTime) -> i128;
+
+        /// The `add()` method returns a new date and time moved forward by
+        /// the given duration, following the rules of its time zone.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/add)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.ZonedDateTime")]
+        pub fn add(this: &ZonedDateTime, duration: &Duration) -> Result<ZonedDateTime, JsValue>;
+
+        /// The `subtract()` method returns a new date and time moved backward
+        /// by the given duration, following the rules of its time zone.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/subtract)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.ZonedDateTime")]
+        pub fn subtract(
+            this: &ZonedDateTime,
+            duration: &Duration,
+        ) -> Result<ZonedDateTime, JsValue>;
+
+        /// The `until()` method returns the duration from this date and time
+        /// until another one.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/until)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.ZonedDateTime")]
+        pub fn until(this: &ZonedDateTime, other: &ZonedDateTime) -> Result<Duration, JsValue>;
+
+        /// The `since()` method returns the duration from another date and
+        /// time until this one.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/since)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.ZonedDateTime")]
+        pub fn since(this: &ZonedDateTime, other: &ZonedDateTime) -> Result<Duration, JsValue>;
+
+        /// The `withTimeZone()` method returns the same instant as a date
+        /// and time in another time zone.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/withTimeZone)
+        #[wasm_bindgen(catch, method, js_class = "Temporal.ZonedDateTime", js_name = withTimeZone)]
+        pub fn with_time_zone(
+            this: &ZonedDateTime,
+            time_zone: &str,
+        ) -> Result<ZonedDateTime, JsValue>;
+
+        /// The `equals()` method returns whether two dates and times are the
+        /// same instant in the same time zone and calendar.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/equals)
+        #[wasm_bindgen(method, js_class = "Temporal.ZonedDateTime")]
+        pub fn equals(this: &ZonedDateTime, other: &ZonedDateTime) -> bool;
+
+        /// The `toInstant()` method returns the instant of this date and
+        /// time.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/toInstant)
+        #[wasm_bindgen(method, js_class = "Temporal.ZonedDateTime", js_name = toInstant)]
+        pub fn to_instant(this: &ZonedDateTime) -> Instant;
+
+        /// The `toPlainDate()` method returns the date part of this date and
+        /// time.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/toPlainDate)
+        #[wasm_bindgen(method, js_class = "Temporal.ZonedDateTime", js_name = toPlainDate)]
+        pub fn to_plain_date(this: &ZonedDateTime) -> PlainDate;
+
+        /// The `toString()` method returns the date and time as an ISO 8601
+        /// string with its offset and time zone, like
+        /// `"2020-01-01T00:00:00+01:00[Europe/Paris]"`.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Temporal/ZonedDateTime/toString)
+        #[wasm_bindgen(method, js_class = "Temporal.ZonedDateTime", js_name = toString)]
+        pub fn to_string(this: &ZonedDateTime) -> JsString;
+    }
+}
+
 // Promise
 #[wasm_bindgen]
 extern "C" {
@@ -4980,4 +5804,12 @@ arrays! {
     /// `Float64Array()`
     /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Float64Array
     Float64Array: f64,
+
+    /// `BigInt64Array()`
+    /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt64Array
+    BigInt64Array: i64,
+
+    /// `BigUint64Array()`
+    /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigUint64Array
+    BigUint64Array: u64,
 }
@@ -0,0 +1,43 @@
+use js_sys::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+// `FinalizationRegistry` isn't available in every JS engine the tests run in.
+fn has_finalization_registry() -> bool {
+    Reflect::has(&global(), &"FinalizationRegistry".into()).unwrap()
+}
+
+fn noop_cleanup() -> Closure<dyn FnMut(JsValue)> {
+    Closure::wrap(Box::new(|_: JsValue| {}) as Box<dyn FnMut(JsValue)>)
+}
+
+#[wasm_bindgen_test]
+fn register_and_unregister() {
+    if !has_finalization_registry() {
+        return;
+    }
+    let cleanup = noop_cleanup();
+    let registry = FinalizationRegistry::new(&cleanup);
+
+    let token = Object::new();
+    registry.register_with_unregister_token(&Object::new(), &1.into(), &token);
+    registry.register_with_unregister_token(&Object::new(), &2.into(), &token);
+    registry.register(&Object::new(), &3.into());
+
+    assert!(registry.unregister(&token));
+    assert!(!registry.unregister(&token));
+    assert!(!registry.unregister(&Object::new()));
+}
+
+#[wasm_bindgen_test]
+fn finalization_registry_inheritance() {
+    if !has_finalization_registry() {
+        return;
+    }
+    let cleanup = noop_cleanup();
+    let registry = FinalizationRegistry::new(&cleanup);
+    assert!(registry.is_instance_of::<FinalizationRegistry>());
+    assert!(registry.is_instance_of::<Object>());
+    let _: &Object = registry.as_ref();
+}
@@ -565,6 +565,19 @@ fn is_valid_utf16() {
     assert!(!JsString::from_char_code1(0xdc00).is_valid_utf16());
 }
 
+#[wasm_bindgen_test]
+fn utf16_conversions() {
+    let s = JsString::from("a🥑");
+    assert_eq!(s.to_utf16(), "a🥑".encode_utf16().collect::<Vec<_>>());
+    assert_eq!(s.try_to_string().unwrap(), "a🥑");
+    assert_eq!(s.to_string_lossy(), "a🥑");
+
+    let lone = JsString::from("a").concat(&JsString::from_char_code1(0xd800));
+    assert_eq!(lone.to_utf16(), [0x61, 0xd800]);
+    assert!(lone.try_to_string().is_err());
+    assert_eq!(lone.to_string_lossy(), "a\u{fffd}");
+}
+
 #[wasm_bindgen_test]
 fn as_char() {
     assert_eq!(JsString::from('a').as_char(), Some('a'));
@@ -0,0 +1,99 @@
+#![cfg(feature = "unstable-temporal")]
+
+use js_sys::Temporal::*;
+use js_sys::{global, Reflect};
+use std::time::{Duration as StdDuration, UNIX_EPOCH};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+// Not every engine ships `Temporal` yet, so these tests pass trivially
+// without it.
+fn has_temporal() -> bool {
+    Reflect::has(&global(), &"Temporal".into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn instant() {
+    if !has_temporal() {
+        return;
+    }
+    let instant = Instant::from_epoch_milliseconds(1_000.0).unwrap();
+    assert_eq!(instant.epoch_milliseconds(), 1_000.0);
+    assert_eq!(instant.epoch_nanoseconds(), 1_000_000_000);
+    assert_eq!(instant.to_string(), "1970-01-01T00:00:01Z");
+
+    let later = Instant::new(1_500_000_000).unwrap();
+    assert_eq!(Instant::compare(&instant, &later), -1);
+    assert_eq!(instant.until(&later).milliseconds(), 500.0);
+    let duration = Duration::from(&"PT0.5S".into()).unwrap();
+    assert!(instant.add(&duration).unwrap().equals(&later));
+    assert!(later.subtract(&duration).unwrap().equals(&instant));
+
+    assert!(Instant::from(&"not an instant".into()).is_err());
+    assert!(Instant::from_epoch_nanoseconds(i128::max_value()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn system_time() {
+    if !has_temporal() {
+        return;
+    }
+    let time = UNIX_EPOCH + StdDuration::new(1_577_836_800, 123_456_789);
+    let instant = Instant::from_system_time(time).unwrap();
+    assert_eq!(instant.epoch_nanoseconds(), 1_577_836_800_123_456_789);
+    assert_eq!(instant.to_system_time(), Some(time));
+
+    // Not every platform, wasm included, has `SystemTime`s before the epoch.
+    let instant = Instant::from_epoch_nanoseconds(-1_000_000_005).unwrap();
+    let before = UNIX_EPOCH.checked_sub(StdDuration::new(1, 5));
+    assert_eq!(instant.to_system_time(), before);
+    if let Some(before) = before {
+        let instant = Instant::from_system_time(before).unwrap();
+        assert_eq!(instant.epoch_nanoseconds(), -1_000_000_005);
+    }
+}
+
+#[wasm_bindgen_test]
+fn plain_date() {
+    if !has_temporal() {
+        return;
+    }
+    let date = PlainDate::new(2020, 2, 28).unwrap();
+    assert_eq!(date.year(), 2020);
+    assert_eq!(date.month(), 2);
+    assert_eq!(date.day(), 28);
+    assert_eq!(date.days_in_month(), 29);
+    assert!(date.in_leap_year());
+    assert_eq!(date.calendar_id(), "iso8601");
+
+    let next = date.add(&Duration::from(&"P2D".into()).unwrap()).unwrap();
+    assert_eq!(next.to_string(), "2020-03-01");
+    assert_eq!(date.until(&next).unwrap().days(), 2.0);
+    assert_eq!(PlainDate::compare(&next, &date), 1);
+    assert!(PlainDate::from(&"2020-03-01".into()).unwrap().equals(&next));
+    assert!(PlainDate::new(2020, 13, 1).is_err());
+}
+
+#[wasm_bindgen_test]
+fn zoned_date_time() {
+    if !has_temporal() {
+        return;
+    }
+    let utc = ZonedDateTime::new(1_577_836_800_000_000_000, "UTC").unwrap();
+    assert_eq!(utc.year(), 2020);
+    assert_eq!(utc.hour(), 0);
+    assert_eq!(utc.offset(), "+00:00");
+
+    let tokyo = utc.with_time_zone("Asia/Tokyo").unwrap();
+    assert_eq!(tokyo.time_zone_id(), "Asia/Tokyo");
+    assert_eq!(tokyo.hour(), 9);
+    assert_eq!(tokyo.offset_nanoseconds(), 9.0 * 3600.0 * 1e9);
+    assert!(tokyo.to_instant().equals(&utc.to_instant()));
+    assert_eq!(tokyo.to_plain_date().to_string(), "2020-01-01");
+    assert!(utc.with_time_zone("Not/AZone").is_err());
+
+    let now = Now::instant();
+    assert!(Instant::compare(&now, &utc.to_instant()) > 0);
+    assert!(Now::time_zone_id().length() > 0);
+    assert!(JsValue::from(Now::zoned_date_time_iso()).is_object());
+}
@@ -14,6 +14,8 @@ macro_rules! each {
         $m!(Int32Array);
         $m!(Float32Array);
         $m!(Float64Array);
+        $m!(BigInt64Array);
+        $m!(BigUint64Array);
     };
 }
 
@@ -138,3 +140,16 @@ fn to_vec() {
     array.fill(5, 0, 10);
     assert_eq!(array.to_vec(), vec![5, 5, 5, 5, 5, 5, 5, 5, 5, 5]);
 }
+
+#[wasm_bindgen_test]
+fn bigint_arrays() {
+    let x = [i64::min_value(), -1, i64::max_value()];
+    let array = unsafe { BigInt64Array::view(&x) };
+    assert_eq!(array.length(), 3);
+    assert_eq!(array.to_vec(), x.to_vec());
+    assert_eq!(BigInt64Array::from(&x[..]).to_vec(), x.to_vec());
+
+    let array = BigUint64Array::new(&3.into());
+    array.fill(u64::max_value(), 1, 3);
+    assert_eq!(array.to_vec(), vec![0, u64::max_value(), u64::max_value()]);
+}
@@ -0,0 +1,30 @@
+use js_sys::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+// `WeakRef` isn't available in every JS engine the tests run in.
+fn has_weak_ref() -> bool {
+    Reflect::has(&global(), &"WeakRef".into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn deref() {
+    if !has_weak_ref() {
+        return;
+    }
+    let target = Object::new();
+    let weak = WeakRef::new(&target);
+    // The target is still alive, so it must be returned.
+    assert_eq!(weak.deref(), Some(target));
+}
+
+#[wasm_bindgen_test]
+fn weak_ref_inheritance() {
+    if !has_weak_ref() {
+        return;
+    }
+    let weak = WeakRef::new(&Object::new());
+    assert!(weak.is_instance_of::<WeakRef>());
+    assert!(weak.is_instance_of::<Object>());
+    let _: &Object = weak.as_ref();
+}
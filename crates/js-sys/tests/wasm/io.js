@@ -0,0 +1,17 @@
+exports.new_sink = function() {
+  return {
+    chunks: [],
+    write(chunk) {
+      this.chunks.push(Array.from(chunk));
+      return Promise.resolve();
+    },
+  };
+};
+
+exports.sink_contents = function(sink) {
+  return new Uint8Array([].concat(...sink.chunks));
+};
+
+exports.sink_chunks = function(sink) {
+  return sink.chunks.length;
+};
@@ -0,0 +1,87 @@
+#![cfg(feature = "std-io")]
+
+use js_sys::io::*;
+use js_sys::*;
+use std::io::{Read, Seek, SeekFrom, Write};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/io.js")]
+extern "C" {
+    fn new_sink() -> WritableSink;
+    fn sink_contents(sink: &WritableSink) -> Uint8Array;
+    fn sink_chunks(sink: &WritableSink) -> u32;
+}
+
+#[wasm_bindgen_test]
+fn read_uint8array() {
+    let array = Uint8Array::from(&b"hello world"[..]);
+    let mut reader = Uint8ArrayReader::new(array);
+
+    let mut buf = [0; 5];
+    assert_eq!(reader.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+    assert_eq!(reader.position(), 5);
+
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, " world");
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+fn seek_uint8array() {
+    let array = Uint8Array::from(&b"abcdef"[..]);
+    let mut reader = Uint8ArrayReader::new(array);
+
+    assert_eq!(reader.seek(SeekFrom::End(-2)).unwrap(), 4);
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, b"ef");
+
+    assert_eq!(reader.seek(SeekFrom::Start(1)).unwrap(), 1);
+    assert_eq!(reader.seek(SeekFrom::Current(1)).unwrap(), 2);
+    assert!(reader.seek(SeekFrom::Current(-3)).is_err());
+
+    assert_eq!(reader.seek(SeekFrom::Start(100)).unwrap(), 100);
+    assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+fn write_uint8array() {
+    let mut writer = Uint8ArrayWriter::new();
+    assert!(writer.is_empty());
+    writer.write_all(b"foo").unwrap();
+    write!(writer, "-{}-", 42).unwrap();
+    writer.write_all(b"bar").unwrap();
+    assert_eq!(writer.len(), 10);
+    assert_eq!(writer.finish().to_vec(), b"foo-42-bar");
+}
+
+#[wasm_bindgen_test]
+fn write_sink_in_chunks() {
+    let sink = new_sink();
+    let mut writer = SinkWriter::with_chunk_size(sink.clone(), 4);
+    assert!(writer.last_write().is_none());
+
+    writer.write_all(b"abcdefghij").unwrap();
+    assert_eq!(sink_chunks(&sink), 2);
+    assert_eq!(sink_contents(&sink).to_vec(), b"abcdefgh");
+    assert!(writer.last_write().unwrap().is_instance_of::<Promise>());
+
+    writer.flush().unwrap();
+    assert_eq!(sink_chunks(&sink), 3);
+    assert_eq!(sink_contents(&sink).to_vec(), b"abcdefghij");
+
+    writer.write_all(b"k").unwrap();
+    drop(writer);
+    assert_eq!(sink_contents(&sink).to_vec(), b"abcdefghijk");
+}
+
+#[wasm_bindgen_test]
+fn write_sink_error() {
+    let sink = JsValue::from(Object::new()).unchecked_into::<WritableSink>();
+    let mut writer = SinkWriter::with_chunk_size(sink, 2);
+    assert!(writer.write_all(b"ab").is_err());
+}
@@ -0,0 +1,32 @@
+const assert = require('assert');
+
+exports.js_tree = function() {
+  const map = new Map();
+  map.set('a', -1);
+  return {
+    name: 'wasm',
+    count: 3,
+    ratio: 0.5,
+    ok: true,
+    missing: null,
+    list: [1, 'two', [3]],
+    map,
+  };
+};
+
+exports.js_check_tree = function(val) {
+  assert.deepStrictEqual(val, {
+    name: 'wasm',
+    count: 3,
+    ratio: 0.5,
+    ok: true,
+    missing: null,
+    list: [1, 'two', [3]],
+  });
+};
+
+exports.js_cyclic = function() {
+  const obj = { a: [] };
+  obj.a.push(obj);
+  return obj;
+};
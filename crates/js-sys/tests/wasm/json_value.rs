@@ -0,0 +1,73 @@
+#![cfg(feature = "serde-json")]
+
+use js_sys::json_value::{from_js, to_js};
+use js_sys::*;
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/json_value.js")]
+extern "C" {
+    fn js_tree() -> JsValue;
+    fn js_check_tree(val: &JsValue);
+    fn js_cyclic() -> JsValue;
+}
+
+#[wasm_bindgen_test]
+fn to_js_tree() {
+    let value = json!({
+        "name": "wasm",
+        "count": 3,
+        "ratio": 0.5,
+        "ok": true,
+        "missing": null,
+        "list": [1, "two", [3]],
+    });
+    js_check_tree(&to_js(&value));
+}
+
+#[wasm_bindgen_test]
+fn from_js_tree() {
+    let value = from_js(&js_tree()).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "name": "wasm",
+            "count": 3,
+            "ratio": 0.5,
+            "ok": true,
+            "missing": null,
+            "list": [1, "two", [3]],
+            "map": { "a": -1 },
+        })
+    );
+    assert!(value["count"].is_u64());
+    assert!(value["map"]["a"].is_i64());
+}
+
+#[wasm_bindgen_test]
+fn round_trip() {
+    let value = json!([{ "a": [true, false] }, 1.5, "", {}]);
+    assert_eq!(from_js(&to_js(&value)).unwrap(), value);
+}
+
+#[wasm_bindgen_test]
+fn from_js_errors() {
+    let unsupported = [
+        JsValue::UNDEFINED,
+        JsValue::from_f64(std::f64::NAN),
+        JsValue::from_f64(std::f64::INFINITY),
+        Function::new_no_args("").into(),
+        Symbol::for_("json").into(),
+        js_cyclic(),
+    ];
+    for value in unsupported.iter() {
+        let err = from_js(value).unwrap_err();
+        assert!(err.is_instance_of::<TypeError>());
+    }
+
+    let map = Map::new();
+    map.set(&JsValue::from(1), &JsValue::from(2));
+    assert!(from_js(&map).is_err());
+}
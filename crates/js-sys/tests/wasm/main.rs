@@ -9,6 +9,7 @@ pub mod DataView;
 pub mod Date;
 pub mod Error;
 pub mod EvalError;
+pub mod FinalizationRegistry;
 pub mod Function;
 pub mod Generator;
 pub mod Intl;
@@ -31,10 +32,14 @@ pub mod SetIterator;
 pub mod SharedArrayBuffer;
 pub mod Symbol;
 pub mod SyntaxError;
+pub mod Temporal;
 pub mod TypeError;
 pub mod TypedArray;
 pub mod UriError;
 pub mod WeakMap;
+pub mod WeakRef;
 pub mod WeakSet;
 pub mod WebAssembly;
 pub mod global_fns;
+pub mod io;
+pub mod json_value;
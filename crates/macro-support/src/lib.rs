@@ -25,13 +25,21 @@ mod parser;
 pub fn expand(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Diagnostic> {
     parser::reset_attrs_used();
     let item = syn::parse2::<syn::Item>(input)?;
-    let opts = syn::parse2(attr)?;
+    let opts: BindgenAttrs = syn::parse2(attr)?;
+    let cfg_feature = opts.feature_gate();
 
     let mut tokens = proc_macro2::TokenStream::new();
     let mut program = backend::ast::Program::default();
     item.macro_parse(&mut program, (Some(opts), &mut tokens))?;
     program.try_to_tokens(&mut tokens)?;
 
+    // An item behind a Cargo feature is compiled out along with everything
+    // generated for it, including its part of the custom section, so that
+    // disabled items don't show up in the bindings at all.
+    if let Some(feature) = cfg_feature {
+        tokens = cfg_feature_gate(tokens, &feature)?;
+    }
+
     // If we successfully got here then we should have used up all attributes
     // and considered all of them to see if they were used. If one was forgotten
     // that's a bug on our end, so sanity check here.
@@ -40,6 +48,92 @@ pub fn expand(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Diag
     Ok(tokens)
 }
 
+/// Adds `#[cfg(feature = "...")]` to each of the items in `tokens`.
+fn cfg_feature_gate(tokens: TokenStream, feature: &str) -> Result<TokenStream, Diagnostic> {
+    let mut file = syn::parse2::<syn::File>(tokens)?;
+    let cfg: syn::Attribute = syn::parse_quote! { #[cfg(feature = #feature)] };
+    for item in file.items.iter_mut() {
+        let attrs = match item {
+            syn::Item::Const(i) => &mut i.attrs,
+            syn::Item::Enum(i) => &mut i.attrs,
+            syn::Item::ExternCrate(i) => &mut i.attrs,
+            syn::Item::Fn(i) => &mut i.attrs,
+            syn::Item::ForeignMod(i) => &mut i.attrs,
+            syn::Item::Impl(i) => &mut i.attrs,
+            syn::Item::Macro(i) => &mut i.attrs,
+            syn::Item::Mod(i) => &mut i.attrs,
+            syn::Item::Static(i) => &mut i.attrs,
+            syn::Item::Struct(i) => &mut i.attrs,
+            syn::Item::Trait(i) => &mut i.attrs,
+            syn::Item::Type(i) => &mut i.attrs,
+            syn::Item::Union(i) => &mut i.attrs,
+            syn::Item::Use(i) => &mut i.attrs,
+            other => bail_span!(other, "cannot use `cfg_feature` with this item"),
+        };
+        attrs.insert(0, cfg.clone());
+    }
+    Ok(file.into_token_stream())
+}
+
+/// Takes the input of a `link_to!` macro and returns an expression evaluating
+/// to the URL of the linked file at runtime.
+pub fn expand_link_to(input: TokenStream) -> Result<TokenStream, Diagnostic> {
+    let opts = syn::parse2::<LinkTo>(input)?;
+    let path = opts.module.value();
+    if !path.starts_with("/") {
+        bail_span!(
+            opts.module,
+            "`link_to!` only supports paths to local files, starting with `/`"
+        );
+    }
+
+    let link_function_name = format!("__wbindgen_link_{}", backend::util::ShortHash(&path));
+    let link = backend::ast::LinkToModule {
+        path,
+        span: opts.module.span(),
+        link_function_name: proc_macro2::Ident::new(
+            &link_function_name,
+            proc_macro2::Span::call_site(),
+        ),
+    };
+    let mut program = backend::ast::Program::default();
+    program.linked_modules.push(link.clone());
+
+    let mut tokens = proc_macro2::TokenStream::new();
+    program.try_to_tokens(&mut tokens)?;
+    link.to_tokens(&mut tokens);
+    Ok(quote::quote! {
+        { #tokens }
+    })
+}
+
+/// Takes the input of a `js_prelude!` macro and returns the item registering
+/// the prelude with the CLI.
+pub fn expand_js_prelude(input: TokenStream) -> Result<TokenStream, Diagnostic> {
+    let prelude = match syn::parse2::<JsPrelude>(input)? {
+        JsPrelude::Inline(js) => backend::ast::JsPrelude::Inline(js.value()),
+        JsPrelude::Module(module) => {
+            let path = module.value();
+            if !path.starts_with("/") {
+                bail_span!(
+                    module,
+                    "`js_prelude!` only supports paths to local files, starting with `/`"
+                );
+            }
+            backend::ast::JsPrelude::Module {
+                path,
+                span: module.span(),
+            }
+        }
+    };
+    let mut program = backend::ast::Program::default();
+    program.js_preludes.push(prelude);
+
+    let mut tokens = proc_macro2::TokenStream::new();
+    program.try_to_tokens(&mut tokens)?;
+    Ok(tokens)
+}
+
 /// Takes the parsed input from a `#[wasm_bindgen]` macro and returns the generated bindings
 pub fn expand_class_marker(
     attr: TokenStream,
@@ -103,3 +197,41 @@ impl Parse for ClassMarker {
         Ok(ClassMarker { class, js_class })
     }
 }
+
+struct LinkTo {
+    module: syn::LitStr,
+}
+
+impl Parse for LinkTo {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let name = input.parse::<syn::Ident>()?;
+        if name != "module" {
+            return Err(syn::Error::new(name.span(), "expected `module = \"...\"`"));
+        }
+        input.parse::<Token![=]>()?;
+        let module = input.parse::<syn::LitStr>()?;
+        Ok(LinkTo { module })
+    }
+}
+
+enum JsPrelude {
+    Inline(syn::LitStr),
+    Module(syn::LitStr),
+}
+
+impl Parse for JsPrelude {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        if input.peek(syn::LitStr) {
+            return Ok(JsPrelude::Inline(input.parse()?));
+        }
+        let name = input.parse::<syn::Ident>()?;
+        if name != "module" {
+            return Err(syn::Error::new(
+                name.span(),
+                "expected a string of JS or `module = \"...\"`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        Ok(JsPrelude::Module(input.parse()?))
+    }
+}
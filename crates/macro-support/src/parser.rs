@@ -1,8 +1,9 @@
 use std::cell::Cell;
+use std::mem;
 
 use backend::ast;
 use backend::util::{ident_ty, ShortHash};
-use backend::Diagnostic;
+use backend::{Diagnostic, TryToTokens};
 use proc_macro2::{Delimiter, Ident, Span, TokenStream, TokenTree};
 use quote::ToTokens;
 use shared;
@@ -43,19 +44,34 @@ macro_rules! attrgen {
             (structural, Structural(Span)),
             (r#final, Final(Span)),
             (readonly, Readonly(Span)),
+            (cached, Cached(Span)),
+            (property, Property(Span)),
             (js_name, JsName(Span, String, Span)),
             (js_class, JsClass(Span, String, Span)),
+            (extends_js, ExtendsJs(Span, Ident)),
+            (custom_element, CustomElement(Span, String, Span)),
+            (observed_attribute, ObservedAttribute(Span, String, Span)),
             (inspectable, Inspectable(Span)),
+            (to_json, ToJson(Span)),
+            (async_destructor, AsyncDestructor(Span)),
+            (iterator, Iterator(Span)),
+            (no_copy, NoCopy(Span)),
+            (unstable, Unstable(Span)),
+            (deprecated, Deprecated(Span, Option<String>)),
+            (extension_trait, ExtensionTrait(Span, Ident)),
             (is_type_of, IsTypeOf(Span, syn::Expr)),
             (extends, Extends(Span, syn::Path)),
             (vendor_prefix, VendorPrefix(Span, Ident)),
+            (js_repr, JsRepr(Span, Ident)),
+            (typed_array, TypedArray(Span, Ident)),
             (variadic, Variadic(Span)),
             (typescript_custom_section, TypescriptCustomSection(Span)),
             (start, Start(Span)),
             (skip, Skip(Span)),
-
-            // For testing purposes only.
             (assert_no_shim, AssertNoShim(Span)),
+            (cfg_feature, CfgFeature(Span, String, Span)),
+            (env, Env(Span, String, Span)),
+            (imported, Imported(Span)),
         }
     };
 }
@@ -177,9 +193,54 @@ impl BindgenAttrs {
         }
     }
 
+    /// Adds the attributes of an enclosing item which aren't also given here,
+    /// such as those of a `#[wasm_bindgen] mod` for the items inside it.
+    ///
+    /// Items of an `extern` block inherit its attributes in the same way,
+    /// except for those which only configure the block itself like `module`.
+    fn inherit(&mut self, parent: &BindgenAttrs, from_extern_block: bool) {
+        for (used, attr) in parent.attrs.iter() {
+            if from_extern_block && is_extern_block_attr(attr) {
+                continue;
+            }
+            let given = self.attrs.iter().any(|(_, a)| {
+                mem::discriminant(a) == mem::discriminant(attr)
+                    || (is_import_module_attr(a) && is_import_module_attr(attr))
+            });
+            if given {
+                continue;
+            }
+            used.set(true);
+            self.attrs.push((Cell::new(true), attr.clone()));
+        }
+    }
+
+    /// Returns the Cargo feature an item is only compiled with, given through
+    /// `cfg_feature`.
+    pub(crate) fn feature_gate(&self) -> Option<String> {
+        self.cfg_feature().map(|s| s.0.to_string())
+    }
+
     attrgen!(methods);
 }
 
+/// Whether `attr` names the JS module an `extern` block is imported from.
+fn is_import_module_attr(attr: &BindgenAttr) -> bool {
+    match attr {
+        BindgenAttr::Module(..) | BindgenAttr::RawModule(..) | BindgenAttr::InlineJs(..) => true,
+        _ => false,
+    }
+}
+
+/// Whether `attr` applies to an `extern` block as a whole rather than to each
+/// of its items.
+fn is_extern_block_attr(attr: &BindgenAttr) -> bool {
+    match attr {
+        BindgenAttr::ExtensionTrait(..) => true,
+        _ => is_import_module_attr(attr),
+    }
+}
+
 impl Default for BindgenAttrs {
     fn default() -> BindgenAttrs {
         // Add 1 to the list of parsed attribute sets. We'll use this counter to
@@ -207,6 +268,7 @@ macro_rules! gen_bindgen_attr {
     ($(($method:ident, $($variants:tt)*),)*) => {
         /// The possible attributes in the `#[wasm_bindgen]`.
         #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+        #[derive(Clone)]
         pub enum BindgenAttr {
             $($($variants)*,)*
         }
@@ -254,6 +316,15 @@ impl Parse for BindgenAttr {
                 }
             });
 
+            (@parser $variant:ident(Span, Option<String>)) => ({
+                if input.parse::<Token![=]>().is_ok() {
+                    let val = input.parse::<syn::LitStr>()?.value();
+                    return Ok(BindgenAttr::$variant(attr_span, Some(val)))
+                } else {
+                    return Ok(BindgenAttr::$variant(attr_span, None));
+                }
+            });
+
             (@parser $variant:ident(Span, syn::Path)) => ({
                 input.parse::<Token![=]>()?;
                 return Ok(BindgenAttr::$variant(attr_span, input.parse()?));
@@ -324,6 +395,33 @@ impl<'a> ConvertToAst<BindgenAttrs> for &'a mut syn::ItemStruct {
             .map(|s| s.0.to_string())
             .unwrap_or(self.ident.to_string());
         let is_inspectable = attrs.inspectable().is_some();
+        let cfg_feature = attrs.cfg_feature().map(|s| s.0.to_string());
+        let extends_js = attrs.extends_js().map(|i| i.to_string());
+        let custom_element = match attrs.custom_element() {
+            Some((tag, span)) => {
+                if extends_js.is_none() {
+                    bail_span!(
+                        self.ident,
+                        "#[wasm_bindgen(custom_element)] requires `extends_js`, \
+                         usually `extends_js = HTMLElement`"
+                    );
+                }
+                // The same rules the browser enforces in
+                // `customElements.define`, checked early for a nicer error.
+                if !tag.contains('-')
+                    || !tag.starts_with(|c: char| c.is_ascii_lowercase())
+                    || tag.chars().any(|c| c.is_ascii_uppercase())
+                {
+                    return Err(Diagnostic::span_error(
+                        span,
+                        "custom element names must start with a lowercase ASCII \
+                         letter, contain a hyphen, and not contain uppercase letters",
+                    ));
+                }
+                Some(tag.to_string())
+            }
+            None => None,
+        };
         for (i, field) in self.fields.iter_mut().enumerate() {
             match field.vis {
                 syn::Visibility::Public(..) => {}
@@ -345,14 +443,36 @@ impl<'a> ConvertToAst<BindgenAttrs> for &'a mut syn::ItemStruct {
             let getter = shared::struct_field_get(&js_name, &name_str);
             let setter = shared::struct_field_set(&js_name, &name_str);
 
+            let readonly = attrs.readonly().is_some();
+            let cached = attrs.cached().is_some();
+            if cached && !readonly {
+                bail_span!(
+                    field,
+                    "#[wasm_bindgen(cached)] fields must also be `readonly`, since the \
+                     cached value can't be updated from JS"
+                );
+            }
+            let kind = struct_field_kind(&field.ty, attrs.typed_array())?;
+            if cached {
+                if let ast::StructFieldKind::TypedArrayView(_) = kind {
+                    bail_span!(
+                        field,
+                        "#[wasm_bindgen(cached)] can't be used with `typed_array = view`, \
+                         since the view is invalidated when wasm memory grows"
+                    );
+                }
+            }
+
             fields.push(ast::StructField {
                 name: member,
                 struct_name: self.ident.clone(),
-                readonly: attrs.readonly().is_some(),
+                readonly,
+                cached,
                 ty: field.ty.clone(),
                 getter: Ident::new(&getter, Span::call_site()),
                 setter: Ident::new(&setter, Span::call_site()),
                 comments,
+                kind,
             });
             attrs.check_used()?;
         }
@@ -364,6 +484,9 @@ impl<'a> ConvertToAst<BindgenAttrs> for &'a mut syn::ItemStruct {
             fields,
             comments,
             is_inspectable,
+            cfg_feature,
+            extends_js,
+            custom_element,
         })
     }
 }
@@ -375,6 +498,9 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
         self,
         (opts, module): (BindgenAttrs, &'a ast::ImportModule),
     ) -> Result<Self::Target, Diagnostic> {
+        if opts.property().is_some() {
+            return import_property(self, opts);
+        }
         let wasm = function_from_decl(
             &self.sig.ident,
             &opts,
@@ -387,17 +513,19 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
         .0;
         let catch = opts.catch().is_some();
         let variadic = opts.variadic().is_some();
-        let js_ret = if catch {
+        let (js_ret, catch_ty) = if catch {
             // TODO: this assumes a whole bunch:
             //
             // * The outer type is actually a `Result`
-            // * The error type is a `JsValue`
             // * The actual type is the first type parameter
             //
             // should probably fix this one day...
-            extract_first_ty_param(wasm.ret.as_ref())?
+            (
+                extract_first_ty_param(wasm.ret.as_ref())?,
+                extract_catch_ty(wasm.ret.as_ref()),
+            )
         } else {
-            wasm.ret.clone()
+            (wasm.ret.clone(), None)
         };
 
         let operation_kind = operation_kind(&opts);
@@ -501,13 +629,37 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
                 return Err(Diagnostic::span_error(*span, msg));
             }
         }
+        if let Some(asyncness) = &self.sig.asyncness {
+            if let ast::ImportFunctionKind::Method {
+                kind: ast::MethodKind::Constructor,
+                ..
+            } = kind
+            {
+                bail_span!(asyncness, "constructors cannot be `async`");
+            }
+            if opts.extension_trait().is_some() {
+                bail_span!(
+                    asyncness,
+                    "methods of an `extension_trait` cannot be `async`"
+                );
+            }
+        }
         let assert_no_shim = opts.assert_no_shim().is_some();
+        let extension_trait = opts.extension_trait().cloned();
+        if let (Some(name), ast::ImportFunctionKind::Normal) = (&extension_trait, &kind) {
+            bail_span!(
+                name,
+                "`extension_trait` can only be used with methods of imported types"
+            );
+        }
         let ret = ast::ImportKind::Function(ast::ImportFunction {
             function: wasm,
             assert_no_shim,
+            extension_trait,
             kind,
             js_ret,
             catch,
+            catch_ty,
             variadic,
             structural: opts.structural().is_some() || opts.r#final().is_none(),
             rust_name: self.sig.ident.clone(),
@@ -561,6 +713,108 @@ impl ConvertToAst<BindgenAttrs> for syn::ForeignItemType {
     }
 }
 
+impl ConvertToAst<BindgenAttrs> for syn::ItemEnum {
+    type Target = ast::ImportKind;
+
+    fn convert(self, attrs: BindgenAttrs) -> Result<Self::Target, Diagnostic> {
+        if self.variants.len() == 0 {
+            bail_span!(self, "cannot import empty enums from JS");
+        }
+        let mut variants = Vec::new();
+        let mut variant_values = Vec::new();
+        for v in self.variants.iter() {
+            match v.fields {
+                syn::Fields::Unit => (),
+                _ => bail_span!(v.fields, "only C-Style enums allowed with #[wasm_bindgen]"),
+            }
+            let value = match &v.discriminant {
+                Some((
+                    _,
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }),
+                )) => ast::ImportEnumValue::String(s.value()),
+                Some((
+                    _,
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(i),
+                        ..
+                    }),
+                )) => match i.base10_digits().parse::<u32>() {
+                    Ok(n) => ast::ImportEnumValue::Number(n),
+                    Err(_) => bail_span!(
+                        i,
+                        "imported enums can only support numbers that can be \
+                         represented as u32"
+                    ),
+                },
+                _ => bail_span!(
+                    v,
+                    "variants of imported enums must specify the JS value they \
+                     correspond to with a string or number literal, for example \
+                     `Foo = \"foo\"`"
+                ),
+            };
+            variants.push(v.ident.clone());
+            variant_values.push(value);
+        }
+        attrs.check_used()?;
+        Ok(ast::ImportKind::Enum(ast::ImportEnum {
+            vis: self.vis,
+            name: self.ident,
+            variants,
+            variant_values,
+            rust_attrs: self.attrs,
+        }))
+    }
+}
+
+/// Converts an imported method marked `#[wasm_bindgen(property)]`, like
+/// `fn color(this: &Style) -> Property<String>`, into a method returning a
+/// handle to the property instead of a call into JS.
+fn import_property(
+    f: syn::ForeignItemFn,
+    opts: BindgenAttrs,
+) -> Result<ast::ImportKind, Diagnostic> {
+    if opts.method().is_none() {
+        bail_span!(f.sig.ident, "`property` can only be used on methods");
+    }
+    let mut inputs = f.sig.inputs.iter();
+    let class = match (inputs.next(), inputs.next()) {
+        (Some(syn::FnArg::Typed(arg)), None) => match &*arg.ty {
+            syn::Type::Reference(r) if r.mutability.is_none() => (*r.elem).clone(),
+            _ => bail_span!(
+                arg.ty,
+                "the receiver of a `property` must be a shared reference"
+            ),
+        },
+        _ => bail_span!(
+            f.sig.inputs,
+            "a `property` must take exactly one argument, the object it's read from"
+        ),
+    };
+    let ty = match &f.sig.output {
+        syn::ReturnType::Type(_, ty) => (**ty).clone(),
+        syn::ReturnType::Default => {
+            bail_span!(f.sig.ident, "a `property` must return a `Property`")
+        }
+    };
+    let js_name = opts
+        .js_name()
+        .map(|p| p.0.to_string())
+        .unwrap_or_else(|| f.sig.ident.to_string());
+    opts.check_used()?;
+    Ok(ast::ImportKind::Property(ast::ImportProperty {
+        vis: f.vis,
+        rust_name: f.sig.ident,
+        js_name,
+        class,
+        ty,
+        rust_attrs: f.attrs,
+    }))
+}
+
 impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignItemStatic {
     type Target = ast::ImportKind;
 
@@ -613,7 +867,7 @@ impl ConvertToAst<BindgenAttrs> for syn::ItemFn {
         }
         assert_not_variadic(&attrs)?;
 
-        let ret = function_from_decl(
+        let (mut ret, _) = function_from_decl(
             &self.sig.ident,
             &attrs,
             self.sig.clone(),
@@ -622,11 +876,119 @@ impl ConvertToAst<BindgenAttrs> for syn::ItemFn {
             false,
             None,
         )?;
+        treat_impl_future_as_async(&mut ret);
+        treat_impl_iterator_as_generator(&mut ret);
         attrs.check_used()?;
-        Ok(ret.0)
+        Ok(ret)
     }
 }
 
+/// Determines the stability of an export from its `unstable` and `deprecated`
+/// attributes, which are mutually exclusive.
+fn stability(opts: &BindgenAttrs) -> Result<ast::Stability, Diagnostic> {
+    match (opts.unstable(), opts.deprecated()) {
+        (Some(span), Some(_)) => Err(Diagnostic::span_error(
+            *span,
+            "an export cannot be both `unstable` and `deprecated`",
+        )),
+        (Some(_), None) => Ok(ast::Stability::Unstable),
+        (None, Some(note)) => Ok(ast::Stability::Deprecated(note.clone())),
+        (None, None) => Ok(ast::Stability::Stable),
+    }
+}
+
+/// Returns whether the return type of `function` is syntactically an `Option`.
+fn returns_option(function: &ast::Function) -> bool {
+    let path = match &function.ret {
+        Some(syn::Type::Path(syn::TypePath { qself: None, path })) => path,
+        _ => return false,
+    };
+    match path.segments.last() {
+        Some(seg) => seg.ident == "Option",
+        None => false,
+    }
+}
+
+/// Returns whether `function` returns `()` or `Result<(), E>`, judging by the
+/// return type as written.
+fn returns_unit(function: &ast::Function) -> bool {
+    let is_unit = |ty: &syn::Type| match ty {
+        syn::Type::Tuple(t) => t.elems.is_empty(),
+        _ => false,
+    };
+    let path = match &function.ret {
+        None => return true,
+        Some(ty) if is_unit(ty) => return true,
+        Some(syn::Type::Path(syn::TypePath { qself: None, path })) => path,
+        _ => return false,
+    };
+    let seg = match path.segments.last() {
+        Some(seg) if seg.ident == "Result" => seg,
+        _ => return false,
+    };
+    match &seg.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(ty)) => is_unit(ty),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Exported functions returning `impl Future<Output = T>` are handled just
+/// like an `async fn` returning `T`, being converted to a JS `Promise`.
+fn treat_impl_future_as_async(function: &mut ast::Function) {
+    if function.r#async {
+        return;
+    }
+    if let Some(output) = extract_impl_trait_binding(function.ret.as_ref(), "Future", "Output") {
+        function.ret = Some(output);
+        function.r#async = true;
+    }
+}
+
+/// Exported functions returning `impl Iterator<Item = T>` hand the iterator
+/// to JS as an object implementing the JS iterator protocol, yielding `T`s.
+fn treat_impl_iterator_as_generator(function: &mut ast::Function) {
+    if function.r#async {
+        return;
+    }
+    if let Some(item) = extract_impl_trait_binding(function.ret.as_ref(), "Iterator", "Item") {
+        function.ret = Some(item);
+        function.generator = true;
+    }
+}
+
+/// Returns `T` for a type `impl Trait<Binding = T>`.
+fn extract_impl_trait_binding(
+    ty: Option<&syn::Type>,
+    trait_name: &str,
+    binding: &str,
+) -> Option<syn::Type> {
+    let bounds = match ty? {
+        syn::Type::ImplTrait(t) => &t.bounds,
+        _ => return None,
+    };
+    bounds.iter().find_map(|bound| {
+        let path = match bound {
+            syn::TypeParamBound::Trait(t) => &t.path,
+            _ => return None,
+        };
+        let segment = path.segments.last()?;
+        if segment.ident != trait_name {
+            return None;
+        }
+        let generics = match &segment.arguments {
+            syn::PathArguments::AngleBracketed(t) => t,
+            _ => return None,
+        };
+        generics.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Binding(b) if b.ident == binding => Some(b.ty.clone()),
+            _ => None,
+        })
+    })
+}
+
 /// Construct a function (and gets the self type if appropriate) for our AST from a syn function.
 fn function_from_decl(
     decl_name: &syn::Ident,
@@ -717,6 +1079,7 @@ fn function_from_decl(
             rust_attrs: attrs,
             rust_vis: vis,
             r#async: sig.asyncness.is_some(),
+            generator: false,
         },
         method_self,
     ))
@@ -767,15 +1130,26 @@ impl<'a> MacroParse<(Option<BindgenAttrs>, &'a mut TokenStream)> for syn::Item {
                 });
                 let rust_name = f.sig.ident.clone();
                 let start = opts.start().is_some();
+                let js_namespace = opts.js_namespace().cloned();
+                let stability = stability(&opts)?;
+                let no_copy = opts.no_copy().is_some();
+                let cfg_feature = opts.cfg_feature().map(|s| s.0.to_string());
                 program.exports.push(ast::Export {
                     comments,
                     function: f.convert(opts)?,
                     js_class: None,
+                    js_namespace,
                     method_kind,
                     method_self: None,
                     rust_class: None,
                     rust_name,
                     start,
+                    iterator: false,
+                    cached: false,
+                    observed_attribute: None,
+                    stability,
+                    no_copy,
+                    cfg_feature,
                 });
             }
             syn::Item::Struct(mut s) => {
@@ -795,25 +1169,42 @@ impl<'a> MacroParse<(Option<BindgenAttrs>, &'a mut TokenStream)> for syn::Item {
                 };
                 f.macro_parse(program, opts)?;
             }
+            syn::Item::Mod(m) => {
+                let opts = opts.unwrap_or_default();
+                m.macro_parse(program, (opts, tokens))?;
+            }
             syn::Item::Enum(e) => {
-                if let Some(opts) = opts {
-                    opts.check_used()?;
+                let opts = opts.unwrap_or_default();
+                // Imported enums are regenerated by codegen since their
+                // discriminants are JS values rather than Rust ones.
+                if opts.imported().is_some() {
+                    let kind = e.convert(opts)?;
+                    program.imports.push(ast::Import {
+                        module: ast::ImportModule::None,
+                        js_namespace: None,
+                        kind,
+                    });
+                } else {
+                    e.to_tokens(tokens);
+                    e.macro_parse(program, opts)?;
                 }
-                e.to_tokens(tokens);
-                e.macro_parse(program, ())?;
             }
             syn::Item::Const(mut c) => {
                 let opts = match opts {
                     Some(opts) => opts,
                     None => BindgenAttrs::find(&mut c.attrs)?,
                 };
-                c.macro_parse(program, opts)?;
+                c.macro_parse(program, (opts, tokens))?;
+            }
+            syn::Item::Trait(t) => {
+                let opts = opts.unwrap_or_default();
+                t.macro_parse(program, (opts, tokens))?;
             }
             _ => {
                 bail_span!(
                     self,
                     "#[wasm_bindgen] can only be applied to a function, \
-                     struct, enum, impl, or extern block",
+                     struct, enum, impl, trait, mod, or extern block",
                 );
             }
         }
@@ -822,6 +1213,87 @@ impl<'a> MacroParse<(Option<BindgenAttrs>, &'a mut TokenStream)> for syn::Item {
     }
 }
 
+impl<'a> MacroParse<(BindgenAttrs, &'a mut TokenStream)> for syn::ItemMod {
+    fn macro_parse(
+        self,
+        _program: &mut ast::Program,
+        (opts, tokens): (BindgenAttrs, &'a mut TokenStream),
+    ) -> Result<(), Diagnostic> {
+        let items = match self.content {
+            Some((_, items)) => items,
+            None => bail_span!(
+                self,
+                "#[wasm_bindgen] can only be applied to modules with an inline body"
+            ),
+        };
+
+        // The items of the module are expanded inside of it, along with the
+        // bindings generated for them, so paths keep resolving the same way.
+        let mut program = ast::Program::default();
+        let mut body = TokenStream::new();
+        let mut errors = Vec::new();
+        for mut item in items {
+            // `extern` blocks are always bound, other items only if they're
+            // annotated with `#[wasm_bindgen]` themselves.
+            let is_extern = match item {
+                syn::Item::ForeignMod(_) => true,
+                _ => false,
+            };
+            let attrs = match &mut item {
+                syn::Item::Fn(i) => &mut i.attrs,
+                syn::Item::Struct(i) => &mut i.attrs,
+                syn::Item::Impl(i) => &mut i.attrs,
+                syn::Item::ForeignMod(i) => &mut i.attrs,
+                syn::Item::Enum(i) => &mut i.attrs,
+                syn::Item::Const(i) => &mut i.attrs,
+                syn::Item::Trait(i) => &mut i.attrs,
+                syn::Item::Mod(i) => &mut i.attrs,
+                _ => {
+                    item.to_tokens(&mut body);
+                    continue;
+                }
+            };
+            let annotated = attrs
+                .iter()
+                .any(|a| a.path.segments[0].ident == "wasm_bindgen");
+            if !annotated && !is_extern {
+                item.to_tokens(&mut body);
+                continue;
+            }
+            let mut item_opts = match BindgenAttrs::find(attrs) {
+                Ok(item_opts) => item_opts,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            item_opts.inherit(&opts, false);
+            if let Err(e) = item.macro_parse(&mut program, (Some(item_opts), &mut body)) {
+                errors.push(e);
+            }
+        }
+        Diagnostic::from_vec(errors)?;
+        program.try_to_tokens(&mut body)?;
+        opts.check_used()?;
+
+        let (outer, inner): (Vec<_>, Vec<_>) = self.attrs.iter().partition(|a| match a.style {
+            syn::AttrStyle::Outer => true,
+            syn::AttrStyle::Inner(_) => false,
+        });
+        let vis = &self.vis;
+        let ident = &self.ident;
+        (quote::quote! {
+            #(#outer)*
+            #vis mod #ident {
+                #(#inner)*
+                #body
+            }
+        })
+        .to_tokens(tokens);
+        Ok(())
+    }
+}
+
 impl<'a> MacroParse<BindgenAttrs> for &'a mut syn::ItemImpl {
     fn macro_parse(
         self,
@@ -923,9 +1395,54 @@ fn prepare_for_impl_recursion(
         },
     );
 
+    // A method behind a Cargo feature is given a `cfg` here, ahead of the
+    // class marker, so that it and its bindings are all compiled out together.
+    // Methods of an `impl` behind a feature inherit it so the feature is also
+    // recorded for each of their exports.
+    let cfg_feature = match method_cfg_feature(&method.attrs)? {
+        Some(feature) => feature,
+        None => match impl_opts.cfg_feature() {
+            Some((feature, _)) => {
+                method
+                    .attrs
+                    .push(syn::parse_quote! { #[wasm_bindgen(cfg_feature = #feature)] });
+                feature.to_string()
+            }
+            None => return Ok(()),
+        },
+    };
+    method
+        .attrs
+        .insert(0, syn::parse_quote! { #[cfg(feature = #cfg_feature)] });
+
     Ok(())
 }
 
+/// Returns the feature given by `cfg_feature` in the `#[wasm_bindgen]`
+/// attributes of an item without consuming them, as they're parsed again when
+/// the item itself is expanded.
+fn method_cfg_feature(attrs: &[syn::Attribute]) -> Result<Option<String>, Diagnostic> {
+    for attr in attrs {
+        if !attr.path.is_ident("wasm_bindgen") {
+            continue;
+        }
+        let group = match attr.tokens.clone().into_iter().next() {
+            Some(TokenTree::Group(group)) => group,
+            _ => continue,
+        };
+        let parsed = syn::parse::Parser::parse2(
+            syn::punctuated::Punctuated::<BindgenAttr, Token![,]>::parse_terminated,
+            group.stream(),
+        )?;
+        for attr in parsed {
+            if let BindgenAttr::CfgFeature(_, feature, _) = attr {
+                return Ok(Some(feature));
+            }
+        }
+    }
+    Ok(None)
+}
+
 impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemMethod {
     fn macro_parse(
         self,
@@ -951,7 +1468,7 @@ impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemMethod {
 
         let opts = BindgenAttrs::find(&mut self.attrs)?;
         let comments = extract_doc_comments(&self.attrs);
-        let (function, method_self) = function_from_decl(
+        let (mut function, method_self) = function_from_decl(
             &self.sig.ident,
             &opts,
             self.sig.clone(),
@@ -960,6 +1477,144 @@ impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemMethod {
             true,
             Some(class),
         )?;
+        treat_impl_future_as_async(&mut function);
+        treat_impl_iterator_as_generator(&mut function);
+        if let Some(span) = opts.to_json() {
+            match method_self {
+                Some(ast::MethodSelf::RefShared) | Some(ast::MethodSelf::RefMutable)
+                    if opts.constructor().is_none() => {}
+                _ => bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(to_json)] can only be used on methods taking `&self`"
+                ),
+            }
+            if opts.js_name().is_some() {
+                bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(to_json)] cannot be combined with `js_name`"
+                );
+            }
+            if let ast::OperationKind::Regular = operation_kind(&opts) {
+            } else {
+                bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(to_json)] cannot be used on getters, setters, or indexers"
+                );
+            }
+            if function.arguments.len() > 0 {
+                bail_span!(
+                    function.arguments[0],
+                    "#[wasm_bindgen(to_json)] methods cannot take arguments"
+                );
+            }
+            function.name = "toJSON".to_string();
+            function.name_span = *span;
+            function.renamed_via_js_name = true;
+        }
+        if let Some(span) = opts.async_destructor() {
+            match method_self {
+                Some(ast::MethodSelf::ByValue) if opts.constructor().is_none() => {}
+                _ => bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(async_destructor)] can only be used on methods taking `self`"
+                ),
+            }
+            if opts.js_name().is_some() || opts.to_json().is_some() {
+                bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(async_destructor)] cannot be combined with `js_name` \
+                     or `to_json`"
+                );
+            }
+            if let ast::OperationKind::Regular = operation_kind(&opts) {
+            } else {
+                bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(async_destructor)] cannot be used on getters, setters, \
+                     or indexers"
+                );
+            }
+            if !function.arguments.is_empty() {
+                bail_span!(
+                    function.arguments[0],
+                    "#[wasm_bindgen(async_destructor)] methods cannot take arguments"
+                );
+            }
+            if !function.r#async || !returns_unit(&function) {
+                bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(async_destructor)] methods must be `async` and return \
+                     `()` or `Result<(), E>`"
+                );
+            }
+            function.name = "dispose".to_string();
+            function.name_span = *span;
+            function.renamed_via_js_name = true;
+        }
+        let iterator = opts.iterator().is_some();
+        if iterator {
+            match method_self {
+                Some(ast::MethodSelf::RefShared) | Some(ast::MethodSelf::RefMutable)
+                    if opts.constructor().is_none() => {}
+                _ => bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(iterator)] can only be used on methods taking \
+                     `&self` or `&mut self`"
+                ),
+            }
+            if let ast::OperationKind::Regular = operation_kind(&opts) {
+            } else {
+                bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(iterator)] cannot be used on getters, setters, or indexers"
+                );
+            }
+            if function.arguments.len() > 0 {
+                bail_span!(
+                    function.arguments[0],
+                    "#[wasm_bindgen(iterator)] methods cannot take arguments"
+                );
+            }
+            if function.r#async || !returns_option(&function) {
+                bail_span!(
+                    self.sig.output,
+                    "#[wasm_bindgen(iterator)] methods must return an `Option`"
+                );
+            }
+        }
+        let observed_attribute = match opts.observed_attribute() {
+            Some((name, _)) => {
+                match method_self {
+                    Some(ast::MethodSelf::RefShared) | Some(ast::MethodSelf::RefMutable)
+                        if opts.constructor().is_none() => {}
+                    _ => bail_span!(
+                        self.sig.ident,
+                        "#[wasm_bindgen(observed_attribute)] can only be used on methods \
+                         taking `&self` or `&mut self`"
+                    ),
+                }
+                if let ast::OperationKind::Regular = operation_kind(&opts) {
+                } else {
+                    bail_span!(
+                        self.sig.ident,
+                        "#[wasm_bindgen(observed_attribute)] cannot be used on getters, \
+                         setters, or indexers"
+                    );
+                }
+                Some(name.to_string())
+            }
+            None => None,
+        };
+        let cached = opts.cached().is_some();
+        if cached {
+            match operation_kind(&opts) {
+                ast::OperationKind::Getter(_) if method_self.is_some() => {}
+                _ => bail_span!(
+                    self.sig.ident,
+                    "#[wasm_bindgen(cached)] can only be used on getters"
+                ),
+            }
+        }
         let method_kind = if opts.constructor().is_some() {
             ast::MethodKind::Constructor
         } else {
@@ -971,19 +1626,26 @@ impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemMethod {
             comments,
             function,
             js_class: Some(js_class.to_string()),
+            js_namespace: None,
             method_kind,
             method_self,
             rust_class: Some(class.clone()),
             rust_name: self.sig.ident.clone(),
             start: false,
+            iterator,
+            cached,
+            observed_attribute,
+            stability: stability(&opts)?,
+            no_copy: opts.no_copy().is_some(),
+            cfg_feature: opts.cfg_feature().map(|s| s.0.to_string()),
         });
         opts.check_used()?;
         Ok(())
     }
 }
 
-impl MacroParse<()> for syn::ItemEnum {
-    fn macro_parse(self, program: &mut ast::Program, (): ()) -> Result<(), Diagnostic> {
+impl MacroParse<BindgenAttrs> for syn::ItemEnum {
+    fn macro_parse(self, program: &mut ast::Program, opts: BindgenAttrs) -> Result<(), Diagnostic> {
         match self.vis {
             syn::Visibility::Public(_) => {}
             _ => bail_span!(self, "only public enums are allowed with #[wasm_bindgen]"),
@@ -1064,22 +1726,45 @@ impl MacroParse<()> for syn::ItemEnum {
             assert!(hole != value);
         }
 
+        let repr = match opts.js_repr() {
+            None => ast::EnumRepr::Number,
+            Some(repr) => match repr.to_string().as_str() {
+                "number" => ast::EnumRepr::Number,
+                "string" => ast::EnumRepr::String,
+                "both" => ast::EnumRepr::Both,
+                _ => bail_span!(
+                    repr,
+                    "`js_repr` must be one of `number`, `string`, or `both`"
+                ),
+            },
+        };
+
         let comments = extract_doc_comments(&self.attrs);
         program.enums.push(ast::Enum {
             name: self.ident,
             variants,
             comments,
             hole,
+            cfg_feature: opts.cfg_feature().map(|s| s.0.to_string()),
+            repr,
         });
+        opts.check_used()?;
         Ok(())
     }
 }
 
-impl MacroParse<BindgenAttrs> for syn::ItemConst {
-    fn macro_parse(self, program: &mut ast::Program, opts: BindgenAttrs) -> Result<(), Diagnostic> {
-        // Shortcut
+/// A `#[wasm_bindgen]` constant is exported to JS with the same value, which
+/// must be a literal. With `#[wasm_bindgen(env = "VAR")]` the value of the
+/// environment variable `VAR` at compile time, if it's set, replaces the
+/// string literal in both Rust and JS.
+impl<'a> MacroParse<(BindgenAttrs, &'a mut TokenStream)> for syn::ItemConst {
+    fn macro_parse(
+        self,
+        program: &mut ast::Program,
+        (opts, tokens): (BindgenAttrs, &'a mut TokenStream),
+    ) -> Result<(), Diagnostic> {
         if opts.typescript_custom_section().is_none() {
-            bail_span!(self, "#[wasm_bindgen] will not work on constants unless you are defining a #[wasm_bindgen(typescript_custom_section)].");
+            return export_const(self, program, opts, tokens);
         }
 
         match *self.expr {
@@ -1100,6 +1785,210 @@ impl MacroParse<BindgenAttrs> for syn::ItemConst {
     }
 }
 
+fn export_const(
+    mut c: syn::ItemConst,
+    program: &mut ast::Program,
+    opts: BindgenAttrs,
+    tokens: &mut TokenStream,
+) -> Result<(), Diagnostic> {
+    if let Some((var, span)) = opts.env() {
+        let default = match &mut *c.expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => s,
+            _ => bail_span!(
+                c.expr,
+                "constants with #[wasm_bindgen(env)] must have a string literal as their \
+                 default value"
+            ),
+        };
+        if let Ok(value) = std::env::var(var) {
+            *default = syn::LitStr::new(&value, default.span());
+        }
+        // Make rustc aware that the constant depends on the variable, so
+        // it's recompiled when the variable changes.
+        let var = syn::LitStr::new(var, span);
+        tokens.extend(quote::quote! {
+            const _: Option<&str> = option_env!(#var);
+        });
+    }
+
+    let value = match &*c.expr {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => const_value(lit, false),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            syn::Expr::Lit(syn::ExprLit { lit, .. }) => const_value(lit, true),
+            _ => None,
+        },
+        _ => None,
+    };
+    let value = match value {
+        Some(value) => value,
+        None => bail_span!(
+            c.expr,
+            "constants exported with #[wasm_bindgen] must have a string, boolean or \
+             number literal as their value"
+        ),
+    };
+    let js_name = opts
+        .js_name()
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| c.ident.to_string());
+    program.exported_consts.push(ast::ExportedConst {
+        js_name,
+        value,
+        comments: extract_doc_comments(&c.attrs),
+    });
+    opts.check_used()?;
+
+    c.to_tokens(tokens);
+    Ok(())
+}
+
+/// Returns the JS value of a literal, which is negated if `neg` is set.
+fn const_value(lit: &syn::Lit, neg: bool) -> Option<ast::ExportedConstValue> {
+    let sign = if neg { "-" } else { "" };
+    Some(match lit {
+        syn::Lit::Str(s) if !neg => ast::ExportedConstValue::String(s.value()),
+        syn::Lit::Bool(b) if !neg => ast::ExportedConstValue::Boolean(b.value),
+        syn::Lit::Int(i) => {
+            ast::ExportedConstValue::Number(format!("{}{}", sign, i.base10_digits()))
+        }
+        syn::Lit::Float(f) => {
+            ast::ExportedConstValue::Number(format!("{}{}", sign, f.base10_digits()))
+        }
+        _ => return None,
+    })
+}
+
+/// A `#[wasm_bindgen]` trait generates an imported type, `{Trait}JsImpl`,
+/// wrapping an arbitrary JS object along with an implementation of the trait
+/// for that type which calls the object's methods of the same name. This lets
+/// JS provide implementations of Rust traits consumed by generic Rust code.
+impl<'a> MacroParse<(BindgenAttrs, &'a mut TokenStream)> for syn::ItemTrait {
+    fn macro_parse(
+        mut self,
+        program: &mut ast::Program,
+        (opts, tokens): (BindgenAttrs, &'a mut TokenStream),
+    ) -> Result<(), Diagnostic> {
+        if self.generics.params.len() > 0 {
+            bail_span!(
+                self.generics,
+                "#[wasm_bindgen] traits cannot have generic parameters",
+            );
+        }
+        let trait_name = self.ident.clone();
+        let js_impl = Ident::new(&format!("{}JsImpl", trait_name), trait_name.span());
+        let vis = self.vis.clone();
+
+        let mut errors = Vec::new();
+        let mut imports = Vec::new();
+        let mut methods = Vec::new();
+        for item in self.items.iter_mut() {
+            let method = match item {
+                syn::TraitItem::Method(m) => m,
+                other => {
+                    errors.push(err_span!(
+                        other,
+                        "only methods can appear in a #[wasm_bindgen] trait"
+                    ));
+                    continue;
+                }
+            };
+            let method_opts = match BindgenAttrs::find(&mut method.attrs) {
+                Ok(opts) => opts,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            let sig = &method.sig;
+            if sig.generics.params.len() > 0 {
+                errors.push(err_span!(
+                    sig.generics,
+                    "methods of #[wasm_bindgen] traits cannot have generic parameters"
+                ));
+                continue;
+            }
+            if let Some(asyncness) = &sig.asyncness {
+                errors.push(err_span!(
+                    asyncness,
+                    "methods of #[wasm_bindgen] traits cannot be `async`"
+                ));
+                continue;
+            }
+            match sig.inputs.first() {
+                Some(syn::FnArg::Receiver(r)) if r.reference.is_some() => {}
+                _ => {
+                    errors.push(err_span!(
+                        sig,
+                        "methods of #[wasm_bindgen] traits must take `&self` \
+                         or `&mut self`"
+                    ));
+                    continue;
+                }
+            }
+
+            let js_name = method_opts
+                .js_name()
+                .map(|p| p.0.to_string())
+                .unwrap_or_else(|| sig.ident.to_string());
+            let shim = Ident::new(&format!("__wbg_{}", sig.ident), sig.ident.span());
+            let mut impl_sig = sig.clone();
+            let mut names = Vec::new();
+            let mut tys = Vec::new();
+            for (i, arg) in impl_sig.inputs.iter_mut().skip(1).enumerate() {
+                if let syn::FnArg::Typed(arg) = arg {
+                    let name = Ident::new(&format!("arg{}", i), Span::call_site());
+                    arg.pat = Box::new(syn::parse_quote! { #name });
+                    names.push(name);
+                    tys.push(arg.ty.clone());
+                }
+            }
+            let output = &sig.output;
+            imports.push(quote::quote! {
+                #[wasm_bindgen(method, structural, js_name = #js_name)]
+                fn #shim(this: &#js_impl, #(#names: #tys),*) #output;
+            });
+            methods.push(quote::quote! {
+                #impl_sig {
+                    self.#shim(#(#names),*)
+                }
+            });
+            method_opts.check_used()?;
+        }
+        Diagnostic::from_vec(errors)?;
+        opts.check_used()?;
+
+        let doc = format!(
+            "A JS object implementing `{}` by calling its methods of the same name.",
+            trait_name,
+        );
+        let foreign: syn::ItemForeignMod = syn::parse_quote! {
+            extern "C" {
+                #[doc = #doc]
+                #[wasm_bindgen(is_type_of = wasm_bindgen::JsValue::is_object)]
+                #vis type #js_impl;
+                #(#imports)*
+            }
+        };
+        foreign.macro_parse(program, BindgenAttrs::default())?;
+
+        self.to_tokens(tokens);
+        (quote::quote! {
+            impl #trait_name for #js_impl {
+                #(#methods)*
+            }
+        })
+        .to_tokens(tokens);
+        Ok(())
+    }
+}
+
 impl MacroParse<BindgenAttrs> for syn::ItemForeignMod {
     fn macro_parse(self, program: &mut ast::Program, opts: BindgenAttrs) -> Result<(), Diagnostic> {
         let mut errors = Vec::new();
@@ -1136,8 +2025,10 @@ impl MacroParse<BindgenAttrs> for syn::ItemForeignMod {
         } else {
             ast::ImportModule::None
         };
+        let extension_trait = opts.extension_trait().cloned();
         for item in self.items.into_iter() {
-            if let Err(e) = item.macro_parse(program, module.clone()) {
+            let ctx = (module.clone(), extension_trait.clone(), &opts);
+            if let Err(e) = item.macro_parse(program, ctx) {
                 errors.push(e);
             }
         }
@@ -1147,11 +2038,11 @@ impl MacroParse<BindgenAttrs> for syn::ItemForeignMod {
     }
 }
 
-impl MacroParse<ast::ImportModule> for syn::ForeignItem {
+impl<'a> MacroParse<(ast::ImportModule, Option<Ident>, &'a BindgenAttrs)> for syn::ForeignItem {
     fn macro_parse(
         mut self,
         program: &mut ast::Program,
-        module: ast::ImportModule,
+        (module, extension_trait, block_opts): (ast::ImportModule, Option<Ident>, &'a BindgenAttrs),
     ) -> Result<(), Diagnostic> {
         let item_opts = {
             let attrs = match self {
@@ -1160,16 +2051,33 @@ impl MacroParse<ast::ImportModule> for syn::ForeignItem {
                 syn::ForeignItem::Static(ref mut s) => &mut s.attrs,
                 _ => panic!("only foreign functions/types allowed for now"),
             };
-            BindgenAttrs::find(attrs)?
+            let mut item_opts = BindgenAttrs::find(attrs)?;
+            item_opts.inherit(block_opts, true);
+            item_opts
         };
         let js_namespace = item_opts.js_namespace().cloned();
-        let kind = match self {
+        let mut kind = match self {
             syn::ForeignItem::Fn(f) => f.convert((item_opts, &module))?,
             syn::ForeignItem::Type(t) => t.convert(item_opts)?,
             syn::ForeignItem::Static(s) => s.convert((item_opts, &module))?,
             _ => panic!("only foreign functions/types allowed for now"),
         };
 
+        // An `extension_trait` on the whole `extern` block applies to all of
+        // its methods, unless they name their own.
+        if let (Some(name), ast::ImportKind::Function(f)) = (extension_trait, &mut kind) {
+            if f.extension_trait.is_none() {
+                if let ast::ImportFunctionKind::Normal = f.kind {
+                    bail_span!(
+                        f.rust_name,
+                        "all functions in an `extern` block with an \
+                         `extension_trait` must be methods of imported types"
+                    );
+                }
+                f.extension_trait = Some(name);
+            }
+        }
+
         program.imports.push(ast::Import {
             module,
             js_namespace,
@@ -1216,6 +2124,77 @@ fn extract_first_ty_param(ty: Option<&syn::Type>) -> Result<Option<syn::Type>, D
     Ok(Some(ty.clone()))
 }
 
+/// Get the error type of a `Result` returned from a `catch` import, returning
+/// `None` if it's `JsValue` (or can't be found) as any exception is caught.
+fn extract_catch_ty(ty: Option<&syn::Type>) -> Option<syn::Type> {
+    let path = match ty? {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+        _ => return None,
+    };
+    let generics = match &path.segments.last()?.arguments {
+        syn::PathArguments::AngleBracketed(t) => t,
+        _ => return None,
+    };
+    let ty = match generics.args.iter().nth(1)? {
+        syn::GenericArgument::Type(t) => t,
+        _ => return None,
+    };
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path })
+            if path.segments.last()?.ident == "JsValue" =>
+        {
+            None
+        }
+        _ => Some(ty.clone()),
+    }
+}
+
+/// Determines how a public struct field is accessed from JS, recognizing the
+/// `Option<Box<T>>` and `Rc<RefCell<T>>` smart pointer types and `Vec<T>`
+/// fields marked with `typed_array`.
+fn struct_field_kind(
+    ty: &syn::Type,
+    typed_array: Option<&Ident>,
+) -> Result<ast::StructFieldKind, Diagnostic> {
+    fn single_generic<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type> {
+        let path = match ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+            _ => return None,
+        };
+        let segment = path.segments.last()?;
+        if segment.ident != name {
+            return None;
+        }
+        let generics = match &segment.arguments {
+            syn::PathArguments::AngleBracketed(t) if t.args.len() == 1 => t,
+            _ => return None,
+        };
+        match &generics.args[0] {
+            syn::GenericArgument::Type(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    if let Some(access) = typed_array {
+        let elem = match single_generic(ty, "Vec") {
+            Some(elem) => elem.clone(),
+            None => bail_span!(ty, "#[wasm_bindgen(typed_array)] fields must be a `Vec<T>`"),
+        };
+        return match access.to_string().as_str() {
+            "view" => Ok(ast::StructFieldKind::TypedArrayView(elem)),
+            "copy" => Ok(ast::StructFieldKind::TypedArrayCopy(elem)),
+            _ => bail_span!(access, "`typed_array` must be one of `view` or `copy`"),
+        };
+    }
+    if let Some(inner) = single_generic(ty, "Option").and_then(|t| single_generic(t, "Box")) {
+        return Ok(ast::StructFieldKind::OptionBox(inner.clone()));
+    }
+    if let Some(inner) = single_generic(ty, "Rc").and_then(|t| single_generic(t, "RefCell")) {
+        return Ok(ast::StructFieldKind::RcRefCell(inner.clone()));
+    }
+    Ok(ast::StructFieldKind::Copy)
+}
+
 /// Extract the documentation comments from a Vec of attributes
 fn extract_doc_comments(attrs: &[syn::Attribute]) -> Vec<String> {
     attrs
@@ -1247,22 +2226,42 @@ fn extract_doc_comments(attrs: &[syn::Attribute]) -> Vec<String> {
 }
 
 /// Check there are no lifetimes on the function.
+///
+/// Lifetimes bound by a `for<'a>` within a trait bound, like in
+/// `&mut dyn for<'a> StackFnOnce<(&'a str,), R>`, are allowed since they don't
+/// refer to anything outside of the type.
 fn assert_no_lifetimes(sig: &syn::Signature) -> Result<(), Diagnostic> {
     struct Walk {
         diagnostics: Vec<Diagnostic>,
+        bound: Vec<Ident>,
     }
 
     impl<'ast> syn::visit::Visit<'ast> for Walk {
         fn visit_lifetime(&mut self, i: &'ast syn::Lifetime) {
+            if self.bound.contains(&i.ident) {
+                return;
+            }
             self.diagnostics.push(err_span!(
                 &*i,
                 "it is currently not sound to use lifetimes in function \
                  signatures"
             ));
         }
+
+        fn visit_trait_bound(&mut self, i: &'ast syn::TraitBound) {
+            let len = self.bound.len();
+            if let Some(lifetimes) = &i.lifetimes {
+                for def in lifetimes.lifetimes.iter() {
+                    self.bound.push(def.lifetime.ident.clone());
+                }
+            }
+            syn::visit::visit_path(self, &i.path);
+            self.bound.truncate(len);
+        }
     }
     let mut walk = Walk {
         diagnostics: Vec::new(),
+        bound: Vec::new(),
     };
     syn::visit::Visit::visit_signature(&mut walk, sig);
     Diagnostic::from_vec(walk.diagnostics)
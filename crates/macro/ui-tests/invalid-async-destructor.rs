@@ -0,0 +1,21 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct A;
+
+#[wasm_bindgen]
+impl A {
+    #[wasm_bindgen(async_destructor)]
+    pub async fn a(&self) {}
+
+    #[wasm_bindgen(async_destructor)]
+    pub async fn b(self, x: u32) {}
+
+    #[wasm_bindgen(async_destructor)]
+    pub fn c(self) {}
+
+    #[wasm_bindgen(async_destructor)]
+    pub async fn d(self) -> u32 { 1 }
+}
+
+fn main() {}
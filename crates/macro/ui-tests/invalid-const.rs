@@ -0,0 +1,12 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub const NOT_LITERAL: u32 = 1 + 2;
+
+#[wasm_bindgen]
+pub const NOT_SUPPORTED: char = 'a';
+
+#[wasm_bindgen(env = "WASM_BINDGEN_UI_TEST_UNSET")]
+pub const NOT_STRING: u32 = 3;
+
+fn main() {}
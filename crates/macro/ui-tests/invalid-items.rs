@@ -31,6 +31,6 @@ pub fn foo5<'a>() {}
 pub fn foo6<'a, T>() {}
 
 #[wasm_bindgen]
-trait X {}
+trait X<T> {}
 
 fn main() {}
@@ -0,0 +1,18 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct A;
+
+#[wasm_bindgen]
+impl A {
+    #[wasm_bindgen(iterator)]
+    pub fn a() -> Option<u32> { None }
+
+    #[wasm_bindgen(iterator)]
+    pub fn b(&mut self, x: u32) -> Option<u32> { Some(x) }
+
+    #[wasm_bindgen(iterator)]
+    pub fn c(&mut self) -> u32 { 1 }
+}
+
+fn main() {}
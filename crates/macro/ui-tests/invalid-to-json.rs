@@ -0,0 +1,21 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct A;
+
+#[wasm_bindgen]
+impl A {
+    #[wasm_bindgen(to_json)]
+    pub fn a() -> u32 { 1 }
+
+    #[wasm_bindgen(to_json)]
+    pub fn b(&self, x: u32) -> u32 { x }
+
+    #[wasm_bindgen(to_json, js_name = foo)]
+    pub fn c(&self) -> u32 { 1 }
+
+    #[wasm_bindgen(to_json)]
+    pub fn d(self) -> u32 { 1 }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(unstable, deprecated)]
+pub fn a() {}
+
+#[wasm_bindgen]
+pub struct B;
+
+#[wasm_bindgen]
+impl B {
+    #[wasm_bindgen(deprecated = "use `c` instead", unstable)]
+    pub fn b(&self) {}
+}
+
+fn main() {}
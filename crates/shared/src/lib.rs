@@ -18,6 +18,9 @@ macro_rules! shared_api {
             inline_js: Vec<&'a str>,
             unique_crate_identifier: &'a str,
             package_json: Option<&'a str>,
+            linked_modules: Vec<LinkedModule<'a>>,
+            js_preludes: Vec<&'a str>,
+            consts: Vec<Const<'a>>,
         }
 
         struct Import<'a> {
@@ -89,17 +92,39 @@ macro_rules! shared_api {
 
         struct Export<'a> {
             class: Option<&'a str>,
+            js_namespace: Option<&'a str>,
             comments: Vec<&'a str>,
+            rust_name: &'a str,
             consumed: bool,
             function: Function<'a>,
             method_kind: MethodKind<'a>,
             start: bool,
+            iterator: bool,
+            cached: bool,
+            observed_attribute: Option<&'a str>,
+            stability: Stability<'a>,
+            no_copy: bool,
+            cfg_feature: Option<&'a str>,
+        }
+
+        enum Stability<'a> {
+            Stable,
+            Unstable,
+            Deprecated(Option<&'a str>),
         }
 
         struct Enum<'a> {
             name: &'a str,
             variants: Vec<EnumVariant<'a>>,
             comments: Vec<&'a str>,
+            cfg_feature: Option<&'a str>,
+            repr: EnumRepr,
+        }
+
+        enum EnumRepr {
+            Number,
+            String,
+            Both,
         }
 
         struct EnumVariant<'a> {
@@ -107,9 +132,22 @@ macro_rules! shared_api {
             value: u32,
         }
 
+        struct Const<'a> {
+            name: &'a str,
+            value: ConstValue<'a>,
+            comments: Vec<&'a str>,
+        }
+
+        enum ConstValue<'a> {
+            String(&'a str),
+            Boolean(bool),
+            Number(&'a str),
+        }
+
         struct Function<'a> {
             arg_names: Vec<String>,
             name: &'a str,
+            renamed_via_js_name: bool,
         }
 
         struct Struct<'a> {
@@ -117,11 +155,15 @@ macro_rules! shared_api {
             fields: Vec<StructField<'a>>,
             comments: Vec<&'a str>,
             is_inspectable: bool,
+            cfg_feature: Option<&'a str>,
+            extends_js: Option<&'a str>,
+            custom_element: Option<&'a str>,
         }
 
         struct StructField<'a> {
             name: &'a str,
             readonly: bool,
+            cached: bool,
             comments: Vec<&'a str>,
         }
 
@@ -129,6 +171,11 @@ macro_rules! shared_api {
             identifier: &'a str,
             contents: &'a str,
         }
+
+        struct LinkedModule<'a> {
+            identifier: &'a str,
+            link_function_name: &'a str,
+        }
         }
     }; // end of mac case
 } // end of mac definition
@@ -140,6 +187,13 @@ pub fn new_function(struct_name: &str) -> String {
     return name;
 }
 
+pub fn instanceof_function(struct_name: &str) -> String {
+    let mut name = format!("__wbg_");
+    name.extend(struct_name.chars().flat_map(|s| s.to_lowercase()));
+    name.push_str("_instanceof");
+    return name;
+}
+
 pub fn free_function(struct_name: &str) -> String {
     let mut name = format!("__wbg_");
     name.extend(struct_name.chars().flat_map(|s| s.to_lowercase()));
@@ -36,7 +36,7 @@ macro_rules! console_log {
 macro_rules! wasm_bindgen_test_configure {
     (run_in_browser $($others:tt)*) => (
         #[link_section = "__wasm_bindgen_test_unstable"]
-        #[cfg(target_arch = "wasm32")]
+        #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
         pub static __WBG_TEST_RUN_IN_BROWSER: [u8; 1] = [0x01];
         $crate::wasm_bindgen_test_configure!($($others)*);
     );
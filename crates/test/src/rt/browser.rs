@@ -30,6 +30,11 @@ extern "C" {
     type BrowserError;
     #[wasm_bindgen(method, getter, structural)]
     fn stack(this: &BrowserError) -> JsValue;
+
+    type Navigator;
+    static navigator: Navigator;
+    #[wasm_bindgen(method, getter = userAgent, structural)]
+    fn user_agent(this: &Navigator) -> String;
 }
 
 impl Browser {
@@ -78,4 +83,18 @@ impl super::Formatter for Browser {
         // Fallback to make sure we don't lose any info
         format!("{}\n{}", header, stack)
     }
+
+    fn environment(&self) -> String {
+        navigator.user_agent()
+    }
+
+    fn write_report(&self, report: &str) {
+        document.getElementById("report").set_text_content(report);
+    }
+
+    fn separate_report(&self) -> bool {
+        // Reports go into their own element, which the test runner scrapes
+        // separately from the status output.
+        true
+    }
 }
@@ -88,7 +88,7 @@
 // always of course welcome!
 
 use console_error_panic_hook;
-use js_sys::{Array, Function, Promise};
+use js_sys::{Array, Date, Function, Promise};
 use std::cell::{Cell, RefCell};
 use std::fmt;
 use std::future::Future;
@@ -109,6 +109,9 @@ const CONCURRENCY: usize = 1;
 pub mod browser;
 pub mod detect;
 pub mod node;
+mod report;
+
+use report::{Format, Outcome, Record};
 
 /// Runtime test harness support instantiated in JS.
 ///
@@ -122,10 +125,16 @@ pub struct Context {
 struct State {
     /// An optional filter used to restrict which tests are actually executed
     /// and which are ignored. This is passed via the `args` function which
-    /// comes from the command line of `wasm-bindgen-test-runner`. Currently
-    /// this is the only "CLI option"
+    /// comes from the command line of `wasm-bindgen-test-runner`.
     filter: RefCell<Option<String>>,
 
+    /// The output format requested with `--format`.
+    format: Cell<Format>,
+
+    /// Results of all finished and ignored tests, used to render reports in
+    /// formats other than `Format::Pretty`.
+    records: RefCell<Vec<Record>>,
+
     /// Counter of the number of tests that have succeeded.
     succeeded: Cell<usize>,
 
@@ -159,10 +168,12 @@ struct Test {
     name: String,
     future: Pin<Box<dyn Future<Output = Result<(), JsValue>>>>,
     output: Rc<RefCell<Output>>,
+    /// When this test was first polled, in milliseconds since the epoch.
+    started: f64,
 }
 
 /// Captured output of each test.
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct Output {
     debug: String,
     log: String,
@@ -181,6 +192,18 @@ trait Formatter {
     /// Convert a thrown value into a string, using platform-specific apis
     /// perhaps to turn the error into a string.
     fn stringify_error(&self, val: &JsValue) -> String;
+
+    /// A description of the environment the tests are running in, such as
+    /// the node.js version or the browser's user agent.
+    fn environment(&self) -> String;
+
+    /// Writes a machine-readable report requested with `--format`.
+    fn write_report(&self, report: &str);
+
+    /// Whether reports are written somewhere separate from the status output
+    /// of `writeln`, in which case status output is still written alongside
+    /// reports.
+    fn separate_report(&self) -> bool;
 }
 
 #[wasm_bindgen]
@@ -217,6 +240,8 @@ impl Context {
         Context {
             state: Rc::new(State {
                 filter: Default::default(),
+                format: Cell::new(Format::Pretty),
+                records: Default::default(),
                 failures: Default::default(),
                 ignored: Default::default(),
                 remaining: Default::default(),
@@ -230,18 +255,33 @@ impl Context {
     /// Inform this context about runtime arguments passed to the test
     /// harness.
     ///
-    /// Eventually this will be used to support flags, but for now it's just
-    /// used to support test filters.
+    /// This supports `--format` to select the output format and at most one
+    /// test filter.
     pub fn args(&mut self, args: Vec<JsValue>) {
-        // Here we want to reject all flags like `--foo` or `-f` as we don't
-        // support anything, and also we only support at most one non-flag
-        // argument as a test filter.
+        // Here we want to reject all flags like `--foo` or `-f` other than
+        // `--format`, and also we only support at most one non-flag argument
+        // as a test filter.
         //
         // Everything else is rejected.
         let mut filter = self.state.filter.borrow_mut();
-        for arg in args {
-            let arg = arg.as_string().unwrap();
-            if arg.starts_with("-") {
+        let mut args = args.into_iter().map(|arg| arg.as_string().unwrap());
+        while let Some(arg) = args.next() {
+            let format = if arg == "--format" {
+                Some(args.next().expect("`--format` requires a value"))
+            } else if arg.starts_with("--format=") {
+                Some(arg["--format=".len()..].to_string())
+            } else {
+                None
+            };
+            if let Some(format) = format {
+                match Format::parse(&format) {
+                    Some(format) => self.state.format.set(format),
+                    None => panic!(
+                        "unknown format `{}`, expected one of `pretty`, `json`, or `junit`",
+                        format
+                    ),
+                }
+            } else if arg.starts_with("-") {
                 panic!("flag {} not supported", arg);
             } else if filter.is_some() {
                 panic!("more than one filter argument cannot be passed");
@@ -262,9 +302,8 @@ impl Context {
     pub fn run(&self, tests: Vec<JsValue>) -> Promise {
         let noun = if tests.len() == 1 { "test" } else { "tests" };
         self.state
-            .formatter
             .writeln(&format!("running {} {}", tests.len(), noun));
-        self.state.formatter.writeln("");
+        self.state.writeln("");
 
         // Execute all our test functions through their wasm shims (unclear how
         // to pass native function pointers around here). Each test will
@@ -337,6 +376,28 @@ pub fn __wbgtest_console_error(args: &Array) {
     record(args, |output| &mut output.error)
 }
 
+/// Returns the LLVM coverage data collected while running tests in the
+/// `.profraw` format, or `None` if this crate wasn't built with the `coverage`
+/// feature.
+///
+/// The test runner calls this once all tests have finished and writes out the
+/// result.
+#[wasm_bindgen]
+pub fn __wbgtest_cov_dump() -> Option<Vec<u8>> {
+    #[cfg(feature = "coverage")]
+    {
+        let mut data = Vec::new();
+        unsafe {
+            minicov::capture_coverage(&mut data).expect_throw("failed to capture coverage");
+        }
+        Some(data)
+    }
+    #[cfg(not(feature = "coverage"))]
+    {
+        None
+    }
+}
+
 fn record(args: &Array, dst: impl FnOnce(&mut Output) -> &mut String) {
     if !CURRENT_OUTPUT.is_set() {
         return;
@@ -380,6 +441,12 @@ impl Context {
             if !name.contains(filter) {
                 let ignored = self.state.ignored.get();
                 self.state.ignored.set(ignored + 1);
+                self.state.records.borrow_mut().push(Record {
+                    name: name.to_string(),
+                    duration_ms: 0.0,
+                    outcome: Outcome::Ignored,
+                    output: Output::default(),
+                });
                 return;
             }
         }
@@ -395,6 +462,7 @@ impl Context {
             name: name.to_string(),
             future: Pin::from(Box::new(future)),
             output,
+            started: 0.0,
         });
     }
 }
@@ -428,6 +496,7 @@ impl Future for ExecuteTests {
                 Some(test) => test,
                 None => break,
             };
+            test.started = Date::now();
             let result = match test.future.as_mut().poll(cx) {
                 Poll::Ready(result) => result,
                 Poll::Pending => {
@@ -449,15 +518,43 @@ impl Future for ExecuteTests {
         assert_eq!(remaining.len(), 0);
 
         self.0.print_results();
+        self.0.print_report();
         let all_passed = self.0.failures.borrow().len() == 0;
         Poll::Ready(all_passed)
     }
 }
 
 impl State {
+    /// Whether human-readable status output is printed, which is skipped if
+    /// it would get mixed up with a machine-readable report.
+    fn status_visible(&self) -> bool {
+        self.format.get() == Format::Pretty || self.formatter.separate_report()
+    }
+
+    fn writeln(&self, line: &str) {
+        if self.status_visible() {
+            self.formatter.writeln(line);
+        }
+    }
+
     fn log_test_result(&self, test: Test, result: Result<(), JsValue>) {
         // Print out information about the test passing or failing
-        self.formatter.log_test(&test.name, &result);
+        if self.status_visible() {
+            self.formatter.log_test(&test.name, &result);
+        }
+
+        if self.format.get() != Format::Pretty {
+            let outcome = match &result {
+                Ok(()) => Outcome::Passed,
+                Err(e) => Outcome::Failed(self.formatter.stringify_error(e)),
+            };
+            self.records.borrow_mut().push(Record {
+                name: test.name.clone(),
+                duration_ms: Date::now() - test.started,
+                outcome,
+                output: test.output.borrow().clone(),
+            });
+        }
 
         // Save off the test for later processing when we print the final
         // results.
@@ -470,17 +567,17 @@ impl State {
     fn print_results(&self) {
         let failures = self.failures.borrow();
         if failures.len() > 0 {
-            self.formatter.writeln("\nfailures:\n");
+            self.writeln("\nfailures:\n");
             for (test, error) in failures.iter() {
                 self.print_failure(test, error);
             }
-            self.formatter.writeln("failures:\n");
+            self.writeln("failures:\n");
             for (test, _) in failures.iter() {
-                self.formatter.writeln(&format!("    {}", test.name));
+                self.writeln(&format!("    {}", test.name));
             }
         }
-        self.formatter.writeln("");
-        self.formatter.writeln(&format!(
+        self.writeln("");
+        self.writeln(&format!(
             "test result: {}. \
              {} passed; \
              {} failed; \
@@ -515,7 +612,17 @@ impl State {
         logs.push_str(&tab(&error_string));
 
         let msg = format!("---- {} output ----\n{}", test.name, tab(&logs));
-        self.formatter.writeln(&msg);
+        self.writeln(&msg);
+    }
+
+    fn print_report(&self) {
+        let format = self.format.get();
+        if format == Format::Pretty {
+            return;
+        }
+        let environment = self.formatter.environment();
+        let report = report::render(format, &environment, &self.records.borrow());
+        self.formatter.write_report(&report);
     }
 }
 
@@ -15,6 +15,15 @@ extern "C" {
     type NodeError;
     #[wasm_bindgen(method, getter, js_class = "Error", structural)]
     fn stack(this: &NodeError) -> String;
+
+    type Process;
+    static process: Process;
+    #[wasm_bindgen(method, getter, structural)]
+    fn version(this: &Process) -> String;
+    #[wasm_bindgen(method, getter, structural)]
+    fn platform(this: &Process) -> String;
+    #[wasm_bindgen(method, getter, structural)]
+    fn arch(this: &Process) -> String;
 }
 
 impl Node {
@@ -42,4 +51,22 @@ impl super::Formatter for Node {
         // TODO: should do a checked cast to `NodeError`
         NodeError::from(err.clone()).stack()
     }
+
+    fn environment(&self) -> String {
+        format!(
+            "node.js {} ({} {})",
+            process.version(),
+            process.platform(),
+            process.arch()
+        )
+    }
+
+    fn write_report(&self, report: &str) {
+        self.writeln(report);
+    }
+
+    fn separate_report(&self) -> bool {
+        // Everything goes to stdout, so only the report is printed
+        false
+    }
 }
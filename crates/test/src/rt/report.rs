@@ -0,0 +1,311 @@
+//! Machine-readable reports of a whole test run, selected with the
+//! `--format json` and `--format junit` flags of the test runner.
+//!
+//! These are meant to be ingested by CI dashboards, so unlike the human
+//! readable output they include every test's duration and captured console
+//! output, plus some information about the environment the tests ran in.
+
+use super::Output;
+use std::fmt::Write;
+
+/// The output format requested for a test run.
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum Format {
+    /// The `libtest`-like output printed as tests run.
+    Pretty,
+    /// One JSON document describing the whole run.
+    Json,
+    /// A JUnit XML report.
+    Junit,
+}
+
+impl Format {
+    pub(super) fn parse(s: &str) -> Option<Format> {
+        match s {
+            "pretty" => Some(Format::Pretty),
+            "json" => Some(Format::Json),
+            "junit" => Some(Format::Junit),
+            _ => None,
+        }
+    }
+}
+
+/// How a single test ended up.
+pub(super) enum Outcome {
+    Passed,
+    /// The test failed with the given stringified exception.
+    Failed(String),
+    /// The test was excluded by a filter.
+    Ignored,
+}
+
+/// The result of a single test, as recorded for reports.
+pub(super) struct Record {
+    pub(super) name: String,
+    pub(super) duration_ms: f64,
+    pub(super) outcome: Outcome,
+    pub(super) output: Output,
+}
+
+/// Renders `records` in the given format, which must not be `Pretty`.
+pub(super) fn render(format: Format, environment: &str, records: &[Record]) -> String {
+    match format {
+        Format::Pretty => panic!("the pretty format has no report"),
+        Format::Json => json(environment, records),
+        Format::Junit => junit(environment, records),
+    }
+}
+
+fn counts(records: &[Record]) -> (usize, usize, usize) {
+    let mut counts = (0, 0, 0);
+    for record in records {
+        match record.outcome {
+            Outcome::Passed => counts.0 += 1,
+            Outcome::Failed(_) => counts.1 += 1,
+            Outcome::Ignored => counts.2 += 1,
+        }
+    }
+    counts
+}
+
+fn json(environment: &str, records: &[Record]) -> String {
+    let (passed, failed, ignored) = counts(records);
+    let duration: f64 = records.iter().map(|r| r.duration_ms).sum();
+    let mut ret = String::new();
+    write!(
+        ret,
+        "{{\"environment\":{},\"passed\":{},\"failed\":{},\"ignored\":{},\
+         \"duration_ms\":{},\"tests\":[",
+        json_str(environment),
+        passed,
+        failed,
+        ignored,
+        duration,
+    )
+    .unwrap();
+    for (i, record) in records.iter().enumerate() {
+        if i != 0 {
+            ret.push(',');
+        }
+        let (status, error) = match &record.outcome {
+            Outcome::Passed => ("passed", None),
+            Outcome::Failed(e) => ("failed", Some(e)),
+            Outcome::Ignored => ("ignored", None),
+        };
+        write!(
+            ret,
+            "{{\"name\":{},\"status\":\"{}\",\"duration_ms\":{}",
+            json_str(&record.name),
+            status,
+            record.duration_ms,
+        )
+        .unwrap();
+        if let Some(error) = error {
+            write!(ret, ",\"error\":{}", json_str(error)).unwrap();
+        }
+        let output = &record.output;
+        write!(
+            ret,
+            ",\"output\":{{\"debug\":{},\"log\":{},\"info\":{},\"warn\":{},\"error\":{}}}}}",
+            json_str(&output.debug),
+            json_str(&output.log),
+            json_str(&output.info),
+            json_str(&output.warn),
+            json_str(&output.error),
+        )
+        .unwrap();
+    }
+    ret.push_str("]}");
+    ret
+}
+
+fn json_str(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len() + 2);
+    ret.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(ret, "\\u{:04x}", c as u32).unwrap(),
+            c => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+fn junit(environment: &str, records: &[Record]) -> String {
+    let (_, failed, ignored) = counts(records);
+    let duration: f64 = records.iter().map(|r| r.duration_ms).sum();
+    let mut ret = String::new();
+    ret.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    writeln!(
+        ret,
+        "  <testsuite name=\"wasm-bindgen-test\" tests=\"{}\" failures=\"{}\" \
+         skipped=\"{}\" time=\"{}\">",
+        records.len(),
+        failed,
+        ignored,
+        duration / 1000.0,
+    )
+    .unwrap();
+    writeln!(
+        ret,
+        "    <properties>\n      <property name=\"environment\" value=\"{}\"/>\n    \
+         </properties>",
+        xml_escape(environment),
+    )
+    .unwrap();
+    for record in records {
+        let (classname, name) = match record.name.rfind("::") {
+            Some(i) => (&record.name[..i], &record.name[i + 2..]),
+            None => ("", &record.name[..]),
+        };
+        writeln!(
+            ret,
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\">",
+            xml_escape(classname),
+            xml_escape(name),
+            record.duration_ms / 1000.0,
+        )
+        .unwrap();
+        match &record.outcome {
+            Outcome::Passed => {}
+            Outcome::Failed(error) => {
+                let message = error.lines().next().unwrap_or("");
+                writeln!(
+                    ret,
+                    "      <failure message=\"{}\">{}</failure>",
+                    xml_escape(message),
+                    xml_escape(error),
+                )
+                .unwrap();
+            }
+            Outcome::Ignored => ret.push_str("      <skipped/>\n"),
+        }
+        let output = &record.output;
+        let stdout = format!("{}{}{}", output.debug, output.log, output.info);
+        let stderr = format!("{}{}", output.warn, output.error);
+        if !stdout.is_empty() {
+            writeln!(
+                ret,
+                "      <system-out>{}</system-out>",
+                xml_escape(&stdout)
+            )
+            .unwrap();
+        }
+        if !stderr.is_empty() {
+            writeln!(
+                ret,
+                "      <system-err>{}</system-err>",
+                xml_escape(&stderr)
+            )
+            .unwrap();
+        }
+        ret.push_str("    </testcase>\n");
+    }
+    ret.push_str("  </testsuite>\n</testsuites>");
+    ret
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&apos;"),
+            '\n' | '\r' | '\t' => ret.push(c),
+            // Other control characters aren't allowed in XML 1.0 at all
+            c if (c as u32) < 0x20 => {}
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                name: "crate::passes".to_string(),
+                duration_ms: 1.5,
+                outcome: Outcome::Passed,
+                output: Output {
+                    log: "hello \"world\"\n".to_string(),
+                    ..Output::default()
+                },
+            },
+            Record {
+                name: "crate::fails".to_string(),
+                duration_ms: 2.0,
+                outcome: Outcome::Failed("Error: 1 < 2\n    at foo".to_string()),
+                output: Output {
+                    warn: "careful\n".to_string(),
+                    ..Output::default()
+                },
+            },
+            Record {
+                name: "ignored".to_string(),
+                duration_ms: 0.0,
+                outcome: Outcome::Ignored,
+                output: Output::default(),
+            },
+        ]
+    }
+
+    #[test]
+    fn json_report() {
+        assert_eq!(
+            render(Format::Json, "node \"14\"", &records()),
+            "{\"environment\":\"node \\\"14\\\"\",\"passed\":1,\"failed\":1,\"ignored\":1,\
+             \"duration_ms\":3.5,\"tests\":[\
+             {\"name\":\"crate::passes\",\"status\":\"passed\",\"duration_ms\":1.5,\
+             \"output\":{\"debug\":\"\",\"log\":\"hello \\\"world\\\"\\n\",\"info\":\"\",\
+             \"warn\":\"\",\"error\":\"\"}},\
+             {\"name\":\"crate::fails\",\"status\":\"failed\",\"duration_ms\":2,\
+             \"error\":\"Error: 1 < 2\\n    at foo\",\
+             \"output\":{\"debug\":\"\",\"log\":\"\",\"info\":\"\",\
+             \"warn\":\"careful\\n\",\"error\":\"\"}},\
+             {\"name\":\"ignored\",\"status\":\"ignored\",\"duration_ms\":0,\
+             \"output\":{\"debug\":\"\",\"log\":\"\",\"info\":\"\",\"warn\":\"\",\"error\":\"\"}}\
+             ]}"
+        );
+    }
+
+    #[test]
+    fn junit_report() {
+        assert_eq!(
+            render(Format::Junit, "node \"14\"", &records()),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites>
+  <testsuite name=\"wasm-bindgen-test\" tests=\"3\" failures=\"1\" skipped=\"1\" time=\"0.0035\">
+    <properties>
+      <property name=\"environment\" value=\"node &quot;14&quot;\"/>
+    </properties>
+    <testcase classname=\"crate\" name=\"passes\" time=\"0.0015\">
+      <system-out>hello &quot;world&quot;
+</system-out>
+    </testcase>
+    <testcase classname=\"crate\" name=\"fails\" time=\"0.002\">
+      <failure message=\"Error: 1 &lt; 2\">Error: 1 &lt; 2
+    at foo</failure>
+      <system-err>careful
+</system-err>
+    </testcase>
+    <testcase classname=\"\" name=\"ignored\" time=\"0\">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>"
+        );
+    }
+}
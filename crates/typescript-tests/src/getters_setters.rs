@@ -66,3 +66,25 @@ impl ColorWithGetterAndSetter {
         };
     }
 }
+
+#[wasm_bindgen]
+pub struct ColorWithOptionalAlpha {
+    a: Option<u8>,
+}
+
+#[wasm_bindgen]
+impl ColorWithOptionalAlpha {
+    #[wasm_bindgen(getter)]
+    pub fn a(&self) -> Option<u8> {
+        self.a
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_a(&mut self, a: Option<u8>) -> Result<(), JsValue> {
+        if a == Some(0) {
+            return Err("alpha must be positive".into());
+        }
+        self.a = a;
+        Ok(())
+    }
+}
@@ -9,3 +9,8 @@ colorWithSetter.r = 1;
 const colorWithGetterAndSetter: wbg.ColorWithGetterAndSetter = new wbg.ColorWithGetterAndSetter;
 colorWithGetterAndSetter.r = 1;
 const _b = colorWithGetterAndSetter.r;
+
+const colorWithOptionalAlpha: wbg.ColorWithOptionalAlpha = new wbg.ColorWithOptionalAlpha;
+colorWithOptionalAlpha.a = undefined;
+colorWithOptionalAlpha.a = 128;
+const _c: number | undefined = colorWithOptionalAlpha.a;
@@ -0,0 +1,20 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct Base {}
+
+#[wasm_bindgen]
+impl Base {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Base {
+        Base {}
+    }
+}
+
+#[wasm_bindgen]
+pub fn takes_js_value(_a: &JsValue, _b: JsValue, _c: Option<JsValue>) -> bool {
+    true
+}
+
+#[wasm_bindgen]
+pub fn takes_base(_a: &Base) {}
@@ -0,0 +1,10 @@
+import * as wbg from '../pkg/typescript_tests';
+
+// `JsValue` parameters accept anything without casts.
+const takes_js_value: (a: any, b: any, c?: any) => boolean = wbg.takes_js_value;
+takes_js_value(1, 'two', { three: 3 });
+takes_js_value(new Map(), null);
+
+// Exported classes accept instances of JS subclasses.
+class Derived extends wbg.Base {}
+wbg.takes_base(new Derived());
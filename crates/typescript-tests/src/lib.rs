@@ -1,5 +1,7 @@
 pub mod custom_section;
 pub mod getters_setters;
+pub mod js_value;
 pub mod opt_args_and_ret;
 pub mod simple_fn;
 pub mod simple_struct;
+pub mod unit_returns;
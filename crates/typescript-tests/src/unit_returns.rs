@@ -0,0 +1,32 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub fn unit_ret() {}
+
+#[wasm_bindgen]
+pub fn unit_result_ret() -> Result<(), JsValue> {
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub async fn async_unit_ret() {}
+
+#[wasm_bindgen]
+pub async fn async_unit_result_ret() -> Result<(), JsValue> {
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub async fn async_value_ret() -> Result<u32, JsValue> {
+    Ok(1)
+}
+
+#[wasm_bindgen]
+pub struct UnitReturns;
+
+#[wasm_bindgen]
+impl UnitReturns {
+    pub fn method(&self) {}
+
+    pub async fn async_static() {}
+}
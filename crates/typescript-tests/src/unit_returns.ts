@@ -0,0 +1,15 @@
+import * as wbg from '../pkg/typescript_tests';
+
+// Only `true` if `A` and `B` are exactly the same type, so `any` doesn't
+// match anything else.
+type Equals<A, B> = (<T>() => T extends A ? 1 : 2) extends (<T>() => T extends B ? 1 : 2)
+    ? true
+    : false;
+
+const unit_ret: Equals<typeof wbg.unit_ret, () => void> = true;
+const unit_result_ret: Equals<typeof wbg.unit_result_ret, () => void> = true;
+const async_unit_ret: Equals<typeof wbg.async_unit_ret, () => Promise<void>> = true;
+const async_unit_result_ret: Equals<typeof wbg.async_unit_result_ret, () => Promise<void>> = true;
+const async_value_ret: Equals<typeof wbg.async_value_ret, () => Promise<any>> = true;
+const method: Equals<wbg.UnitReturns['method'], () => void> = true;
+const async_static: Equals<typeof wbg.UnitReturns.async_static, () => Promise<void>> = true;
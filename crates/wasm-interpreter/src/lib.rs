@@ -173,8 +173,10 @@ impl Interpreter {
         let func = module.funcs.get(id);
         let params = module.types.get(func.ty()).params();
         assert!(
-            params.iter().all(|p| *p == walrus::ValType::I32),
-            "closure descriptors should only have i32 params"
+            params
+                .iter()
+                .all(|p| *p == walrus::ValType::I32 || *p == walrus::ValType::I64),
+            "closure descriptors should only have integer params"
         );
         let num_params = params.len();
         assert!(
@@ -189,19 +191,30 @@ impl Interpreter {
                 .take()
                 .expect("descriptor function should return index") as usize;
 
-        // After we've got the table index of the descriptor function we're
-        // interested go take a look in the function table to find what the
-        // actual index of the function is.
-        let functions = self.functions.expect("function table should be present");
+        self.interpret_descriptor_at(descriptor_table_idx, module, entry_removal_list)
+    }
+
+    /// Executes the descriptor function found at `descriptor_table_idx` in the
+    /// function table.
+    ///
+    /// This is used when the table index passed to
+    /// `__wbindgen_describe_closure` is already known statically, for example
+    /// when the closure shim has been inlined into its caller. The index is
+    /// added to `entry_removal_list` just like `interpret_closure_descriptor`.
+    pub fn interpret_descriptor_at(
+        &mut self,
+        descriptor_table_idx: usize,
+        module: &Module,
+        entry_removal_list: &mut HashSet<usize>,
+    ) -> Option<&[u32]> {
+        // Take a look in the function table to find what the actual index of
+        // the function is.
+        let functions = self.functions?;
         let functions = match &module.tables.get(functions).kind {
             walrus::TableKind::Function(f) => f,
             _ => unreachable!(),
         };
-        let descriptor_id = functions
-            .elements
-            .get(descriptor_table_idx)
-            .expect("out of bounds read of function table")
-            .expect("attempting to execute null function");
+        let descriptor_id = (*functions.elements.get(descriptor_table_idx)?)?;
 
         // This is used later to actually remove the entry from the table, but
         // we don't do the removal just yet
@@ -270,9 +283,13 @@ impl Frame<'_> {
         let stack = &mut self.interp.scratch;
 
         match instr {
+            // Modules with 64-bit memories use `i64` for the stack pointer and
+            // other addresses, which are small enough for descriptors to be
+            // treated like everything else.
             Instr::Const(c) => match c.value {
                 Value::I32(n) => stack.push(n),
-                _ => panic!("non-i32 constant"),
+                Value::I64(n) => stack.push(n as i32),
+                _ => panic!("non-integer constant"),
             },
             Instr::LocalGet(e) => stack.push(self.locals.get(&e.local).cloned().unwrap_or(0)),
             Instr::LocalSet(e) => {
@@ -293,8 +310,8 @@ impl Frame<'_> {
                 let rhs = stack.pop().unwrap();
                 let lhs = stack.pop().unwrap();
                 stack.push(match e.op {
-                    BinaryOp::I32Sub => lhs - rhs,
-                    BinaryOp::I32Add => lhs + rhs,
+                    BinaryOp::I32Sub | BinaryOp::I64Sub => lhs - rhs,
+                    BinaryOp::I32Add | BinaryOp::I64Add => lhs + rhs,
                     op => panic!("invalid binary op {:?}", op),
                 });
             }
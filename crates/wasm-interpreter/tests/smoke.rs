@@ -114,6 +114,34 @@ fn arithmetic() {
     interpret(wat, "foo", Some(&[3, 1]));
 }
 
+#[test]
+fn stack_pointer_64() {
+    let wat = r#"
+        (module
+            (import "__wbindgen_placeholder__" "__wbindgen_describe"
+              (func $__wbindgen_describe (param i32)))
+
+            (global (mut i64) (i64.const 1024))
+
+            (func $foo
+                global.get 0
+                i64.const 16
+                i64.sub
+                global.set 0
+                i32.const 7
+                call $__wbindgen_describe
+                global.get 0
+                i64.const 16
+                i64.add
+                global.set 0
+            )
+
+            (export "foo" (func $foo))
+        )
+    "#;
+    interpret(wat, "foo", Some(&[7]));
+}
+
 #[test]
 fn return_early() {
     let wat = r#"
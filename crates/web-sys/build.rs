@@ -1,11 +1,15 @@
 use anyhow::{Context, Result};
 use sourcefile::SourceFile;
-use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashSet};
 use std::env;
 use std::ffi::OsStr;
 use std::fs;
-use std::path::{self, PathBuf};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
+use std::thread;
 
 fn main() -> Result<()> {
     #[cfg(feature = "env_logger")]
@@ -63,39 +67,127 @@ fn main() -> Result<()> {
         Some(&allowed[..])
     };
 
-    let bindings = match wasm_bindgen_webidl::compile(&source.contents, allowed) {
-        Ok(bindings) => bindings,
-        Err(e) => {
-            if let Some(err) = e.downcast_ref::<wasm_bindgen_webidl::WebIDLParseError>() {
-                if let Some(pos) = source.resolve_offset(err.0) {
-                    let ctx = format!(
-                        "compiling WebIDL into wasm-bindgen bindings in file \
-                         \"{}\", line {} column {}",
-                        pos.filename,
-                        pos.line + 1,
-                        pos.col + 1
-                    );
-                    return Err(e.context(ctx));
-                } else {
-                    return Err(e.context("compiling WebIDL into wasm-bindgen bindings"));
-                }
-            }
-            return Err(e.context("compiling WebIDL into wasm-bindgen bindings"));
-        }
-    };
-
+    // Code generation is split up into shards, one per job Cargo gives us,
+    // with each thread generating and formatting its own share of the chunks
+    // of bindings.
+    let shards = env::var("NUM_JOBS")
+        .ok()
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(1)
+        .max(1);
     let out_dir = env::var("OUT_DIR").context("reading OUT_DIR environment variable")?;
-    let out_file_path = path::Path::new(&out_dir).join("bindings.rs");
-    fs::write(&out_file_path, bindings).context("writing bindings to output file")?;
-    println!("cargo:rustc-env=BINDINGS={}", out_file_path.display());
+    let chunks_dir = Path::new(&out_dir).join("bindings");
+    fs::create_dir_all(&chunks_dir).context("creating bindings directory")?;
 
-    // run rustfmt on the generated file - really handy for debugging
+    // run rustfmt on the generated files - really handy for debugging
     //
     // This is opportunistic though so don't assert that it succeeds.
     println!("cargo:rerun-if-env-changed=WEBIDL_RUSTFMT_BINDINGS");
-    if env::var("WEBIDL_RUSTFMT_BINDINGS").ok() != Some("0".to_string()) {
-        drop(Command::new("rustfmt").arg(&out_file_path).status());
+    let rustfmt = env::var("WEBIDL_RUSTFMT_BINDINGS").ok() != Some("0".to_string());
+
+    let contents = Arc::new(source.contents.clone());
+    let allowed = allowed.map(|a| a.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+    let threads = (0..shards)
+        .map(|shard| {
+            let contents = contents.clone();
+            let allowed = allowed.clone();
+            let chunks_dir = chunks_dir.clone();
+            thread::spawn(move || -> Result<Vec<String>> {
+                let allowed = allowed
+                    .as_ref()
+                    .map(|a| a.iter().map(|s| &s[..]).collect::<Vec<_>>());
+                let chunks = wasm_bindgen_webidl::compile_chunks(
+                    &contents,
+                    allowed.as_ref().map(|a| &a[..]),
+                    shard,
+                    shards,
+                )?;
+                let mut names = Vec::new();
+                for (name, code) in chunks {
+                    write_chunk(&chunks_dir, &name, &code, rustfmt)?;
+                    names.push(name);
+                }
+                Ok(names)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut names = BTreeSet::new();
+    for thread in threads {
+        let result = thread.join().expect("webidl compilation thread panicked");
+        match result {
+            Ok(chunk_names) => names.extend(chunk_names),
+            Err(e) => return Err(webidl_error(&source, e)),
+        }
+    }
+
+    // Remove chunks left over from a previous build with other features
+    // enabled, so they don't pile up in `OUT_DIR`.
+    for entry in fs::read_dir(&chunks_dir).context("reading bindings directory")? {
+        let path = entry?.path();
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        if !names.contains(stem) {
+            fs::remove_file(&path)
+                .with_context(|| format!("removing stale bindings \"{}\"", path.display()))?;
+        }
+    }
+
+    let mut bindings = String::new();
+    for name in names.iter() {
+        let path = chunks_dir.join(format!("{}.rs", name));
+        bindings.push_str(&format!("include!({:?});\n", path.display().to_string()));
     }
+    let out_file_path = Path::new(&out_dir).join("bindings.rs");
+    write_if_changed(&out_file_path, &bindings)?;
+    println!("cargo:rustc-env=BINDINGS={}", out_file_path.display());
+
+    Ok(())
+}
 
+/// Writes the generated code for one chunk of bindings, skipping it entirely
+/// (including running rustfmt) if its contents haven't changed since the last
+/// build according to the hash saved alongside it.
+fn write_chunk(dir: &Path, name: &str, code: &str, rustfmt: bool) -> Result<()> {
+    let path = dir.join(format!("{}.rs", name));
+    let hash_path = dir.join(format!("{}.hash", name));
+
+    let mut hasher = DefaultHasher::new();
+    code.hash(&mut hasher);
+    rustfmt.hash(&mut hasher);
+    let hash = format!("{:016x}", hasher.finish());
+
+    if path.exists() && fs::read_to_string(&hash_path).ok().as_ref() == Some(&hash) {
+        return Ok(());
+    }
+    fs::write(&path, code)
+        .with_context(|| format!("writing bindings to \"{}\"", path.display()))?;
+    if rustfmt {
+        drop(Command::new("rustfmt").arg(&path).status());
+    }
+    fs::write(&hash_path, hash)
+        .with_context(|| format!("writing bindings hash to \"{}\"", hash_path.display()))?;
     Ok(())
 }
+
+fn write_if_changed(path: &Path, contents: &str) -> Result<()> {
+    if fs::read_to_string(path).ok().as_ref().map(|s| &s[..]) == Some(contents) {
+        return Ok(());
+    }
+    fs::write(path, contents).with_context(|| format!("writing bindings to \"{}\"", path.display()))
+}
+
+fn webidl_error(source: &SourceFile, e: anyhow::Error) -> anyhow::Error {
+    if let Some(err) = e.downcast_ref::<wasm_bindgen_webidl::WebIDLParseError>() {
+        if let Some(pos) = source.resolve_offset(err.0) {
+            let ctx = format!(
+                "compiling WebIDL into wasm-bindgen bindings in file \
+                 \"{}\", line {} column {}",
+                pos.filename,
+                pos.line + 1,
+                pos.col + 1
+            );
+            return e.context(ctx);
+        }
+    }
+    e.context("compiling WebIDL into wasm-bindgen bindings")
+}
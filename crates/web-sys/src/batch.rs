@@ -0,0 +1,337 @@
+//! Recording DOM operations into a command buffer which is applied by JS with
+//! a single call, enabled with the `batch` feature.
+
+use std::fmt;
+
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::Node;
+
+// Each command is an opcode followed by its operands in the `ops` buffer.
+// Strings are stored as a UTF-16 offset and length into one string which is
+// passed along with the buffer, and nodes as indices into the node table.
+const CREATE_ELEMENT: u32 = 0;
+const CREATE_ELEMENT_NS: u32 = 1;
+const CREATE_TEXT_NODE: u32 = 2;
+const SET_ATTRIBUTE: u32 = 3;
+const REMOVE_ATTRIBUTE: u32 = 4;
+const SET_TEXT_CONTENT: u32 = 5;
+const SET_STYLE: u32 = 6;
+const APPEND_CHILD: u32 = 7;
+const INSERT_BEFORE: u32 = 8;
+const REMOVE: u32 = 9;
+
+#[wasm_bindgen(inline_js = "
+export function apply_commands(ops, strings, nodes) {
+    // Copy the commands out of wasm memory in case it grows while they're
+    // applied, for example in the constructor of a custom element.
+    ops = ops.slice();
+    let i = 0;
+    const str = () => {
+        const start = ops[i++];
+        const len = ops[i++];
+        return strings.substring(start, start + len);
+    };
+    const node = () => nodes[ops[i++]];
+    while (i < ops.length) {
+        switch (ops[i++]) {
+            case 0: {
+                const id = ops[i++];
+                nodes[id] = document.createElement(str());
+                break;
+            }
+            case 1: {
+                const id = ops[i++];
+                const ns = str();
+                nodes[id] = document.createElementNS(ns, str());
+                break;
+            }
+            case 2: {
+                const id = ops[i++];
+                nodes[id] = document.createTextNode(str());
+                break;
+            }
+            case 3: {
+                const target = node();
+                const name = str();
+                target.setAttribute(name, str());
+                break;
+            }
+            case 4:
+                node().removeAttribute(str());
+                break;
+            case 5:
+                node().textContent = str();
+                break;
+            case 6: {
+                const target = node();
+                const name = str();
+                target.style.setProperty(name, str());
+                break;
+            }
+            case 7: {
+                const parent = node();
+                parent.appendChild(node());
+                break;
+            }
+            case 8: {
+                const parent = node();
+                const child = node();
+                const reference = ops[i++];
+                parent.insertBefore(child, reference === 0xffffffff ? null : nodes[reference]);
+                break;
+            }
+            case 9:
+                node().remove();
+                break;
+            default:
+                throw new Error('invalid batched DOM command');
+        }
+    }
+}
+")]
+extern "C" {
+    #[wasm_bindgen(catch)]
+    fn apply_commands(ops: &[u32], strings: &str, nodes: &Array) -> Result<(), JsValue>;
+}
+
+/// A reference to a DOM node within a `CommandBuffer`.
+///
+/// These are only meaningful for the buffer which created them, and are
+/// invalidated when it's cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeRef(u32);
+
+/// A buffer of DOM operations recorded in Rust and applied by JS all at once.
+///
+/// Every call to a `web-sys` method crosses the boundary between wasm and JS,
+/// which adds up when rendering many nodes. Operations recorded into a
+/// `CommandBuffer` are instead encoded into a compact buffer in wasm memory,
+/// and `flush` hands the whole buffer to a small JS interpreter which applies
+/// them in order.
+///
+/// Nodes are referred to with `NodeRef`s, either for nodes created by the
+/// buffer or for existing nodes registered with `node`. The nodes behind them
+/// can be retrieved with `get` once the commands creating them are flushed.
+///
+/// Elements and text nodes are created in the global `document`, and the JS
+/// interpreter is included as an inline JS snippet, so this isn't supported
+/// with `--target no-modules`.
+///
+/// *This API requires the following crate features to be activated: `batch`*
+///
+/// ```no_run
+/// use wasm_bindgen::prelude::*;
+/// use web_sys::{CommandBuffer, Node};
+///
+/// fn render(root: &Node, items: &[&str]) -> Result<(), JsValue> {
+///     let mut buffer = CommandBuffer::new();
+///     let root = buffer.node(root);
+///     let list = buffer.create_element("ul");
+///     for item in items {
+///         let li = buffer.create_element("li");
+///         buffer.set_attribute(li, "class", "item");
+///         buffer.set_text_content(li, item);
+///         buffer.append_child(list, li);
+///     }
+///     buffer.append_child(root, list);
+///     buffer.flush()
+/// }
+/// ```
+pub struct CommandBuffer {
+    ops: Vec<u32>,
+    strings: String,
+    strings_len: u32,
+    nodes: Array,
+    next_node: u32,
+    commands: usize,
+}
+
+impl CommandBuffer {
+    /// Creates a new, empty command buffer.
+    pub fn new() -> CommandBuffer {
+        CommandBuffer {
+            ops: Vec::new(),
+            strings: String::new(),
+            strings_len: 0,
+            nodes: Array::new(),
+            next_node: 0,
+            commands: 0,
+        }
+    }
+
+    /// Registers an existing node with this buffer, returning a reference
+    /// which commands can use to refer to it.
+    pub fn node(&mut self, node: &Node) -> NodeRef {
+        let id = self.alloc_node();
+        self.nodes.set(id.0, node.clone().into());
+        id
+    }
+
+    /// Records `document.createElement(tag)`, returning a reference to the
+    /// element to be created.
+    pub fn create_element(&mut self, tag: &str) -> NodeRef {
+        let id = self.alloc_node();
+        self.command(CREATE_ELEMENT);
+        self.ops.push(id.0);
+        self.string(tag);
+        id
+    }
+
+    /// Records `document.createElementNS(namespace, tag)`, returning a
+    /// reference to the element to be created.
+    pub fn create_element_ns(&mut self, namespace: &str, tag: &str) -> NodeRef {
+        let id = self.alloc_node();
+        self.command(CREATE_ELEMENT_NS);
+        self.ops.push(id.0);
+        self.string(namespace);
+        self.string(tag);
+        id
+    }
+
+    /// Records `document.createTextNode(text)`, returning a reference to the
+    /// text node to be created.
+    pub fn create_text_node(&mut self, text: &str) -> NodeRef {
+        let id = self.alloc_node();
+        self.command(CREATE_TEXT_NODE);
+        self.ops.push(id.0);
+        self.string(text);
+        id
+    }
+
+    /// Records `element.setAttribute(name, value)`.
+    pub fn set_attribute(&mut self, element: NodeRef, name: &str, value: &str) {
+        self.command(SET_ATTRIBUTE);
+        self.ops.push(element.0);
+        self.string(name);
+        self.string(value);
+    }
+
+    /// Records `element.removeAttribute(name)`.
+    pub fn remove_attribute(&mut self, element: NodeRef, name: &str) {
+        self.command(REMOVE_ATTRIBUTE);
+        self.ops.push(element.0);
+        self.string(name);
+    }
+
+    /// Records setting `node.textContent` to `text`.
+    pub fn set_text_content(&mut self, node: NodeRef, text: &str) {
+        self.command(SET_TEXT_CONTENT);
+        self.ops.push(node.0);
+        self.string(text);
+    }
+
+    /// Records `element.style.setProperty(property, value)`.
+    pub fn set_style(&mut self, element: NodeRef, property: &str, value: &str) {
+        self.command(SET_STYLE);
+        self.ops.push(element.0);
+        self.string(property);
+        self.string(value);
+    }
+
+    /// Records `parent.appendChild(child)`.
+    pub fn append_child(&mut self, parent: NodeRef, child: NodeRef) {
+        self.command(APPEND_CHILD);
+        self.ops.push(parent.0);
+        self.ops.push(child.0);
+    }
+
+    /// Records `parent.insertBefore(child, reference)`, appending `child` if
+    /// `reference` is `None`.
+    pub fn insert_before(&mut self, parent: NodeRef, child: NodeRef, reference: Option<NodeRef>) {
+        self.command(INSERT_BEFORE);
+        self.ops.push(parent.0);
+        self.ops.push(child.0);
+        self.ops
+            .push(reference.map(|r| r.0).unwrap_or(u32::max_value()));
+    }
+
+    /// Records `node.remove()`, removing it from its parent.
+    pub fn remove(&mut self, node: NodeRef) {
+        self.command(REMOVE);
+        self.ops.push(node.0);
+    }
+
+    /// Returns the number of commands recorded since the last flush.
+    pub fn len(&self) -> usize {
+        self.commands
+    }
+
+    /// Returns whether no commands have been recorded since the last flush.
+    pub fn is_empty(&self) -> bool {
+        self.commands == 0
+    }
+
+    /// Applies all recorded commands in order with a single call into JS.
+    ///
+    /// The recorded commands are cleared even if applying them fails, in
+    /// which case the error thrown by the failing command is returned and
+    /// the commands after it are skipped. References to nodes stay valid
+    /// across flushes.
+    pub fn flush(&mut self) -> Result<(), JsValue> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let result = apply_commands(&self.ops, &self.strings, &self.nodes);
+        self.ops.clear();
+        self.strings.clear();
+        self.strings_len = 0;
+        self.commands = 0;
+        result
+    }
+
+    /// Returns the node behind `node`, or `None` if the command creating it
+    /// hasn't been flushed yet.
+    pub fn get(&self, node: NodeRef) -> Option<Node> {
+        self.nodes.get(node.0).dyn_into().ok()
+    }
+
+    /// Discards all recorded commands and registered nodes, invalidating
+    /// every `NodeRef` previously returned by this buffer.
+    pub fn clear(&mut self) {
+        self.ops.clear();
+        self.strings.clear();
+        self.strings_len = 0;
+        self.commands = 0;
+        self.nodes = Array::new();
+        self.next_node = 0;
+    }
+
+    fn alloc_node(&mut self) -> NodeRef {
+        let id = self.next_node;
+        self.next_node += 1;
+        NodeRef(id)
+    }
+
+    fn command(&mut self, op: u32) {
+        self.commands += 1;
+        self.ops.push(op);
+    }
+
+    fn string(&mut self, s: &str) {
+        // JS strings are indexed by UTF-16 code units, so track offsets in
+        // those rather than in bytes.
+        let len = s.encode_utf16().count() as u32;
+        self.ops.push(self.strings_len);
+        self.ops.push(len);
+        self.strings.push_str(s);
+        self.strings_len += len;
+    }
+}
+
+impl Default for CommandBuffer {
+    fn default() -> CommandBuffer {
+        CommandBuffer::new()
+    }
+}
+
+impl fmt::Debug for CommandBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CommandBuffer")
+            .field("commands", &self.commands)
+            .field("nodes", &self.next_node)
+            .finish()
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::CssStyleDeclaration;
+
+/// Map-like access to a `CSSStyleDeclaration`, such as the inline style of an
+/// element returned by `HtmlElement::style`.
+///
+/// Values are set and removed with the generated `set_property` and
+/// `remove_property` methods.
+///
+/// *This API requires the following crate features to be activated: `CssStyleDeclaration`*
+///
+/// ```no_run
+/// use web_sys::CssStyleDeclaration;
+///
+/// fn hide(style: &CssStyleDeclaration) -> Result<(), wasm_bindgen::JsValue> {
+///     style.set_property("display", "none")?;
+///     assert_eq!(style.get_property("display"), Some("none".to_string()));
+///     for (property, value) in style {
+///         println!("{}: {}", property, value);
+///     }
+///     Ok(())
+/// }
+/// ```
+impl CssStyleDeclaration {
+    /// Returns the value of the CSS property `property`, or `None` if it isn't
+    /// set.
+    ///
+    /// Unlike `get_property_value`, this doesn't distinguish between a
+    /// property which isn't set and a property which can't be read.
+    pub fn get_property(&self, property: &str) -> Option<String> {
+        match self.get_property_value(property) {
+            Ok(value) if !value.is_empty() => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the CSS property `property` is set.
+    pub fn contains_property(&self, property: &str) -> bool {
+        self.get_property(property).is_some()
+    }
+
+    /// Returns an iterator over the names and values of all properties which
+    /// are set, in declaration order.
+    ///
+    /// Shorthand properties such as `margin` are listed as their longhand
+    /// properties.
+    pub fn iter(&self) -> CssStyleDeclarationIter {
+        CssStyleDeclarationIter {
+            style: self.clone(),
+            index: 0,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a CssStyleDeclaration {
+    type Item = (String, String);
+    type IntoIter = CssStyleDeclarationIter;
+
+    fn into_iter(self) -> CssStyleDeclarationIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the properties of a `CssStyleDeclaration`, created with
+/// [`CssStyleDeclaration::iter`].
+///
+/// *This API requires the following crate features to be activated: `CssStyleDeclaration`*
+pub struct CssStyleDeclarationIter {
+    style: CssStyleDeclaration,
+    index: u32,
+}
+
+impl Iterator for CssStyleDeclarationIter {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<(String, String)> {
+        while self.index < self.style.length() {
+            let property = self.style.item(self.index);
+            self.index += 1;
+            if let Some(value) = self.style.get_property(&property) {
+                return Some((property, value));
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Debug for CssStyleDeclarationIter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CssStyleDeclarationIter")
+            .field("index", &self.index)
+            .finish()
+    }
+}
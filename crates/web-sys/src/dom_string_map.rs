@@ -0,0 +1,97 @@
+use std::fmt;
+
+use wasm_bindgen::JsCast;
+
+use crate::DomStringMap;
+
+/// Map-like access to a `DOMStringMap`, such as the `data-*` attributes of an
+/// element returned by `HtmlElement::dataset`.
+///
+/// *This API requires the following crate features to be activated: `DomStringMap`*
+///
+/// ```no_run
+/// use web_sys::DomStringMap;
+///
+/// fn mark_selected(dataset: &DomStringMap) {
+///     // sets the `data-selected-at` attribute
+///     dataset.set("selectedAt", "now").unwrap();
+///     for (name, value) in dataset {
+///         println!("{} = {}", name, value);
+///     }
+///     dataset.remove("selectedAt");
+/// }
+/// ```
+impl DomStringMap {
+    /// Returns whether an entry named `name` exists.
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Removes the entry named `name`, returning its value if it existed.
+    pub fn remove(&self, name: &str) -> Option<String> {
+        let value = self.get(name);
+        if value.is_some() {
+            self.delete(name);
+        }
+        value
+    }
+
+    /// Returns the names of all entries, in the order of their attributes.
+    pub fn keys(&self) -> Vec<String> {
+        js_sys::Object::keys(self.unchecked_ref())
+            .iter()
+            .filter_map(|name| name.as_string())
+            .collect()
+    }
+
+    /// Returns an iterator over the names and values of all entries.
+    ///
+    /// The names are read when this is called, so entries added while
+    /// iterating aren't visited and entries removed while iterating are
+    /// skipped.
+    pub fn iter(&self) -> DomStringMapIter {
+        DomStringMapIter {
+            map: self.clone(),
+            keys: self.keys().into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DomStringMap {
+    type Item = (String, String);
+    type IntoIter = DomStringMapIter;
+
+    fn into_iter(self) -> DomStringMapIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a `DomStringMap`, created with
+/// [`DomStringMap::iter`].
+///
+/// *This API requires the following crate features to be activated: `DomStringMap`*
+pub struct DomStringMapIter {
+    map: DomStringMap,
+    keys: std::vec::IntoIter<String>,
+}
+
+impl Iterator for DomStringMapIter {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<(String, String)> {
+        for name in &mut self.keys {
+            if let Some(value) = self.map.get(&name) {
+                return Some((name, value));
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Debug for DomStringMapIter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DomStringMapIter")
+            .field("keys", &self.keys.as_slice())
+            .finish()
+    }
+}
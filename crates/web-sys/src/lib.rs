@@ -31,4 +31,24 @@ pub fn window() -> Option<Window> {
     js_sys::global().dyn_into::<Window>().ok()
 }
 
+#[cfg(feature = "EventTarget")]
+mod listener;
+#[cfg(feature = "EventTarget")]
+pub use crate::listener::EventListenerHandle;
+
+#[cfg(feature = "DomStringMap")]
+mod dom_string_map;
+#[cfg(feature = "DomStringMap")]
+pub use crate::dom_string_map::DomStringMapIter;
+
+#[cfg(feature = "CssStyleDeclaration")]
+mod css_style_declaration;
+#[cfg(feature = "CssStyleDeclaration")]
+pub use crate::css_style_declaration::CssStyleDeclarationIter;
+
+#[cfg(feature = "batch")]
+mod batch;
+#[cfg(feature = "batch")]
+pub use crate::batch::{CommandBuffer, NodeRef};
+
 include!(env!("BINDINGS"));
@@ -0,0 +1,115 @@
+use std::fmt;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::EventTarget;
+
+/// A guard for an event listener registered with `addEventListener`.
+///
+/// The handle owns the `Closure` used as the listener. When the handle is
+/// dropped the listener is removed from its target and only then is the
+/// closure freed, so JS can never call into a deallocated closure and the
+/// closure is never leaked by accident.
+///
+/// *This API requires the following crate features to be activated: `EventTarget`*
+///
+/// ```no_run
+/// use wasm_bindgen::prelude::*;
+/// use web_sys::{Event, EventListenerHandle, EventTarget};
+///
+/// fn on_click(target: &EventTarget) -> Result<EventListenerHandle<dyn FnMut(Event)>, JsValue> {
+///     let closure = Closure::wrap(Box::new(|_event: Event| {
+///         // ...
+///     }) as Box<dyn FnMut(Event)>);
+///     EventListenerHandle::new(target, "click", closure)
+/// }
+/// ```
+pub struct EventListenerHandle<T: ?Sized> {
+    target: EventTarget,
+    event_type: String,
+    capture: bool,
+    closure: Option<Closure<T>>,
+}
+
+impl<T: ?Sized> EventListenerHandle<T> {
+    /// Registers `closure` as a listener for `event_type` events on `target`.
+    ///
+    /// Returns an error if `addEventListener` throws.
+    pub fn new(
+        target: &EventTarget,
+        event_type: &str,
+        closure: Closure<T>,
+    ) -> Result<EventListenerHandle<T>, JsValue> {
+        EventListenerHandle::new_with_capture(target, event_type, closure, false)
+    }
+
+    /// Same as `new`, but registers the listener for the capture phase if
+    /// `capture` is `true`.
+    pub fn new_with_capture(
+        target: &EventTarget,
+        event_type: &str,
+        closure: Closure<T>,
+        capture: bool,
+    ) -> Result<EventListenerHandle<T>, JsValue> {
+        target.add_event_listener_with_callback_and_bool(
+            event_type,
+            closure.as_ref().unchecked_ref(),
+            capture,
+        )?;
+        Ok(EventListenerHandle {
+            target: target.clone(),
+            event_type: event_type.to_string(),
+            capture,
+            closure: Some(closure),
+        })
+    }
+
+    /// Returns the target this listener was added to.
+    pub fn target(&self) -> &EventTarget {
+        &self.target
+    }
+
+    /// Returns the name of the event this listener was added for.
+    pub fn event_type(&self) -> &str {
+        &self.event_type
+    }
+
+    /// Returns the closure used as the listener.
+    pub fn closure(&self) -> &Closure<T> {
+        self.closure.as_ref().unwrap()
+    }
+
+    /// Leaves the listener attached for the rest of the program, leaking its
+    /// closure.
+    pub fn forget(mut self) {
+        self.closure.take().unwrap().forget();
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for EventListenerHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventListenerHandle")
+            .field("target", &self.target)
+            .field("event_type", &self.event_type)
+            .field("capture", &self.capture)
+            .finish()
+    }
+}
+
+impl<T: ?Sized> Drop for EventListenerHandle<T> {
+    fn drop(&mut self) {
+        let closure = match self.closure.take() {
+            Some(closure) => closure,
+            None => return,
+        };
+        // Removing a listener only throws if the target is some exotic object
+        // which isn't a real `EventTarget`, in which case there's nothing left
+        // to detach, so the error is ignored and the closure freed regardless.
+        drop(self.target.remove_event_listener_with_callback_and_bool(
+            &self.event_type,
+            closure.as_ref().unchecked_ref(),
+            self.capture,
+        ));
+    }
+}
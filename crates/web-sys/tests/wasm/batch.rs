@@ -0,0 +1,78 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{CommandBuffer, Element, Node};
+
+fn root() -> Node {
+    let document = web_sys::window().unwrap().document().unwrap();
+    document.create_element("div").unwrap().into()
+}
+
+#[wasm_bindgen_test]
+fn builds_tree() {
+    let root = root();
+    let mut buffer = CommandBuffer::new();
+    let parent = buffer.node(&root);
+    let list = buffer.create_element("ul");
+    buffer.set_attribute(list, "id", "list");
+    for (i, text) in ["a", "b", "ü✓"].iter().enumerate() {
+        let li = buffer.create_element("li");
+        buffer.set_attribute(li, "data-index", &i.to_string());
+        buffer.set_text_content(li, text);
+        buffer.append_child(list, li);
+    }
+    buffer.append_child(parent, list);
+    assert_eq!(buffer.len(), 15);
+    assert!(buffer.get(list).is_none());
+
+    buffer.flush().unwrap();
+    assert!(buffer.is_empty());
+    let list = buffer.get(list).unwrap().dyn_into::<Element>().unwrap();
+    assert_eq!(list.id(), "list");
+    assert_eq!(
+        root.unchecked_ref::<Element>().inner_html(),
+        "<ul id=\"list\"><li data-index=\"0\">a</li><li data-index=\"1\">b</li>\
+         <li data-index=\"2\">ü✓</li></ul>"
+    );
+}
+
+#[wasm_bindgen_test]
+fn refs_survive_flushes() {
+    let root = root();
+    let mut buffer = CommandBuffer::new();
+    let parent = buffer.node(&root);
+    let first = buffer.create_text_node("first");
+    buffer.append_child(parent, first);
+    buffer.flush().unwrap();
+
+    let second = buffer.create_element("span");
+    buffer.set_style(second, "color", "red");
+    buffer.insert_before(parent, second, Some(first));
+    buffer.remove_attribute(second, "missing");
+    buffer.flush().unwrap();
+    assert_eq!(
+        root.unchecked_ref::<Element>().inner_html(),
+        "<span style=\"color: red;\"></span>first"
+    );
+
+    buffer.remove(first);
+    buffer.flush().unwrap();
+    assert_eq!(root.text_content().unwrap(), "");
+}
+
+#[wasm_bindgen_test]
+fn flush_error() {
+    let root = root();
+    let mut buffer = CommandBuffer::new();
+    let parent = buffer.node(&root);
+    let orphan = buffer.create_element("p");
+    let child = buffer.create_element("b");
+    // `orphan` isn't a child of `parent`, so this throws.
+    buffer.insert_before(parent, child, Some(orphan));
+    buffer.append_child(parent, orphan);
+    assert!(buffer.flush().is_err());
+    assert!(buffer.is_empty());
+    assert_eq!(root.child_nodes().length(), 0);
+
+    buffer.clear();
+    assert!(buffer.get(parent).is_none());
+}
@@ -0,0 +1,51 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_sys::{RequestInit, Response};
+
+// These routes are registered with the test server in `tests/fixtures.json`.
+
+async fn fetch(url: &str, init: &RequestInit) -> Response {
+    let window = web_sys::window().unwrap();
+    JsFuture::from(window.fetch_with_str_and_init(url, init))
+        .await
+        .unwrap()
+        .dyn_into()
+        .unwrap()
+}
+
+async fn text(response: &Response) -> String {
+    JsFuture::from(response.text().unwrap())
+        .await
+        .unwrap()
+        .as_string()
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn fixture_body_and_headers() {
+    let response = fetch("/fixtures/hello", &RequestInit::new()).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("X-Fixture").unwrap(),
+        Some("yes".to_string())
+    );
+    assert_eq!(text(&response).await, "hello!");
+}
+
+#[wasm_bindgen_test]
+async fn fixture_status() {
+    let response = fetch("/fixtures/missing", &RequestInit::new()).await;
+    assert!(!response.ok());
+    assert_eq!(response.status(), 404);
+}
+
+#[wasm_bindgen_test]
+async fn fixture_echo() {
+    let mut init = RequestInit::new();
+    init.method("POST").body(Some(&JsValue::from_str("ping")));
+    let response = fetch("/fixtures/echo", &init).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(text(&response).await, "ping");
+}
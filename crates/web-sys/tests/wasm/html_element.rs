@@ -159,3 +159,53 @@ fn test_html_element() {
         "Shouldn't have an offset_height yet"
     );
 }
+
+#[wasm_bindgen_test]
+fn test_dataset() {
+    let element = new_html();
+    let dataset = element.dataset();
+    assert_eq!(dataset.get("fooBar"), None);
+    assert!(!dataset.contains_key("fooBar"));
+
+    dataset.set("fooBar", "1").unwrap();
+    dataset.set("baz", "2").unwrap();
+    assert_eq!(element.get_attribute("data-foo-bar").unwrap(), "1");
+    assert!(dataset.contains_key("fooBar"));
+    assert_eq!(dataset.keys(), ["fooBar", "baz"]);
+    let entries = dataset.iter().collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            ("fooBar".to_string(), "1".to_string()),
+            ("baz".to_string(), "2".to_string()),
+        ]
+    );
+
+    assert_eq!(dataset.remove("fooBar"), Some("1".to_string()));
+    assert_eq!(dataset.remove("fooBar"), None);
+    assert!(!element.has_attribute("data-foo-bar"));
+}
+
+#[wasm_bindgen_test]
+fn test_style() {
+    let element = new_html();
+    let style = element.style();
+    assert_eq!(style.get_property("color"), None);
+    assert_eq!(style.iter().count(), 0);
+
+    style.set_property("color", "red").unwrap();
+    style.set_property("display", "none").unwrap();
+    assert_eq!(style.get_property("color"), Some("red".to_string()));
+    assert!(style.contains_property("display"));
+    let entries = (&style).into_iter().collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            ("color".to_string(), "red".to_string()),
+            ("display".to_string(), "none".to_string()),
+        ]
+    );
+
+    style.remove_property("color").unwrap();
+    assert!(!style.contains_property("color"));
+}
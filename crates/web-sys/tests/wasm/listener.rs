@@ -0,0 +1,39 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+use web_sys::{Event, EventListenerHandle, EventTarget};
+
+fn counting_listener(hits: &Rc<Cell<u32>>) -> Closure<dyn FnMut(Event)> {
+    let hits = hits.clone();
+    Closure::wrap(Box::new(move |_: Event| hits.set(hits.get() + 1)) as Box<dyn FnMut(Event)>)
+}
+
+#[wasm_bindgen_test]
+fn removed_on_drop() {
+    let target = EventTarget::new().unwrap();
+    let hits = Rc::new(Cell::new(0));
+    let handle = EventListenerHandle::new(&target, "ping", counting_listener(&hits)).unwrap();
+    assert_eq!(handle.event_type(), "ping");
+
+    target.dispatch_event(&Event::new("ping").unwrap()).unwrap();
+    assert_eq!(hits.get(), 1);
+
+    drop(handle);
+    target.dispatch_event(&Event::new("ping").unwrap()).unwrap();
+    assert_eq!(hits.get(), 1);
+    assert_eq!(Rc::strong_count(&hits), 1);
+}
+
+#[wasm_bindgen_test]
+fn forget_keeps_listener() {
+    let target = EventTarget::new().unwrap();
+    let hits = Rc::new(Cell::new(0));
+    EventListenerHandle::new_with_capture(&target, "ping", counting_listener(&hits), true)
+        .unwrap()
+        .forget();
+
+    target.dispatch_event(&Event::new("ping").unwrap()).unwrap();
+    target.dispatch_event(&Event::new("ping").unwrap()).unwrap();
+    assert_eq!(hits.get(), 2);
+}
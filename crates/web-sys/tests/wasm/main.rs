@@ -5,6 +5,7 @@ use wasm_bindgen_test::*;
 wasm_bindgen_test_configure!(run_in_browser);
 
 pub mod anchor_element;
+pub mod batch;
 pub mod body_element;
 pub mod br_element;
 pub mod button_element;
@@ -12,6 +13,7 @@ pub mod console;
 pub mod div_element;
 pub mod element;
 pub mod event;
+pub mod fixtures;
 pub mod head_element;
 pub mod headers;
 pub mod heading_element;
@@ -20,6 +22,7 @@ pub mod hr_element;
 pub mod html_element;
 pub mod html_html_element;
 pub mod input_element;
+pub mod listener;
 //TODO: Both menu-related tests completely break in Chrome, but run fine in Firefox.
 //pub mod menu_element;
 //pub mod menu_item_element;
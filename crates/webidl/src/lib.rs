@@ -23,11 +23,13 @@ use crate::util::{
 use anyhow::{bail, Result};
 use proc_macro2::{Ident, Span};
 use quote::{quote, ToTokens};
-use std::collections::{BTreeSet, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::env;
 use std::fmt;
 use std::fmt::Display;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
 use wasm_bindgen_backend::ast;
 use wasm_bindgen_backend::defined::ImportedTypeReferences;
@@ -220,15 +222,150 @@ fn immutable_slice_whitelist() -> BTreeSet<&'static str> {
     ])
 }
 
+/// Compile the given WebIDL source text like `compile`, but split the
+/// generated Rust source into one chunk per interface, dictionary, enum, or
+/// namespace, returned as `(name, source)` pairs sorted by name.
+///
+/// Only the chunks assigned to `shard` (out of `shards`, based on a hash of
+/// their name) are generated, so code generation can be spread across
+/// threads which each compile their own shard of the same source.
+pub fn compile_chunks(
+    webidl_source: &str,
+    allowed_types: Option<&[&str]>,
+    shard: usize,
+    shards: usize,
+) -> Result<Vec<(String, String)>> {
+    let mut ast = parse(webidl_source, allowed_types)?;
+    remove_undefined_imports(&mut ast, shard == 0);
+
+    let in_shard = |name: &str| {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        hasher.finish() as usize % shards == shard
+    };
+
+    let mut chunks = BTreeMap::new();
+    for (name, program) in split_program(ast.main) {
+        if !in_shard(&name) {
+            continue;
+        }
+        let mut tokens = proc_macro2::TokenStream::new();
+        if let Err(e) = program.try_to_tokens(&mut tokens) {
+            e.panic();
+        }
+        chunks.insert(name, tokens.to_string());
+    }
+    for (name, m) in ast.submodules.iter() {
+        if !in_shard(name) {
+            continue;
+        }
+        let mut m_tokens = proc_macro2::TokenStream::new();
+        if let Err(e) = m.try_to_tokens(&mut m_tokens) {
+            e.panic();
+        }
+        let ident = Ident::new(name, Span::call_site());
+        let code = quote! {
+            pub mod #ident { #m_tokens }
+        };
+        chunks.insert(name.clone(), code.to_string());
+    }
+    Ok(chunks.into_iter().collect())
+}
+
+/// Splits the items of `program` up by the interface, dictionary, or enum
+/// they belong to, placing everything else in a chunk named `global`.
+fn split_program(program: ast::Program) -> BTreeMap<String, ast::Program> {
+    let mut chunks = BTreeMap::<String, ast::Program>::new();
+    let global = || String::from("global");
+    let class_chunk = |ty: &syn::Type| match ty {
+        syn::Type::Path(ty) => match ty.path.segments.last() {
+            Some(seg) => seg.ident.to_string(),
+            None => global(),
+        },
+        _ => global(),
+    };
+    for import in program.imports {
+        let name = match (&import.js_namespace, &import.kind) {
+            (Some(ns), _) => ns.to_string(),
+            (None, ast::ImportKind::Type(t)) => t.rust_name.to_string(),
+            (None, ast::ImportKind::Enum(e)) => e.name.to_string(),
+            (None, ast::ImportKind::Function(f)) => match &f.kind {
+                ast::ImportFunctionKind::Method { ty, .. } => class_chunk(ty),
+                _ => global(),
+            },
+            (None, ast::ImportKind::Property(p)) => class_chunk(&p.class),
+            (None, ast::ImportKind::Static(_)) => global(),
+        };
+        chunks.entry(name).or_default().imports.push(import);
+    }
+    for c in program.consts {
+        let name = c
+            .class
+            .as_ref()
+            .map(|c| c.to_string())
+            .unwrap_or_else(global);
+        chunks.entry(name).or_default().consts.push(c);
+    }
+    for d in program.dictionaries {
+        chunks
+            .entry(d.name.to_string())
+            .or_default()
+            .dictionaries
+            .push(d);
+    }
+    for e in program.enums {
+        chunks.entry(e.name.to_string()).or_default().enums.push(e);
+    }
+    let rest = chunks.entry(global()).or_default();
+    rest.exports.extend(program.exports);
+    rest.structs.extend(program.structs);
+    rest.typescript_custom_sections
+        .extend(program.typescript_custom_sections);
+    rest.inline_js.extend(program.inline_js);
+    rest.linked_modules.extend(program.linked_modules);
+    rest.js_preludes.extend(program.js_preludes);
+    rest.exported_consts.extend(program.exported_consts);
+    chunks
+}
+
 /// Run codegen on the AST to generate rust code.
 fn compile_ast(mut ast: Program) -> String {
+    remove_undefined_imports(&mut ast, true);
+
+    let mut tokens = proc_macro2::TokenStream::new();
+    if let Err(e) = ast.main.try_to_tokens(&mut tokens) {
+        e.panic();
+    }
+    for (name, m) in ast.submodules.iter() {
+        let mut m_tokens = proc_macro2::TokenStream::new();
+        if let Err(e) = m.try_to_tokens(&mut m_tokens) {
+            e.panic();
+        }
+
+        let name = Ident::new(name, Span::call_site());
+
+        (quote! {
+            pub mod #name { #m_tokens }
+        })
+        .to_tokens(&mut tokens);
+    }
+    tokens.to_string()
+}
+
+/// Removes imports which reference types that aren't defined, optionally
+/// recording all definitions for `__WASM_BINDGEN_DUMP_FEATURES`.
+fn remove_undefined_imports(ast: &mut Program, dump_features: bool) {
     // Iteratively prune all entries from the AST which reference undefined
     // fields. Each pass may remove definitions of types and so we need to
     // reexecute this pass to see if we need to keep removing types until we
     // reach a steady state.
     let builtin = builtin_idents();
     let mut all_definitions = BTreeSet::new();
-    let track = env::var_os("__WASM_BINDGEN_DUMP_FEATURES");
+    let track = if dump_features {
+        env::var_os("__WASM_BINDGEN_DUMP_FEATURES")
+    } else {
+        None
+    };
     loop {
         let mut defined = builtin.clone();
         {
@@ -264,25 +401,6 @@ fn compile_ast(mut ast: Program) -> String {
             .join("\n");
         fs::write(path, contents).unwrap();
     }
-
-    let mut tokens = proc_macro2::TokenStream::new();
-    if let Err(e) = ast.main.try_to_tokens(&mut tokens) {
-        e.panic();
-    }
-    for (name, m) in ast.submodules.iter() {
-        let mut m_tokens = proc_macro2::TokenStream::new();
-        if let Err(e) = m.try_to_tokens(&mut m_tokens) {
-            e.panic();
-        }
-
-        let name = Ident::new(name, Span::call_site());
-
-        (quote! {
-            pub mod #name { #m_tokens }
-        })
-        .to_tokens(&mut tokens);
-    }
-    tokens.to_string()
 }
 
 impl<'src> FirstPassRecord<'src> {
@@ -304,7 +422,10 @@ impl<'src> FirstPassRecord<'src> {
                         }
                     })
                     .collect(),
-                variant_values: variants.iter().map(|v| v.0.to_string()).collect(),
+                variant_values: variants
+                    .iter()
+                    .map(|v| ast::ImportEnumValue::String(v.0.to_string()))
+                    .collect(),
                 rust_attrs: vec![syn::parse_quote!(#[derive(Copy, Clone, PartialEq, Debug)])],
             }),
         });
@@ -311,13 +311,16 @@ impl<'src> FirstPassRecord<'src> {
                 rust_attrs: vec![],
                 rust_vis: public(),
                 r#async: false,
+                generator: false,
             },
             rust_name: rust_ident(rust_name),
             js_ret: js_ret.clone(),
             variadic,
             catch,
+            catch_ty: None,
             structural,
             assert_no_shim: false,
+            extension_trait: None,
             shim: {
                 let ns = match kind {
                     ast::ImportFunctionKind::Normal => "",
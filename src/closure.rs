@@ -4,11 +4,12 @@
 //! closures" from Rust to JS. Some more details can be found on the `Closure`
 //! type itself.
 
+use std::cell::Cell;
 use std::fmt;
-#[cfg(feature = "nightly")]
-use std::marker::Unsize;
+use std::marker::PhantomData;
 use std::mem::{self, ManuallyDrop};
 use std::prelude::v1::*;
+use std::rc::Rc;
 
 use crate::convert::*;
 use crate::describe::*;
@@ -29,11 +30,16 @@ use crate::UnwrapThrowExt;
 /// to you to arrange for `Closure` to be properly deallocate at an appropriate
 /// location in your program.
 ///
+/// A `Closure` can be cheaply cloned to register the same callback with
+/// several JS APIs. All clones share the same JS function and Rust closure,
+/// which are only invalidated once the last clone is dropped.
+///
 /// The type parameter on `Closure` is the type of closure that this represents.
-/// Currently this can only be the `Fn` and `FnMut` traits with up to 7
-/// arguments (and an optional return value). The arguments/return value of the
-/// trait must be numbers like `u32` for now, although this restriction may be
-/// lifted in the future!
+/// Currently this can only be the `Fn` and `FnMut` traits with up to 12
+/// arguments (and an optional return value). The arguments and return value
+/// can be any type which can be passed to and from JS, including types
+/// imported with `#[wasm_bindgen]` like `web_sys::Event`, so event listeners
+/// can take a typed event rather than a raw `JsValue`.
 ///
 /// # Examples
 ///
@@ -69,11 +75,11 @@ use crate::UnwrapThrowExt;
 ///
 /// #[wasm_bindgen]
 /// pub fn run() -> IntervalHandle {
-///     // First up we use `Closure::wrap` to wrap up a Rust closure and create
+///     // First up we use `Closure::new` to wrap up a Rust closure and create
 ///     // a JS closure.
-///     let cb = Closure::wrap(Box::new(|| {
+///     let cb = Closure::new(|| {
 ///         log("interval elapsed!");
-///     }) as Box<FnMut()>);
+///     });
 ///
 ///     // Next we pass this via reference to the `setInterval` function, and
 ///     // `setInterval` gets a handle to the corresponding JS closure.
@@ -113,9 +119,9 @@ use crate::UnwrapThrowExt;
 ///
 /// #[wasm_bindgen]
 /// pub fn run() -> Result<IntervalHandle, JsValue> {
-///     let cb = Closure::wrap(Box::new(|| {
+///     let cb = Closure::<FnMut()>::new(|| {
 ///         web_sys::console::log_1(&"inverval elapsed!".into());
-///     }) as Box<FnMut()>);
+///     });
 ///
 ///     let window = web_sys::window().unwrap();
 ///     let interval_id = window.set_interval_with_callback_and_timeout_and_arguments_0(
@@ -241,8 +247,15 @@ use crate::UnwrapThrowExt;
 /// }
 /// ```
 pub struct Closure<T: ?Sized> {
+    inner: Rc<ClosureInner<T>>,
+}
+
+struct ClosureInner<T: ?Sized> {
     js: ManuallyDrop<JsValue>,
     data: ManuallyDrop<Box<T>>,
+    // Whether any clone was forgotten, in which case the closure is leaked
+    // rather than invalidated once the last clone is dropped.
+    forgotten: Cell<bool>,
 }
 
 union FatPtr<T: ?Sized> {
@@ -250,22 +263,26 @@ union FatPtr<T: ?Sized> {
     fields: (usize, usize),
 }
 
-impl<T> Closure<T>
-where
-    T: ?Sized + WasmClosure,
-{
+impl<T: ?Sized> Closure<T> {
     /// A more ergonomic version of `Closure::wrap` that does the boxing and
     /// cast to trait object for you.
     ///
-    /// *This method requires the `nightly` feature of the `wasm-bindgen` crate
-    /// to be enabled, meaning this is a nightly-only API. Users on stable
-    /// should use `Closure::wrap`.*
-    #[cfg(feature = "nightly")]
-    pub fn new<F>(t: F) -> Closure<T>
+    /// The type of the closure is usually inferred from where the `Closure`
+    /// is used, but can also be given explicitly:
+    ///
+    /// ```rust,ignore
+    /// use wasm_bindgen::prelude::*;
+    ///
+    /// let cb = Closure::<dyn FnMut(u32)>::new(|n| {
+    ///     // ...
+    /// });
+    /// ```
+    pub fn new<F, M>(t: F) -> Closure<T>
     where
-        F: Unsize<T> + 'static,
+        F: IntoWasmClosure<T, M> + 'static,
+        T: WasmClosure<M>,
     {
-        Closure::wrap(Box::new(t) as Box<T>)
+        Closure::wrap(F::unsize(Box::new(t)))
     }
 
     /// Creates a new instance of `Closure` from the provided boxed Rust
@@ -280,12 +297,25 @@ where
     /// * It must be `'static`, aka no stack references (use the `move`
     ///   keyword).
     ///
-    /// * It can have at most 7 arguments.
+    /// * It can have at most 12 arguments.
     ///
     /// * Its arguments and return values are all types that can be shared with
     ///   JS (i.e. have `#[wasm_bindgen]` annotations or are simple numbers,
-    ///   etc.)
-    pub fn wrap(mut data: Box<T>) -> Closure<T> {
+    ///   etc.) A closure with a single argument may also take it by reference,
+    ///   like `&str` or `&[u8]`, in which case it's borrowed for the duration
+    ///   of the call rather than copied into a `String` or `Vec`. Taking it by
+    ///   mutable reference, like `&mut [f32]`, copies any modifications back
+    ///   into the typed array JS passed once the closure returns.
+    ///
+    /// * It may return `Result<T, JsValue>`, in which case returning `Err`
+    ///   throws the error from the JS function, like for exported functions.
+    ///
+    /// Closures which don't capture anything are zero-sized and don't allocate
+    /// any memory, and only their function pointer is passed to JS.
+    pub fn wrap<M>(mut data: Box<T>) -> Closure<T>
+    where
+        T: WasmClosure<M>,
+    {
         assert_eq!(mem::size_of::<*const T>(), mem::size_of::<FatPtr<T>>());
         let (a, b) = unsafe {
             FatPtr {
@@ -316,11 +346,13 @@ where
         // * First, it finds all functions that call
         //   `__wbindgen_describe_closure`. These are all `breaks_if_inlined`
         //   defined below as the symbol isn't called anywhere else.
-        // * Next, `wasm-bindgen` executes the `breaks_if_inlined`
-        //   monomorphized functions, passing it dummy arguments. This will
-        //   execute the function just enough to invoke the special import,
-        //   namely telling us about the function pointer that is the describe
-        //   shim.
+        // * Next, `wasm-bindgen` looks for the function pointer that is the
+        //   describe shim. If the call is directly passed a constant (which
+        //   is the case even when LTO inlines `breaks_if_inlined` into its
+        //   callers) that constant is used. Otherwise `wasm-bindgen` executes
+        //   the `breaks_if_inlined` monomorphized functions, passing it dummy
+        //   arguments. This will execute the function just enough to invoke
+        //   the special import.
         // * This knowledge is then used to actually find the descriptor in the
         //   function table which is then executed to figure out the signature
         //   of the closure.
@@ -338,21 +370,36 @@ where
         // See crates/cli-support/src/js/closures.rs for a more information
         // about what's going on here.
 
-        extern "C" fn describe<T: WasmClosure + ?Sized>() {
+        extern "C" fn describe<T: WasmClosure<M> + ?Sized, M>() {
             inform(CLOSURE);
             T::describe()
         }
 
         #[inline(never)]
-        unsafe fn breaks_if_inlined<T: WasmClosure + ?Sized>(a: usize, b: usize) -> u32 {
-            super::__wbindgen_describe_closure(a as u32, b as u32, describe::<T> as u32)
+        unsafe fn breaks_if_inlined<T: WasmClosure<M> + ?Sized, M>(a: usize, b: usize) -> u32 {
+            super::__wbindgen_describe_closure(
+                a as WasmWord,
+                b as WasmWord,
+                describe::<T, M> as u32,
+            )
         }
 
-        let idx = unsafe { breaks_if_inlined::<T>(a, b) };
+        // Closures which capture nothing have no environment on the heap, and
+        // their data pointer is always the dangling pointer 1, so only the
+        // vtable holding their function pointer is passed. A zero data pointer
+        // tells the JS glue to fill in the dangling pointer itself.
+        let stateless = mem::size_of_val(&*data) == 0 && mem::align_of_val(&*data) == 1;
+        debug_assert!(!stateless || a == 1);
+        let a = if stateless { 0 } else { a };
+
+        let idx = unsafe { breaks_if_inlined::<T, M>(a, b) };
 
         Closure {
-            js: ManuallyDrop::new(JsValue::_new(idx)),
-            data: ManuallyDrop::new(data),
+            inner: Rc::new(ClosureInner {
+                js: ManuallyDrop::new(JsValue::_new(idx)),
+                data: ManuallyDrop::new(data),
+                forgotten: Cell::new(false),
+            }),
         }
     }
 
@@ -367,11 +414,74 @@ where
     /// the entire duration of the program, so this can be used to conveniently
     /// leak this instance of `Closure` while performing as much internal
     /// cleanup as it can.
+    ///
+    /// Forgetting any one clone of a `Closure` leaks the shared closure, so
+    /// dropping the remaining clones will no longer invalidate it. They can
+    /// still be used until they're dropped as well.
+    ///
+    /// When the `weak-closures` feature of this crate is enabled, or the
+    /// bindings are generated with `wasm-bindgen --weak-closures`, the closure
+    /// isn't leaked after all in JS engines which support
+    /// `FinalizationRegistry`, but is instead destroyed once the JS function
+    /// is garbage collected after the last clone is dropped.
     pub fn forget(self) {
-        unsafe {
-            super::__wbindgen_cb_forget(self.js.idx);
-            mem::forget(self);
-        }
+        // The closure is handed over to JS when the last clone is dropped, so
+        // the remaining clones keep their reference to the JS function.
+        self.inner.forgotten.set(true);
+    }
+
+    /// Leaks this `Closure` like `forget`, but returns the underlying JS
+    /// function so it can still be passed around, for example to be stored on
+    /// a JS object or registered as a callback for the rest of the program.
+    ///
+    /// > **Note**: this function will leak memory unless the `weak-closures`
+    /// > feature or `--weak-closures` is used, see `forget`.
+    ///
+    /// ```rust,ignore
+    /// use wasm_bindgen::prelude::*;
+    ///
+    /// let f: JsValue = Closure::wrap(Box::new(|| {
+    ///     // ...
+    /// }) as Box<dyn FnMut()>)
+    /// .into_js_value();
+    /// ```
+    pub fn into_js_value(self) -> JsValue {
+        let js = (*self.inner.js).clone();
+        self.forget();
+        js
+    }
+
+    /// Passes a closure borrowing data from the stack to JS functions which
+    /// only call it synchronously, like `Array.prototype.forEach`.
+    ///
+    /// Since `f` isn't `'static` it can't be kept in a `Closure` like usual.
+    /// Instead `body` is called with a `ScopedClosure` which can be passed to
+    /// JS, and the JS function is invalidated as soon as `body` returns, just
+    /// like when a `Closure` is dropped. Calling it after that throws an
+    /// exception.
+    ///
+    /// ```rust,ignore
+    /// use wasm_bindgen::prelude::*;
+    ///
+    /// #[wasm_bindgen]
+    /// extern "C" {
+    ///     // Calls `f` with each number in some JS array.
+    ///     fn for_each_number(f: &ScopedClosure<dyn FnMut(u32)>);
+    /// }
+    ///
+    /// let mut sum = 0;
+    /// Closure::scoped(|x: u32| sum += x, |f| for_each_number(f));
+    /// ```
+    pub fn scoped<'a, F, R, M>(f: F, body: impl FnOnce(&ScopedClosure<'a, T>) -> R) -> R
+    where
+        F: IntoScopedClosure<'a, T>,
+        T: WasmClosure<M>,
+    {
+        let scoped = ScopedClosure {
+            closure: Closure::wrap(f.into_scoped_box()),
+            _marker: PhantomData,
+        };
+        body(&scoped)
     }
 }
 
@@ -382,10 +492,16 @@ impl Closure<dyn FnOnce()> {
     /// Create a `Closure` from a function that can only be called once.
     ///
     /// Since we have no way of enforcing that JS cannot attempt to call this
-    /// `FnOne(A...) -> R` more than once, this produces a `Closure<FnMut(A...)
+    /// `FnOnce(A...) -> R` more than once, this produces a `Closure<FnMut(A...)
     /// -> R>` that will dynamically throw a JavaScript error if called more
     /// than once.
     ///
+    /// Functions taking up to twelve arguments are supported, or a single
+    /// argument by shared or mutable reference. Everything the `FnOnce` closes
+    /// over is dropped as soon as it's called, while the JS function itself
+    /// stays valid (and throws when called) until the returned `Closure` is
+    /// dropped.
+    ///
     /// # Example
     ///
     /// ```rust,no_run
@@ -406,9 +522,10 @@ impl Closure<dyn FnOnce()> {
     /// // is `FnMut`, even though `f` is `FnOnce`.
     /// let closure: Closure<FnMut() -> String> = Closure::once(f);
     /// ```
-    pub fn once<F, A, R>(fn_once: F) -> Closure<F::FnMut>
+    pub fn once<F, A, R, M>(fn_once: F) -> Closure<F::FnMut>
     where
         F: 'static + WasmClosureFnOnce<A, R>,
+        F::FnMut: WasmClosure<M>,
     {
         Closure::wrap(fn_once.into_fn_mut())
     }
@@ -446,37 +563,101 @@ impl Closure<dyn FnOnce()> {
 /// will throw if ever called more than once.
 #[doc(hidden)]
 pub trait WasmClosureFnOnce<A, R>: 'static {
-    type FnMut: ?Sized + 'static + WasmClosure;
+    type FnMut: ?Sized + 'static;
 
     fn into_fn_mut(self) -> Box<Self::FnMut>;
 
     fn into_js_function(self) -> JsValue;
 }
 
+/// A trait for converting a boxed Rust closure into the boxed trait object
+/// `Box<T>` of a `Closure<T>`, see `Closure::new`.
+///
+/// This trait is not stable and it's not recommended to use this in bounds or
+/// implement yourself.
+#[doc(hidden)]
+pub trait IntoWasmClosure<T: ?Sized, M = ()> {
+    fn unsize(self: Box<Self>) -> Box<T>;
+}
+
+/// A trait for Rust closures which can be passed to JS as a `Closure<T>`
+/// borrowing data for `'a`, see `Closure::scoped`.
+///
+/// This trait is not stable and it's not recommended to use this in bounds or
+/// implement yourself.
+#[doc(hidden)]
+pub unsafe trait IntoScopedClosure<'a, T: ?Sized> {
+    fn into_scoped_box(self) -> Box<T>;
+}
+
+/// A JS function backed by a Rust closure which borrows data from the stack,
+/// created with `Closure::scoped`.
+///
+/// Unlike a `Closure` this can't be cloned or leaked, so the Rust closure is
+/// guaranteed to be invalidated before the data it borrows goes away. It can
+/// be passed by reference to imports, or converted to a `JsValue` through
+/// `AsRef`.
+pub struct ScopedClosure<'a, T: ?Sized> {
+    closure: Closure<T>,
+    _marker: PhantomData<&'a mut ()>,
+}
+
+impl<'a, T: ?Sized> AsRef<JsValue> for ScopedClosure<'a, T> {
+    fn as_ref(&self) -> &JsValue {
+        self.closure.as_ref()
+    }
+}
+
+impl<'a, T: ?Sized> WasmDescribe for ScopedClosure<'a, T> {
+    fn describe() {
+        inform(ANYREF);
+    }
+}
+
+impl<'a, 'b, T: ?Sized> IntoWasmAbi for &'b ScopedClosure<'a, T> {
+    type Abi = u32;
+
+    fn into_abi(self) -> u32 {
+        (&self.closure).into_abi()
+    }
+}
+
+impl<'a, T: ?Sized> fmt::Debug for ScopedClosure<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ScopedClosure {{ ... }}")
+    }
+}
+
 impl<T: ?Sized> AsRef<JsValue> for Closure<T> {
     fn as_ref(&self) -> &JsValue {
-        &self.js
+        &self.inner.js
     }
 }
 
-impl<T> WasmDescribe for Closure<T>
-where
-    T: WasmClosure + ?Sized,
-{
+impl<T: ?Sized> WasmDescribe for Closure<T> {
     fn describe() {
         inform(ANYREF);
     }
 }
 
-// `Closure` can only be passed by reference to imports.
-impl<'a, T> IntoWasmAbi for &'a Closure<T>
-where
-    T: WasmClosure + ?Sized,
-{
+// `Closure` can be passed by reference to imports, in which case Rust keeps
+// ownership of it.
+impl<'a, T: ?Sized> IntoWasmAbi for &'a Closure<T> {
     type Abi = u32;
 
     fn into_abi(self) -> u32 {
-        (&*self.js).into_abi()
+        (&*self.inner.js).into_abi()
+    }
+}
+
+// Passing a `Closure` by value hands it over to JS, which is how closures are
+// returned from exports or from other closures. JS has no way of telling Rust
+// when it's done with the function, so it's leaked as with `Closure::forget`.
+impl<T: ?Sized> IntoWasmAbi for Closure<T> {
+    type Abi = u32;
+
+    fn into_abi(self) -> u32 {
+        self.into_js_value().into_abi()
     }
 }
 
@@ -488,6 +669,9 @@ fn _check() {
     _assert::<&Closure<dyn FnMut()>>();
     _assert::<&Closure<dyn FnMut(String)>>();
     _assert::<&Closure<dyn FnMut() -> String>>();
+    _assert::<Closure<dyn FnMut()>>();
+    _assert::<&ScopedClosure<dyn FnMut(u32)>>();
+    _assert::<Closure<dyn FnMut(u32) -> Closure<dyn FnMut(u32) -> u32>>>();
 }
 
 impl<T> fmt::Debug for Closure<T>
@@ -499,12 +683,35 @@ where
     }
 }
 
-impl<T> Drop for Closure<T>
+impl<T> Clone for Closure<T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Closure<T> {
+        Closure {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Drop for ClosureInner<T>
 where
     T: ?Sized,
 {
     fn drop(&mut self) {
         unsafe {
+            // A forgotten closure stays valid, and the boxed closure is left
+            // for JS to destroy, if ever. Our strong reference is handed over
+            // to JS as well.
+            if self.forgotten.get() {
+                if cfg!(feature = "weak-closures") {
+                    super::__wbindgen_cb_forget_weak(self.js.idx);
+                } else {
+                    super::__wbindgen_cb_forget(self.js.idx);
+                }
+                return;
+            }
+
             // this will implicitly drop our strong reference in addition to
             // invalidating all future invocations of the closure
             if super::__wbindgen_cb_drop(self.js.idx) != 0 {
@@ -516,13 +723,30 @@ where
 
 /// An internal trait for the `Closure` type.
 ///
+/// The `M` parameter only tells apart the impls for closures taking a single
+/// argument by reference, see `RefArgument`.
+///
 /// This trait is not stable and it's not recommended to use this in bounds or
 /// implement yourself.
 #[doc(hidden)]
-pub unsafe trait WasmClosure {
+pub unsafe trait WasmClosure<M = ()> {
     fn describe();
 }
 
+/// Marker for the `WasmClosure` and `IntoWasmClosure` impls of closures taking
+/// a single argument by reference, like `dyn FnMut(&str)`.
+///
+/// Such closures are higher-ranked over the lifetime of their argument, so
+/// without a marker of their own these impls would overlap the ones for
+/// closures taking any single argument by value.
+#[doc(hidden)]
+pub struct RefArgument;
+
+/// Marker for the `WasmClosure` and `IntoWasmClosure` impls of closures taking
+/// a JS function as their single argument, like `dyn FnMut(&dyn Fn(u32))`.
+#[doc(hidden)]
+pub struct FnArgument;
+
 // The memory safety here in these implementations below is a bit tricky. We
 // want to be able to drop the `Closure` object from within the invocation of a
 // `Closure` for cases like promises. That means that while it's running we
@@ -659,7 +883,6 @@ macro_rules! doit {
             }
 
             fn into_js_function(self) -> JsValue {
-                use std::rc::Rc;
                 use crate::__rt::WasmRefCell;
 
                 let mut me = Some(self);
@@ -704,6 +927,10 @@ doit! {
     (A B C D E F)
     (A B C D E F G)
     (A B C D E F G H)
+    (A B C D E F G H I)
+    (A B C D E F G H I J)
+    (A B C D E F G H I J K)
+    (A B C D E F G H I J K L)
 }
 
 // Copy the above impls down here for where there's only one argument and it's a
@@ -712,13 +939,13 @@ doit! {
 // just this one! Maybe someone else can figure out voodoo so we don't have to
 // duplicate.
 
-unsafe impl<A, R> WasmClosure for dyn Fn(&A) -> R
-    where A: RefFromWasmAbi,
+unsafe impl<A, R> WasmClosure<RefArgument> for dyn Fn(&A) -> R
+    where A: RefFromWasmAbi + ?Sized,
           R: ReturnWasmAbi + 'static,
 {
     fn describe() {
         #[allow(non_snake_case)]
-        unsafe extern "C" fn invoke<A: RefFromWasmAbi, R: ReturnWasmAbi>(
+        unsafe extern "C" fn invoke<A: RefFromWasmAbi + ?Sized, R: ReturnWasmAbi>(
             a: usize,
             b: usize,
             arg: <A as RefFromWasmAbi>::Abi,
@@ -740,7 +967,7 @@ unsafe impl<A, R> WasmClosure for dyn Fn(&A) -> R
 
         inform(invoke::<A, R> as u32);
 
-        unsafe extern fn destroy<A: RefFromWasmAbi, R: ReturnWasmAbi>(
+        unsafe extern fn destroy<A: RefFromWasmAbi + ?Sized, R: ReturnWasmAbi>(
             a: usize,
             b: usize,
         ) {
@@ -754,17 +981,17 @@ unsafe impl<A, R> WasmClosure for dyn Fn(&A) -> R
         }
         inform(destroy::<A, R> as u32);
 
-        <&Self>::describe();
+        <&Self as RefArgStackClosure>::describe();
     }
 }
 
-unsafe impl<A, R> WasmClosure for dyn FnMut(&A) -> R
-    where A: RefFromWasmAbi,
+unsafe impl<A, R> WasmClosure<RefArgument> for dyn FnMut(&A) -> R
+    where A: RefFromWasmAbi + ?Sized,
           R: ReturnWasmAbi + 'static,
 {
     fn describe() {
         #[allow(non_snake_case)]
-        unsafe extern "C" fn invoke<A: RefFromWasmAbi, R: ReturnWasmAbi>(
+        unsafe extern "C" fn invoke<A: RefFromWasmAbi + ?Sized, R: ReturnWasmAbi>(
             a: usize,
             b: usize,
             arg: <A as RefFromWasmAbi>::Abi,
@@ -787,7 +1014,7 @@ unsafe impl<A, R> WasmClosure for dyn FnMut(&A) -> R
 
         inform(invoke::<A, R> as u32);
 
-        unsafe extern fn destroy<A: RefFromWasmAbi, R: ReturnWasmAbi>(
+        unsafe extern fn destroy<A: RefFromWasmAbi + ?Sized, R: ReturnWasmAbi>(
             a: usize,
             b: usize,
         ) {
@@ -801,14 +1028,14 @@ unsafe impl<A, R> WasmClosure for dyn FnMut(&A) -> R
         }
         inform(destroy::<A, R> as u32);
 
-        <&mut Self>::describe();
+        <&mut Self as RefArgStackClosure>::describe();
     }
 }
 
 #[allow(non_snake_case)]
 impl<T, A, R> WasmClosureFnOnce<(&A,), R> for T
     where T: 'static + FnOnce(&A) -> R,
-          A: RefFromWasmAbi + 'static,
+          A: RefFromWasmAbi + ?Sized + 'static,
           R: ReturnWasmAbi + 'static
 {
     type FnMut = dyn FnMut(&A) -> R;
@@ -822,7 +1049,6 @@ impl<T, A, R> WasmClosureFnOnce<(&A,), R> for T
     }
 
     fn into_js_function(self) -> JsValue {
-        use std::rc::Rc;
         use crate::__rt::WasmRefCell;
 
         let mut me = Some(self);
@@ -854,3 +1080,440 @@ impl<T, A, R> WasmClosureFnOnce<(&A,), R> for T
         js_val
     }
 }
+
+// And the same again for a single mutable reference argument, like
+// `&mut [u8]`. The generated JS copies a typed array passed to the closure into
+// wasm memory, and copies any modifications back into it once the closure
+// returns. These are implemented per slice type, rather than for any
+// `RefMutFromWasmAbi` type, so they can't overlap the single argument impls
+// above.
+
+macro_rules! mut_slice_closures {
+    ($($t:ident)*) => ($(
+        unsafe impl<R> WasmClosure<RefArgument> for dyn Fn(&mut [$t]) -> R
+            where R: ReturnWasmAbi + 'static,
+        {
+            fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                    arg: <[$t] as RefMutFromWasmAbi>::Abi,
+                ) -> <R as ReturnWasmAbi>::Abi {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Make sure all stack variables are converted before we
+                    // convert `ret` as it may throw (for `Result`, for
+                    // example)
+                    let ret = {
+                        let f: *const dyn Fn(&mut [$t]) -> R =
+                            FatPtr { fields: (a, b) }.ptr;
+                        let arg = <[$t] as RefMutFromWasmAbi>::ref_mut_from_abi(arg);
+                        (*f)(&mut *arg)
+                    };
+                    ret.return_abi()
+                }
+
+                inform(invoke::<R> as u32);
+
+                unsafe extern fn destroy<R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                ) {
+                    // See `Fn()` above for why we simply return
+                    if a == 0 {
+                        return;
+                    }
+                    drop(Box::from_raw(FatPtr::<dyn Fn(&mut [$t]) -> R> {
+                        fields: (a, b)
+                    }.ptr));
+                }
+                inform(destroy::<R> as u32);
+
+                <&Self as RefArgStackClosure>::describe();
+            }
+        }
+
+        unsafe impl<R> WasmClosure<RefArgument> for dyn FnMut(&mut [$t]) -> R
+            where R: ReturnWasmAbi + 'static,
+        {
+            fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                    arg: <[$t] as RefMutFromWasmAbi>::Abi,
+                ) -> <R as ReturnWasmAbi>::Abi {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Make sure all stack variables are converted before we
+                    // convert `ret` as it may throw (for `Result`, for
+                    // example)
+                    let ret = {
+                        let f: *const dyn FnMut(&mut [$t]) -> R =
+                            FatPtr { fields: (a, b) }.ptr;
+                        let f = f as *mut dyn FnMut(&mut [$t]) -> R;
+                        let arg = <[$t] as RefMutFromWasmAbi>::ref_mut_from_abi(arg);
+                        (*f)(&mut *arg)
+                    };
+                    ret.return_abi()
+                }
+
+                inform(invoke::<R> as u32);
+
+                unsafe extern fn destroy<R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                ) {
+                    // See `Fn()` above for why we simply return
+                    if a == 0 {
+                        return;
+                    }
+                    drop(Box::from_raw(FatPtr::<dyn FnMut(&mut [$t]) -> R> {
+                        fields: (a, b)
+                    }.ptr));
+                }
+                inform(destroy::<R> as u32);
+
+                <&mut Self as RefArgStackClosure>::describe();
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<T, R> WasmClosureFnOnce<(&mut [$t],), R> for T
+            where T: 'static + FnOnce(&mut [$t]) -> R,
+                  R: ReturnWasmAbi + 'static
+        {
+            type FnMut = dyn FnMut(&mut [$t]) -> R;
+
+            fn into_fn_mut(self) -> Box<Self::FnMut> {
+                let mut me = Some(self);
+                Box::new(move |arg| {
+                    let me = me.take().expect_throw("FnOnce called more than once");
+                    me(arg)
+                })
+            }
+
+            fn into_js_function(self) -> JsValue {
+                use crate::__rt::WasmRefCell;
+
+                let mut me = Some(self);
+
+                let rc1 = Rc::new(WasmRefCell::new(None));
+                let rc2 = rc1.clone();
+
+                let closure = Closure::wrap(Box::new(move |arg: &mut [$t]| {
+                    // Invoke ourself and get the result.
+                    let me = me.take().expect_throw("FnOnce called more than once");
+                    let result = me(arg);
+
+                    // And then drop the `Rc` holding this function's `Closure`
+                    // alive.
+                    debug_assert_eq!(Rc::strong_count(&rc2), 1);
+                    let option_closure = rc2.borrow_mut().take();
+                    debug_assert!(option_closure.is_some());
+                    drop(option_closure);
+
+                    result
+                }) as Box<dyn FnMut(&mut [$t]) -> R>);
+
+                let js_val = closure.as_ref().clone();
+
+                *rc1.borrow_mut() = Some(closure);
+                debug_assert_eq!(Rc::strong_count(&rc1), 2);
+                drop(rc1);
+
+                js_val
+            }
+        }
+
+        impl<T, R> IntoWasmClosure<dyn Fn(&mut [$t]) -> R, RefArgument> for T
+        where
+            T: Fn(&mut [$t]) -> R + 'static,
+        {
+            fn unsize(self: Box<Self>) -> Box<dyn Fn(&mut [$t]) -> R> {
+                self
+            }
+        }
+
+        impl<T, R> IntoWasmClosure<dyn FnMut(&mut [$t]) -> R, RefArgument> for T
+        where
+            T: FnMut(&mut [$t]) -> R + 'static,
+        {
+            fn unsize(self: Box<Self>) -> Box<dyn FnMut(&mut [$t]) -> R> {
+                self
+            }
+        }
+    )*)
+}
+
+mut_slice_closures! {
+    u8 i8 u16 i16 u32 i32 u64 i64 usize isize f32 f64
+}
+
+// And once more for closures taking a JS function as their only argument, like
+// `dyn FnMut(&dyn Fn(u32))`. The JS function is borrowed for the duration of
+// the call, and calling it converts its arguments into `JsValue`s and ignores
+// whatever it returns.
+
+fn call_function(f: &JsValue, args: Box<[JsValue]>) {
+    unsafe {
+        drop(JsValue::_new(super::__wbindgen_function_call(
+            f.idx,
+            args.into_abi(),
+        )));
+    }
+}
+
+macro_rules! fn_argument_closures {
+    ($(
+        ($cnt:tt $($var:ident)*)
+    )*) => ($(
+        unsafe impl<$($var,)* R> WasmClosure<FnArgument> for dyn Fn(&dyn Fn($($var),*)) -> R
+            where $($var: Into<JsValue> + WasmDescribe + 'static,)*
+                  R: ReturnWasmAbi + 'static,
+        {
+            fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<$($var: Into<JsValue>,)* R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                    arg: u32,
+                ) -> <R as ReturnWasmAbi>::Abi {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Make sure all stack variables are converted before we
+                    // convert `ret` as it may throw (for `Result`, for
+                    // example)
+                    let ret = {
+                        let f: *const dyn Fn(&dyn Fn($($var),*)) -> R =
+                            FatPtr { fields: (a, b) }.ptr;
+                        let arg = <JsValue as RefFromWasmAbi>::ref_from_abi(arg);
+                        (*f)(&|$($var),*| call_function(&arg, vec![$($var.into()),*].into_boxed_slice()))
+                    };
+                    ret.return_abi()
+                }
+
+                inform(invoke::<$($var,)* R> as u32);
+
+                unsafe extern "C" fn destroy<$($var,)* R>(
+                    a: usize,
+                    b: usize,
+                ) {
+                    // See `Fn()` above for why we simply return
+                    if a == 0 {
+                        return;
+                    }
+                    drop(Box::from_raw(FatPtr::<dyn Fn(&dyn Fn($($var),*)) -> R> {
+                        fields: (a, b)
+                    }.ptr));
+                }
+                inform(destroy::<$($var,)* R> as u32);
+
+                inform(REF);
+                // The argument is described as a reference to a function
+                // without a shim, which the JS glue passes to Rust like a
+                // borrowed `JsValue`.
+                inform(FUNCTION);
+                inform(0);
+                inform(1);
+                inform(REF);
+                inform(FUNCTION);
+                inform(0);
+                inform($cnt);
+                $(<$var as WasmDescribe>::describe();)*
+                <() as WasmDescribe>::describe();
+                <R as WasmDescribe>::describe();
+            }
+        }
+
+        unsafe impl<$($var,)* R> WasmClosure<FnArgument> for dyn FnMut(&dyn Fn($($var),*)) -> R
+            where $($var: Into<JsValue> + WasmDescribe + 'static,)*
+                  R: ReturnWasmAbi + 'static,
+        {
+            fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<$($var: Into<JsValue>,)* R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                    arg: u32,
+                ) -> <R as ReturnWasmAbi>::Abi {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Make sure all stack variables are converted before we
+                    // convert `ret` as it may throw (for `Result`, for
+                    // example)
+                    let ret = {
+                        let f: *const dyn FnMut(&dyn Fn($($var),*)) -> R =
+                            FatPtr { fields: (a, b) }.ptr;
+                        let f = f as *mut dyn FnMut(&dyn Fn($($var),*)) -> R;
+                        let arg = <JsValue as RefFromWasmAbi>::ref_from_abi(arg);
+                        (*f)(&|$($var),*| call_function(&arg, vec![$($var.into()),*].into_boxed_slice()))
+                    };
+                    ret.return_abi()
+                }
+
+                inform(invoke::<$($var,)* R> as u32);
+
+                unsafe extern "C" fn destroy<$($var,)* R>(
+                    a: usize,
+                    b: usize,
+                ) {
+                    // See `Fn()` above for why we simply return
+                    if a == 0 {
+                        return;
+                    }
+                    drop(Box::from_raw(FatPtr::<dyn FnMut(&dyn Fn($($var),*)) -> R> {
+                        fields: (a, b)
+                    }.ptr));
+                }
+                inform(destroy::<$($var,)* R> as u32);
+
+                inform(REFMUT);
+                // See `Fn` above
+                inform(FUNCTION);
+                inform(0);
+                inform(1);
+                inform(REF);
+                inform(FUNCTION);
+                inform(0);
+                inform($cnt);
+                $(<$var as WasmDescribe>::describe();)*
+                <() as WasmDescribe>::describe();
+                <R as WasmDescribe>::describe();
+            }
+        }
+
+        impl<T, $($var,)* R> IntoWasmClosure<dyn Fn(&dyn Fn($($var),*)) -> R, FnArgument> for T
+            where T: Fn(&dyn Fn($($var),*)) -> R + 'static,
+        {
+            fn unsize(self: Box<Self>) -> Box<dyn Fn(&dyn Fn($($var),*)) -> R> {
+                self
+            }
+        }
+
+        impl<T, $($var,)* R> IntoWasmClosure<dyn FnMut(&dyn Fn($($var),*)) -> R, FnArgument> for T
+            where T: FnMut(&dyn Fn($($var),*)) -> R + 'static,
+        {
+            fn unsize(self: Box<Self>) -> Box<dyn FnMut(&dyn Fn($($var),*)) -> R> {
+                self
+            }
+        }
+    )*)
+}
+
+fn_argument_closures! {
+    (0)
+    (1 A)
+    (2 A B)
+    (3 A B C)
+}
+
+// These allow `Closure::new` to do the unsizing coercion to a trait object
+// which `Closure::wrap` leaves to the caller, without relying on the unstable
+// `Unsize` trait.
+macro_rules! unsize {
+    ($(
+        ($($var:ident)*)
+    )*) => ($(
+        impl<T, $($var,)* R> IntoWasmClosure<dyn Fn($($var),*) -> R> for T
+            where T: Fn($($var),*) -> R + 'static,
+                  $($var: FromWasmAbi + 'static,)*
+        {
+            fn unsize(self: Box<Self>) -> Box<dyn Fn($($var),*) -> R> {
+                self
+            }
+        }
+
+        impl<T, $($var,)* R> IntoWasmClosure<dyn FnMut($($var),*) -> R> for T
+            where T: FnMut($($var),*) -> R + 'static,
+                  $($var: FromWasmAbi + 'static,)*
+        {
+            fn unsize(self: Box<Self>) -> Box<dyn FnMut($($var),*) -> R> {
+                self
+            }
+        }
+    )*)
+}
+
+unsize! {
+    ()
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
+    (A B C D E F G H I)
+    (A B C D E F G H I J)
+    (A B C D E F G H I J K)
+    (A B C D E F G H I J K L)
+}
+
+impl<T, A, R> IntoWasmClosure<dyn Fn(&A) -> R, RefArgument> for T
+where
+    T: Fn(&A) -> R + 'static,
+    A: ?Sized,
+{
+    fn unsize(self: Box<Self>) -> Box<dyn Fn(&A) -> R> {
+        self
+    }
+}
+
+impl<T, A, R> IntoWasmClosure<dyn FnMut(&A) -> R, RefArgument> for T
+where
+    T: FnMut(&A) -> R + 'static,
+    A: ?Sized,
+{
+    fn unsize(self: Box<Self>) -> Box<dyn FnMut(&A) -> R> {
+        self
+    }
+}
+
+// `Closure::scoped` invalidates the closure before `'a` ends, so the
+// lifetime of the boxed closure can be erased to fit in a `Closure`.
+macro_rules! scoped {
+    ($(
+        ($($var:ident)*)
+    )*) => ($(
+        unsafe impl<'a, T, $($var,)* R> IntoScopedClosure<'a, dyn Fn($($var),*) -> R> for T
+            where T: Fn($($var),*) -> R + 'a,
+        {
+            fn into_scoped_box(self) -> Box<dyn Fn($($var),*) -> R> {
+                let data: Box<dyn Fn($($var),*) -> R + 'a> = Box::new(self);
+                unsafe { mem::transmute(data) }
+            }
+        }
+
+        unsafe impl<'a, T, $($var,)* R> IntoScopedClosure<'a, dyn FnMut($($var),*) -> R> for T
+            where T: FnMut($($var),*) -> R + 'a,
+        {
+            fn into_scoped_box(self) -> Box<dyn FnMut($($var),*) -> R> {
+                let data: Box<dyn FnMut($($var),*) -> R + 'a> = Box::new(self);
+                unsafe { mem::transmute(data) }
+            }
+        }
+    )*)
+}
+
+scoped! {
+    ()
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
+    (A B C D E F G H I)
+    (A B C D E F G H I J)
+    (A B C D E F G H I J K)
+    (A B C D E F G H I J K L)
+}
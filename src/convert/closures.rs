@@ -1,13 +1,42 @@
 use core::mem;
 
 use crate::convert::slices::WasmSlice;
-use crate::convert::RefFromWasmAbi;
-use crate::convert::{FromWasmAbi, IntoWasmAbi, ReturnWasmAbi};
-use crate::describe::{inform, WasmDescribe, FUNCTION};
+use crate::convert::WasmWord;
+use crate::convert::{FromWasmAbi, IntoWasmAbi, ReturnWasmAbi, WasmAbi};
+use crate::convert::{RefFromWasmAbi, RefMutFromWasmAbi};
+use crate::describe::{inform, WasmDescribe, FUNCTION, REF};
 use crate::throw_str;
 
+/// A closure which can be called at most once, passed to JS by mutable
+/// reference for the duration of a call to an imported function.
+///
+/// This is implemented for `Option<F>` where `F` is an `FnOnce`, and `A` is a
+/// tuple of the closure's arguments. The closure is taken out of the `Option`
+/// the first time it's called from JS, and JS receives an exception if it
+/// calls it again. Like other stack closures, the JS function is invalidated
+/// once the imported function returns, so stashing it away and calling it
+/// later also throws an exception.
+///
+/// Closures taking a single reference argument are passed as
+/// `&mut dyn for<'a> StackFnOnce<(&'a T,), R>`.
+///
+/// ```rust,ignore
+/// #[wasm_bindgen]
+/// extern "C" {
+///     fn call_once(f: &mut dyn StackFnOnce<(u32,), String>);
+/// }
+///
+/// let s = String::from("hello");
+/// call_once(&mut Some(move |n| s.repeat(n as usize)));
+/// ```
+pub trait StackFnOnce<A, R> {
+    /// Invokes the closure if it hasn't been called yet, returning `None`
+    /// otherwise.
+    fn call_once_on_stack(&mut self, args: A) -> Option<R>;
+}
+
 macro_rules! stack_closures {
-    ($( ($cnt:tt $invoke:ident $invoke_mut:ident $($var:ident)*) )*) => ($(
+    ($( ($cnt:tt $invoke:ident $invoke_mut:ident $invoke_once:ident $($var:ident)*) )*) => ($(
         impl<'a, 'b, $($var,)* R> IntoWasmAbi for &'a (dyn Fn($($var),*) -> R + 'b)
             where $($var: FromWasmAbi,)*
                   R: ReturnWasmAbi
@@ -17,7 +46,7 @@ macro_rules! stack_closures {
             fn into_abi(self) -> WasmSlice {
                 unsafe {
                     let (a, b): (usize, usize) = mem::transmute(self);
-                    WasmSlice { ptr: a as u32, len: b as u32 }
+                    WasmSlice { ptr: a as WasmWord, len: b as WasmWord }
                 }
             }
         }
@@ -65,7 +94,7 @@ macro_rules! stack_closures {
             fn into_abi(self) -> WasmSlice {
                 unsafe {
                     let (a, b): (usize, usize) = mem::transmute(self);
-                    WasmSlice { ptr: a as u32, len: b as u32 }
+                    WasmSlice { ptr: a as WasmWord, len: b as WasmWord }
                 }
             }
         }
@@ -103,24 +132,112 @@ macro_rules! stack_closures {
                 <R as WasmDescribe>::describe();
             }
         }
+
+        // `T` rather than `F` since `F` is the name of the sixth argument.
+        #[allow(non_snake_case)]
+        impl<T, $($var,)* R> StackFnOnce<($($var,)*), R> for Option<T>
+            where T: FnOnce($($var),*) -> R
+        {
+            fn call_once_on_stack(&mut self, args: ($($var,)*)) -> Option<R> {
+                let ($($var,)*) = args;
+                self.take().map(|f| f($($var),*))
+            }
+        }
+
+        impl<'a, 'b, $($var,)* R> IntoWasmAbi for &'a mut (dyn StackFnOnce<($($var,)*), R> + 'b)
+            where $($var: FromWasmAbi,)*
+                  R: ReturnWasmAbi
+        {
+            type Abi = WasmSlice;
+
+            fn into_abi(self) -> WasmSlice {
+                unsafe {
+                    let (a, b): (usize, usize) = mem::transmute(self);
+                    WasmSlice { ptr: a as WasmWord, len: b as WasmWord }
+                }
+            }
+        }
+
+        #[allow(non_snake_case)]
+        unsafe extern "C" fn $invoke_once<$($var: FromWasmAbi,)* R: ReturnWasmAbi>(
+            a: usize,
+            b: usize,
+            $($var: <$var as FromWasmAbi>::Abi),*
+        ) -> <R as ReturnWasmAbi>::Abi {
+            if a == 0 {
+                throw_str(
+                    "`FnOnce` closure invoked recursively or after the \
+                     function it was passed to returned",
+                );
+            }
+            // Scope all local variables before we call `return_abi` to
+            // ensure they're all destroyed as `return_abi` may throw
+            let ret = {
+                let f: &mut dyn StackFnOnce<($($var,)*), R> = mem::transmute((a, b));
+                $(
+                    let $var = <$var as FromWasmAbi>::from_abi($var);
+                )*
+                f.call_once_on_stack(($($var,)*))
+            };
+            match ret {
+                Some(ret) => ret.return_abi(),
+                None => throw_str("`FnOnce` closure invoked more than once"),
+            }
+        }
+
+        impl<'a, $($var,)* R> WasmDescribe for dyn StackFnOnce<($($var,)*), R> + 'a
+            where $($var: FromWasmAbi,)*
+                  R: ReturnWasmAbi
+        {
+            fn describe() {
+                inform(FUNCTION);
+                inform($invoke_once::<$($var,)* R> as u32);
+                inform($cnt);
+                $(<$var as WasmDescribe>::describe();)*
+                <R as WasmDescribe>::describe();
+            }
+        }
     )*)
 }
 
 stack_closures! {
-    (0 invoke0 invoke0_mut)
-    (1 invoke1 invoke1_mut A)
-    (2 invoke2 invoke2_mut A B)
-    (3 invoke3 invoke3_mut A B C)
-    (4 invoke4 invoke4_mut A B C D)
-    (5 invoke5 invoke5_mut A B C D E)
-    (6 invoke6 invoke6_mut A B C D E F)
-    (7 invoke7 invoke7_mut A B C D E F G)
-    (8 invoke8 invoke8_mut A B C D E F G H)
+    (0 invoke0 invoke0_mut invoke0_once)
+    (1 invoke1 invoke1_mut invoke1_once A)
+    (2 invoke2 invoke2_mut invoke2_once A B)
+    (3 invoke3 invoke3_mut invoke3_once A B C)
+    (4 invoke4 invoke4_mut invoke4_once A B C D)
+    (5 invoke5 invoke5_mut invoke5_once A B C D E)
+    (6 invoke6 invoke6_mut invoke6_once A B C D E F)
+    (7 invoke7 invoke7_mut invoke7_once A B C D E F G)
+    (8 invoke8 invoke8_mut invoke8_once A B C D E F G H)
+    (9 invoke9 invoke9_mut invoke9_once A B C D E F G H I)
+    (10 invoke10 invoke10_mut invoke10_once A B C D E F G H I J)
+    (11 invoke11 invoke11_mut invoke11_once A B C D E F G H I J K)
+    (12 invoke12 invoke12_mut invoke12_once A B C D E F G H I J K L)
 }
 
-impl<'a, 'b, A, R> IntoWasmAbi for &'a (dyn Fn(&A) -> R + 'b)
+/// A stack closure taking a single argument by reference, like
+/// `&dyn Fn(&str)`, passed to an imported function.
+///
+/// Such closures are higher-ranked over the lifetime of their argument, so
+/// `IntoWasmAbi` and `WasmDescribe` impls for them would overlap the ones for
+/// closures taking a single argument by value above. `#[wasm_bindgen]` instead
+/// converts and describes arguments of these types through this trait.
+///
+/// This trait is not stable and it's not recommended to use this in bounds or
+/// implement yourself.
+#[doc(hidden)]
+pub trait RefArgStackClosure {
+    type Abi: WasmAbi;
+
+    fn into_abi(self) -> Self::Abi;
+
+    fn describe();
+}
+
+impl<'a, 'b, A, R> RefArgStackClosure for &'a (dyn Fn(&A) -> R + 'b)
 where
-    A: RefFromWasmAbi,
+    A: RefFromWasmAbi + ?Sized,
     R: ReturnWasmAbi,
 {
     type Abi = WasmSlice;
@@ -129,15 +246,24 @@ where
         unsafe {
             let (a, b): (usize, usize) = mem::transmute(self);
             WasmSlice {
-                ptr: a as u32,
-                len: b as u32,
+                ptr: a as WasmWord,
+                len: b as WasmWord,
             }
         }
     }
+
+    fn describe() {
+        inform(REF);
+        inform(FUNCTION);
+        inform(invoke1_ref::<A, R> as u32);
+        inform(1);
+        <&A as WasmDescribe>::describe();
+        <R as WasmDescribe>::describe();
+    }
 }
 
 #[allow(non_snake_case)]
-unsafe extern "C" fn invoke1_ref<A: RefFromWasmAbi, R: ReturnWasmAbi>(
+unsafe extern "C" fn invoke1_ref<A: RefFromWasmAbi + ?Sized, R: ReturnWasmAbi>(
     a: usize,
     b: usize,
     arg: <A as RefFromWasmAbi>::Abi,
@@ -155,23 +281,9 @@ unsafe extern "C" fn invoke1_ref<A: RefFromWasmAbi, R: ReturnWasmAbi>(
     ret.return_abi()
 }
 
-impl<'a, A, R> WasmDescribe for dyn Fn(&A) -> R + 'a
-where
-    A: RefFromWasmAbi,
-    R: ReturnWasmAbi,
-{
-    fn describe() {
-        inform(FUNCTION);
-        inform(invoke1_ref::<A, R> as u32);
-        inform(1);
-        <&A as WasmDescribe>::describe();
-        <R as WasmDescribe>::describe();
-    }
-}
-
-impl<'a, 'b, A, R> IntoWasmAbi for &'a mut (dyn FnMut(&A) -> R + 'b)
+impl<'a, 'b, A, R> RefArgStackClosure for &'a mut (dyn FnMut(&A) -> R + 'b)
 where
-    A: RefFromWasmAbi,
+    A: RefFromWasmAbi + ?Sized,
     R: ReturnWasmAbi,
 {
     type Abi = WasmSlice;
@@ -180,15 +292,24 @@ where
         unsafe {
             let (a, b): (usize, usize) = mem::transmute(self);
             WasmSlice {
-                ptr: a as u32,
-                len: b as u32,
+                ptr: a as WasmWord,
+                len: b as WasmWord,
             }
         }
     }
+
+    fn describe() {
+        inform(REF);
+        inform(FUNCTION);
+        inform(invoke1_mut_ref::<A, R> as u32);
+        inform(1);
+        <&A as WasmDescribe>::describe();
+        <R as WasmDescribe>::describe();
+    }
 }
 
 #[allow(non_snake_case)]
-unsafe extern "C" fn invoke1_mut_ref<A: RefFromWasmAbi, R: ReturnWasmAbi>(
+unsafe extern "C" fn invoke1_mut_ref<A: RefFromWasmAbi + ?Sized, R: ReturnWasmAbi>(
     a: usize,
     b: usize,
     arg: <A as RefFromWasmAbi>::Abi,
@@ -206,16 +327,146 @@ unsafe extern "C" fn invoke1_mut_ref<A: RefFromWasmAbi, R: ReturnWasmAbi>(
     ret.return_abi()
 }
 
-impl<'a, A, R> WasmDescribe for dyn FnMut(&A) -> R + 'a
+// Closures taking a mutable slice are implemented per slice type, rather than
+// for any `RefMutFromWasmAbi` type, so they can't overlap the impls above.
+macro_rules! mut_slice_stack_closures {
+    ($($t:ident)*) => ($(
+        impl<'a, 'b, R> RefArgStackClosure for &'a (dyn Fn(&mut [$t]) -> R + 'b)
+            where R: ReturnWasmAbi
+        {
+            type Abi = WasmSlice;
+
+            fn into_abi(self) -> WasmSlice {
+                unsafe {
+                    let (a, b): (usize, usize) = mem::transmute(self);
+                    WasmSlice { ptr: a as WasmWord, len: b as WasmWord }
+                }
+            }
+
+            fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                    arg: <[$t] as RefMutFromWasmAbi>::Abi,
+                ) -> <R as ReturnWasmAbi>::Abi {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Scope all local variables before we call `return_abi` to
+                    // ensure they're all destroyed as `return_abi` may throw
+                    let ret = {
+                        let f: &dyn Fn(&mut [$t]) -> R = mem::transmute((a, b));
+                        let arg = <[$t] as RefMutFromWasmAbi>::ref_mut_from_abi(arg);
+                        f(&mut *arg)
+                    };
+                    ret.return_abi()
+                }
+
+                inform(REF);
+                inform(FUNCTION);
+                inform(invoke::<R> as u32);
+                inform(1);
+                <&mut [$t] as WasmDescribe>::describe();
+                <R as WasmDescribe>::describe();
+            }
+        }
+
+        impl<'a, 'b, R> RefArgStackClosure for &'a mut (dyn FnMut(&mut [$t]) -> R + 'b)
+            where R: ReturnWasmAbi
+        {
+            type Abi = WasmSlice;
+
+            fn into_abi(self) -> WasmSlice {
+                unsafe {
+                    let (a, b): (usize, usize) = mem::transmute(self);
+                    WasmSlice { ptr: a as WasmWord, len: b as WasmWord }
+                }
+            }
+
+            fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<R: ReturnWasmAbi>(
+                    a: usize,
+                    b: usize,
+                    arg: <[$t] as RefMutFromWasmAbi>::Abi,
+                ) -> <R as ReturnWasmAbi>::Abi {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Scope all local variables before we call `return_abi` to
+                    // ensure they're all destroyed as `return_abi` may throw
+                    let ret = {
+                        let f: &mut dyn FnMut(&mut [$t]) -> R = mem::transmute((a, b));
+                        let arg = <[$t] as RefMutFromWasmAbi>::ref_mut_from_abi(arg);
+                        f(&mut *arg)
+                    };
+                    ret.return_abi()
+                }
+
+                inform(REF);
+                inform(FUNCTION);
+                inform(invoke::<R> as u32);
+                inform(1);
+                <&mut [$t] as WasmDescribe>::describe();
+                <R as WasmDescribe>::describe();
+            }
+        }
+    )*)
+}
+
+mut_slice_stack_closures! {
+    u8 i8 u16 i16 u32 i32 u64 i64 usize isize f32 f64
+}
+
+impl<'a, 'b, A, R> RefArgStackClosure for &'a mut (dyn for<'c> StackFnOnce<(&'c A,), R> + 'b)
 where
-    A: RefFromWasmAbi,
+    A: RefFromWasmAbi + ?Sized,
     R: ReturnWasmAbi,
 {
+    type Abi = WasmSlice;
+
+    fn into_abi(self) -> WasmSlice {
+        unsafe {
+            let (a, b): (usize, usize) = mem::transmute(self);
+            WasmSlice {
+                ptr: a as WasmWord,
+                len: b as WasmWord,
+            }
+        }
+    }
+
     fn describe() {
+        inform(REF);
         inform(FUNCTION);
-        inform(invoke1_mut_ref::<A, R> as u32);
+        inform(invoke1_once_ref::<A, R> as u32);
         inform(1);
         <&A as WasmDescribe>::describe();
         <R as WasmDescribe>::describe();
     }
 }
+
+#[allow(non_snake_case)]
+unsafe extern "C" fn invoke1_once_ref<A: RefFromWasmAbi + ?Sized, R: ReturnWasmAbi>(
+    a: usize,
+    b: usize,
+    arg: <A as RefFromWasmAbi>::Abi,
+) -> <R as ReturnWasmAbi>::Abi {
+    if a == 0 {
+        throw_str(
+            "`FnOnce` closure invoked recursively or after the \
+             function it was passed to returned",
+        );
+    }
+    // Scope all local variables before we call `return_abi` to
+    // ensure they're all destroyed as `return_abi` may throw
+    let ret = {
+        let f: &mut dyn for<'c> StackFnOnce<(&'c A,), R> = mem::transmute((a, b));
+        let arg = <A as RefFromWasmAbi>::ref_from_abi(arg);
+        f.call_once_on_stack((&*arg,))
+    };
+    match ret {
+        Some(ret) => ret.return_abi(),
+        None => throw_str("`FnOnce` closure invoked more than once"),
+    }
+}
@@ -1,9 +1,10 @@
 use core::char;
 use core::mem::{self, ManuallyDrop};
 
-use crate::convert::traits::WasmAbi;
+use crate::convert::traits::{WasmAbi, WasmWord};
 use crate::convert::{FromWasmAbi, IntoWasmAbi, RefFromWasmAbi};
 use crate::convert::{OptionFromWasmAbi, OptionIntoWasmAbi, ReturnWasmAbi};
+use crate::describe::WasmDescribe;
 use crate::{Clamped, JsValue};
 
 unsafe impl WasmAbi for () {}
@@ -57,6 +58,16 @@ pub struct WasmOptional64 {
 
 unsafe impl WasmAbi for WasmOptional64 {}
 
+#[repr(C)]
+pub struct Wasm128 {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}
+
+unsafe impl WasmAbi for Wasm128 {}
+
 macro_rules! type_wasm_native {
     ($($t:tt as $c:tt => $r:tt)*) => ($(
         impl IntoWasmAbi for $t {
@@ -108,13 +119,17 @@ macro_rules! type_wasm_native {
 
 type_wasm_native!(
     i32 as i32 => WasmOptionalI32
-    isize as i32 => WasmOptionalI32
     u32 as u32 => WasmOptionalU32
-    usize as u32 => WasmOptionalU32
     f32 as f32 => WasmOptionalF32
     f64 as f64 => WasmOptionalF64
 );
 
+#[cfg(not(target_arch = "wasm64"))]
+type_wasm_native!(
+    isize as i32 => WasmOptionalI32
+    usize as u32 => WasmOptionalU32
+);
+
 macro_rules! type_abi_as_u32 {
     ($($t:tt)*) => ($(
         impl IntoWasmAbi for $t {
@@ -205,6 +220,84 @@ macro_rules! type_64 {
 
 type_64!(i64 u64);
 
+// Pointer-sized integers are passed like their 64-bit counterparts on targets
+// with 64-bit memories.
+#[cfg(target_arch = "wasm64")]
+macro_rules! type_pointer_width_64 {
+    ($($t:tt as $c:tt)*) => ($(
+        impl IntoWasmAbi for $t {
+            type Abi = Wasm64;
+
+            #[inline]
+            fn into_abi(self) -> Wasm64 {
+                (self as $c).into_abi()
+            }
+        }
+
+        impl FromWasmAbi for $t {
+            type Abi = Wasm64;
+
+            #[inline]
+            unsafe fn from_abi(js: Wasm64) -> $t {
+                $c::from_abi(js) as $t
+            }
+        }
+
+        impl IntoWasmAbi for Option<$t> {
+            type Abi = WasmOptional64;
+
+            #[inline]
+            fn into_abi(self) -> WasmOptional64 {
+                self.map(|me| me as $c).into_abi()
+            }
+        }
+
+        impl FromWasmAbi for Option<$t> {
+            type Abi = WasmOptional64;
+
+            #[inline]
+            unsafe fn from_abi(js: WasmOptional64) -> Self {
+                Option::<$c>::from_abi(js).map(|me| me as $t)
+            }
+        }
+    )*)
+}
+
+#[cfg(target_arch = "wasm64")]
+type_pointer_width_64!(isize as i64 usize as u64);
+
+macro_rules! type_128 {
+    ($($t:tt)*) => ($(
+        impl IntoWasmAbi for $t {
+            type Abi = Wasm128;
+
+            #[inline]
+            fn into_abi(self) -> Wasm128 {
+                Wasm128 {
+                    a: self as u32,
+                    b: (self >> 32) as u32,
+                    c: (self >> 64) as u32,
+                    d: (self >> 96) as u32,
+                }
+            }
+        }
+
+        impl FromWasmAbi for $t {
+            type Abi = Wasm128;
+
+            #[inline]
+            unsafe fn from_abi(js: Wasm128) -> $t {
+                $t::from(js.a)
+                    | ($t::from(js.b) << 32)
+                    | ($t::from(js.c) << 64)
+                    | ($t::from(js.d) << 96)
+            }
+        }
+    )*)
+}
+
+type_128!(i128 u128);
+
 impl IntoWasmAbi for bool {
     type Abi = u32;
 
@@ -270,37 +363,37 @@ impl OptionFromWasmAbi for char {
 }
 
 impl<T> IntoWasmAbi for *const T {
-    type Abi = u32;
+    type Abi = WasmWord;
 
     #[inline]
-    fn into_abi(self) -> u32 {
-        self as u32
+    fn into_abi(self) -> WasmWord {
+        self as WasmWord
     }
 }
 
 impl<T> FromWasmAbi for *const T {
-    type Abi = u32;
+    type Abi = WasmWord;
 
     #[inline]
-    unsafe fn from_abi(js: u32) -> *const T {
+    unsafe fn from_abi(js: WasmWord) -> *const T {
         js as *const T
     }
 }
 
 impl<T> IntoWasmAbi for *mut T {
-    type Abi = u32;
+    type Abi = WasmWord;
 
     #[inline]
-    fn into_abi(self) -> u32 {
-        self as u32
+    fn into_abi(self) -> WasmWord {
+        self as WasmWord
     }
 }
 
 impl<T> FromWasmAbi for *mut T {
-    type Abi = u32;
+    type Abi = WasmWord;
 
     #[inline]
-    unsafe fn from_abi(js: u32) -> *mut T {
+    unsafe fn from_abi(js: WasmWord) -> *mut T {
         js as *mut T
     }
 }
@@ -407,3 +500,16 @@ impl<T: IntoWasmAbi> ReturnWasmAbi for Result<T, JsValue> {
         }
     }
 }
+
+impl<T> ReturnWasmAbi for Option<Result<T, JsValue>>
+where
+    T: WasmDescribe,
+    Option<T>: IntoWasmAbi,
+{
+    type Abi = <Option<T> as IntoWasmAbi>::Abi;
+
+    #[inline]
+    fn return_abi(self) -> Self::Abi {
+        self.transpose().return_abi()
+    }
+}
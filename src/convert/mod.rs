@@ -1,11 +1,20 @@
 //! This is mostly an internal module, no stability guarantees are provided. Use
-//! at your own risk.
+//! at your own risk. The exception is the `WasmRepr` trait (along with the
+//! `wasm_repr!` macro) which is the supported way for other crates to pass
+//! their own types to and from JS.
 
 mod closures;
 mod impls;
+mod repr;
 mod slices;
+#[cfg(feature = "std-time")]
+mod time;
 mod traits;
+mod tuples;
 
+pub use self::closures::{RefArgStackClosure, StackFnOnce};
 pub use self::impls::*;
+pub use self::repr::WasmRepr;
 pub use self::slices::WasmSlice;
 pub use self::traits::*;
+pub use self::tuples::*;
@@ -0,0 +1,84 @@
+use crate::convert::{FromWasmAbi, IntoWasmAbi};
+use crate::describe::WasmDescribe;
+
+/// A type which is passed to and from JS through another type, its `Repr`,
+/// which can already cross the boundary.
+///
+/// Unlike the other traits in this module, this trait and the [`wasm_repr!`]
+/// macro are a stable extension point covered by semver, intended for
+/// downstream crates to pass their own types such as newtypes or small
+/// structs. After implementing this trait, invoke `wasm_repr!` with the type
+/// to implement the (unstable) traits used by `#[wasm_bindgen]` in terms of
+/// it:
+///
+/// ```
+/// use wasm_bindgen::convert::WasmRepr;
+///
+/// pub struct UserId(u32);
+///
+/// impl WasmRepr for UserId {
+///     type Repr = u32;
+///
+///     fn into_repr(self) -> u32 {
+///         self.0
+///     }
+///
+///     fn from_repr(repr: u32) -> UserId {
+///         UserId(repr)
+///     }
+/// }
+///
+/// wasm_bindgen::wasm_repr!(UserId);
+/// ```
+///
+/// `UserId` can then be used as an argument or return value of exported and
+/// imported functions, showing up in JS and TypeScript as a `number`.
+///
+/// [`wasm_repr!`]: ../macro.wasm_repr.html
+pub trait WasmRepr: Sized {
+    /// The type `Self` is represented as when passed to and from JS.
+    type Repr: IntoWasmAbi + FromWasmAbi + WasmDescribe;
+
+    /// Converts `self` into its representation to pass it to JS.
+    fn into_repr(self) -> Self::Repr;
+
+    /// Recovers a `Self` from its representation after receiving it from JS.
+    fn from_repr(repr: Self::Repr) -> Self;
+}
+
+/// Implements the traits used by `#[wasm_bindgen]` to pass a type to and from
+/// JS in terms of its [`WasmRepr`] implementation.
+///
+/// [`WasmRepr`]: convert/trait.WasmRepr.html
+#[macro_export]
+macro_rules! wasm_repr {
+    ($($ty:ty),* $(,)?) => ($(
+        impl $crate::describe::WasmDescribe for $ty {
+            fn describe() {
+                <<$ty as $crate::convert::WasmRepr>::Repr as $crate::describe::WasmDescribe>::describe()
+            }
+        }
+
+        impl $crate::convert::IntoWasmAbi for $ty {
+            type Abi = <<$ty as $crate::convert::WasmRepr>::Repr as $crate::convert::IntoWasmAbi>::Abi;
+
+            #[inline]
+            fn into_abi(self) -> Self::Abi {
+                $crate::convert::IntoWasmAbi::into_abi(
+                    <$ty as $crate::convert::WasmRepr>::into_repr(self),
+                )
+            }
+        }
+
+        impl $crate::convert::FromWasmAbi for $ty {
+            type Abi = <<$ty as $crate::convert::WasmRepr>::Repr as $crate::convert::FromWasmAbi>::Abi;
+
+            #[inline]
+            unsafe fn from_abi(js: Self::Abi) -> Self {
+                <$ty as $crate::convert::WasmRepr>::from_repr(
+                    $crate::convert::FromWasmAbi::from_abi(js),
+                )
+            }
+        }
+    )*)
+}
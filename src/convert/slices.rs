@@ -5,6 +5,7 @@ use core::slice;
 use core::str;
 
 use crate::convert::OptionIntoWasmAbi;
+use crate::convert::WasmWord;
 use crate::convert::{FromWasmAbi, IntoWasmAbi, RefFromWasmAbi, RefMutFromWasmAbi, WasmAbi};
 use cfg_if::cfg_if;
 
@@ -15,8 +16,8 @@ if_std! {
 
 #[repr(C)]
 pub struct WasmSlice {
-    pub ptr: u32,
-    pub len: u32,
+    pub ptr: WasmWord,
+    pub len: WasmWord,
 }
 
 unsafe impl WasmAbi for WasmSlice {}
@@ -39,7 +40,7 @@ macro_rules! vectors {
                     mem::forget(self);
                     WasmSlice {
                         ptr: ptr.into_abi(),
-                        len: len as u32,
+                        len: len as WasmWord,
                     }
                 }
             }
@@ -73,7 +74,7 @@ macro_rules! vectors {
             fn into_abi(self) -> WasmSlice {
                 WasmSlice {
                     ptr: self.as_ptr().into_abi(),
-                    len: self.len() as u32,
+                    len: self.len() as WasmWord,
                 }
             }
         }
@@ -245,7 +246,7 @@ if_std! {
             mem::forget(self);
             WasmSlice {
                 ptr: ptr.into_abi(),
-                len: len as u32,
+                len: len as WasmWord,
             }
         }
     }
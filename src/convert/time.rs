@@ -0,0 +1,85 @@
+//! Conversions of `std::time` types, enabled by the `std-time` feature.
+//!
+//! Durations are passed to and from JS as a number of milliseconds, and
+//! `SystemTime`s as a number of milliseconds since the Unix epoch, matching
+//! timer APIs like `setTimeout` and the representation used by `Date`.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::convert::{FromWasmAbi, IntoWasmAbi};
+use crate::describe::{inform, WasmDescribe, F64};
+
+fn duration_to_millis(d: Duration) -> f64 {
+    d.as_secs() as f64 * 1000.0 + f64::from(d.subsec_nanos()) / 1_000_000.0
+}
+
+fn millis_to_duration(ms: f64) -> Duration {
+    // Negative and `NaN` values have no `Duration` equivalent, so they're
+    // clamped to zero much like `setTimeout` does with its delay.
+    if !(ms > 0.0) {
+        return Duration::from_secs(0);
+    }
+    match Duration::try_from_secs_f64(ms / 1000.0) {
+        Ok(d) => d,
+        Err(_) => crate::throw_str("duration is too large to convert to a `Duration`"),
+    }
+}
+
+impl WasmDescribe for Duration {
+    fn describe() {
+        inform(F64)
+    }
+}
+
+impl IntoWasmAbi for Duration {
+    type Abi = f64;
+
+    #[inline]
+    fn into_abi(self) -> f64 {
+        duration_to_millis(self)
+    }
+}
+
+impl FromWasmAbi for Duration {
+    type Abi = f64;
+
+    #[inline]
+    unsafe fn from_abi(js: f64) -> Duration {
+        millis_to_duration(js)
+    }
+}
+
+impl WasmDescribe for SystemTime {
+    fn describe() {
+        inform(F64)
+    }
+}
+
+impl IntoWasmAbi for SystemTime {
+    type Abi = f64;
+
+    #[inline]
+    fn into_abi(self) -> f64 {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(d) => duration_to_millis(d),
+            Err(e) => -duration_to_millis(e.duration()),
+        }
+    }
+}
+
+impl FromWasmAbi for SystemTime {
+    type Abi = f64;
+
+    #[inline]
+    unsafe fn from_abi(js: f64) -> SystemTime {
+        let time = if js >= 0.0 {
+            UNIX_EPOCH.checked_add(millis_to_duration(js))
+        } else {
+            UNIX_EPOCH.checked_sub(millis_to_duration(-js))
+        };
+        match time {
+            Some(time) => time,
+            None => crate::throw_str("time can't be represented as a `SystemTime`"),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use core::ops::{Deref, DerefMut};
 
 use crate::describe::*;
+use crate::JsValue;
 
 /// A trait for anything that can be converted into a type that can cross the
 /// wasm ABI directly, eg `u32` or `f64`.
@@ -103,6 +104,19 @@ unsafe impl WasmAbi for i32 {}
 unsafe impl WasmAbi for f32 {}
 unsafe impl WasmAbi for f64 {}
 
+/// The type pointers and lengths are passed across the wasm ABI as, which is
+/// as wide as the target's addresses.
+#[cfg(not(target_arch = "wasm64"))]
+pub type WasmWord = u32;
+
+/// The type pointers and lengths are passed across the wasm ABI as, which is
+/// as wide as the target's addresses.
+#[cfg(target_arch = "wasm64")]
+pub type WasmWord = u64;
+
+#[cfg(target_arch = "wasm64")]
+unsafe impl WasmAbi for u64 {}
+
 /// A trait representing how to interepret the return value of a function for
 /// the wasm ABI.
 ///
@@ -127,3 +141,20 @@ impl<T: IntoWasmAbi> ReturnWasmAbi for T {
         self.into_abi()
     }
 }
+
+/// A trait implemented for every Rust type exported to JS as a class with
+/// `#[wasm_bindgen]`.
+///
+/// This is the exported-type counterpart of `JsCast::instanceof` and can be
+/// used to validate that a `JsValue` is a handle to an instance of `Self`
+/// before trusting it, for example when handles are passed between multiple
+/// wasm modules.
+pub trait ExportedClass {
+    /// Tests whether `val` is an instance of the JS class generated for this
+    /// type by this module.
+    ///
+    /// Instances of a class with the same name created by a *different* wasm
+    /// module are not considered instances, since their pointers refer to
+    /// another module's memory.
+    fn is_instance(val: &JsValue) -> bool;
+}
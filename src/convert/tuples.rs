@@ -0,0 +1,102 @@
+//! Conversions for small tuples of numbers and booleans, which JS sees as
+//! arrays.
+//!
+//! Each element of a tuple is passed as its own `f64` scalar, which can
+//! represent every supported element type exactly, so no memory is allocated
+//! to move a tuple across the boundary. JS sees `(1.0, 2.0)` as the array
+//! `[1, 2]`.
+//!
+//! Tuples are returned by value but can only be taken by reference, as in
+//! `&(f64, f64)`. A by-value `FromWasmAbi` impl would make a tuple argument
+//! indistinguishable from multiple arguments to the `WasmClosureFnOnce`
+//! impls.
+
+use core::mem::ManuallyDrop;
+
+use crate::convert::{IntoWasmAbi, RefFromWasmAbi, WasmAbi};
+use crate::describe::{inform, WasmDescribe, TUPLE};
+
+/// A scalar which can be an element of a tuple passed to or from JS.
+pub trait TupleElement: WasmDescribe + Copy {
+    /// Converts this element to the `f64` it's passed as.
+    fn into_f64(self) -> f64;
+
+    /// Recovers an element from the `f64` it was passed as.
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! tuple_elements {
+    ($($t:ident)*) => ($(
+        impl TupleElement for $t {
+            #[inline]
+            fn into_f64(self) -> f64 { self as f64 }
+
+            #[inline]
+            fn from_f64(value: f64) -> $t { value as $t }
+        }
+    )*)
+}
+
+tuple_elements!(i8 u8 i16 u16 i32 u32 f32 f64);
+
+impl TupleElement for bool {
+    #[inline]
+    fn into_f64(self) -> f64 {
+        if self {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    #[inline]
+    fn from_f64(value: f64) -> bool {
+        value != 0.0
+    }
+}
+
+macro_rules! tuples {
+    ($($abi:ident $len:tt ($($t:ident $f:ident)*))*) => ($(
+        #[repr(C)]
+        pub struct $abi {
+            $(pub $f: f64,)*
+        }
+
+        unsafe impl WasmAbi for $abi {}
+
+        impl<$($t: TupleElement,)*> WasmDescribe for ($($t,)*) {
+            fn describe() {
+                inform(TUPLE);
+                inform($len);
+                $($t::describe();)*
+            }
+        }
+
+        impl<$($t: TupleElement,)*> IntoWasmAbi for ($($t,)*) {
+            type Abi = $abi;
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn into_abi(self) -> $abi {
+                let ($($t,)*) = self;
+                $abi { $($f: $t.into_f64(),)* }
+            }
+        }
+
+        impl<$($t: TupleElement,)*> RefFromWasmAbi for ($($t,)*) {
+            type Abi = $abi;
+            type Anchor = ManuallyDrop<($($t,)*)>;
+
+            #[inline]
+            unsafe fn ref_from_abi(js: $abi) -> Self::Anchor {
+                ManuallyDrop::new(($($t::from_f64(js.$f),)*))
+            }
+        }
+    )*)
+}
+
+tuples! {
+    WasmTuple2 2 (A a B b)
+    WasmTuple3 3 (A a B b C c)
+    WasmTuple4 4 (A a B b C c D d)
+}
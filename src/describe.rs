@@ -43,6 +43,10 @@ tys! {
     OPTIONAL
     UNIT
     CLAMPED
+    I128
+    U128
+    PROMISE
+    TUPLE
 }
 
 #[inline(always)] // see `interpret.rs` in the the cli-support crate
@@ -71,8 +75,8 @@ simple! {
     u32 => U32
     i64 => I64
     u64 => U64
-    isize => I32
-    usize => U32
+    i128 => I128
+    u128 => U128
     f32 => F32
     f64 => F64
     bool => BOOLEAN
@@ -80,6 +84,18 @@ simple! {
     JsValue => ANYREF
 }
 
+#[cfg(not(target_arch = "wasm64"))]
+simple! {
+    isize => I32
+    usize => U32
+}
+
+#[cfg(target_arch = "wasm64")]
+simple! {
+    isize => I64
+    usize => U64
+}
+
 cfg_if! {
     if #[cfg(feature = "enable-interning")] {
         simple! {
@@ -170,7 +186,8 @@ impl WasmDescribe for () {
 }
 
 // Note that this is only for `ReturnWasmAbi for Result<T, JsValue>`, which
-// throws the result, so we only need to inform about the `T`.
+// throws the result, so we only need to inform about the `T`. This also means
+// that `Option<Result<T, JsValue>>` is described just like `Option<T>`.
 impl<T: WasmDescribe> WasmDescribe for Result<T, JsValue> {
     fn describe() {
         T::describe()
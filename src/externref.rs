@@ -0,0 +1,57 @@
+//! Low-level management of the slots which hold JS values referenced by Rust.
+//!
+//! Every `JsValue` is an index into a table of JS values maintained by the JS
+//! glue generated by `wasm-bindgen` (or into the wasm `anyref` table when
+//! reference types are enabled). The functions in this module expose these
+//! indices directly, for advanced users writing their own binding layers on
+//! top of raw wasm imports and exports. Most code should use `JsValue`, which
+//! manages its slot automatically.
+//!
+//! Slot indices are only meaningful to the module they came from: passing
+//! one to a different wasm module's glue or reading a slot after it has been
+//! freed is undefined behavior.
+
+use core::mem;
+
+use crate::JsValue;
+
+/// Moves `value` into a slot owned by the caller, returning the slot's index.
+///
+/// The slot stays allocated until it's passed to [`free`] or [`take`].
+pub fn alloc(value: JsValue) -> u32 {
+    let idx = value.idx;
+    mem::forget(value);
+    idx
+}
+
+/// Returns a new `JsValue` referencing the same JS value as the slot `idx`,
+/// which remains allocated.
+///
+/// # Safety
+///
+/// `idx` must be a slot which is currently allocated, for example one
+/// returned by [`alloc`] which hasn't been freed yet.
+pub unsafe fn get(idx: u32) -> JsValue {
+    JsValue::_new(crate::__wbindgen_object_clone_ref(idx))
+}
+
+/// Takes ownership of the slot `idx`, returning a `JsValue` which frees it
+/// when dropped.
+///
+/// # Safety
+///
+/// `idx` must be a slot which is currently allocated and owned by the caller,
+/// and it must not be used again afterwards.
+pub unsafe fn take(idx: u32) -> JsValue {
+    JsValue::_new(idx)
+}
+
+/// Frees the slot `idx`, releasing the reference to the JS value it holds.
+///
+/// # Safety
+///
+/// `idx` must be a slot which is currently allocated and owned by the caller,
+/// and it must not be used again afterwards.
+pub unsafe fn free(idx: u32) {
+    drop(take(idx));
+}
@@ -0,0 +1,98 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+use crate::JsValue;
+
+/// A wrapper which compares and hashes a JS value by its identity.
+///
+/// `JsValue` itself doesn't implement `Hash` or `Eq`, as JS values don't have
+/// a natural hash and `NaN` isn't equal to itself. Wrapping a value in
+/// `Identity` makes it usable as a key in a `HashMap` or `HashSet`, which for
+/// example allows caching data per DOM node:
+///
+/// ```no_run
+/// use std::collections::HashMap;
+/// use wasm_bindgen::prelude::*;
+/// use wasm_bindgen::Identity;
+///
+/// # struct Layout;
+/// # fn compute_layout(_: &JsValue) -> Layout { Layout }
+/// fn layout<'a>(cache: &'a mut HashMap<Identity, Layout>, node: &JsValue) -> &'a Layout {
+///     cache
+///         .entry(Identity::new(node.clone()))
+///         .or_insert_with(|| compute_layout(node))
+/// }
+/// ```
+///
+/// Two identities are equal if their values are the same according to JS's
+/// `Object.is`. Objects and functions are hashed with an id which is assigned
+/// the first time they're hashed and stored in a JS `WeakMap`, so they aren't
+/// kept alive by being hashed. Primitives are hashed by value.
+///
+/// Note that an `Identity` holds on to its value, so the JS object stays alive
+/// for as long as it's used as a key.
+#[derive(Clone)]
+pub struct Identity<T = JsValue> {
+    value: T,
+}
+
+impl<T: AsRef<JsValue>> Identity<T> {
+    /// Wraps `value` so it's compared and hashed by its identity.
+    pub fn new(value: T) -> Identity<T> {
+        Identity { value }
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: AsRef<JsValue>> From<T> for Identity<T> {
+    fn from(value: T) -> Identity<T> {
+        Identity::new(value)
+    }
+}
+
+impl<T> Deref for Identity<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: AsRef<JsValue>> PartialEq for Identity<T> {
+    fn eq(&self, other: &Identity<T>) -> bool {
+        let a = self.value.as_ref();
+        let b = other.value.as_ref();
+        if a.as_f64().map_or(false, |n| n.is_nan()) {
+            return b.as_f64().map_or(false, |n| n.is_nan());
+        }
+        // `===` only differs from `Object.is` for `NaN`, handled above, and for
+        // the zeros, which `===` considers equal.
+        if a == b {
+            if let (Some(x), Some(y)) = (a.as_f64(), b.as_f64()) {
+                return x.is_sign_negative() == y.is_sign_negative();
+            }
+            return true;
+        }
+        false
+    }
+}
+
+impl<T: AsRef<JsValue>> Eq for Identity<T> {}
+
+impl<T: AsRef<JsValue>> Hash for Identity<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let id = unsafe { crate::__wbindgen_object_id(self.value.as_ref().idx) };
+        id.hash(state);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Identity<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Identity").field(&self.value).finish()
+    }
+}
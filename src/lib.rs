@@ -7,14 +7,13 @@
 
 #![no_std]
 #![doc(html_root_url = "https://docs.rs/wasm-bindgen/0.2")]
-#![cfg_attr(feature = "nightly", feature(unsize))]
 
 use core::fmt;
 use core::marker;
 use core::mem;
 use core::ops::{Deref, DerefMut};
 
-use crate::convert::{FromWasmAbi, WasmOptionalF64, WasmSlice};
+use crate::convert::{FromWasmAbi, IntoWasmAbi, WasmOptionalF64, WasmSlice, WasmWord};
 
 macro_rules! if_std {
     ($($i:item)*) => ($(
@@ -24,17 +23,17 @@ macro_rules! if_std {
 
 macro_rules! externs {
     ($(#[$attr:meta])* extern "C" { $(fn $name:ident($($args:tt)*) -> $ret:ty;)* }) => (
-        #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+        #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten")))]
         $(#[$attr])*
         extern "C" {
             $(fn $name($($args)*) -> $ret;)*
         }
 
         $(
-            #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+            #[cfg(not(all(any(target_arch = "wasm32", target_arch = "wasm64"), not(target_os = "emscripten"))))]
             #[allow(unused_variables)]
             unsafe extern fn $name($($args)*) -> $ret {
-                panic!("function not implemented on non-wasm32 targets")
+                panic!("function not implemented on non-wasm targets")
             }
         )*
     )
@@ -53,7 +52,7 @@ pub mod prelude {
     pub use wasm_bindgen_macro::wasm_bindgen;
 
     if_std! {
-        pub use crate::closure::Closure;
+        pub use crate::closure::{Closure, ScopedClosure};
     }
 }
 
@@ -63,14 +62,61 @@ pub mod describe;
 mod cast;
 pub use crate::cast::JsCast;
 
+mod identity;
+pub use crate::identity::Identity;
+
+mod weak;
+pub use crate::weak::JsWeakRef;
+
+mod try_from;
+pub use crate::try_from::TryFromJsValueError;
+
+pub mod schedule;
+
+pub mod externref;
+
+#[cfg(feature = "std-time")]
+pub mod time;
+
+/// Registers JS to run at the top of the generated JS module, before any
+/// bindings are used.
+///
+/// This is intended for polyfills, feature flags or environment detection
+/// that the bindings of a crate rely on. The JS can be given inline, or as the
+/// path of a local file relative to the crate root, like the `module`
+/// attribute:
+///
+/// ```ignore
+/// wasm_bindgen::js_prelude!("globalThis.TextDecoder ??= require('util').TextDecoder;");
+/// wasm_bindgen::js_prelude!(module = "/js/polyfills.js");
+/// ```
+///
+/// Preludes are inserted in the order they're found, and a prelude which is
+/// registered more than once is only inserted once.
+pub use wasm_bindgen_macro::js_prelude;
+
 if_std! {
     extern crate std;
     use std::prelude::v1::*;
     pub mod closure;
     mod anyref;
 
+    mod property;
+    pub use property::Property;
+
     mod cache;
     pub use cache::intern::{intern, unintern};
+    pub use schedule::{spawn_macrotask, spawn_microtask};
+
+    /// Returns the URL, as a `String`, of a file in this crate which is copied
+    /// into the output directory alongside the generated JS bindings.
+    ///
+    /// The path is relative to the crate root, like the `module` attribute:
+    ///
+    /// ```ignore
+    /// let url = wasm_bindgen::link_to!(module = "/src/worker.js");
+    /// ```
+    pub use wasm_bindgen_macro::link_to;
 }
 
 /// Representation of an object owned by JS.
@@ -263,7 +309,9 @@ impl JsValue {
     /// are encoded as UTF-8. This can cause the Rust string to look a bit
     /// different than the JS string sometimes. For more details see the
     /// [documentation about the `str` type][caveats] which contains a few
-    /// caveats about the encodings.
+    /// caveats about the encodings. In short, any unpaired surrogates in the
+    /// JS string are replaced with U+FFFD; use `as_utf16` for a strict or
+    /// lossless conversion.
     ///
     /// [caveats]: https://rustwasm.github.io/docs/wasm-bindgen/reference/types/str.html
     #[cfg(feature = "std")]
@@ -271,6 +319,20 @@ impl JsValue {
         unsafe { FromWasmAbi::from_abi(__wbindgen_string_get(self.idx)) }
     }
 
+    /// Returns the UTF-16 code units of this JS value if it's an instance of
+    /// a string.
+    ///
+    /// Unlike `as_string` this is lossless, as any unpaired surrogates are
+    /// kept rather than replaced with U+FFFD. Pass the result to
+    /// `String::from_utf16` to reject such strings instead.
+    ///
+    /// If this JS value is not an instance of a string then this returns
+    /// `None`.
+    #[cfg(feature = "std")]
+    pub fn as_utf16(&self) -> Option<Vec<u16>> {
+        unsafe { FromWasmAbi::from_abi(__wbindgen_string_get_utf16(self.idx)) }
+    }
+
     /// Returns the `bool` value of this JS value if it's an instance of a
     /// boolean.
     ///
@@ -332,6 +394,48 @@ impl JsValue {
         unsafe { __wbindgen_is_falsy(self.idx) == 1 }
     }
 
+    /// Looks up a path of properties, like `value?.a?.b?.c` in JS.
+    ///
+    /// Returns `None` if this value, any property along the path or the final
+    /// property is `null` or `undefined`. The whole path is looked up in a
+    /// single call into JS, which makes this cheaper than reading each
+    /// property in turn when probing deeply nested configuration objects:
+    ///
+    /// ```no_run
+    /// # use wasm_bindgen::JsValue;
+    /// # fn config() -> JsValue { JsValue::undefined() }
+    /// let verbose = config()
+    ///     .get_path(&["debug", "logging", "verbose"])
+    ///     .and_then(|v| v.as_bool())
+    ///     .unwrap_or(false);
+    /// ```
+    ///
+    /// Note that reading a property may run a getter, which can throw.
+    #[cfg(feature = "std")]
+    pub fn get_path(&self, path: &[&str]) -> Option<JsValue> {
+        use crate::convert::IntoWasmAbi;
+
+        // The keys are passed as one string along with the length of each key
+        // in UTF-16 code units, which is how JS slices it back apart.
+        let keys = path.concat();
+        let lens = path
+            .iter()
+            .map(|key| key.encode_utf16().count() as u32)
+            .collect::<Vec<_>>();
+        let ret = unsafe {
+            JsValue::_new(__wbindgen_object_get_path(
+                self.idx,
+                keys.as_bytes().into_abi(),
+                lens.as_slice().into_abi(),
+            ))
+        };
+        if ret.is_null() || ret.is_undefined() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+
     /// Get a string representation of the JavaScript object for debugging
     #[cfg(feature = "std")]
     fn as_debug_string(&self) -> String {
@@ -507,17 +611,37 @@ externs! {
         fn __wbindgen_number_get(idx: u32) -> WasmOptionalF64;
         fn __wbindgen_boolean_get(idx: u32) -> u32;
         fn __wbindgen_string_get(idx: u32) -> WasmSlice;
+        fn __wbindgen_bigint_get_as_string(idx: u32) -> WasmSlice;
+        fn __wbindgen_string_get_utf16(idx: u32) -> WasmSlice;
 
         fn __wbindgen_debug_string(ret: *mut [usize; 2], idx: u32) -> ();
 
         fn __wbindgen_throw(a: *const u8, b: usize) -> !;
         fn __wbindgen_rethrow(a: u32) -> !;
+        fn __wbindgen_try_catch(f: u32, this: u32, args: u32) -> u32;
+        fn __wbindgen_function_call(f: u32, args: WasmSlice) -> u32;
 
         fn __wbindgen_cb_drop(idx: u32) -> u32;
         fn __wbindgen_cb_forget(idx: u32) -> ();
+        fn __wbindgen_cb_forget_weak(idx: u32) -> ();
+
+        fn __wbindgen_queue_microtask(f: u32) -> ();
+        fn __wbindgen_queue_macrotask(f: u32) -> ();
+        fn __wbindgen_promise_then(value: u32, resolve: u32, reject: u32) -> ();
+
+        fn __wbindgen_performance_now() -> f64;
+        fn __wbindgen_date_now() -> f64;
+        fn __wbindgen_random_fill(buf: WasmSlice) -> u32;
 
         fn __wbindgen_describe(v: u32) -> ();
-        fn __wbindgen_describe_closure(a: u32, b: u32, c: u32) -> u32;
+        fn __wbindgen_describe_closure(a: WasmWord, b: WasmWord, c: u32) -> u32;
+
+        fn __wbindgen_object_id(idx: u32) -> u32;
+        fn __wbindgen_weak_ref_new(idx: u32) -> u32;
+        fn __wbindgen_weak_ref_deref(idx: u32) -> u32;
+        fn __wbindgen_object_get(idx: u32, key: WasmSlice) -> u32;
+        fn __wbindgen_object_set(idx: u32, key: WasmSlice, val: u32) -> ();
+        fn __wbindgen_object_get_path(idx: u32, keys: WasmSlice, lens: WasmSlice) -> u32;
 
         fn __wbindgen_json_parse(ptr: *const u8, len: usize) -> u32;
         fn __wbindgen_json_serialize(idx: u32) -> WasmSlice;
@@ -658,6 +782,52 @@ pub fn throw_val(s: JsValue) -> ! {
     }
 }
 
+/// Calls the JS function `f` with `this` as its receiver and the elements of
+/// the JS array `args` as its arguments, catching any exception it throws.
+/// If `args` is `undefined` then `f` is called without any arguments.
+///
+/// This behaves like `Reflect.apply(f, this, args)` wrapped in `try`/`catch`:
+/// if the call returns normally its return value is returned as `Ok`, and if
+/// it throws then the thrown value is returned as `Err`. Unlike imports
+/// annotated with `#[wasm_bindgen(catch)]` this works for any JS function,
+/// which makes it possible to tunnel errors through user-provided callbacks.
+/// For example if `f` calls back into Rust, which then uses [`throw_val`] to
+/// abort with a particular value, that value is returned here.
+///
+/// Note that, as with `throw_val`, the Rust frames between the thrown
+/// exception and this call are popped without running any destructors.
+pub fn try_catch(f: &JsValue, this: &JsValue, args: &JsValue) -> Result<JsValue, JsValue> {
+    unsafe {
+        let idx = __wbindgen_try_catch(f.idx, this.idx, args.idx);
+        __rt::take_last_exception()?;
+        Ok(JsValue::_new(idx))
+    }
+}
+
+/// Calls the Rust closure `f`, catching any JS exception thrown while it runs.
+///
+/// This is like [`try_catch`], but `f` can be arbitrary Rust code, so
+/// exceptions thrown by imports which aren't annotated with
+/// `#[wasm_bindgen(catch)]` can be recovered as well. If `f` returns normally
+/// its return value is returned as `Ok`, and if an exception is thrown from
+/// within it, for example by an import or by [`throw_val`], the thrown value
+/// is returned as `Err`.
+///
+/// As with [`try_catch`], the frames of `f` and anything it calls are popped
+/// without running any destructors when an exception is thrown.
+#[cfg(feature = "std")]
+pub fn try_catch_with<R>(f: impl FnOnce() -> R) -> Result<R, JsValue> {
+    use crate::closure::Closure;
+
+    let mut f = Some(f);
+    let mut ret = None;
+    Closure::<dyn FnMut()>::scoped(
+        || ret = Some((f.take().unwrap())()),
+        |closure| try_catch(closure.as_ref(), &JsValue::UNDEFINED, &JsValue::UNDEFINED),
+    )?;
+    Ok(ret.unwrap())
+}
+
 /// Get the count of live `anyref`s / `JsValue`s in `wasm-bindgen`'s heap.
 ///
 /// ## Usage
@@ -713,7 +883,7 @@ pub fn anyref_heap_live_count() -> u32 {
 /// `Option::unwrap` and `Option::expect` methods, but they are specific to
 /// working with wasm and JS.
 ///
-/// On non-wasm32 targets, defaults to the normal unwrap/expect calls.
+/// On non-wasm targets, defaults to the normal unwrap/expect calls.
 ///
 /// # Example
 ///
@@ -750,7 +920,10 @@ pub trait UnwrapThrowExt<T>: Sized {
 
 impl<T> UnwrapThrowExt<T> for Option<T> {
     fn expect_throw(self, message: &str) -> T {
-        if cfg!(all(target_arch = "wasm32", not(target_os = "emscripten"))) {
+        if cfg!(all(
+            any(target_arch = "wasm32", target_arch = "wasm64"),
+            not(target_os = "emscripten")
+        )) {
             match self {
                 Some(val) => val,
                 None => throw_str(message),
@@ -766,7 +939,10 @@ where
     E: core::fmt::Debug,
 {
     fn expect_throw(self, message: &str) -> T {
-        if cfg!(all(target_arch = "wasm32", not(target_os = "emscripten"))) {
+        if cfg!(all(
+            any(target_arch = "wasm32", target_arch = "wasm64"),
+            not(target_os = "emscripten")
+        )) {
             match self {
                 Ok(val) => val,
                 Err(_) => throw_str(message),
@@ -799,8 +975,84 @@ pub fn function_table() -> JsValue {
     unsafe { JsValue::_new(__wbindgen_function_table()) }
 }
 
+/// Fills `buf` with cryptographically secure random bytes.
+///
+/// This uses `crypto.getRandomValues` where it's available, and the `crypto`
+/// module otherwise in Node.js. An error is returned if neither is available,
+/// for example in some embedded JS engines.
+///
+/// This is also a convenient source of entropy for crates like `getrandom`
+/// which let the application provide their randomness:
+///
+/// ```ignore
+/// fn fill(buf: &mut [u8]) -> Result<(), getrandom::Error> {
+///     wasm_bindgen::random_fill(buf).map_err(|_| getrandom::Error::UNSUPPORTED)
+/// }
+///
+/// getrandom::register_custom_getrandom!(fill);
+/// ```
+pub fn random_fill(buf: &mut [u8]) -> Result<(), JsValue> {
+    let err = unsafe { JsValue::_new(__wbindgen_random_fill(buf.into_abi())) };
+    if err.is_undefined() {
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
+
+/// Reads a chain of properties from a JS object, like `obj.a.b.c` in JS.
+///
+/// The object can be a `JsValue` or any imported type, and expressions other
+/// than a plain identifier need to be wrapped in parentheses. This evaluates
+/// to a `Result<JsValue, JsValue>` which is an error if any object along the
+/// way is `undefined` or `null`. With the `enable-interning` feature the
+/// property names are interned so they're only sent to JS once.
+///
+/// ```ignore
+/// let name = wasm_bindgen::get_prop!(event.target.name)?;
+/// let width = wasm_bindgen::get_prop!((window()).screen.width)?;
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! get_prop {
+    ($obj:ident $(. $key:ident)+) => {
+        $crate::get_prop!(($obj) $(. $key)+)
+    };
+    (($obj:expr) $(. $key:ident)+) => {
+        $crate::__rt::get_path(
+            $crate::__rt::core::convert::AsRef::<$crate::JsValue>::as_ref(&$obj),
+            &[$(stringify!($key)),+],
+        )
+    };
+}
+
+/// Sets a property at the end of a chain of properties of a JS object, like
+/// `obj.a.b.c = value` in JS.
+///
+/// The value can be anything convertible into a `JsValue`. This evaluates to a
+/// `Result<(), JsValue>`, following the same rules as [`get_prop!`].
+///
+/// ```ignore
+/// wasm_bindgen::set_prop!(config.network.retries = 3)?;
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! set_prop {
+    ($obj:ident $(. $key:ident)+ = $val:expr) => {
+        $crate::set_prop!(($obj) $(. $key)+ = $val)
+    };
+    (($obj:expr) $(. $key:ident)+ = $val:expr) => {
+        $crate::__rt::set_path(
+            $crate::__rt::core::convert::AsRef::<$crate::JsValue>::as_ref(&$obj),
+            &[$(stringify!($key)),+],
+            $crate::JsValue::from($val),
+        )
+    };
+}
+
 #[doc(hidden)]
 pub mod __rt {
+    use crate::describe::{inform, ANYREF, UNIT};
     use crate::JsValue;
     use core::cell::{Cell, UnsafeCell};
     use core::ops::{Deref, DerefMut};
@@ -1079,24 +1331,204 @@ pub mod __rt {
         }
     }
 
+    /// Reads the property at the end of `path` on `obj`, following each key
+    /// in turn, used by the `get_prop!` macro.
+    #[cfg(feature = "std")]
+    pub fn get_path(obj: &JsValue, path: &[&str]) -> Result<JsValue, JsValue> {
+        let mut cur = get_prop(obj, path[0])?;
+        for key in path[1..].iter() {
+            cur = get_prop(&cur, key)?;
+        }
+        Ok(cur)
+    }
+
+    /// Sets the property at the end of `path` on `obj` to `val`, used by the
+    /// `set_prop!` macro.
+    #[cfg(feature = "std")]
+    pub fn set_path(obj: &JsValue, path: &[&str], val: JsValue) -> Result<(), JsValue> {
+        let (last, parents) = path.split_last().unwrap();
+        if parents.is_empty() {
+            return set_prop(obj, last, val);
+        }
+        set_prop(&get_path(obj, parents)?, last, val)
+    }
+
+    #[cfg(feature = "std")]
+    fn get_prop(obj: &JsValue, key: &str) -> Result<JsValue, JsValue> {
+        use crate::convert::IntoWasmAbi;
+        check_prop_base(obj, "read", key)?;
+        #[cfg(feature = "enable-interning")]
+        crate::intern(key);
+        unsafe {
+            let idx = super::__wbindgen_object_get(obj.idx, key.into_abi());
+            Ok(JsValue::_new(idx))
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn set_prop(obj: &JsValue, key: &str, val: JsValue) -> Result<(), JsValue> {
+        use crate::convert::IntoWasmAbi;
+        check_prop_base(obj, "set", key)?;
+        #[cfg(feature = "enable-interning")]
+        crate::intern(key);
+        unsafe {
+            super::__wbindgen_object_set(obj.idx, key.into_abi(), val.into_abi());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    fn check_prop_base(obj: &JsValue, action: &str, key: &str) -> Result<(), JsValue> {
+        let base = if obj.is_undefined() {
+            "undefined"
+        } else if obj.is_null() {
+            "null"
+        } else {
+            return Ok(());
+        };
+        Err(JsValue::from_str(&std::format!(
+            "cannot {} property `{}` of {}",
+            action, key, base
+        )))
+    }
+
+    /// Used by `catch` imports whose error type isn't `JsValue` to only catch
+    /// exceptions of that type. Anything else is rethrown, continuing to
+    /// propagate as if the import hadn't been marked `catch`.
+    pub fn catch_instanceof<T: crate::JsCast>(exn: super::JsValue) -> T {
+        use crate::JsCast;
+
+        match exn.dyn_into::<T>() {
+            Ok(exn) => exn,
+            Err(exn) => crate::throw_val(exn),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub use self::await_js::{await_js, resolved_into, AwaitJs};
+
+    /// Support for `async fn` imports, whose futures resolve along with the
+    /// value returned by the JS function.
+    #[cfg(feature = "std")]
+    mod await_js {
+        use crate::closure::Closure;
+        use crate::{JsCast, JsValue};
+        use core::future::Future;
+        use core::pin::Pin;
+        use core::task::{Context, Poll, Waker};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        /// The future returned by an imported `async fn`.
+        pub struct AwaitJs {
+            state: Rc<RefCell<State>>,
+        }
+
+        struct State {
+            result: Option<Result<JsValue, JsValue>>,
+            waker: Option<Waker>,
+            callbacks: Option<(Closure<dyn FnMut(JsValue)>, Closure<dyn FnMut(JsValue)>)>,
+        }
+
+        /// Awaits the value returned by an imported `async fn`.
+        ///
+        /// Values which aren't promises are resolved immediately, just like
+        /// `await` in JS would do.
+        pub fn await_js(value: JsValue) -> AwaitJs {
+            // JS calls one of the callbacks eventually, even if the future
+            // has been dropped by then, so they're only dropped once one of
+            // them is called, like in `wasm_bindgen_futures::JsFuture`.
+            let state = Rc::new(RefCell::new(State {
+                result: None,
+                waker: None,
+                callbacks: None,
+            }));
+
+            fn finish(state: &RefCell<State>, val: Result<JsValue, JsValue>) {
+                let waker = {
+                    let mut state = state.borrow_mut();
+                    drop(state.callbacks.take());
+                    state.result = Some(val);
+                    state.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+
+            let resolve = {
+                let state = state.clone();
+                Closure::once(move |val| finish(&state, Ok(val)))
+            };
+            let reject = {
+                let state = state.clone();
+                Closure::once(move |val| finish(&state, Err(val)))
+            };
+            unsafe {
+                crate::__wbindgen_promise_then(
+                    value.idx,
+                    resolve.as_ref().idx,
+                    reject.as_ref().idx,
+                );
+            }
+            state.borrow_mut().callbacks = Some((resolve, reject));
+            AwaitJs { state }
+        }
+
+        impl Future for AwaitJs {
+            type Output = Result<JsValue, JsValue>;
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+                let mut state = self.state.borrow_mut();
+                if let Some(val) = state.result.take() {
+                    return Poll::Ready(val);
+                }
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+
+        /// Casts the value an imported `async fn` resolved to into its
+        /// declared return type, failing if it's of another type.
+        pub fn resolved_into<T: JsCast>(value: JsValue) -> Result<T, JsValue> {
+            value.dyn_into::<T>().map_err(|_| {
+                JsValue::from_str(
+                    "the value returned by an `async` import resolved to a \
+                     different type than the import declares",
+                )
+            })
+        }
+    }
+
     /// An internal helper trait for usage in `#[wasm_bindgen]` on `async`
     /// functions to convert the return value of the function to
     /// `Result<JsValue, JsValue>` which is what we'll return to JS (where an
     /// error is a failed future).
     pub trait IntoJsResult {
         fn into_js_result(self) -> Result<JsValue, JsValue>;
+
+        /// Describes the value that the promise returned to JS resolves to.
+        fn describe_resolved();
     }
 
     impl IntoJsResult for () {
         fn into_js_result(self) -> Result<JsValue, JsValue> {
             Ok(JsValue::undefined())
         }
+
+        fn describe_resolved() {
+            inform(UNIT)
+        }
     }
 
     impl<T: Into<JsValue>> IntoJsResult for T {
         fn into_js_result(self) -> Result<JsValue, JsValue> {
             Ok(self.into())
         }
+
+        fn describe_resolved() {
+            inform(ANYREF)
+        }
     }
 
     impl<T: Into<JsValue>, E: Into<JsValue>> IntoJsResult for Result<T, E> {
@@ -1106,6 +1538,10 @@ pub mod __rt {
                 Err(e) => Err(e.into()),
             }
         }
+
+        fn describe_resolved() {
+            inform(ANYREF)
+        }
     }
 
     impl<E: Into<JsValue>> IntoJsResult for Result<(), E> {
@@ -1115,8 +1551,24 @@ pub mod __rt {
                 Err(e) => Err(e.into()),
             }
         }
+
+        fn describe_resolved() {
+            inform(UNIT)
+        }
     }
 
+    impl<T: Into<JsValue>, E: Into<JsValue>> IntoJsResult for Option<Result<T, E>> {
+        fn into_js_result(self) -> Result<JsValue, JsValue> {
+            match self {
+                Some(r) => r.into_js_result(),
+                None => Ok(JsValue::undefined()),
+            }
+        }
+
+        fn describe_resolved() {
+            inform(ANYREF)
+        }
+    }
 
     /// An internal helper trait for usage in `#[wasm_bindgen(start)]`
     /// functions to throw the error (if it is `Err`).
@@ -0,0 +1,124 @@
+//! Handles to properties of JS objects.
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+use std::prelude::v1::*;
+
+use crate::convert::IntoWasmAbi;
+use crate::JsValue;
+
+/// A handle to a property of a JS object, which is read with `get` and written
+/// with `set`.
+///
+/// Unlike a pair of imported `getter` and `setter` methods, a `Property` is a
+/// value of its own, so it can be stored and passed to generic code which
+/// only cares about reading and writing some value of type `T`.
+///
+/// These are usually created by imported methods marked with
+/// `#[wasm_bindgen(property)]`, which take the object as their only argument
+/// and return a `Property` instead of calling into JS:
+///
+/// ```no_run
+/// use wasm_bindgen::prelude::*;
+/// use wasm_bindgen::Property;
+///
+/// #[wasm_bindgen]
+/// extern "C" {
+///     type Style;
+///
+///     #[wasm_bindgen(method, property)]
+///     fn color(this: &Style) -> Property<String>;
+///
+///     #[wasm_bindgen(method, property, js_name = zIndex)]
+///     fn z_index(this: &Style) -> Property<f64>;
+/// }
+///
+/// fn highlight(style: &Style) {
+///     style.color().set("red".to_string());
+///     let z = style.z_index();
+///     z.set(z.get().unwrap_or(0.0) + 1.0);
+/// }
+/// ```
+///
+/// The property is accessed like `obj[name]` in JS, so any getters and
+/// setters defined for it are invoked.
+pub struct Property<T> {
+    obj: JsValue,
+    name: Cow<'static, str>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Property<T> {
+    /// Creates a handle to the property `name` of `obj`.
+    pub fn new(obj: &JsValue, name: impl Into<Cow<'static, str>>) -> Property<T> {
+        Property {
+            obj: obj.clone(),
+            name: name.into(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the object this property belongs to.
+    pub fn object(&self) -> &JsValue {
+        &self.obj
+    }
+
+    /// Returns the name of this property.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Reads the property without converting it to `T`.
+    pub fn get_js(&self) -> JsValue {
+        #[cfg(feature = "enable-interning")]
+        crate::intern(&self.name);
+        unsafe {
+            let idx = crate::__wbindgen_object_get(self.obj.idx, (&*self.name).into_abi());
+            JsValue::_new(idx)
+        }
+    }
+
+    /// Writes the property without converting from `T`.
+    pub fn set_js(&self, value: JsValue) {
+        #[cfg(feature = "enable-interning")]
+        crate::intern(&self.name);
+        unsafe {
+            crate::__wbindgen_object_set(self.obj.idx, (&*self.name).into_abi(), value.into_abi());
+        }
+    }
+}
+
+impl<T: TryFrom<JsValue>> Property<T> {
+    /// Reads the property, failing if its value can't be converted to `T`.
+    pub fn get(&self) -> Result<T, T::Error> {
+        T::try_from(self.get_js())
+    }
+}
+
+impl<T: Into<JsValue>> Property<T> {
+    /// Writes `value` to the property.
+    pub fn set(&self, value: T) {
+        self.set_js(value.into());
+    }
+}
+
+impl<T> Clone for Property<T> {
+    fn clone(&self) -> Property<T> {
+        Property {
+            obj: self.obj.clone(),
+            name: self.name.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Property<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Property")
+            .field("obj", &self.obj)
+            .field("name", &self.name)
+            .finish()
+    }
+}
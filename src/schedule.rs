@@ -0,0 +1,62 @@
+//! Scheduling Rust callbacks to run later on the JS event loop.
+//!
+//! The JS glue picks the best primitive the host offers for each kind of
+//! task, so these work the same in browsers, workers and Node.js. Futures
+//! executors and other custom schedulers can build on the low-level
+//! [`queue_microtask`] and [`queue_macrotask`], which take a JS function,
+//! while [`spawn_microtask`] and [`spawn_macrotask`] take a Rust closure.
+
+use crate::JsValue;
+
+if_std! {
+    use crate::closure::Closure;
+}
+
+/// Queues the JS function `callback` to be called as a microtask.
+///
+/// Microtasks run once the currently executing JS has finished, before
+/// control returns to the event loop, so they run before any pending
+/// rendering, I/O or timers. This uses `queueMicrotask` where it's available
+/// and falls back to a resolved `Promise` otherwise.
+pub fn queue_microtask(callback: &JsValue) {
+    unsafe { crate::__wbindgen_queue_microtask(callback.idx) }
+}
+
+/// Queues the JS function `callback` to be called as a new task on the event
+/// loop, similar to `setImmediate`.
+///
+/// Unlike a microtask, this yields to the event loop first, so rendering,
+/// I/O and other tasks get a chance to run before `callback` is called. This
+/// uses `setImmediate` where it's available, a `MessageChannel` otherwise,
+/// and falls back to `setTimeout` with no delay as a last resort.
+pub fn queue_macrotask(callback: &JsValue) {
+    unsafe { crate::__wbindgen_queue_macrotask(callback.idx) }
+}
+
+if_std! {
+    /// Runs `f` as a microtask.
+    ///
+    /// See [`queue_microtask`] for when microtasks run.
+    ///
+    /// ```no_run
+    /// wasm_bindgen::spawn_microtask(|| {
+    ///     // runs once the current JS call stack has unwound
+    /// });
+    /// ```
+    pub fn spawn_microtask<F>(f: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        queue_microtask(&Closure::once_into_js(f));
+    }
+
+    /// Runs `f` as a new task on the event loop, similar to `setImmediate`.
+    ///
+    /// See [`queue_macrotask`] for when macrotasks run.
+    pub fn spawn_macrotask<F>(f: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        queue_macrotask(&Closure::once_into_js(f));
+    }
+}
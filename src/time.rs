@@ -0,0 +1,188 @@
+//! Clocks backed by JS, enabled by the `std-time` feature.
+//!
+//! `std::time::Instant::now` and `std::time::SystemTime::now` panic on
+//! `wasm32-unknown-unknown` as there's no clock std can use there. This module
+//! provides replacements which ask JS for the time instead: [`Instant`] is
+//! backed by `performance.now()` and [`system_time_now`] by `Date.now()`.
+//!
+//! ```no_run
+//! use wasm_bindgen::time::Instant;
+//!
+//! let start = Instant::now();
+//! // ...
+//! let elapsed = start.elapsed();
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn millis_to_duration(ms: f64) -> Duration {
+    // Round to whole nanoseconds so that, for example, adding 5ms to an
+    // instant and subtracting it again gives back exactly 5ms.
+    Duration::from_nanos((ms * 1_000_000.0).round() as u64)
+}
+
+fn duration_to_millis(d: Duration) -> f64 {
+    d.as_secs() as f64 * 1000.0 + f64::from(d.subsec_nanos()) / 1_000_000.0
+}
+
+/// A measurement of a monotonically nondecreasing clock, like
+/// `std::time::Instant`.
+///
+/// Instants are read from `performance.now()`, so they're only comparable with
+/// instants read in the same JS realm, and count from when that realm was
+/// created. In environments without `performance`, `Date.now()` is used
+/// instead, which isn't monotonic.
+#[derive(Copy, Clone)]
+pub struct Instant {
+    millis: f64,
+}
+
+impl Instant {
+    /// Returns an instant corresponding to "now".
+    pub fn now() -> Instant {
+        let millis = unsafe { crate::__wbindgen_performance_now() };
+        Instant { millis }
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one,
+    /// or zero if that instant is later than this one.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one,
+    /// or `None` if that instant is later than this one.
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        if earlier.millis <= self.millis {
+            Some(millis_to_duration(self.millis - earlier.millis))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one,
+    /// or zero if that instant is later than this one.
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Returns the amount of time elapsed since this instant was created.
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+
+    /// Returns `Some(t)` where `t` is the time `self + duration` if `t` can be
+    /// represented, and `None` otherwise.
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        let millis = self.millis + duration_to_millis(duration);
+        if millis.is_finite() {
+            Some(Instant { millis })
+        } else {
+            None
+        }
+    }
+
+    /// Returns `Some(t)` where `t` is the time `self - duration` if `t` can be
+    /// represented, and `None` otherwise.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        let millis = self.millis - duration_to_millis(duration);
+        if millis.is_finite() {
+            Some(Instant { millis })
+        } else {
+            None
+        }
+    }
+}
+
+// `performance.now()` never returns `NaN`, so instants are totally ordered.
+impl PartialEq for Instant {
+    fn eq(&self, other: &Instant) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Instant {}
+
+impl PartialOrd for Instant {
+    fn partial_cmp(&self, other: &Instant) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Instant {
+    fn cmp(&self, other: &Instant) -> Ordering {
+        self.millis
+            .partial_cmp(&other.millis)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    /// # Panics
+    ///
+    /// This function may panic if the resulting point in time cannot be
+    /// represented. See [`Instant::checked_add`] for a version without panic.
+    fn add(self, other: Duration) -> Instant {
+        self.checked_add(other)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, other: Duration) {
+        *self = *self + other;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    fn sub(self, other: Duration) -> Instant {
+        self.checked_sub(other)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, other: Duration) {
+        *self = *self - other;
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    /// Returns the amount of time elapsed from another instant to this one,
+    /// or zero if that instant is later than this one.
+    fn sub(self, other: Instant) -> Duration {
+        self.duration_since(other)
+    }
+}
+
+impl fmt::Debug for Instant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Instant")
+            .field("millis", &self.millis)
+            .finish()
+    }
+}
+
+/// Returns the current system time, like `std::time::SystemTime::now`, read
+/// from `Date.now()`.
+pub fn system_time_now() -> SystemTime {
+    let millis = unsafe { crate::__wbindgen_date_now() };
+    let time = if millis >= 0.0 {
+        UNIX_EPOCH.checked_add(millis_to_duration(millis))
+    } else {
+        UNIX_EPOCH.checked_sub(millis_to_duration(-millis))
+    };
+    match time {
+        Some(time) => time,
+        None => crate::throw_str("current time can't be represented as a `SystemTime`"),
+    }
+}
@@ -0,0 +1,141 @@
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::JsValue;
+
+/// The error returned when converting a `JsValue` into a primitive Rust type
+/// with `TryFrom` fails.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use wasm_bindgen::{JsValue, TryFromJsValueError};
+///
+/// fn port(value: JsValue) -> Result<u16, TryFromJsValueError> {
+///     u16::try_from(value)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryFromJsValueError {
+    /// The value isn't of the JS type the Rust type is converted from, for
+    /// example a string when converting to `f64`.
+    WrongType {
+        /// The JS type which was expected, like `"number"`.
+        expected: &'static str,
+    },
+    /// The value is a number or `BigInt` which can't be represented by the
+    /// Rust integer type, because it's out of its range or isn't an integer.
+    OutOfRange {
+        /// The Rust type which was converted to, like `"u8"`.
+        target: &'static str,
+    },
+}
+
+impl fmt::Display for TryFromJsValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TryFromJsValueError::WrongType { expected } => {
+                write!(f, "expected a JS value of type {}", expected)
+            }
+            TryFromJsValueError::OutOfRange { target } => {
+                write!(f, "number can't be represented as a `{}`", target)
+            }
+        }
+    }
+}
+
+if_std! {
+    use crate::convert::FromWasmAbi;
+    use std::string::{String, ToString};
+
+    impl std::error::Error for TryFromJsValueError {}
+
+    impl From<TryFromJsValueError> for JsValue {
+        fn from(err: TryFromJsValueError) -> JsValue {
+            JsValue::from(err.to_string())
+        }
+    }
+
+    impl TryFrom<JsValue> for String {
+        type Error = TryFromJsValueError;
+
+        fn try_from(value: JsValue) -> Result<String, TryFromJsValueError> {
+            value.as_string().ok_or(TryFromJsValueError::WrongType { expected: "string" })
+        }
+    }
+
+    macro_rules! bigints {
+        ($($n:ident)*) => ($(
+            impl TryFrom<JsValue> for $n {
+                type Error = TryFromJsValueError;
+
+                /// Converts a JS `BigInt`, failing if it's out of range.
+                fn try_from(value: JsValue) -> Result<$n, TryFromJsValueError> {
+                    let digits: Option<String> = unsafe {
+                        FromWasmAbi::from_abi(crate::__wbindgen_bigint_get_as_string(value.idx))
+                    };
+                    let digits = digits.ok_or(TryFromJsValueError::WrongType {
+                        expected: "bigint",
+                    })?;
+                    // The digits always form a valid integer, so parsing only
+                    // fails if it doesn't fit.
+                    digits
+                        .parse()
+                        .map_err(|_| TryFromJsValueError::OutOfRange { target: stringify!($n) })
+                }
+            }
+        )*)
+    }
+
+    bigints! { i128 u128 }
+}
+
+impl TryFrom<JsValue> for f64 {
+    type Error = TryFromJsValueError;
+
+    fn try_from(value: JsValue) -> Result<f64, TryFromJsValueError> {
+        value
+            .as_f64()
+            .ok_or(TryFromJsValueError::WrongType { expected: "number" })
+    }
+}
+
+impl TryFrom<JsValue> for f32 {
+    type Error = TryFromJsValueError;
+
+    /// Converts a JS number to the nearest `f32`, like `as` does.
+    fn try_from(value: JsValue) -> Result<f32, TryFromJsValueError> {
+        f64::try_from(value).map(|n| n as f32)
+    }
+}
+
+impl TryFrom<JsValue> for bool {
+    type Error = TryFromJsValueError;
+
+    fn try_from(value: JsValue) -> Result<bool, TryFromJsValueError> {
+        value.as_bool().ok_or(TryFromJsValueError::WrongType {
+            expected: "boolean",
+        })
+    }
+}
+
+macro_rules! integers {
+    ($($n:ident)*) => ($(
+        impl TryFrom<JsValue> for $n {
+            type Error = TryFromJsValueError;
+
+            fn try_from(value: JsValue) -> Result<$n, TryFromJsValueError> {
+                let n = f64::try_from(value)?;
+                // Casting saturates and truncates, so the number is only
+                // representable if it survives the round trip. This also
+                // rejects `NaN`.
+                if n as $n as f64 == n {
+                    Ok(n as $n)
+                } else {
+                    Err(TryFromJsValueError::OutOfRange { target: stringify!($n) })
+                }
+            }
+        }
+    )*)
+}
+
+integers! { i8 u8 i16 u16 i32 u32 }
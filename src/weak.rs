@@ -0,0 +1,51 @@
+use core::fmt;
+
+use crate::JsValue;
+
+/// A weak reference to a JS object, backed by JS's `WeakRef`.
+///
+/// Unlike a `JsValue`, a `JsWeakRef` doesn't keep the object it refers to
+/// alive, so long-lived caches of, for example, DOM nodes can hold on to them
+/// without leaking nodes which have been removed from the page. The object
+/// can be accessed again with [`JsWeakRef::upgrade`] for as long as it hasn't
+/// been garbage collected.
+///
+/// `WeakRef` isn't available in all JS engines, so [`JsWeakRef::new`] returns
+/// `None` where it isn't supported.
+#[derive(Clone)]
+pub struct JsWeakRef {
+    weak_ref: JsValue,
+}
+
+impl JsWeakRef {
+    /// Creates a weak reference to `target`.
+    ///
+    /// Returns `None` if the JS engine doesn't support `WeakRef`, or if
+    /// `target` isn't an object or function: primitives can't be referenced
+    /// weakly.
+    pub fn new(target: &JsValue) -> Option<JsWeakRef> {
+        let weak_ref = unsafe { JsValue::_new(crate::__wbindgen_weak_ref_new(target.idx)) };
+        if weak_ref.is_undefined() {
+            None
+        } else {
+            Some(JsWeakRef { weak_ref })
+        }
+    }
+
+    /// Returns a strong reference to the target object, or `None` if it has
+    /// already been garbage collected.
+    pub fn upgrade(&self) -> Option<JsValue> {
+        let target = unsafe { JsValue::_new(crate::__wbindgen_weak_ref_deref(self.weak_ref.idx)) };
+        if target.is_undefined() {
+            None
+        } else {
+            Some(target)
+        }
+    }
+}
+
+impl fmt::Debug for JsWeakRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JsWeakRef").finish()
+    }
+}
@@ -1,6 +1,8 @@
 const wasm = require('wasm-bindgen-test.js');
 const assert = require('assert');
 
+exports.api_lone_surrogate = () => 'a\uD800';
+
 exports.assert_null = x => {
     assert.strictEqual(x, null);
 };
@@ -10,6 +10,7 @@ extern "C" {
     fn assert_null(v: JsValue);
     fn debug_values() -> JsValue;
     fn assert_function_table(a: JsValue, b: usize);
+    fn api_lone_surrogate() -> JsValue;
 }
 
 #[wasm_bindgen_test]
@@ -106,6 +107,17 @@ pub fn api_acquire_string(a: &JsValue, b: &JsValue) {
     assert_eq!(b.as_string(), None);
 }
 
+#[wasm_bindgen_test]
+fn as_utf16() {
+    assert_eq!(
+        JsValue::from("a🥑").as_utf16().unwrap(),
+        "a🥑".encode_utf16().collect::<Vec<_>>()
+    );
+    assert_eq!(JsValue::from("").as_utf16().unwrap(), []);
+    assert_eq!(JsValue::from(1).as_utf16(), None);
+    assert_eq!(api_lone_surrogate().as_utf16().unwrap(), [0x61, 0xd800]);
+}
+
 #[wasm_bindgen]
 pub fn api_acquire_string2(a: &JsValue) -> String {
     a.as_string().unwrap_or("wrong".to_string())
@@ -183,3 +195,79 @@ fn function_table_is() {
 
 #[no_mangle]
 pub extern "C" fn function_table_lookup() {}
+
+#[wasm_bindgen_test]
+fn identity_hashing() {
+    use std::collections::HashMap;
+    use wasm_bindgen::Identity;
+
+    let a = js_sys::Object::new();
+    let b = js_sys::Object::new();
+    let mut map = HashMap::new();
+    map.insert(Identity::new(JsValue::from(a.clone())), 1);
+    map.insert(Identity::new(JsValue::from(b.clone())), 2);
+    map.insert(Identity::new(JsValue::from("a")), 3);
+    map.insert(Identity::new(JsValue::from(std::f64::NAN)), 4);
+    map.insert(Identity::new(JsValue::from(0.0)), 5);
+    map.insert(Identity::new(JsValue::from(-0.0)), 6);
+    assert_eq!(map.len(), 6);
+
+    assert_eq!(map[&Identity::new(JsValue::from(a))], 1);
+    assert_eq!(map[&Identity::new(JsValue::from(b))], 2);
+    assert_eq!(map[&Identity::new(JsValue::from("a"))], 3);
+    assert_eq!(map[&Identity::new(JsValue::from(std::f64::NAN))], 4);
+    assert_eq!(map[&Identity::new(JsValue::from(0.0))], 5);
+    assert_eq!(map[&Identity::new(JsValue::from(-0.0))], 6);
+    assert!(!map.contains_key(&Identity::new(js_sys::Object::new().into())));
+}
+
+#[wasm_bindgen_test]
+fn weak_refs() {
+    use wasm_bindgen::JsWeakRef;
+
+    assert!(JsWeakRef::new(&JsValue::from(1)).is_none());
+    assert!(JsWeakRef::new(&JsValue::NULL).is_none());
+
+    let object = JsValue::from(js_sys::Object::new());
+    // `WeakRef` isn't supported everywhere the tests run.
+    if let Some(weak) = JsWeakRef::new(&object) {
+        assert_eq!(weak.upgrade(), Some(object.clone()));
+        assert_eq!(weak.clone().upgrade(), Some(object));
+    }
+}
+
+#[wasm_bindgen_test]
+fn random_fill() {
+    // Larger than the 65536 bytes `getRandomValues` fills at once.
+    let mut buf = vec![0u8; 100_000];
+    wasm_bindgen::random_fill(&mut buf).unwrap();
+    assert!(buf[..1000].iter().any(|b| *b != 0));
+    assert!(buf[99_000..].iter().any(|b| *b != 0));
+
+    let mut other = vec![0u8; 1000];
+    wasm_bindgen::random_fill(&mut other).unwrap();
+    assert_ne!(other, &buf[..1000]);
+
+    wasm_bindgen::random_fill(&mut []).unwrap();
+}
+
+#[wasm_bindgen_test]
+fn externref_slots() {
+    use wasm_bindgen::externref;
+
+    let initial = wasm_bindgen::anyref_heap_live_count();
+    let idx = externref::alloc(JsValue::from("slot"));
+    assert_eq!(wasm_bindgen::anyref_heap_live_count(), initial + 1);
+
+    let value = unsafe { externref::get(idx) };
+    assert_eq!(value, "slot");
+    drop(value);
+    assert_eq!(wasm_bindgen::anyref_heap_live_count(), initial + 1);
+
+    let other = externref::alloc(JsValue::from(1));
+    unsafe {
+        assert_eq!(externref::take(other).as_f64(), Some(1.0));
+        externref::free(idx);
+    }
+    assert_eq!(wasm_bindgen::anyref_heap_live_count(), initial);
+}
@@ -31,11 +31,12 @@ exports.js_strings = () => {
 };
 
 exports.js_exceptions = () => {
-    // this test only works when `--debug` is passed to `wasm-bindgen` (or the
-    // equivalent thereof)
+    assert.throws(() => new wasm.ClassesExceptions1(), /ClassesExceptions1 cannot be constructed from JS/);
+
+    // the rest of this test only works when `--debug` is passed to
+    // `wasm-bindgen` (or the equivalent thereof)
     if (require('process').env.WASM_BINDGEN_NO_DEBUG)
         return;
-    assert.throws(() => new wasm.ClassesExceptions1(), /cannot invoke `new` directly/);
     let a = wasm.ClassesExceptions1.new();
     a.free();
     assert.throws(() => a.free(), /null pointer passed to rust/);
@@ -205,7 +206,7 @@ exports.js_test_inspectable_classes = () => {
     assert.strictEqual(not_inspectable.toJSON, undefined);
     assert.strictEqual(not_inspectable.toString(), '[object Object]');
     // Non-inspectable classes in Node.js have no special console.log formatting
-    assert.strictEqual(console_log_to_string(not_inspectable), `NotInspectable { ptr: ${not_inspectable.ptr} }`);
+    assert.strictEqual(console_log_to_string(not_inspectable), `NotInspectable {}`);
     inspectable.free();
     not_inspectable.free();
 };
@@ -218,3 +219,183 @@ exports.js_test_inspectable_classes_can_override_generated_methods = () => {
     assert.strictEqual(overridden_inspectable.toString(), 'string was overwritten');
     overridden_inspectable.free();
 };
+
+exports.js_test_to_json = () => {
+    const a = wasm.ToJson.new(1, 'foo');
+    // Rust-owned objects nested in larger JS structures serialize through the
+    // `#[wasm_bindgen(to_json)]` method rather than as `{"ptr":N}`.
+    assert.strictEqual(
+        JSON.stringify({ items: [a], count: 1 }),
+        '{"items":[{"id":1,"name":"foo"}],"count":1}',
+    );
+    assert.strictEqual(wasm.ToJson.prototype.serialize, undefined);
+    a.free();
+
+    // A `to_json` method takes precedence over the one generated for
+    // `inspectable` classes, and `toString` picks it up as well.
+    const b = wasm.InspectableToJson.new();
+    assert.strictEqual(b.toJSON(), 'a is 3');
+    assert.strictEqual(b.toString(), '"a is 3"');
+    b.free();
+};
+
+exports.js_test_ptr_is_hidden = () => {
+    const a = new wasm.ClassesSimple();
+    // The pointer isn't an enumerable or string-keyed property, so it can't
+    // be serialized or copied around by accident.
+    assert.strictEqual(a.ptr, undefined);
+    assert.deepStrictEqual(Object.keys(a), []);
+    assert.strictEqual(JSON.stringify(a), '{}');
+    assert.deepStrictEqual(Object.assign({}, a), {});
+    // ... but it's still available for debugging with `--debug`.
+    if (require('process').env.WASM_BINDGEN_NO_DEBUG) {
+        assert.strictEqual(a.__wbg_ptr, undefined);
+        a.free();
+        return;
+    }
+    assert.notStrictEqual(a.__wbg_ptr(), 0);
+    a.free();
+    assert.strictEqual(a.__wbg_ptr(), 0);
+};
+
+exports.js_test_smart_pointer_fields = () => {
+    const a = wasm.SmartPointerFields.new();
+
+    // `Option<Box<T>>` fields are read and written as `T | undefined`, and
+    // reading one hands out a clone.
+    const child = a.child;
+    assert.strictEqual(child.value, 1);
+    child.value = 2;
+    assert.strictEqual(a.child.value, 1);
+    a.child = new wasm.SmartPointerChild(3);
+    assert.strictEqual(a.child.value, 3);
+    a.child = undefined;
+    assert.strictEqual(a.child, undefined);
+
+    // `Rc<RefCell<T>>` fields write through to the shared value.
+    const b = a.alias();
+    assert.strictEqual(b.shared, 'a');
+    a.shared = 'b';
+    assert.strictEqual(b.shared, 'b');
+
+    child.free();
+    a.free();
+    b.free();
+};
+
+exports.js_test_class_brands = () => {
+    const brand = Symbol.for('wasm-bindgen.brand');
+    const a = new wasm.ClassesSimple();
+    assert.strictEqual(a[brand], 'ClassesSimple');
+    assert.strictEqual(wasm.is_classes_simple(a), true);
+
+    // An object branded like `ClassesSimple` but not created by this module
+    // (as if it came from another wasm module) is rejected with a clear error.
+    const foreign = { ptr: 8, [brand]: 'ClassesSimple' };
+    assert.strictEqual(wasm.is_classes_simple(foreign), false);
+    assert.throws(() => a.take_other(foreign), /created by a different wasm module/);
+    a.free();
+};
+
+exports.js_test_iterator = () => {
+    const countdown = new wasm.Countdown(3);
+    assert.deepStrictEqual([...countdown], [3, 2, 1]);
+
+    // The iterator is exhausted along with the underlying Rust state.
+    assert.deepStrictEqual(Array.from(countdown), []);
+    assert.strictEqual(countdown.next_value(), undefined);
+
+    const values = [];
+    for (const value of new wasm.Countdown(2)) {
+        values.push(value);
+    }
+    assert.deepStrictEqual(values, [2, 1]);
+    countdown.free();
+};
+
+exports.js_test_overloads = () => {
+    const a = new wasm.Overloaded();
+    assert.strictEqual(a.add(1), 1);
+    assert.strictEqual(a.add('2'), 3);
+    const b = new wasm.Overloaded();
+    b.add(5);
+    assert.strictEqual(a.add(b), 8);
+    assert.strictEqual(a.add(2, 3), 14);
+    assert.strictEqual(a.add(1, undefined), 15);
+    assert.throws(() => a.add(true), /no overload of `Overloaded.add`/);
+    assert.throws(() => a.add(1, 2, 3), /no overload of `Overloaded.add`/);
+
+    assert.strictEqual(wasm.Overloaded.describe(), 'overloaded');
+    assert.strictEqual(wasm.Overloaded.describe(4), 'overloaded 4');
+    a.free();
+    b.free();
+};
+
+exports.js_test_returned_iterators = () => {
+    const values = wasm.countdown_from(3);
+    assert.ok(values instanceof wasm.__wbg_countdown_from_Iterator);
+    assert.deepStrictEqual(values.next(), { value: 3, done: false });
+    assert.deepStrictEqual([...values], [2, 1]);
+    assert.deepStrictEqual(values.next(), { value: undefined, done: true });
+    values.free();
+
+    const countdown = new wasm.Countdown(2);
+    const strings = countdown.remaining_strings();
+    assert.ok(strings instanceof wasm.__wbg_Countdown_remaining_strings_Iterator);
+    assert.deepStrictEqual(Array.from(strings), ['2', '1']);
+    strings.free();
+    countdown.free();
+};
+
+exports.js_test_typed_array_fields = () => {
+    const a = new wasm.TypedArrayFields();
+
+    // `typed_array = view` fields are read as views of wasm memory, so they
+    // observe later changes made from Rust, and writes through them are seen
+    // by Rust as well.
+    const positions = a.positions;
+    assert.ok(positions instanceof Float32Array);
+    assert.deepStrictEqual(Array.from(positions), [1, 2, 3]);
+    a.scale(2);
+    assert.deepStrictEqual(Array.from(positions), [2, 4, 6]);
+    positions[0] = 10;
+    assert.strictEqual(a.sum_positions(), 20);
+
+    // `typed_array = copy` fields hand out a copy instead.
+    const weights = a.weights;
+    assert.ok(weights instanceof Float64Array);
+    a.scale(2);
+    assert.deepStrictEqual(Array.from(weights), [0.5, 0.25]);
+    assert.deepStrictEqual(Array.from(a.weights), [1, 0.5]);
+    assert.ok(a.ids instanceof Uint32Array);
+    assert.deepStrictEqual(Array.from(a.ids), [7, 8]);
+
+    // Both kinds are assigned by copying into a new `Vec`.
+    const replacement = new Float32Array([1, 1]);
+    a.positions = replacement;
+    replacement[0] = 5;
+    assert.strictEqual(a.sum_positions(), 2);
+    a.weights = new Float64Array([3]);
+    assert.deepStrictEqual(Array.from(a.weights), [3]);
+
+    a.free();
+};
+
+exports.js_test_extends_js = () => {
+    const e = new wasm.ExtendsError(3);
+
+    // The instance is created by the `Error` constructor, so it's a real error
+    // with a stack trace.
+    assert.ok(e instanceof Error);
+    assert.ok(e instanceof wasm.ExtendsError);
+    assert.strictEqual(typeof e.stack, 'string');
+
+    // The constructor arguments are passed along to `Error` too.
+    assert.strictEqual(e.message, '3');
+
+    // Rust methods are available, and override those of `Error`.
+    assert.strictEqual(e.code, 3);
+    assert.strictEqual(String(e), 'ExtendsError 3');
+
+    e.free();
+};
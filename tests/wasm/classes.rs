@@ -1,6 +1,9 @@
 #[allow(unused_imports)] // test for #919
 use std::borrow::BorrowMut;
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use wasm_bindgen::convert::ExportedClass;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_test::*;
 
@@ -32,6 +35,15 @@ extern "C" {
     fn js_test_option_classes();
     fn js_test_inspectable_classes();
     fn js_test_inspectable_classes_can_override_generated_methods();
+    fn js_test_class_brands();
+    fn js_test_to_json();
+    fn js_test_ptr_is_hidden();
+    fn js_test_smart_pointer_fields();
+    fn js_test_iterator();
+    fn js_test_overloads();
+    fn js_test_returned_iterators();
+    fn js_test_typed_array_fields();
+    fn js_test_extends_js();
 }
 
 #[wasm_bindgen_test]
@@ -63,6 +75,8 @@ impl ClassesSimple {
     pub fn consume(self) -> u32 {
         self.contents
     }
+
+    pub fn take_other(&self, _other: &ClassesSimple) {}
 }
 
 #[wasm_bindgen_test]
@@ -553,3 +567,273 @@ impl OverriddenInspectable {
         String::from("string was overwritten")
     }
 }
+
+#[wasm_bindgen_test]
+fn to_json() {
+    js_test_to_json();
+}
+
+#[wasm_bindgen]
+pub struct ToJson {
+    id: u32,
+    name: String,
+}
+
+#[wasm_bindgen]
+impl ToJson {
+    pub fn new(id: u32, name: String) -> ToJson {
+        ToJson { id, name }
+    }
+
+    #[wasm_bindgen(to_json)]
+    pub fn serialize(&self) -> JsValue {
+        let obj = js_sys::Object::new();
+        js_sys::Reflect::set(&obj, &"id".into(), &self.id.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"name".into(), &self.name.as_str().into()).unwrap();
+        obj.into()
+    }
+}
+
+#[wasm_bindgen(inspectable)]
+pub struct InspectableToJson {
+    pub a: u32,
+}
+
+#[wasm_bindgen]
+impl InspectableToJson {
+    pub fn new() -> InspectableToJson {
+        InspectableToJson { a: 3 }
+    }
+
+    #[wasm_bindgen(to_json)]
+    pub fn to_json(&self) -> String {
+        format!("a is {}", self.a)
+    }
+}
+
+#[wasm_bindgen_test]
+fn ptr_is_hidden() {
+    js_test_ptr_is_hidden();
+}
+
+#[wasm_bindgen_test]
+fn smart_pointer_fields() {
+    js_test_smart_pointer_fields();
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct SmartPointerChild {
+    pub value: u32,
+}
+
+#[wasm_bindgen]
+impl SmartPointerChild {
+    #[wasm_bindgen(constructor)]
+    pub fn new(value: u32) -> SmartPointerChild {
+        SmartPointerChild { value }
+    }
+}
+
+#[wasm_bindgen]
+pub struct SmartPointerFields {
+    pub child: Option<Box<SmartPointerChild>>,
+    pub shared: Rc<RefCell<String>>,
+}
+
+#[wasm_bindgen]
+impl SmartPointerFields {
+    pub fn new() -> SmartPointerFields {
+        SmartPointerFields {
+            child: Some(Box::new(SmartPointerChild { value: 1 })),
+            shared: Rc::new(RefCell::new("a".to_string())),
+        }
+    }
+
+    /// Returns a second object sharing the same `shared` string.
+    pub fn alias(&self) -> SmartPointerFields {
+        SmartPointerFields {
+            child: None,
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn class_brands() {
+    js_test_class_brands();
+}
+
+#[wasm_bindgen]
+pub fn is_classes_simple(val: &JsValue) -> bool {
+    ClassesSimple::is_instance(val)
+}
+
+#[wasm_bindgen_test]
+fn exported_class_is_instance() {
+    let val = JsValue::from(ClassesExceptions1::new());
+    assert!(ClassesExceptions1::is_instance(&val));
+    assert!(!ClassesExceptions2::is_instance(&val));
+    assert!(!ClassesExceptions1::is_instance(&JsValue::NULL));
+}
+
+#[wasm_bindgen_test]
+fn iterator() {
+    js_test_iterator();
+}
+
+#[wasm_bindgen]
+pub struct Countdown {
+    remaining: u32,
+}
+
+#[wasm_bindgen]
+impl Countdown {
+    #[wasm_bindgen(constructor)]
+    pub fn new(from: u32) -> Countdown {
+        Countdown { remaining: from }
+    }
+
+    #[wasm_bindgen(iterator)]
+    pub fn next_value(&mut self) -> Option<u32> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.remaining + 1)
+    }
+}
+
+#[wasm_bindgen_test]
+fn overloads() {
+    js_test_overloads();
+}
+
+#[wasm_bindgen]
+pub struct Overloaded {
+    value: u32,
+}
+
+#[wasm_bindgen]
+impl Overloaded {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Overloaded {
+        Overloaded { value: 0 }
+    }
+
+    #[wasm_bindgen(js_name = add)]
+    pub fn add_number(&mut self, n: u32) -> u32 {
+        self.value += n;
+        self.value
+    }
+
+    #[wasm_bindgen(js_name = add)]
+    pub fn add_parsed(&mut self, s: &str) -> u32 {
+        self.add_number(s.parse().unwrap())
+    }
+
+    #[wasm_bindgen(js_name = add)]
+    pub fn add_other(&mut self, other: &Overloaded) -> u32 {
+        self.add_number(other.value)
+    }
+
+    #[wasm_bindgen(js_name = add)]
+    pub fn add_scaled(&mut self, n: u32, factor: Option<u32>) -> u32 {
+        self.add_number(n * factor.unwrap_or(1))
+    }
+
+    #[wasm_bindgen(js_name = describe)]
+    pub fn describe_default() -> String {
+        "overloaded".to_string()
+    }
+
+    #[wasm_bindgen(js_name = describe)]
+    pub fn describe_value(value: u32) -> String {
+        format!("overloaded {}", value)
+    }
+}
+
+#[wasm_bindgen_test]
+fn returned_iterators() {
+    js_test_returned_iterators();
+}
+
+#[wasm_bindgen]
+pub fn countdown_from(from: u32) -> impl Iterator<Item = u32> {
+    (1..=from).rev()
+}
+
+#[wasm_bindgen]
+impl Countdown {
+    pub fn remaining_strings(&self) -> impl Iterator<Item = String> {
+        (1..=self.remaining).rev().map(|n| n.to_string())
+    }
+}
+
+#[wasm_bindgen_test]
+fn typed_array_fields() {
+    js_test_typed_array_fields();
+}
+
+#[wasm_bindgen]
+pub struct TypedArrayFields {
+    #[wasm_bindgen(typed_array = view)]
+    pub positions: Vec<f32>,
+    #[wasm_bindgen(typed_array = copy)]
+    pub weights: Vec<f64>,
+    #[wasm_bindgen(readonly, typed_array = copy)]
+    pub ids: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl TypedArrayFields {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> TypedArrayFields {
+        TypedArrayFields {
+            positions: vec![1.0, 2.0, 3.0],
+            weights: vec![0.5, 0.25],
+            ids: vec![7, 8],
+        }
+    }
+
+    pub fn scale(&mut self, factor: f32) {
+        for p in self.positions.iter_mut() {
+            *p *= factor;
+        }
+        for w in self.weights.iter_mut() {
+            *w *= f64::from(factor);
+        }
+    }
+
+    pub fn sum_positions(&self) -> f32 {
+        self.positions.iter().sum()
+    }
+}
+
+#[wasm_bindgen_test]
+fn extends_js() {
+    js_test_extends_js();
+}
+
+#[wasm_bindgen(extends_js = Error)]
+pub struct ExtendsError {
+    code: u32,
+}
+
+#[wasm_bindgen]
+impl ExtendsError {
+    #[wasm_bindgen(constructor)]
+    pub fn new(code: u32) -> ExtendsError {
+        ExtendsError { code }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        format!("ExtendsError {}", self.code)
+    }
+}
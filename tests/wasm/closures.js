@@ -68,6 +68,16 @@ exports.long_fnmut_recursive_cache = a => {
 exports.long_fnmut_recursive_call = () => {
     LONG_FNMUT_RECURSIVE_CACHE();
 };
+exports.long_fnmut_recursive_error = () => {
+    assert.throws(LONG_FNMUT_RECURSIVE_CACHE, /closure.* invoked recursively/);
+};
+
+let FN_REENTRANT_CACHE = null;
+
+exports.fn_reentrant_cache = a => {
+    FN_REENTRANT_CACHE = a;
+};
+exports.fn_reentrant_call = n => FN_REENTRANT_CACHE(n);
 
 exports.fnmut_call = a => {
     a();
@@ -117,6 +127,15 @@ exports.calling_it_throws = a => {
 
 exports.call_val = f => f();
 
+exports.call_val_with_args = f => f(1, 'x');
+
+exports.call_executor = executor => {
+  let settled = null;
+  executor(v => { settled = `resolved ${v}`; }, e => { settled = `rejected ${e}`; });
+  assert.throws(() => executor(() => {}, () => {}), /FnOnce called more than once/);
+  return settled;
+};
+
 exports.pass_reference_first_arg_twice = (a, b, c) => {
   b(a);
   c(a);
@@ -124,7 +143,7 @@ exports.pass_reference_first_arg_twice = (a, b, c) => {
 };
 
 exports.call_destroyed = f => {
-  assert.throws(f, /invoked recursively or destroyed/);
+  assert.throws(f, /closure.* invoked after being destroyed/);
 };
 
 let FORGOTTEN_CLOSURE = null;
@@ -136,3 +155,167 @@ exports.js_store_forgotten_closure = f => {
 exports.js_call_forgotten_closure = () => {
   FORGOTTEN_CLOSURE();
 };
+
+exports.stateless_call = a => a(a(1) + a(1));
+
+const STACK_CLOSURES_SEEN = new Set();
+
+exports.stack_closure_reentrant_call = (a, x) => {
+  STACK_CLOSURES_SEEN.add(a);
+  return a(x);
+};
+
+exports.stack_closures_seen = () => STACK_CLOSURES_SEEN.size;
+
+let STASHED_STACK_CLOSURE = null;
+
+exports.stack_closure_stash_call = (a, x) => {
+  STASHED_STACK_CLOSURE = a;
+  return a(x);
+};
+
+exports.stack_closure_call_stashed = x => {
+  assert.throws(() => STASHED_STACK_CLOSURE(x), /invoked after being destroyed/);
+};
+
+exports.fnonce_stack_call = a => a(3);
+
+exports.fnonce_stack_call_twice = a => {
+  a();
+  assert.throws(a, /invoked more than once/);
+};
+
+let SAVED_FNONCE = null;
+
+exports.fnonce_stack_save = a => {
+  SAVED_FNONCE = a;
+};
+
+exports.fnonce_stack_call_with_str = a => a('hello');
+
+exports.fnonce_stack_call_saved = () => {
+  assert.throws(SAVED_FNONCE, /after the function it was passed to returned/);
+};
+
+exports.closure_returning_closure_call = a => {
+  const add = a(1);
+  assert.strictEqual(typeof add, 'function');
+  return add(2);
+};
+
+exports.returned_closure_call = () => {
+  const add = wasm.closures_make_adder(3);
+  assert.strictEqual(add(4), 7);
+  assert.strictEqual(add(5), 8);
+};
+
+exports.closure_argument_call = a => {
+  const seen = [];
+  const ret = a(x => seen.push(x));
+  assert.deepStrictEqual(seen, [1, 2]);
+  return ret;
+};
+
+exports.closure_argument_call_with_strings = a => {
+  const seen = [];
+  const f = (n, s) => seen.push([n, s]);
+  a(f);
+  a(f);
+  assert.deepStrictEqual(seen, [[1, 'call 1'], [2, 'call 2']]);
+};
+
+let LEAKED_CLOSURE = null;
+
+exports.js_store_leaked_closure = f => {
+  LEAKED_CLOSURE = f;
+};
+
+exports.js_call_leaked_closure = () => {
+  LEAKED_CLOSURE();
+};
+
+let SAVED_SCOPED = null;
+
+exports.scoped_call_twice = f => {
+  f(1);
+  f(2);
+  SAVED_SCOPED = f;
+};
+
+exports.scoped_call_saved_throws = () => {
+  try {
+    SAVED_SCOPED(3);
+    return false;
+  } catch (_) {
+    return true;
+  }
+};
+
+exports.call_with_str = f => {
+  f('hello');
+  f('wörld');
+};
+
+exports.call_with_bytes = f => f(new Uint8Array([1, 2, 3]));
+
+exports.fill_f32_buffer = f => {
+  const buf = new Float32Array(4);
+  f(buf);
+  assert.deepStrictEqual(Array.from(buf), [0, 0.5, 1, 1.5]);
+  f(buf);
+  assert.deepStrictEqual(Array.from(buf), [2, 2.5, 3, 3.5]);
+};
+
+exports.double_bytes = f => {
+  const buf = new Uint8Array([1, 2, 3]);
+  f(buf);
+  assert.deepStrictEqual(Array.from(buf), [2, 4, 6]);
+};
+
+class ClosureEvent {
+  constructor(type) {
+    this.type = type;
+  }
+}
+
+exports.dispatch_closure_event = f => f(new ClosureEvent('click'));
+
+exports.dispatch_closure_event_ref = f => {
+  assert.strictEqual(f(new ClosureEvent('click')), 'click');
+};
+
+exports.dispatch_optional_closure_event = f => f(new ClosureEvent('keydown'), undefined);
+
+exports.call_with_twelve_args = f => f(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 'sum');
+
+exports.call_fallible = f => {
+  assert.strictEqual(f(1), 1);
+  assert.throws(() => f(0), err => err === 'zero');
+  // The closure can still be called after it threw.
+  assert.strictEqual(f(2), 3);
+};
+
+exports.pass_ticket_to_closure = f => {
+  const one = new wasm.ClosureTicket(1);
+  f(one);
+  // Ownership of the ticket moved into Rust.
+  assert.throws(() => one.number(), /Attempt to use a moved value|null pointer passed to rust/);
+  f(new wasm.ClosureTicket(2));
+};
+
+exports.take_ticket_from_closure = f => {
+  const a = f();
+  const b = f();
+  assert.ok(a instanceof wasm.ClosureTicket);
+  const sum = a.number() + b.number();
+  a.free();
+  b.free();
+  return sum;
+};
+
+exports.exchange_ticket = f => {
+  const ticket = f(new wasm.ClosureTicket(21));
+  assert.strictEqual(ticket.number(), 42);
+  ticket.free();
+  assert.strictEqual(f(new wasm.ClosureTicket(0)), undefined);
+};
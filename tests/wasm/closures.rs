@@ -1,7 +1,6 @@
-#![cfg(feature = "nightly")]
-
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use wasm_bindgen::convert::StackFnOnce;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_test::*;
 
@@ -72,6 +71,10 @@ extern "C" {
     fn long_fnmut_recursive_cache(a: &Closure<FnMut()>);
     #[wasm_bindgen(catch)]
     fn long_fnmut_recursive_call() -> Result<(), JsValue>;
+    fn long_fnmut_recursive_error();
+
+    fn fn_reentrant_cache(a: &Closure<Fn(u32) -> u32>);
+    fn fn_reentrant_call(n: u32) -> u32;
 
     fn fnmut_call(a: &mut FnMut());
     fn fnmut_thread(a: &mut FnMut(u32) -> u32) -> u32;
@@ -95,6 +98,8 @@ extern "C" {
 
     #[wasm_bindgen(js_name = calling_it_throws)]
     fn call_val_throws(f: &JsValue) -> bool;
+    fn call_val_with_args(f: &JsValue) -> String;
+    fn call_executor(f: &Closure<FnMut(js_sys::Function, js_sys::Function)>) -> String;
 
     fn pass_reference_first_arg_twice(
         a: RefFirstArgument,
@@ -111,6 +116,63 @@ extern "C" {
 
     fn js_store_forgotten_closure(closure: &Closure<Fn()>);
     fn js_call_forgotten_closure();
+
+    fn stateless_call(a: &Closure<FnMut(u32) -> u32>) -> u32;
+
+    fn stack_closure_reentrant_call(a: &mut FnMut(u32) -> u32, x: u32) -> u32;
+    fn stack_closures_seen() -> u32;
+    fn stack_closure_stash_call(a: &mut FnMut(u32) -> u32, x: u32) -> u32;
+    fn stack_closure_call_stashed(x: u32);
+
+    fn fnonce_stack_call(a: &mut dyn StackFnOnce<(u32,), String>) -> String;
+    fn fnonce_stack_call_twice(a: &mut dyn StackFnOnce<(), ()>);
+    fn fnonce_stack_save(a: &mut dyn StackFnOnce<(), ()>);
+    fn fnonce_stack_call_saved();
+    fn fnonce_stack_call_with_str(a: &mut dyn for<'a> StackFnOnce<(&'a str,), String>) -> String;
+
+    fn closure_returning_closure_call(a: &Closure<FnMut(u32) -> Closure<FnMut(u32) -> u32>>)
+        -> u32;
+    fn returned_closure_call();
+    fn closure_argument_call(a: &Closure<dyn Fn(&dyn Fn(u32)) -> u32>) -> u32;
+    fn closure_argument_call_with_strings(a: &Closure<dyn FnMut(&dyn Fn(u32, String))>);
+
+    fn js_store_leaked_closure(f: &JsValue);
+    fn js_call_leaked_closure();
+
+    fn scoped_call_twice(f: &ScopedClosure<FnMut(u32)>);
+    fn scoped_call_saved_throws() -> bool;
+
+    fn call_with_str(f: &Closure<FnMut(&str)>);
+    fn call_with_bytes(f: &Closure<Fn(&[u8]) -> u32>) -> u32;
+    #[wasm_bindgen(js_name = call_with_str)]
+    fn call_with_str_stack(f: &mut FnMut(&str));
+    fn fill_f32_buffer(f: &Closure<FnMut(&mut [f32])>);
+    fn double_bytes(f: &Closure<Fn(&mut [u8])>);
+    #[wasm_bindgen(js_name = double_bytes)]
+    fn double_bytes_stack(f: &mut FnMut(&mut [u8]));
+
+    type ClosureEvent;
+    #[wasm_bindgen(method, getter, js_name = type)]
+    fn type_(this: &ClosureEvent) -> String;
+    fn dispatch_closure_event(f: &Closure<FnMut(ClosureEvent)>);
+    fn dispatch_closure_event_ref(f: &Closure<Fn(&ClosureEvent) -> String>);
+    fn dispatch_optional_closure_event(f: &Closure<FnMut(ClosureEvent, Option<ClosureEvent>)>);
+
+    fn call_with_twelve_args(
+        f: &Closure<Fn(u32, u32, u32, u32, u32, u32, u32, u32, u32, u32, u32, String) -> String>,
+    ) -> String;
+    #[wasm_bindgen(js_name = call_with_twelve_args)]
+    fn call_with_twelve_args_stack(
+        f: &Fn(u32, u32, u32, u32, u32, u32, u32, u32, u32, u32, u32, String) -> String,
+    ) -> String;
+
+    fn call_fallible(f: &Closure<FnMut(u32) -> Result<u32, JsValue>>);
+    #[wasm_bindgen(js_name = call_fallible)]
+    fn call_fallible_stack(f: &Fn(u32) -> Result<u32, JsValue>);
+
+    fn pass_ticket_to_closure(f: &Closure<FnMut(ClosureTicket)>);
+    fn take_ticket_from_closure(f: &Closure<FnMut() -> ClosureTicket>) -> u32;
+    fn exchange_ticket(f: &mut FnMut(ClosureTicket) -> Option<ClosureTicket>);
 }
 
 #[wasm_bindgen_test]
@@ -265,6 +327,29 @@ fn call_fn_once_twice() {
     assert!(calling_it_throws(&c));
 }
 
+#[wasm_bindgen_test]
+fn fn_once_drops_environment_after_call() {
+    let dropped = Rc::new(Cell::new(false));
+    let dropper = Dropper(dropped.clone());
+    let args = Rc::new(Cell::new((0, 0)));
+
+    let c = Closure::once({
+        let args = args.clone();
+        // Only borrows `dropper`, so it's dropped along with the rest of the
+        // environment once the call returns.
+        move |a: u32, b: u32| {
+            assert!(!dropper.0.get());
+            args.set((a, b));
+        }
+    });
+    assert!(!dropped.get());
+
+    many_arity_call_mut3(&c);
+    assert_eq!(args.get(), (1, 2));
+    assert!(dropped.get());
+    assert_eq!(Rc::strong_count(&args), 1);
+}
+
 #[wasm_bindgen_test]
 fn once_into_js() {
     let dropped = Rc::new(Cell::new(false));
@@ -287,6 +372,34 @@ fn once_into_js() {
     assert!(call_val_throws(&f));
 }
 
+#[wasm_bindgen_test]
+fn once_into_js_args_and_return() {
+    let dropped = Rc::new(Cell::new(false));
+    let dropper = Dropper(dropped.clone());
+    let f = Closure::once_into_js(move |a: u32, b: String| {
+        drop(dropper);
+        format!("{}{}", b, a)
+    });
+    assert!(!dropped.get());
+    assert_eq!(call_val_with_args(&f), "x1");
+    assert!(dropped.get());
+    assert!(call_val_throws(&f));
+}
+
+#[wasm_bindgen_test]
+fn once_promise_executor() {
+    let dropped = Rc::new(Cell::new(false));
+    let dropper = Dropper(dropped.clone());
+    let executor = Closure::once(
+        move |resolve: js_sys::Function, _reject: js_sys::Function| {
+            drop(dropper);
+            resolve.call1(&JsValue::NULL, &"done".into()).unwrap();
+        },
+    );
+    assert_eq!(call_executor(&executor), "resolved done");
+    assert!(dropped.get());
+}
+
 #[wasm_bindgen_test]
 fn long_lived_dropping() {
     let hit = Rc::new(Cell::new(false));
@@ -300,6 +413,25 @@ fn long_lived_dropping() {
     assert!(long_lived_dropping_call().is_err());
 }
 
+#[wasm_bindgen_test]
+fn clones_share_closure() {
+    let hits = Rc::new(Cell::new(0));
+    let hits2 = hits.clone();
+    let a = Closure::wrap(Box::new(move || hits2.set(hits2.get() + 1)) as Box<Fn()>);
+    let b = a.clone();
+    assert_eq!(a.as_ref(), b.as_ref());
+    long_lived_dropping_cache(&a);
+    drop(a);
+    assert!(long_lived_dropping_call().is_ok());
+    assert_eq!(hits.get(), 1);
+    long_lived_dropping_cache(&b);
+    assert!(long_lived_dropping_call().is_ok());
+    assert_eq!(hits.get(), 2);
+    drop(b);
+    assert!(long_lived_dropping_call().is_err());
+    assert_eq!(Rc::strong_count(&hits), 1);
+}
+
 #[wasm_bindgen_test]
 fn long_fnmut_recursive() {
     let a = Closure::new(|| {
@@ -309,6 +441,26 @@ fn long_fnmut_recursive() {
     assert!(long_fnmut_recursive_call().is_ok());
 }
 
+#[wasm_bindgen_test]
+fn long_fnmut_recursive_error_message() {
+    let a = Closure::new(|| long_fnmut_recursive_error());
+    long_fnmut_recursive_cache(&a);
+    assert!(long_fnmut_recursive_call().is_ok());
+}
+
+#[wasm_bindgen_test]
+fn fn_closures_are_reentrant() {
+    let a = Closure::wrap(Box::new(|n: u32| {
+        if n == 0 {
+            0
+        } else {
+            fn_reentrant_call(n - 1) + 1
+        }
+    }) as Box<Fn(u32) -> u32>);
+    fn_reentrant_cache(&a);
+    assert_eq!(fn_reentrant_call(3), 3);
+}
+
 #[wasm_bindgen_test]
 fn fnmut() {
     let mut a = false;
@@ -499,6 +651,8 @@ fn reference_as_first_argument_builds_at_all() {
     Closure::wrap(Box::new(|_: &RefFirstArgument| ()) as Box<FnMut(&RefFirstArgument)>);
     Closure::once(|_: &RefFirstArgument| ());
     Closure::once_into_js(|_: &RefFirstArgument| ());
+    Closure::<Fn(&JsValue)>::new(|_: &JsValue| ());
+    Closure::<FnMut(&RefFirstArgument)>::new(|_: &RefFirstArgument| ());
 }
 
 #[wasm_bindgen_test]
@@ -583,4 +737,380 @@ fn forget_works() {
     js_store_forgotten_closure(&a);
     a.forget();
     js_call_forgotten_closure();
+
+    // Forgetting one clone leaves the others usable, and the closure isn't
+    // invalidated when they're dropped.
+    let hits = Rc::new(Cell::new(0));
+    let hits2 = hits.clone();
+    let a = Closure::wrap(Box::new(move || hits2.set(hits2.get() + 1)) as Box<Fn()>);
+    let b = a.clone();
+    a.forget();
+    long_lived_dropping_cache(&b);
+    assert!(long_lived_dropping_call().is_ok());
+    drop(b);
+    assert!(long_lived_dropping_call().is_ok());
+    assert_eq!(hits.get(), 2);
+}
+
+#[wasm_bindgen_test]
+fn stateless_closures() {
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+    let a = Closure::wrap(Box::new(double) as Box<FnMut(u32) -> u32>);
+    assert_eq!(stateless_call(&a), 8);
+    let b = a.as_ref().clone();
+    drop(a);
+    call_destroyed(&b);
+
+    let a = Closure::wrap(Box::new(|x: u32| x + 1) as Box<FnMut(u32) -> u32>);
+    assert_eq!(stateless_call(&a), 5);
+
+    // A closure capturing only a zero-sized value is still stateless, but
+    // its destructor has to run when it's dropped while being invoked.
+    static mut HIT: bool = false;
+    struct A;
+    impl Drop for A {
+        fn drop(&mut self) {
+            unsafe {
+                HIT = true;
+            }
+        }
+    }
+    thread_local!(static SAVED: RefCell<Option<Closure<Fn()>>> = RefCell::new(None));
+
+    let a = A;
+    let x: Closure<Fn()> = Closure::new(move || {
+        drop(SAVED.with(|saved| saved.borrow_mut().take()));
+        drop(&a);
+        unsafe {
+            assert!(!HIT);
+        }
+    });
+    drop_during_call_save(&x);
+    SAVED.with(|saved| *saved.borrow_mut() = Some(x));
+    drop_during_call_call();
+    unsafe {
+        assert!(HIT);
+    }
+}
+
+#[wasm_bindgen_test]
+fn stack_closures_reused() {
+    for i in 0..100 {
+        let mut hit = 0;
+        assert_eq!(
+            stack_closure_reentrant_call(
+                &mut |x| {
+                    hit += 1;
+                    x + i
+                },
+                1
+            ),
+            1 + i
+        );
+        assert_eq!(hit, 1);
+    }
+    // Every call above was handed the same cached JS function.
+    assert_eq!(stack_closures_seen(), 1);
+
+    // Calling the same import while one of its stack closures is still
+    // active must not clobber the outer closure's state, so the inner call
+    // gets a JS function of its own.
+    let mut inner_hit = false;
+    let ret = stack_closure_reentrant_call(
+        &mut |x| {
+            let y = stack_closure_reentrant_call(
+                &mut |y| {
+                    inner_hit = true;
+                    y * 10
+                },
+                x,
+            );
+            y + 1
+        },
+        2,
+    );
+    assert_eq!(ret, 21);
+    assert!(inner_hit);
+    assert_eq!(stack_closures_seen(), 2);
+}
+
+#[wasm_bindgen_test]
+fn stack_closures_stashed_across_calls() {
+    // The JS function is reused by the next call to the same import, but one
+    // stashed away by JS still throws when called in between calls.
+    let mut hits = 0;
+    for i in 1..3 {
+        assert_eq!(
+            stack_closure_stash_call(
+                &mut |x| {
+                    hits += 1;
+                    x * i
+                },
+                2
+            ),
+            2 * i
+        );
+        stack_closure_call_stashed(1);
+    }
+    assert_eq!(hits, 2);
+}
+
+#[wasm_bindgen_test]
+fn fnonce_stack_closures() {
+    let s = String::from("a");
+    assert_eq!(
+        fnonce_stack_call(&mut Some(move |n: u32| s.repeat(n as usize))),
+        "aaa"
+    );
+
+    let mut hit = 0;
+    fnonce_stack_call_twice(&mut Some(|| hit += 1));
+    assert_eq!(hit, 1);
+
+    fnonce_stack_save(&mut Some(|| {}));
+    fnonce_stack_call_saved();
+}
+
+#[wasm_bindgen_test]
+fn fnonce_stack_closures_with_reference_arguments() {
+    let suffix = String::from("!");
+    assert_eq!(
+        fnonce_stack_call_with_str(&mut Some(move |s: &str| s.to_string() + &suffix)),
+        "hello!"
+    );
+}
+
+#[wasm_bindgen_test]
+fn closures_returning_closures() {
+    let mut made = 0;
+    let a = Closure::wrap(Box::new(move |x: u32| {
+        made += 1;
+        assert_eq!(made, 1);
+        Closure::wrap(Box::new(move |y: u32| x + y) as Box<FnMut(u32) -> u32>)
+    }) as Box<FnMut(u32) -> Closure<FnMut(u32) -> u32>>);
+    assert_eq!(closure_returning_closure_call(&a), 3);
+}
+
+#[wasm_bindgen]
+pub fn closures_make_adder(x: u32) -> Closure<FnMut(u32) -> u32> {
+    Closure::wrap(Box::new(move |y| x + y) as Box<FnMut(u32) -> u32>)
+}
+
+#[wasm_bindgen_test]
+fn exports_return_closures() {
+    returned_closure_call();
+}
+
+#[wasm_bindgen_test]
+fn closures_taking_closures() {
+    let a = Closure::wrap(Box::new(|f: &dyn Fn(u32)| -> u32 {
+        f(1);
+        f(2);
+        3
+    }) as Box<dyn Fn(&dyn Fn(u32)) -> u32>);
+    assert_eq!(closure_argument_call(&a), 3);
+
+    let mut calls = 0;
+    let a = Closure::new(move |f: &dyn Fn(u32, String)| {
+        calls += 1;
+        f(calls, format!("call {}", calls));
+    });
+    closure_argument_call_with_strings(&a);
+}
+
+#[wasm_bindgen_test]
+fn into_js_value() {
+    let hits = Rc::new(Cell::new(0));
+    let hits2 = hits.clone();
+    let f =
+        Closure::wrap(Box::new(move || hits2.set(hits2.get() + 1)) as Box<FnMut()>).into_js_value();
+    js_store_leaked_closure(&f);
+    drop(f);
+    js_call_leaked_closure();
+    js_call_leaked_closure();
+    assert_eq!(hits.get(), 2);
+}
+
+#[wasm_bindgen_test]
+fn scoped_closures() {
+    let mut sum = 0;
+    let ret = Closure::scoped(
+        |x: u32| sum += x,
+        |f| {
+            scoped_call_twice(f);
+            "done"
+        },
+    );
+    assert_eq!(ret, "done");
+    assert_eq!(sum, 3);
+
+    // The JS function is invalidated once the scope ends.
+    assert!(scoped_call_saved_throws());
+}
+
+#[wasm_bindgen_test]
+fn closures_with_str_and_slice_arguments() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen2 = seen.clone();
+    let f = Closure::wrap(
+        Box::new(move |s: &str| seen2.borrow_mut().push(s.to_string())) as Box<FnMut(&str)>,
+    );
+    call_with_str(&f);
+    assert_eq!(*seen.borrow(), ["hello", "wörld"]);
+
+    let mut lens = Vec::new();
+    call_with_str_stack(&mut |s| lens.push(s.len()));
+    assert_eq!(lens, [5, 6]);
+
+    let f = Closure::wrap(
+        Box::new(|bytes: &[u8]| bytes.iter().map(|&b| u32::from(b)).sum()) as Box<Fn(&[u8]) -> u32>,
+    );
+    assert_eq!(call_with_bytes(&f), 6);
+}
+
+#[wasm_bindgen_test]
+fn closure_new_with_reference_arguments() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen2 = seen.clone();
+    let f = Closure::new(move |s: &str| seen2.borrow_mut().push(s.to_string()));
+    call_with_str(&f);
+    assert_eq!(*seen.borrow(), ["hello", "wörld"]);
+
+    let f = Closure::new(|bytes: &[u8]| bytes.iter().map(|&b| u32::from(b)).sum());
+    assert_eq!(call_with_bytes(&f), 6);
+}
+
+#[wasm_bindgen_test]
+fn closures_with_mutable_slice_arguments() {
+    let mut next = 0.0;
+    let f = Closure::new(move |buf: &mut [f32]| {
+        for sample in buf.iter_mut() {
+            *sample = next;
+            next += 0.5;
+        }
+    });
+    fill_f32_buffer(&f);
+
+    let f = Closure::wrap(Box::new(|buf: &mut [u8]| {
+        for b in buf.iter_mut() {
+            *b *= 2;
+        }
+    }) as Box<Fn(&mut [u8])>);
+    double_bytes(&f);
+
+    double_bytes_stack(&mut |buf| {
+        for b in buf.iter_mut() {
+            *b *= 2;
+        }
+    });
+}
+
+#[wasm_bindgen_test]
+fn closures_with_imported_type_arguments() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen2 = seen.clone();
+    let f = Closure::wrap(Box::new(move |event: ClosureEvent| {
+        seen2.borrow_mut().push(event.type_());
+    }) as Box<FnMut(ClosureEvent)>);
+    dispatch_closure_event(&f);
+    assert_eq!(*seen.borrow(), ["click"]);
+
+    let f = Closure::wrap(
+        Box::new(|event: &ClosureEvent| event.type_()) as Box<Fn(&ClosureEvent) -> String>
+    );
+    dispatch_closure_event_ref(&f);
+
+    let seen2 = seen.clone();
+    let f = Closure::wrap(Box::new(move |a: ClosureEvent, b: Option<ClosureEvent>| {
+        seen2.borrow_mut().push(a.type_());
+        assert!(b.is_none());
+    }) as Box<FnMut(ClosureEvent, Option<ClosureEvent>)>);
+    dispatch_optional_closure_event(&f);
+    assert_eq!(*seen.borrow(), ["click", "keydown"]);
+}
+
+#[wasm_bindgen_test]
+fn closures_with_many_arguments() {
+    type Twelve = Fn(u32, u32, u32, u32, u32, u32, u32, u32, u32, u32, u32, String) -> String;
+    let sum = |args: [u32; 11]| args.iter().sum::<u32>();
+    let f = Closure::wrap(
+        Box::new(move |a, b, c, d, e, f, g, h, i, j, k, name: String| {
+            format!("{} {}", name, sum([a, b, c, d, e, f, g, h, i, j, k]))
+        }) as Box<Twelve>,
+    );
+    assert_eq!(call_with_twelve_args(&f), "sum 66");
+
+    let ret = call_with_twelve_args_stack(&|a, b, c, d, e, f, g, h, i, j, k, name| {
+        format!("{} {}", name, a * b * c * d * e * f * g * h * i * j * k)
+    });
+    assert_eq!(ret, "sum 39916800");
+}
+
+#[wasm_bindgen_test]
+fn closures_returning_result() {
+    let mut total = 0;
+    let f = Closure::wrap(Box::new(move |x: u32| {
+        if x == 0 {
+            return Err(JsValue::from("zero"));
+        }
+        total += x;
+        Ok(total)
+    }) as Box<FnMut(u32) -> Result<u32, JsValue>>);
+    call_fallible(&f);
+
+    let total = Cell::new(0);
+    call_fallible_stack(&|x| {
+        if x == 0 {
+            return Err(JsValue::from("zero"));
+        }
+        total.set(total.get() + x);
+        Ok(total.get())
+    });
+}
+
+#[wasm_bindgen]
+pub struct ClosureTicket {
+    number: u32,
+}
+
+#[wasm_bindgen]
+impl ClosureTicket {
+    #[wasm_bindgen(constructor)]
+    pub fn new(number: u32) -> ClosureTicket {
+        ClosureTicket { number }
+    }
+
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+}
+
+#[wasm_bindgen_test]
+fn closures_with_exported_structs() {
+    let received = Rc::new(Cell::new(0));
+    let f = {
+        let received = received.clone();
+        Closure::wrap(Box::new(move |ticket: ClosureTicket| {
+            received.set(received.get() + ticket.number)
+        }) as Box<FnMut(ClosureTicket)>)
+    };
+    pass_ticket_to_closure(&f);
+    assert_eq!(received.get(), 3);
+
+    let mut next = 10;
+    let f = Closure::wrap(Box::new(move || {
+        next += 1;
+        ClosureTicket::new(next)
+    }) as Box<FnMut() -> ClosureTicket>);
+    assert_eq!(take_ticket_from_closure(&f), 23);
+
+    exchange_ticket(&mut |ticket| {
+        if ticket.number == 0 {
+            None
+        } else {
+            Some(ClosureTicket::new(ticket.number * 2))
+        }
+    });
 }
@@ -28,3 +28,30 @@ exports.js_expect_enum = (a, b) => {
 exports.js_expect_enum_none = a => {
   assert.strictEqual(a, undefined);
 };
+
+exports.js_ready_state = x => x;
+exports.js_shape = x => x;
+exports.js_roundtrip_shape = x => {
+  assert.strictEqual(x, 'circle');
+  return x;
+};
+
+exports.js_enum_reprs = () => {
+  assert.strictEqual(wasm.Direction.Up, 'Up');
+  assert.strictEqual(wasm.Direction.Down, 'Down');
+  assert.strictEqual(Object.keys(wasm.Direction).length, 2);
+  assert.strictEqual(wasm.enum_flip_direction('Up'), 'Down');
+  assert.strictEqual(wasm.enum_flip_direction(wasm.Direction.Down), wasm.Direction.Up);
+  assert.throws(() => wasm.enum_flip_direction('Left'), /invalid enum value passed/);
+
+  assert.strictEqual(wasm.Weekday.Monday, 1);
+  assert.strictEqual(wasm.Weekday[1], 'Monday');
+  assert.strictEqual(wasm.Weekday[wasm.Weekday.Tuesday], 'Tuesday');
+  assert.strictEqual(Object.keys(wasm.Weekday).length, 4);
+  assert.strictEqual(wasm.enum_next_weekday(wasm.Weekday.Monday), wasm.Weekday.Tuesday);
+};
+
+exports.js_roundtrip_string_enum = x => {
+  assert.ok(x === undefined || x === 'Down');
+  return x;
+};
@@ -9,6 +9,8 @@ extern "C" {
     fn js_handle_optional_enums(x: Option<Color>) -> Option<Color>;
     fn js_expect_enum(x: Color, y: Option<Color>);
     fn js_expect_enum_none(x: Option<Color>);
+    fn js_enum_reprs();
+    fn js_roundtrip_string_enum(x: Option<Direction>) -> Option<Direction>;
 }
 
 #[wasm_bindgen]
@@ -82,3 +84,82 @@ fn test_optional_enum_values() {
     js_expect_enum(Red, Some(Red));
     js_expect_enum_none(None);
 }
+
+#[wasm_bindgen(imported)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ImportedReadyState {
+    Unsent = 0,
+    Opened = 1,
+    Done = 4,
+}
+
+#[wasm_bindgen(imported)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ImportedShape {
+    Circle = "circle",
+    Square = "square",
+}
+
+#[wasm_bindgen(module = "tests/wasm/enums.js")]
+extern "C" {
+    fn js_ready_state(x: u32) -> ImportedReadyState;
+    fn js_shape(x: &str) -> ImportedShape;
+    fn js_roundtrip_shape(x: ImportedShape) -> ImportedShape;
+}
+
+#[wasm_bindgen_test]
+fn imported_enums() {
+    assert_eq!(js_ready_state(0), ImportedReadyState::Unsent);
+    assert_eq!(js_ready_state(4), ImportedReadyState::Done);
+    assert_eq!(js_ready_state(2), ImportedReadyState::__Nonexhaustive);
+    assert_eq!(js_shape("square"), ImportedShape::Square);
+    assert_eq!(js_shape("triangle"), ImportedShape::__Nonexhaustive);
+    assert_eq!(
+        js_roundtrip_shape(ImportedShape::Circle),
+        ImportedShape::Circle
+    );
+    assert_eq!(
+        ImportedReadyState::from_js_value(&JsValue::from(1)),
+        Some(ImportedReadyState::Opened)
+    );
+    assert_eq!(ImportedShape::from_js_value(&JsValue::from(1)), None);
+}
+
+#[wasm_bindgen(js_repr = string)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+#[wasm_bindgen(js_repr = both)]
+pub enum Weekday {
+    Monday = 1,
+    Tuesday = 2,
+}
+
+#[wasm_bindgen]
+pub fn enum_flip_direction(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+    }
+}
+
+#[wasm_bindgen]
+pub fn enum_next_weekday(day: Weekday) -> Weekday {
+    match day {
+        Weekday::Monday => Weekday::Tuesday,
+        Weekday::Tuesday => Weekday::Monday,
+    }
+}
+
+#[wasm_bindgen_test]
+fn enum_reprs() {
+    js_enum_reprs();
+    assert_eq!(js_roundtrip_string_enum(None), None);
+    assert_eq!(
+        js_roundtrip_string_enum(Some(Direction::Down)),
+        Some(Direction::Down)
+    );
+}
@@ -13,4 +13,42 @@ exports.call_exports = async function() {
   assert.strictEqual(7, (await wasm.async_return_7()).val);
   assert.strictEqual(8, (await wasm.async_return_8()).val);
   await assert.rejects(wasm.async_throw(), /async message/);
+
+  assert.strictEqual(9, await wasm.impl_future_return_9());
+  assert.strictEqual(10, await wasm.impl_future_js_future());
+  await assert.rejects(wasm.impl_future_throw(), /impl future message/);
+  assert.strictEqual(11, (await wasm.AsyncCustomReturn.impl_future_new(11)).val);
+
+  const resource = new wasm.AsyncResource(2);
+  const disposed = resource.dispose();
+  assert.throws(() => resource.dispose(), /Attempt to use a moved value|null pointer passed to rust/);
+  assert.strictEqual(0, wasm.async_resources_closed());
+  assert.strictEqual(undefined, await disposed);
+  assert.strictEqual(2, wasm.async_resources_closed());
+};
+
+let nothingCalled = false;
+
+exports.async_import_double = async x => x * 2;
+exports.async_import_nothing = async () => {
+  nothingCalled = true;
+};
+exports.async_import_nothing_called = () => nothingCalled;
+exports.async_import_reject = async msg => {
+  throw new Error(msg);
+};
+exports.async_import_throw_sync = () => {
+  throw 'thrown synchronously';
+};
+exports.async_import_not_a_promise = () => 7;
+
+exports.AsyncCounter = class {
+  constructor() {
+    this.count = 0;
+  }
+
+  async incrementLater(amt) {
+    this.count += amt;
+    return this.count;
+  }
 };
@@ -1,9 +1,26 @@
+use std::future::Future;
+
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen(module = "tests/wasm/futures.js")]
 extern "C" {
     fn call_exports() -> js_sys::Promise;
+
+    async fn async_import_double(x: u32) -> JsValue;
+    async fn async_import_nothing();
+    #[wasm_bindgen(js_name = async_import_double)]
+    async fn async_import_double_number(x: u32) -> js_sys::Number;
+    #[wasm_bindgen(catch)]
+    async fn async_import_reject(msg: &str) -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(catch, js_name = async_import_reject)]
+    async fn async_import_reject_error(msg: &str) -> Result<JsValue, js_sys::Error>;
+    #[wasm_bindgen(catch)]
+    async fn async_import_throw_sync() -> Result<(), JsValue>;
+    #[wasm_bindgen(catch, js_name = async_import_double)]
+    async fn async_import_double_string(x: u32) -> Result<js_sys::JsString, JsValue>;
+    async fn async_import_not_a_promise() -> JsValue;
 }
 
 #[wasm_bindgen_test]
@@ -70,3 +87,129 @@ pub async fn async_return_8() -> Result<AsyncCustomReturn, AsyncCustomReturn> {
 pub async fn async_throw() -> Result<(), js_sys::Error> {
     Err(js_sys::Error::new("async message"))
 }
+
+#[wasm_bindgen]
+pub fn impl_future_return_9() -> impl Future<Output = u32> {
+    async { 9 }
+}
+
+#[wasm_bindgen]
+pub fn impl_future_js_future() -> impl Future<Output = Result<JsValue, JsValue>> {
+    wasm_bindgen_futures::JsFuture::from(js_sys::Promise::resolve(&10.into()))
+}
+
+#[wasm_bindgen]
+pub fn impl_future_throw() -> impl Future<Output = Result<(), js_sys::Error>> {
+    async { Err(js_sys::Error::new("impl future message")) }
+}
+
+#[wasm_bindgen]
+impl AsyncCustomReturn {
+    pub fn impl_future_new(val: u32) -> impl Future<Output = AsyncCustomReturn> {
+        async move { AsyncCustomReturn { val } }
+    }
+}
+
+thread_local! {
+    static ASYNC_RESOURCES_CLOSED: std::cell::Cell<u32> = std::cell::Cell::new(0);
+}
+
+#[wasm_bindgen]
+pub struct AsyncResource {
+    closed_by: u32,
+}
+
+#[wasm_bindgen]
+impl AsyncResource {
+    #[wasm_bindgen(constructor)]
+    pub fn new(closed_by: u32) -> AsyncResource {
+        AsyncResource { closed_by }
+    }
+
+    #[wasm_bindgen(async_destructor)]
+    pub async fn close(self) -> Result<(), JsValue> {
+        async_import_nothing().await;
+        ASYNC_RESOURCES_CLOSED.with(|c| c.set(c.get() + self.closed_by));
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+pub fn async_resources_closed() -> u32 {
+    ASYNC_RESOURCES_CLOSED.with(|c| c.get())
+}
+
+#[wasm_bindgen_test]
+async fn async_imports() {
+    assert_eq!(async_import_double(2).await, 4);
+    async_import_nothing().await;
+    assert_eq!(async_import_double_number(3).await.value_of(), 6.0);
+    assert_eq!(async_import_not_a_promise().await, 7);
+
+    let err = async_import_reject("rejected").await.unwrap_err();
+    assert!(err.is_instance_of::<js_sys::Error>());
+    let err = async_import_reject_error("rejected error")
+        .await
+        .unwrap_err();
+    assert_eq!(String::from(err.message()), "rejected error");
+    let err = async_import_throw_sync().await.unwrap_err();
+    assert_eq!(err, "thrown synchronously");
+
+    // The resolved value is checked against the declared type.
+    let err = async_import_double_string(2).await.unwrap_err();
+    assert!(err.as_string().unwrap().contains("different type"));
+}
+
+#[wasm_bindgen_test]
+async fn async_import_called_eagerly() {
+    let future = async_import_nothing();
+    assert!(async_import_nothing_called());
+    future.await;
+}
+
+#[wasm_bindgen(module = "tests/wasm/futures.js")]
+extern "C" {
+    fn async_import_nothing_called() -> bool;
+}
+
+#[wasm_bindgen_test]
+async fn spawned_tasks_run_in_order() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let order = Rc::new(RefCell::new(Vec::new()));
+    let done = js_sys::Promise::new(&mut |resolve, _reject| {
+        let order2 = order.clone();
+        wasm_bindgen::spawn_macrotask(move || {
+            order2.borrow_mut().push("macrotask");
+            resolve.call0(&JsValue::undefined()).unwrap();
+        });
+        let order2 = order.clone();
+        wasm_bindgen::spawn_microtask(move || order2.borrow_mut().push("microtask"));
+    });
+    order.borrow_mut().push("sync");
+
+    wasm_bindgen_futures::JsFuture::from(done).await.unwrap();
+    assert_eq!(*order.borrow(), ["sync", "microtask", "macrotask"]);
+}
+
+#[wasm_bindgen(module = "tests/wasm/futures.js")]
+extern "C" {
+    type AsyncCounter;
+    #[wasm_bindgen(constructor)]
+    fn new() -> AsyncCounter;
+}
+
+#[wasm_bindgen(module = "tests/wasm/futures.js", extension_trait = AsyncCounterExt)]
+extern "C" {
+    #[wasm_bindgen(method, js_name = incrementLater)]
+    async fn increment_later(this: &AsyncCounter, amt: u32) -> JsValue;
+}
+
+#[wasm_bindgen_test]
+async fn async_extension_trait_methods() {
+    let counter = AsyncCounter::new();
+    assert_eq!(counter.increment_later(2).await, 2);
+    let future = <AsyncCounter as AsyncCounterExt>::increment_later(&counter, 3);
+    assert_eq!(future.await, 5);
+}
@@ -56,3 +56,23 @@ exports.test_getter_compute = x => {
 exports.test_setter_compute = x => {
   x.foo = 97;
 };
+
+exports.test_optional_and_fallible = x => {
+  assert.strictEqual(x.celsius, undefined);
+  x.celsius = 21.5;
+  assert.strictEqual(x.celsius, 21.5);
+  assert.throws(() => { x.celsius = -300; }, RangeError);
+  assert.strictEqual(x.celsius, 21.5);
+  x.celsius = undefined;
+  assert.strictEqual(x.celsius, undefined);
+};
+
+exports.test_cached_getters = x => {
+  assert.strictEqual(x.reads(), 0);
+  assert.strictEqual(x.name, 'config');
+  assert.strictEqual(x.name, 'config');
+  assert.strictEqual(x.reads(), 1);
+  assert.strictEqual(x.version, 3);
+  assert.strictEqual(x.version, 3);
+  assert.ok(Object.getOwnPropertyDescriptor(x, 'name') !== undefined);
+};
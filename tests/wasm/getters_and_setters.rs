@@ -15,6 +15,8 @@ extern "C" {
 
     fn test_getter_compute(x: GetterCompute);
     fn test_setter_compute(x: SetterCompute);
+    fn test_optional_and_fallible(x: Temperature);
+    fn test_cached_getters(x: Snapshot);
 }
 
 // Each getter/setter combination is derived
@@ -163,3 +165,62 @@ fn setter_compute() {
     test_setter_compute(SetterCompute(r.clone()));
     assert_eq!(r.get(), 100);
 }
+
+#[wasm_bindgen]
+pub struct Temperature {
+    celsius: Option<f64>,
+}
+
+#[wasm_bindgen]
+impl Temperature {
+    #[wasm_bindgen(getter)]
+    pub fn celsius(&self) -> Option<f64> {
+        self.celsius
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_celsius(&mut self, celsius: Option<f64>) -> Result<(), JsValue> {
+        match celsius {
+            Some(c) if c < -273.15 => Err(js_sys::RangeError::new("below absolute zero").into()),
+            _ => {
+                self.celsius = celsius;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn optional_and_fallible() {
+    test_optional_and_fallible(Temperature { celsius: None });
+}
+
+#[wasm_bindgen]
+pub struct Snapshot {
+    #[wasm_bindgen(readonly, cached)]
+    pub version: u32,
+    reads: Rc<Cell<u32>>,
+}
+
+#[wasm_bindgen]
+impl Snapshot {
+    #[wasm_bindgen(getter, cached)]
+    pub fn name(&self) -> String {
+        self.reads.set(self.reads.get() + 1);
+        "config".to_string()
+    }
+
+    pub fn reads(&self) -> u32 {
+        self.reads.get()
+    }
+}
+
+#[wasm_bindgen_test]
+fn cached_getters() {
+    let reads = Rc::new(Cell::new(0));
+    test_cached_getters(Snapshot {
+        version: 3,
+        reads: reads.clone(),
+    });
+    assert_eq!(reads.get(), 1);
+}
@@ -58,6 +58,17 @@ exports.test_exception_propagates = function() {
   assert.throws(wasm.exceptions_propagate, /error!/);
 };
 
+class ImportsTypedError extends Error {}
+exports.ImportsTypedError = ImportsTypedError;
+exports.exceptions_throw_typed = function(typed) {
+  if (typed)
+    throw new ImportsTypedError('typed');
+  throw new Error('untyped');
+};
+exports.test_untyped_exception_propagates = function() {
+  assert.throws(wasm.exceptions_typed_propagate, /untyped/);
+};
+
 exports.assert_valid_error = function(obj) {
   assert.strictEqual(obj instanceof Error, true);
   assert.strictEqual(obj.message, 'error2');
@@ -127,3 +138,29 @@ exports.receive_some = val => {
 };
 
 exports.get_some_val = () => VAL;
+
+class SplitCounter {
+  constructor() {
+    this._count = 0;
+  }
+
+  static startingAt(count) {
+    const ret = new SplitCounter();
+    ret._count = count;
+    return ret;
+  }
+
+  get count() {
+    return this._count;
+  }
+
+  increment() {
+    this._count += 1;
+  }
+
+  incrementBy(amt) {
+    this._count += amt;
+  }
+}
+
+exports.SplitCounter = SplitCounter;
@@ -21,6 +21,13 @@ extern "C" {
     fn exceptions_throw2() -> Result<(), JsValue>;
     fn test_exception_propagates();
 
+    type ImportsTypedError;
+    #[wasm_bindgen(method, getter)]
+    fn message(this: &ImportsTypedError) -> String;
+    #[wasm_bindgen(catch)]
+    fn exceptions_throw_typed(typed: bool) -> Result<u32, ImportsTypedError>;
+    fn test_untyped_exception_propagates();
+
     fn assert_valid_error(val: JsValue);
 
     static IMPORT: JsValue;
@@ -128,6 +135,18 @@ fn exn_caught() {
     assert_valid_error(exceptions_throw2().unwrap_err());
 }
 
+#[wasm_bindgen_test]
+fn exn_caught_typed() {
+    assert_eq!(exceptions_throw_typed(true).unwrap_err().message(), "typed");
+    test_untyped_exception_propagates();
+}
+
+#[wasm_bindgen]
+pub fn exceptions_typed_propagate() {
+    let _ = exceptions_throw_typed(false);
+    panic!("untyped exception should have propagated");
+}
+
 #[wasm_bindgen_test]
 fn free_imports() {
     assert_eq!(parseInt("3"), 3);
@@ -274,3 +293,49 @@ fn pass_out_options_as_undefined() {
     receive_some_owned(Some(v.clone()));
     receive_some_owned(Some(v));
 }
+
+#[wasm_bindgen(module = "tests/wasm/imports.js")]
+extern "C" {
+    type SplitCounter;
+    #[wasm_bindgen(constructor)]
+    fn new() -> SplitCounter;
+    #[wasm_bindgen(method)]
+    fn increment(this: &SplitCounter);
+}
+
+#[wasm_bindgen(module = "tests/wasm/imports.js")]
+extern "C" {
+    #[wasm_bindgen(method, getter)]
+    fn count(this: &SplitCounter) -> u32;
+}
+
+#[wasm_bindgen(module = "tests/wasm/imports.js", extension_trait = SplitCounterExt)]
+extern "C" {
+    #[wasm_bindgen(method, js_name = incrementBy)]
+    fn increment_by(this: &SplitCounter, amt: u32);
+    #[wasm_bindgen(static_method_of = SplitCounter, js_name = startingAt)]
+    fn starting_at(count: u32) -> SplitCounter;
+}
+
+#[wasm_bindgen(module = "tests/wasm/imports.js")]
+extern "C" {
+    #[wasm_bindgen(method, js_name = incrementBy, extension_trait = SplitCounterExt2)]
+    fn add(this: &SplitCounter, amt: u32);
+}
+
+#[wasm_bindgen_test]
+fn methods_split_across_extern_blocks() {
+    let counter = SplitCounter::new();
+    counter.increment();
+    counter.increment();
+    assert_eq!(counter.count(), 2);
+}
+
+#[wasm_bindgen_test]
+fn methods_in_extension_traits() {
+    let counter = <SplitCounter as SplitCounterExt>::starting_at(10);
+    counter.increment_by(5);
+    assert_eq!(counter.count(), 15);
+    counter.add(1);
+    assert_eq!(counter.count(), 16);
+}
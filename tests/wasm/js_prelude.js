@@ -0,0 +1,3 @@
+// Inserted at the top of the generated JS through `js_prelude!`, so this is a
+// plain script rather than a module.
+globalThis.__wbg_test_prelude_file = 'from a file';
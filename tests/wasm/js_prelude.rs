@@ -0,0 +1,24 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen::js_prelude!(
+    "globalThis.__wbg_test_prelude_count = (globalThis.__wbg_test_prelude_count || 0) + 1;"
+);
+wasm_bindgen::js_prelude!(
+    "globalThis.__wbg_test_prelude_count = (globalThis.__wbg_test_prelude_count || 0) + 1;"
+);
+wasm_bindgen::js_prelude!(module = "/tests/wasm/js_prelude.js");
+
+fn global(name: &str) -> JsValue {
+    js_sys::Reflect::get(&js_sys::global(), &name.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn inline_prelude_runs_once() {
+    assert_eq!(global("__wbg_test_prelude_count"), 1);
+}
+
+#[wasm_bindgen_test]
+fn file_prelude_runs() {
+    assert_eq!(global("__wbg_test_prelude_file"), "from a file");
+}
@@ -0,0 +1,2 @@
+// This file is only linked to, and never imported, by `link_to.rs`.
+exports.unused = () => {};
@@ -0,0 +1,9 @@
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn link_to_local_file() {
+    let url = wasm_bindgen::link_to!(module = "/tests/wasm/link_to.js");
+    assert!(url.ends_with("/tests/wasm/link_to.js"), "{}", url);
+    let again = wasm_bindgen::link_to!(module = "/tests/wasm/link_to.js");
+    assert_eq!(url, again);
+}
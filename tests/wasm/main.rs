@@ -28,17 +28,27 @@ pub mod getters_and_setters;
 pub mod import_class;
 pub mod imports;
 pub mod js_objects;
+pub mod js_prelude;
+pub mod link_to;
 pub mod jscast;
 pub mod math;
+pub mod mod_attrs;
 pub mod no_shims;
 pub mod node;
 pub mod option;
 pub mod optional_primitives;
+pub mod props;
+pub mod repr;
 pub mod rethrow;
 pub mod simple;
 pub mod slice;
 pub mod structural;
+pub mod time;
+pub mod traits;
 pub mod truthy_falsy;
+pub mod tuples;
+pub mod try_from;
+pub mod u128;
 pub mod u64;
 pub mod validate_prt;
 pub mod variadic;
@@ -0,0 +1,15 @@
+const wasm = require('wasm-bindgen-test.js');
+
+exports.mod_attrs_double = x => x * 2;
+
+exports.counter = {
+    value: 0,
+    increment() {
+        this.value += 1;
+    },
+    count() {
+        return this.value;
+    },
+};
+
+exports.js_call_square = x => wasm.mod_attrs_square(x);
@@ -0,0 +1,58 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/mod_attrs.js")]
+mod imports {
+    extern "C" {
+        pub fn mod_attrs_double(x: u32) -> u32;
+    }
+
+    #[wasm_bindgen(js_namespace = counter)]
+    extern "C" {
+        pub fn increment();
+        #[wasm_bindgen(js_name = count)]
+        pub fn get_count() -> u32;
+    }
+
+    #[wasm_bindgen(inline_js = "export function mod_attrs_triple(x) { return x * 3; }")]
+    extern "C" {
+        pub fn mod_attrs_triple(x: u32) -> u32;
+    }
+
+    #[wasm_bindgen]
+    pub fn mod_attrs_square(x: u32) -> u32 {
+        x * x
+    }
+
+    // Items without `#[wasm_bindgen]` are left alone.
+    pub fn quadruple(x: u32) -> u32 {
+        mod_attrs_double(mod_attrs_double(x))
+    }
+}
+
+#[wasm_bindgen(module = "tests/wasm/mod_attrs.js")]
+mod outer {
+    #[wasm_bindgen(js_namespace = counter)]
+    pub mod inner {
+        extern "C" {
+            pub fn count() -> u32;
+        }
+    }
+}
+
+#[wasm_bindgen(module = "tests/wasm/mod_attrs.js")]
+extern "C" {
+    fn js_call_square(x: u32) -> u32;
+}
+
+#[wasm_bindgen_test]
+fn attributes_are_inherited() {
+    assert_eq!(imports::mod_attrs_double(2), 4);
+    assert_eq!(imports::quadruple(2), 8);
+    imports::increment();
+    imports::increment();
+    assert_eq!(imports::get_count(), 2);
+    assert_eq!(outer::inner::count(), 2);
+    assert_eq!(imports::mod_attrs_triple(2), 6);
+    assert_eq!(js_call_square(4), 16);
+}
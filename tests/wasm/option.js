@@ -26,3 +26,21 @@ exports.test_option_values = () => {
     assert.ok(x !== null && x !== undefined);
     assert.ok(x instanceof MyType);
 };
+
+exports.test_nested_results = async () => {
+    assert.strictEqual(wasm.rust_result_option_u32(3), 3);
+    assert.strictEqual(wasm.rust_result_option_u32(0), undefined);
+    assert.throws(() => wasm.rust_result_option_u32(-1), /negative/);
+
+    assert.strictEqual(wasm.rust_option_result_string(3), '3');
+    assert.strictEqual(wasm.rust_option_result_string(0), undefined);
+    assert.throws(() => wasm.rust_option_result_string(-1), /negative/);
+
+    assert.ok(wasm.rust_option_result_my_type(1) instanceof MyType);
+    assert.strictEqual(wasm.rust_option_result_my_type(0), undefined);
+    assert.throws(() => wasm.rust_option_result_my_type(-1), /negative/);
+
+    assert.strictEqual(await wasm.rust_option_result_async(3), 3);
+    assert.strictEqual(await wasm.rust_option_result_async(0), undefined);
+    await assert.rejects(wasm.rust_option_result_async(-1), /negative/);
+};
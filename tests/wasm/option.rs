@@ -13,6 +13,7 @@ extern "C" {
     fn return_null_byval() -> Option<MyType>;
     fn return_some_byval() -> Option<MyType>;
     fn test_option_values();
+    fn test_nested_results() -> js_sys::Promise;
 
     #[wasm_bindgen(js_name = take_none_byval)]
     fn take_none_byref(t: Option<&MyType>);
@@ -59,3 +60,42 @@ fn import_by_ref() {
     take_none_byref(None);
     take_some_byref(Some(&MyType::new()));
 }
+
+#[wasm_bindgen_test]
+async fn nested_results() {
+    wasm_bindgen_futures::JsFuture::from(test_nested_results())
+        .await
+        .unwrap();
+}
+
+#[wasm_bindgen]
+pub fn rust_result_option_u32(n: i32) -> Result<Option<u32>, JsValue> {
+    match n {
+        n if n < 0 => Err("negative".into()),
+        0 => Ok(None),
+        n => Ok(Some(n as u32)),
+    }
+}
+
+#[wasm_bindgen]
+pub fn rust_option_result_string(n: i32) -> Option<Result<String, JsValue>> {
+    match n {
+        n if n < 0 => Some(Err("negative".into())),
+        0 => None,
+        n => Some(Ok(n.to_string())),
+    }
+}
+
+#[wasm_bindgen]
+pub fn rust_option_result_my_type(n: i32) -> Option<Result<MyType, JsValue>> {
+    match n {
+        n if n < 0 => Some(Err("negative".into())),
+        0 => None,
+        _ => Some(Ok(MyType::new())),
+    }
+}
+
+#[wasm_bindgen]
+pub async fn rust_option_result_async(n: i32) -> Option<Result<u32, JsValue>> {
+    rust_result_option_u32(n).transpose()
+}
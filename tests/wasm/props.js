@@ -0,0 +1,29 @@
+const assert = require('assert');
+
+exports.props_config = () => ({
+  name: 'config',
+  network: { host: 'example.com', retries: 2 },
+});
+
+exports.props_check_config = config => {
+  assert.strictEqual(config.name, 'updated');
+  assert.strictEqual(config.network.retries, 5);
+  assert.strictEqual(config.missing, undefined);
+};
+
+exports.PropsStyle = class {
+  constructor() {
+    this.color = 'black';
+    this._width = 10;
+    this.widthWrites = 0;
+  }
+
+  get width() {
+    return this._width;
+  }
+
+  set width(width) {
+    this.widthWrites += 1;
+    this._width = width;
+  }
+};
@@ -0,0 +1,129 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::{get_prop, set_prop, Property, TryFromJsValueError};
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/props.js")]
+extern "C" {
+    type PropsConfig;
+    fn props_config() -> PropsConfig;
+    fn props_check_config(config: &PropsConfig);
+
+    type PropsStyle;
+    #[wasm_bindgen(constructor)]
+    fn new() -> PropsStyle;
+    #[wasm_bindgen(method, property)]
+    fn color(this: &PropsStyle) -> Property<String>;
+    #[wasm_bindgen(method, property, js_name = width)]
+    fn style_width(this: &PropsStyle) -> Property<f64>;
+    #[wasm_bindgen(method, property, js_name = widthWrites)]
+    fn width_writes(this: &PropsStyle) -> Property<u32>;
+}
+
+#[wasm_bindgen_test]
+fn get_nested() {
+    let config = props_config();
+    assert_eq!(get_prop!(config.name).unwrap(), "config");
+    assert_eq!(
+        get_prop!(config.network.retries).unwrap().as_f64(),
+        Some(2.0)
+    );
+    assert_eq!(
+        get_prop!((props_config()).network.host).unwrap(),
+        "example.com"
+    );
+    assert!(get_prop!(config.missing).unwrap().is_undefined());
+}
+
+#[wasm_bindgen_test]
+fn get_through_undefined() {
+    let config: JsValue = props_config().into();
+    let err = get_prop!(config.missing.nested).unwrap_err();
+    assert_eq!(
+        err.as_string().unwrap(),
+        "cannot read property `nested` of undefined"
+    );
+}
+
+#[wasm_bindgen_test]
+fn set_nested() {
+    let config = props_config();
+    set_prop!(config.network.retries = 5).unwrap();
+    set_prop!(config.name = "updated").unwrap();
+    props_check_config(&config);
+
+    let err = set_prop!(config.missing.nested = true).unwrap_err();
+    assert_eq!(
+        err.as_string().unwrap(),
+        "cannot set property `nested` of undefined"
+    );
+}
+
+#[wasm_bindgen_test]
+fn get_path_short_circuits() {
+    let config: JsValue = props_config().into();
+    assert_eq!(config.get_path(&["name"]).unwrap(), "config");
+    assert_eq!(
+        config
+            .get_path(&["network", "retries"])
+            .and_then(|v| v.as_f64()),
+        Some(2.0)
+    );
+    assert_eq!(config.get_path(&[]), Some(config.clone()));
+    assert_eq!(config.get_path(&["missing"]), None);
+    assert_eq!(config.get_path(&["missing", "nested", "deeper"]), None);
+    assert_eq!(config.get_path(&["name", "length"]).unwrap(), 6);
+    assert_eq!(JsValue::NULL.get_path(&["anything"]), None);
+    assert_eq!(JsValue::UNDEFINED.get_path(&[]), None);
+}
+
+#[wasm_bindgen_test]
+fn get_path_non_ascii_keys() {
+    let obj = js_sys::Object::new();
+    let inner = js_sys::Object::new();
+    js_sys::Reflect::set(&inner, &"ñ😀".into(), &"deep".into()).unwrap();
+    js_sys::Reflect::set(&obj, &"日本".into(), &inner).unwrap();
+    let obj = JsValue::from(obj);
+    assert_eq!(obj.get_path(&["日本", "ñ😀"]).unwrap(), "deep");
+}
+
+#[wasm_bindgen_test]
+fn property_handles() {
+    let style = PropsStyle::new();
+    let color = style.color();
+    assert_eq!(color.name(), "color");
+    assert_eq!(color.get().unwrap(), "black");
+    color.set("red".to_string());
+    assert_eq!(style.color().get().unwrap(), "red");
+
+    // Accessors defined in JS are invoked.
+    let width = style.style_width();
+    assert_eq!(width.get(), Ok(10.0));
+    width.set(20.0);
+    assert_eq!(width.get(), Ok(20.0));
+    assert_eq!(style.width_writes().get(), Ok(1));
+
+    width.set_js("wide".into());
+    assert_eq!(
+        width.get(),
+        Err(TryFromJsValueError::WrongType { expected: "number" })
+    );
+}
+
+fn double(prop: &Property<f64>) {
+    prop.set(prop.get().unwrap() * 2.0);
+}
+
+#[wasm_bindgen_test]
+fn property_handles_are_values() {
+    let style = PropsStyle::new();
+    double(&style.style_width());
+    assert_eq!(style.style_width().get(), Ok(20.0));
+
+    let obj: JsValue = js_sys::Object::new().into();
+    let count = Property::<f64>::new(&obj, "count");
+    assert!(count.get_js().is_undefined());
+    count.set(1.0);
+    double(&count);
+    assert_eq!(get_prop!(obj.count).unwrap(), 2.0);
+    assert_eq!(count.clone().get(), Ok(2.0));
+}
@@ -0,0 +1,9 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.repr_next_id = id => id + 1;
+
+exports.js_repr_round_trip = () => {
+  assert.strictEqual(wasm.repr_email_domain('someone@rust-lang.org'), 'rust-lang.org');
+  assert.strictEqual(wasm.repr_make_email('me'), 'me@example.com');
+};
@@ -0,0 +1,71 @@
+use wasm_bindgen::convert::WasmRepr;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/repr.js")]
+extern "C" {
+    fn js_repr_round_trip();
+    fn repr_next_id(id: ReprUserId) -> ReprUserId;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReprUserId(u32);
+
+impl WasmRepr for ReprUserId {
+    type Repr = u32;
+
+    fn into_repr(self) -> u32 {
+        self.0
+    }
+
+    fn from_repr(repr: u32) -> ReprUserId {
+        ReprUserId(repr)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReprEmail {
+    user: String,
+    domain: String,
+}
+
+impl WasmRepr for ReprEmail {
+    type Repr = String;
+
+    fn into_repr(self) -> String {
+        format!("{}@{}", self.user, self.domain)
+    }
+
+    fn from_repr(repr: String) -> ReprEmail {
+        let at = repr.find('@').unwrap();
+        ReprEmail {
+            user: repr[..at].to_string(),
+            domain: repr[at + 1..].to_string(),
+        }
+    }
+}
+
+wasm_bindgen::wasm_repr!(ReprUserId, ReprEmail);
+
+#[wasm_bindgen]
+pub fn repr_email_domain(email: ReprEmail) -> String {
+    email.domain
+}
+
+#[wasm_bindgen]
+pub fn repr_make_email(user: &str) -> ReprEmail {
+    ReprEmail {
+        user: user.to_string(),
+        domain: "example.com".to_string(),
+    }
+}
+
+#[wasm_bindgen_test]
+fn imports() {
+    assert_eq!(repr_next_id(ReprUserId(3)), ReprUserId(4));
+}
+
+#[wasm_bindgen_test]
+fn exports() {
+    js_repr_round_trip();
+}
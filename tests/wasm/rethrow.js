@@ -12,3 +12,32 @@ exports.call_throw_one = function() {
 exports.call_ok = function() {
   wasm.nothrow();
 };
+
+exports.adder = function() {
+  return function(a, b) {
+    'use strict';
+    assert.strictEqual(this, 'receiver');
+    return a + b;
+  };
+};
+
+exports.thrower = function() {
+  return function(val) {
+    throw val;
+  };
+};
+
+exports.reentrant = function() {
+  return function(val) {
+    wasm.throw_back(val);
+    throw new Error('unreachable');
+  };
+};
+
+exports.args = function(...args) {
+  return args;
+};
+
+exports.throws_without_catch = function(val) {
+  throw val;
+};
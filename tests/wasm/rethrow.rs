@@ -5,6 +5,14 @@ use wasm_bindgen_test::*;
 extern "C" {
     fn call_throw_one();
     fn call_ok();
+    fn adder() -> JsValue;
+    fn thrower() -> JsValue;
+    fn reentrant() -> JsValue;
+    #[wasm_bindgen(js_name = args)]
+    fn args1(a: &JsValue) -> JsValue;
+    #[wasm_bindgen(js_name = args)]
+    fn args2(a: u32, b: u32) -> JsValue;
+    fn throws_without_catch(val: &JsValue);
 }
 
 #[wasm_bindgen_test]
@@ -26,3 +34,35 @@ fn ok_works() {
 pub fn nothrow() -> Result<u32, JsValue> {
     Ok(1)
 }
+
+#[wasm_bindgen]
+pub fn throw_back(val: JsValue) {
+    wasm_bindgen::throw_val(val);
+}
+
+#[wasm_bindgen_test]
+fn try_catch_works() {
+    let ret = wasm_bindgen::try_catch(&adder(), &"receiver".into(), &args2(1, 2));
+    assert_eq!(ret.unwrap().as_f64(), Some(3.0));
+
+    let ret = wasm_bindgen::try_catch(&thrower(), &JsValue::NULL, &args1(&"oops".into()));
+    assert_eq!(ret.unwrap_err(), "oops");
+}
+
+#[wasm_bindgen_test]
+fn try_catch_reentrant() {
+    let ret = wasm_bindgen::try_catch(&reentrant(), &JsValue::NULL, &args1(&7.into()));
+    assert_eq!(ret.unwrap_err().as_f64(), Some(7.0));
+}
+
+#[wasm_bindgen_test]
+fn try_catch_with_works() {
+    let ret = wasm_bindgen::try_catch_with(|| 1 + 2);
+    assert_eq!(ret.unwrap(), 3);
+
+    let ret = wasm_bindgen::try_catch_with(|| throws_without_catch(&"oops".into()));
+    assert_eq!(ret.unwrap_err(), "oops");
+
+    let ret = wasm_bindgen::try_catch_with(|| throw_back(8.into()));
+    assert_eq!(ret.unwrap_err().as_f64(), Some(8.0));
+}
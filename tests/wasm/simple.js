@@ -105,3 +105,9 @@ exports.test_string_roundtrip = () => {
   test('a longer string');
   test('a longer 💖 string');
 };
+
+exports.test_namespaced_exports = function() {
+  assert.strictEqual(wasm.simple_ns.simple_ns_add(1, 2), 3);
+  assert.strictEqual(wasm.simple_ns.simple_ns_renamed(), 7);
+  assert.strictEqual(wasm.simple_ns_add, undefined);
+};
@@ -225,3 +225,23 @@ fn anyref_heap_live_count() {
     drop(y);
     assert_eq!(x, wasm_bindgen::anyref_heap_live_count());
 }
+
+#[wasm_bindgen(module = "tests/wasm/simple.js")]
+extern "C" {
+    fn test_namespaced_exports();
+}
+
+#[wasm_bindgen(js_namespace = simple_ns)]
+pub fn simple_ns_add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[wasm_bindgen(js_namespace = simple_ns, js_name = simple_ns_renamed)]
+pub fn simple_ns_other() -> u32 {
+    7
+}
+
+#[wasm_bindgen_test]
+fn namespaced_exports() {
+    test_namespaced_exports();
+}
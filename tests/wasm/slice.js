@@ -188,6 +188,28 @@ exports.js_return_vec = () => {
     }
 };
 
+exports.js_return_owned_view = () => {
+    const a = wasm.return_owned_view_u8();
+    assert.ok(a instanceof Uint8Array);
+    assert.deepStrictEqual(Array.from(a), [1, 2, 3]);
+    // the view points straight at the vector's allocation in wasm memory
+    assert.strictEqual(a.byteOffset, wasm.owned_view_ptr());
+    a.free();
+    a.free();
+
+    const b = wasm.return_owned_view_f64();
+    assert.ok(b instanceof Float64Array);
+    assert.deepStrictEqual(Array.from(b), [1.5, 2.5]);
+    b.free();
+
+    const app = wasm.return_vec_web_main();
+    const c = app.owned_thing();
+    assert.ok(c instanceof Uint32Array);
+    assert.deepStrictEqual(Array.from(c), [0, 0, 0, 0, 0]);
+    c.free();
+    app.free();
+};
+
 exports.js_clamped = (a, offset) => {
   assert.ok(a instanceof Uint8ClampedArray);
   assert.equal(a.length, 3);
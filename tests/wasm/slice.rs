@@ -1,3 +1,4 @@
+use std::cell::Cell;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::Clamped;
 use wasm_bindgen_test::*;
@@ -14,6 +15,8 @@ extern "C" {
 
     fn js_return_vec();
 
+    fn js_return_owned_view();
+
     fn js_clamped(val: Clamped<&[u8]>, offset: u8);
     #[wasm_bindgen(js_name = js_clamped)]
     fn js_clamped2(val: Clamped<Vec<u8>>, offset: u8);
@@ -232,6 +235,40 @@ fn return_vec() {
     js_return_vec();
 }
 
+thread_local! {
+    static OWNED_VIEW_PTR: Cell<u32> = Cell::new(0);
+}
+
+#[wasm_bindgen(no_copy)]
+pub fn return_owned_view_u8() -> Vec<u8> {
+    let v = vec![1, 2, 3];
+    OWNED_VIEW_PTR.with(|p| p.set(v.as_ptr() as u32));
+    v
+}
+
+#[wasm_bindgen]
+pub fn owned_view_ptr() -> u32 {
+    OWNED_VIEW_PTR.with(|p| p.get())
+}
+
+#[wasm_bindgen(no_copy)]
+pub fn return_owned_view_f64() -> Box<[f64]> {
+    vec![1.5, 2.5].into_boxed_slice()
+}
+
+#[wasm_bindgen]
+impl ReturnVecApplication {
+    #[wasm_bindgen(no_copy)]
+    pub fn owned_thing(&self) -> Vec<u32> {
+        self.thing.clone()
+    }
+}
+
+#[wasm_bindgen_test]
+fn return_owned_view() {
+    js_return_owned_view();
+}
+
 #[wasm_bindgen_test]
 fn take_clamped() {
     js_clamped(Clamped(&[1, 2, 3]), 1);
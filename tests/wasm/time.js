@@ -0,0 +1,17 @@
+const assert = require('assert');
+const wasm = require('wasm-bindgen-test');
+
+exports.js_duration_millis = d => d;
+exports.js_duration_from_millis = ms => ms;
+exports.js_system_time_from_date = year => Date.UTC(year, 0, 1);
+exports.js_system_time_to_year = t => new Date(t).getUTCFullYear();
+exports.js_date_now = () => Date.now();
+
+exports.js_out_of_range_times = () => {
+  assert.strictEqual(wasm.time_duration_round_trip(NaN), 0);
+  assert.throws(() => wasm.time_duration_round_trip(Infinity), /too large/);
+  assert.throws(() => wasm.time_duration_round_trip(1e300), /too large/);
+  // `SystemTime` can't represent times before the epoch on wasm.
+  const before = Date.UTC(1960, 0, 1);
+  assert.throws(() => wasm.time_system_time_round_trip(before), /can't be represented/);
+};
@@ -0,0 +1,76 @@
+#![cfg(feature = "std-time")]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::time::{system_time_now, Instant};
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/time.js")]
+extern "C" {
+    fn js_duration_millis(d: Duration) -> f64;
+    fn js_duration_from_millis(ms: f64) -> Duration;
+    fn js_system_time_from_date(year: u32) -> SystemTime;
+    fn js_system_time_to_year(t: SystemTime) -> u32;
+    fn js_date_now() -> SystemTime;
+    fn js_out_of_range_times();
+}
+
+#[wasm_bindgen_test]
+fn durations() {
+    assert_eq!(js_duration_millis(Duration::from_millis(1500)), 1500.0);
+    assert_eq!(js_duration_millis(Duration::from_micros(250)), 0.25);
+    assert_eq!(
+        js_duration_from_millis(2000.5),
+        Duration::from_micros(2_000_500)
+    );
+    assert_eq!(js_duration_from_millis(-1.0), Duration::from_secs(0));
+}
+
+#[wasm_bindgen_test]
+fn system_times() {
+    let t = js_system_time_from_date(2000);
+    assert_eq!(
+        t.duration_since(UNIX_EPOCH).unwrap(),
+        Duration::from_secs(946_684_800)
+    );
+    assert_eq!(js_system_time_to_year(t), 2000);
+}
+
+#[wasm_bindgen]
+pub fn time_duration_round_trip(d: Duration) -> Duration {
+    d
+}
+
+#[wasm_bindgen]
+pub fn time_system_time_round_trip(t: SystemTime) -> SystemTime {
+    t
+}
+
+#[wasm_bindgen_test]
+fn out_of_range_times() {
+    js_out_of_range_times();
+}
+
+#[wasm_bindgen_test]
+fn instants() {
+    let start = Instant::now();
+    let later = start + Duration::from_millis(5);
+    assert!(later > start);
+    assert_eq!(later - start, Duration::from_millis(5));
+    assert_eq!(later.duration_since(start), Duration::from_millis(5));
+    assert_eq!(start.duration_since(later), Duration::from_secs(0));
+    assert_eq!(start.checked_duration_since(later), None);
+    assert!(Instant::now() >= start);
+    assert!(start.elapsed() < Duration::from_secs(60));
+}
+
+#[wasm_bindgen_test]
+fn system_time_now_matches_date() {
+    let now = system_time_now();
+    let diff = match js_date_now().duration_since(now) {
+        Ok(d) => d,
+        Err(e) => e.duration(),
+    };
+    assert!(diff < Duration::from_secs(1));
+    assert!(js_system_time_to_year(now) >= 2020);
+}
@@ -0,0 +1,31 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.js_trait_implemented_in_js = () => {
+    let calls = 0;
+    const greeter = {
+        greet(name) {
+            calls += 1;
+            return `hello ${name}!`;
+        },
+        count() {
+            return calls;
+        },
+        sayGoodbye() {},
+    };
+    assert.strictEqual(wasm.traits_greet_twice(greeter, 'js'), 'hello js! hello js!');
+    assert.strictEqual(calls, 2);
+};
+
+exports.js_trait_renamed_method = () => {
+    let goodbye = false;
+    class Greeter {
+        greet() { return 'hi'; }
+        count() { return 2; }
+        sayGoodbye() { goodbye = true; }
+    }
+    wasm.traits_greet_twice(new Greeter(), 'class');
+    assert.strictEqual(goodbye, true);
+    assert.strictEqual(wasm.traits_is_object(new Greeter()), true);
+    assert.strictEqual(wasm.traits_is_object(3), false);
+};
@@ -0,0 +1,45 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/traits.js")]
+extern "C" {
+    fn js_trait_implemented_in_js();
+    fn js_trait_renamed_method();
+}
+
+#[wasm_bindgen]
+pub trait Greeter {
+    fn greet(&self, name: &str) -> String;
+    fn count(&mut self) -> u32;
+    #[wasm_bindgen(js_name = sayGoodbye)]
+    fn say_goodbye(&self);
+}
+
+fn greet_twice<G: Greeter>(greeter: &mut G, name: &str) -> String {
+    let a = greeter.greet(name);
+    let b = greeter.greet(name);
+    assert_eq!(greeter.count(), 2);
+    greeter.say_goodbye();
+    format!("{} {}", a, b)
+}
+
+#[wasm_bindgen]
+pub fn traits_greet_twice(mut greeter: GreeterJsImpl, name: &str) -> String {
+    greet_twice(&mut greeter, name)
+}
+
+#[wasm_bindgen]
+pub fn traits_is_object(val: &JsValue) -> bool {
+    val.dyn_ref::<GreeterJsImpl>().is_some()
+}
+
+#[wasm_bindgen_test]
+fn implemented_in_js() {
+    js_trait_implemented_in_js();
+}
+
+#[wasm_bindgen_test]
+fn renamed_method() {
+    js_trait_renamed_method();
+}
@@ -0,0 +1,61 @@
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::TryFromJsValueError;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn numbers() {
+    assert_eq!(f64::try_from(JsValue::from(1.5)), Ok(1.5));
+    assert_eq!(f32::try_from(JsValue::from(0.25)), Ok(0.25));
+    assert_eq!(i32::try_from(JsValue::from(-7)), Ok(-7));
+    assert_eq!(u8::try_from(JsValue::from(255)), Ok(255));
+    assert_eq!(u32::try_from(JsValue::from(-0.0)), Ok(0));
+
+    let wrong_type = TryFromJsValueError::WrongType { expected: "number" };
+    assert_eq!(f64::try_from(JsValue::from("1")), Err(wrong_type));
+    assert_eq!(i32::try_from(JsValue::NULL), Err(wrong_type));
+}
+
+#[wasm_bindgen_test]
+fn out_of_range() {
+    let u8_range = TryFromJsValueError::OutOfRange { target: "u8" };
+    assert_eq!(u8::try_from(JsValue::from(256)), Err(u8_range));
+    assert_eq!(u8::try_from(JsValue::from(-1)), Err(u8_range));
+    assert_eq!(u8::try_from(JsValue::from(1.5)), Err(u8_range));
+    assert_eq!(u8::try_from(JsValue::from(std::f64::NAN)), Err(u8_range));
+    assert_eq!(
+        i32::try_from(JsValue::from(std::f64::INFINITY)),
+        Err(TryFromJsValueError::OutOfRange { target: "i32" })
+    );
+}
+
+#[wasm_bindgen_test]
+fn bools_and_strings() {
+    assert_eq!(bool::try_from(JsValue::TRUE), Ok(true));
+    assert_eq!(
+        bool::try_from(JsValue::from(1)),
+        Err(TryFromJsValueError::WrongType {
+            expected: "boolean"
+        })
+    );
+    assert_eq!(String::try_from(JsValue::from("hi")), Ok("hi".to_string()));
+    assert_eq!(
+        String::try_from(JsValue::UNDEFINED),
+        Err(TryFromJsValueError::WrongType { expected: "string" })
+    );
+}
+
+#[wasm_bindgen_test]
+fn question_mark() {
+    fn sum(values: Vec<JsValue>) -> Result<u32, JsValue> {
+        let mut total = 0;
+        for value in values {
+            total += u32::try_from(value)?;
+        }
+        Ok(total)
+    }
+
+    assert_eq!(sum(vec![1.into(), 2.into()]).unwrap(), 3);
+    let err = sum(vec![1.into(), "2".into()]).unwrap_err();
+    assert_eq!(err, "expected a JS value of type number");
+}
@@ -0,0 +1,18 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.js_call_with_point = (f, x, y) => f([x, y]);
+
+exports.js_call_with_pair = f => {
+    assert.deepStrictEqual(f([3, true]), [true, 5]);
+    return true;
+};
+
+exports.js_works = () => {
+    assert.deepStrictEqual(wasm.rust_midpoint([0, 0], [3, 5]), [1.5, 2.5]);
+    assert.strictEqual(wasm.rust_rgb([255, 128, 0]), '#ff8000');
+    assert.deepStrictEqual(wasm.rust_rect(4, 6), [-2, -3, 4, 6]);
+    assert.deepStrictEqual(wasm.rust_flip([true, 0.5]), [0.5, false]);
+
+    assert.throws(() => wasm.rust_midpoint([1], [2, 3]), /expected an array of length 2, got 1/);
+};
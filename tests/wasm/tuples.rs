@@ -0,0 +1,47 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/tuples.js")]
+extern "C" {
+    fn js_call_with_point(f: &Closure<dyn Fn(&(f64, f64)) -> f64>, x: f64, y: f64) -> f64;
+    fn js_call_with_pair(f: &Closure<dyn Fn(&(u8, bool)) -> (bool, u8)>) -> JsValue;
+    fn js_works();
+}
+
+#[wasm_bindgen]
+pub fn rust_midpoint(a: &(f64, f64), b: &(f64, f64)) -> (f64, f64) {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+#[wasm_bindgen]
+pub fn rust_rgb(color: &(u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.0, color.1, color.2)
+}
+
+#[wasm_bindgen]
+pub fn rust_rect(width: u32, height: u32) -> (i32, i32, u32, u32) {
+    (-(width as i32) / 2, -(height as i32) / 2, width, height)
+}
+
+#[wasm_bindgen]
+pub fn rust_flip(pair: &(bool, f32)) -> (f32, bool) {
+    (pair.1, !pair.0)
+}
+
+#[wasm_bindgen_test]
+fn works() {
+    js_works();
+}
+
+#[wasm_bindgen_test]
+fn closure_arguments() {
+    let length =
+        Closure::wrap(Box::new(|&(x, y): &(f64, f64)| (x * x + y * y).sqrt())
+            as Box<dyn Fn(&(f64, f64)) -> f64>);
+    assert_eq!(js_call_with_point(&length, 3.0, 4.0), 5.0);
+
+    let flip = Closure::wrap(
+        Box::new(|&(a, b): &(u8, bool)| (b, a + 2)) as Box<dyn Fn(&(u8, bool)) -> (bool, u8)>
+    );
+    assert!(js_call_with_pair(&flip).is_truthy());
+}
@@ -0,0 +1,22 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.i128_js_identity = a => a;
+exports.u128_js_identity = a => a;
+exports.bigint = s => BigInt(s);
+
+exports.js_works_128 = () => {
+    const i128_min = -(BigInt(1) << BigInt(127));
+    const u128_max = (BigInt(1) << BigInt(128)) - BigInt(1);
+    assert.strictEqual(wasm.i128_min(), i128_min);
+    assert.strictEqual(wasm.u128_max(), u128_max);
+
+    assert.strictEqual(wasm.i128_rust_identity(BigInt('0')), BigInt('0'));
+    assert.strictEqual(wasm.i128_rust_identity(BigInt('-1')), BigInt('-1'));
+    assert.strictEqual(wasm.i128_rust_identity(i128_min), i128_min);
+    assert.strictEqual(wasm.u128_rust_identity(u128_max), u128_max);
+
+    assert.throws(() => wasm.u128_rust_identity(u128_max + BigInt(1)), RangeError);
+    assert.throws(() => wasm.u128_rust_identity(BigInt(-1)), RangeError);
+    assert.throws(() => wasm.i128_rust_identity(-i128_min), RangeError);
+};
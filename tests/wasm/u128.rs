@@ -0,0 +1,70 @@
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::TryFromJsValueError;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/u128.js")]
+extern "C" {
+    fn i128_js_identity(a: i128) -> i128;
+    fn u128_js_identity(a: u128) -> u128;
+    fn js_works_128();
+    fn bigint(s: &str) -> JsValue;
+}
+
+#[wasm_bindgen]
+pub fn i128_min() -> i128 {
+    i128::min_value()
+}
+
+#[wasm_bindgen]
+pub fn u128_max() -> u128 {
+    u128::max_value()
+}
+
+#[wasm_bindgen]
+pub fn i128_rust_identity(a: i128) -> i128 {
+    i128_js_identity(a)
+}
+
+#[wasm_bindgen]
+pub fn u128_rust_identity(a: u128) -> u128 {
+    u128_js_identity(a)
+}
+
+#[wasm_bindgen_test]
+fn works() {
+    js_works_128();
+}
+
+#[wasm_bindgen_test]
+fn round_trips_through_js() {
+    for &n in [0, 1, -1, i128::min_value(), i128::max_value(), 1 << 100].iter() {
+        assert_eq!(i128_js_identity(n), n);
+    }
+    for &n in [0, 1, u128::max_value(), 0xdead_beef << 64].iter() {
+        assert_eq!(u128_js_identity(n), n);
+    }
+}
+
+#[wasm_bindgen_test]
+fn try_from_bigints() {
+    let u128_max = "340282366920938463463374607431768211455";
+    let i128_min = "-170141183460469231731687303715884105728";
+    assert_eq!(u128::try_from(bigint(u128_max)), Ok(u128::max_value()));
+    assert_eq!(i128::try_from(bigint(i128_min)), Ok(i128::min_value()));
+    assert_eq!(i128::try_from(bigint("-1")), Ok(-1));
+
+    let u128_overflow = TryFromJsValueError::OutOfRange { target: "u128" };
+    let i128_overflow = TryFromJsValueError::OutOfRange { target: "i128" };
+    let past_u128_max = "340282366920938463463374607431768211456";
+    let past_i128_min = "-170141183460469231731687303715884105729";
+    assert_eq!(u128::try_from(bigint(past_u128_max)), Err(u128_overflow));
+    assert_eq!(u128::try_from(bigint("-1")), Err(u128_overflow));
+    assert_eq!(i128::try_from(bigint(past_i128_min)), Err(i128_overflow));
+    assert_eq!(i128::try_from(bigint(u128_max)), Err(i128_overflow));
+
+    assert_eq!(
+        u128::try_from(JsValue::from(1)),
+        Err(TryFromJsValueError::WrongType { expected: "bigint" })
+    );
+}
@@ -4,6 +4,24 @@ const assert = require('assert');
 exports.i64_js_identity = a => a;
 exports.u64_js_identity = a => a;
 
+exports.i64_js_sum = a => {
+    // borrowed slices are passed as views of wasm's memory
+    assert.ok(a instanceof BigInt64Array);
+    return a.reduce((sum, x) => sum + x, BigInt('0'));
+};
+
+exports.u64_js_double = a => {
+    assert.ok(a instanceof BigUint64Array);
+    for (let i = 0; i < a.length; i++) {
+        a[i] *= BigInt('2');
+    }
+};
+
+exports.u64_js_vec = a => {
+    assert.ok(a instanceof BigUint64Array);
+    return a.reverse();
+};
+
 exports.js_works = () => {
     assert.strictEqual(wasm.zero(), BigInt('0'));
     assert.strictEqual(wasm.one(), BigInt('1'));
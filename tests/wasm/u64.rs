@@ -6,6 +6,9 @@ extern "C" {
     fn i64_js_identity(a: i64) -> i64;
     fn u64_js_identity(a: u64) -> u64;
     fn js_works();
+    fn i64_js_sum(a: &[i64]) -> i64;
+    fn u64_js_double(a: &mut [u64]);
+    fn u64_js_vec(a: Vec<u64>) -> Vec<u64>;
 }
 
 #[wasm_bindgen]
@@ -67,3 +70,14 @@ pub fn u64_slice(a: &[u64]) -> Vec<u64> {
 fn works() {
     js_works();
 }
+
+#[wasm_bindgen_test]
+fn slices_to_js() {
+    assert_eq!(i64_js_sum(&[i64::min_value(), i64::max_value(), 1]), 0);
+
+    let mut a = [1, 2, u64::max_value() / 2];
+    u64_js_double(&mut a);
+    assert_eq!(a, [2, 4, u64::max_value() - 1]);
+
+    assert_eq!(u64_js_vec(vec![1, u64::max_value()]), [u64::max_value(), 1]);
+}